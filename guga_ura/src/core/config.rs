@@ -1,289 +1,180 @@
 //! 配置管理
 //!
-//! 支持热重载：通过 ArcSwap 实现无锁读取 + 原子交换
+//! 配置结构与读写由共享的 `guga_ura_config` crate 提供；这里只负责 DLL 侧的
+//! 路径定位（游戏 EXE 同级）与日志。热重载通过 ArcSwap 实现无锁读取 + 原子交换。
 
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-/// 配置结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {
-    /// Notifier服务地址
-    #[serde(default = "Config::default_notifier_host")]
-    pub notifier_host: String,
+pub use guga_ura_config::{Config, ConfigLoadOutcome, ConfigMigration};
 
-    /// Receiver监听地址（接收端使用）
-    #[serde(default = "Config::default_receiver_listen_addr")]
-    pub receiver_listen_addr: String,
+use guga_ura_config::profiles::{self, ProfileResolution};
+use guga_ura_config::reload::{self, ReloadState, ReloadStatus};
+use guga_ura_transport::signing;
 
-    /// HTTP超时时间(毫秒)
-    #[serde(default = "Config::default_timeout_ms")]
-    pub timeout_ms: u64,
-
-    /// 目标帧数 (-1 表示使用游戏默认, 30/60/120/240 等)
-    #[serde(default = "Config::default_target_fps")]
-    pub target_fps: i32,
-
-    /// VSync 设置 (-1 表示使用游戏默认, 0=关闭, 1=开启)
-    #[serde(default = "Config::default_vsync_count")]
-    pub vsync_count: i32,
-
-    /// Debug 模式：将拦截的 msgpack 数据转为 JSON 保存到本地
-    #[serde(default)]
-    pub debug_mode: bool,
-
-    /// Debug 输出目录（绝对路径，优先使用）
-    #[serde(default)]
-    pub debug_output_dir: Option<String>,
-
-    /// 社团Fans 输出目录（绝对路径优先，接收端使用）
-    #[serde(default)]
-    pub fans_output_dir: Option<String>,
-
-    /// Fans 数据保存开关（接收端聚合）
-    #[serde(default = "Config::default_fans_enabled")]
-    pub fans_enabled: bool,
-
-    /// Relay 开关（接收端二次转发）
-    #[serde(default = "Config::default_relay_enabled")]
-    pub relay_enabled: bool,
-
-    /// Relay 目标地址（接收端二次转发）
-    #[serde(default)]
-    pub relay_target_host: Option<String>,
-
-    /// 种马/玩家数据输出开关（接收端处理）
-    #[serde(default = "Config::default_stallion_output_enabled")]
-    pub stallion_output_enabled: bool,
-
-    /// 种马/玩家数据输出目录（绝对路径优先，接收端使用）
-    #[serde(default)]
-    pub stallion_output_dir: Option<String>,
+/// 获取配置文件路径（DLL 同目录，即游戏目录）
+pub fn config_path() -> PathBuf {
+    Config::exe_config_path()
 }
 
-impl Config {
-    fn default_notifier_host() -> String {
-        "http://127.0.0.1:4693".to_string()
-    }
-
-    fn default_receiver_listen_addr() -> String {
-        "127.0.0.1:4693".to_string()
-    }
-
-    fn default_timeout_ms() -> u64 {
-        100
-    }
+/// 获取配置方案目录（配置文件同级）
+pub fn profiles_dir() -> PathBuf {
+    profiles::profiles_dir(&config_path())
+}
 
-    fn default_target_fps() -> i32 {
-        -1 // -1 表示使用游戏默认
+/// 加载配置
+///
+/// 文件不存在时写入默认配置；文件损坏时使用默认配置但不覆盖原文件。
+/// 主配置选择了方案时使用方案内容。
+pub fn load() -> Config {
+    let path = config_path();
+    info!("Loading config from: {}", path.display());
+
+    let (config, outcome) = Config::load_or_create_file(&path);
+    match outcome {
+        ConfigLoadOutcome::Loaded => {
+            let config = resolve_profile(&path, config);
+            log_config("Config loaded from", &path, &config);
+            log_validation(&path, &config);
+            return config;
+        }
+        ConfigLoadOutcome::Migrated(migration) => {
+            log_migration(&path, &migration);
+            let config = resolve_profile(&path, config);
+            log_config("Config loaded from", &path, &config);
+            log_validation(&path, &config);
+            return config;
+        }
+        ConfigLoadOutcome::CreatedDefault => {
+            info!(
+                "Default config written to {} (debug_mode = {})",
+                path.display(),
+                config.debug_mode
+            );
+        }
+        ConfigLoadOutcome::CreateFailed(e) => {
+            warn!("Failed to save default config {}: {}", path.display(), e);
+        }
+        ConfigLoadOutcome::InvalidKeptFile(e) => {
+            warn!(
+                "Failed to load config {}, keeping current/default config without overwriting file: {}",
+                path.display(),
+                e
+            );
+        }
     }
 
-    fn default_vsync_count() -> i32 {
-        -1 // -1 表示使用游戏默认
-    }
+    log_config("Using default config for", &path, &config);
+    config
+}
 
-    fn default_fans_enabled() -> bool {
-        true
-    }
+/// 尝试从磁盘加载配置，失败时返回错误，不覆盖现有文件。
+///
+/// 旧版本配置会先备份原文件，再改写为迁移后的文档；主配置选择了方案时使用方案内容。
+pub fn try_load() -> Result<Config, String> {
+    let path = config_path();
+    info!("Loading config from: {}", path.display());
+    let (config, migration) = Config::load_file_with_migration(&path)?;
+    if let Some(migration) = migration.as_ref() {
+        log_migration(&path, migration);
+    }
+    let config = resolve_profile(&path, config);
+    log_config("Config loaded from", &path, &config);
+    log_validation(&path, &config);
+    Ok(config)
+}
 
-    fn default_relay_enabled() -> bool {
-        false
+/// 热重载：读取并校验新配置，返回应当生效的配置
+///
+/// 读取失败、文件被删除或校验有错误时返回 `None`，调用方继续使用当前配置。
+/// 每次判定结果都会写入重载状态文件。
+pub fn reload(trigger: &str, current_active_profile: Option<&str>) -> Option<Config> {
+    let path = config_path();
+    let (config, status) =
+        reload::evaluate_reload(&path, trigger, try_load(), current_active_profile);
+
+    if status.state != ReloadState::Applied {
+        warn!(
+            "Config reload rejected (trigger = {}), keeping last known good config: {}",
+            trigger, status.message
+        );
     }
+    record_reload_status(&status);
+    config
+}
 
-    fn default_stallion_output_enabled() -> bool {
-        true
-    }
+/// 记录启动时的加载结果
+pub fn record_startup_status(config: &Config) {
+    record_reload_status(&reload::startup_status(config));
+}
 
-    /// 获取配置文件路径
-    pub fn config_path() -> PathBuf {
-        // 配置文件放在DLL同目录下
-        let mut path = std::env::current_exe().unwrap_or_default();
-        path.pop();
-        path.push("guga_ura_config.json");
-        path
+fn record_reload_status(status: &ReloadStatus) {
+    let path = config_path();
+    if let Err(e) = reload::write_reload_status(&path, status) {
+        warn!("Failed to write config reload status: {}", e);
     }
+}
 
-    /// 加载配置
-    pub fn load() -> Config {
-        let path = Self::config_path();
-        match Self::try_load() {
-            Ok(config) => return config,
-            Err(e) if path.exists() => {
-                warn!(
-                    "Failed to load config {}, keeping current/default config without overwriting file: {}",
-                    path.display(),
-                    e
-                );
-            }
-            Err(e) => {
-                warn!(
-                    "Config file not found or unreadable, will create default at {}: {}",
-                    path.display(),
-                    e
-                );
-            }
+fn resolve_profile(path: &std::path::Path, config: Config) -> Config {
+    let (config, resolution) = profiles::apply_active_profile(path, config);
+    match resolution {
+        ProfileResolution::NotActive => {}
+        ProfileResolution::Applied(name) => {
+            info!("Config profile applied: {}", name);
         }
-
-        let config = Config::default();
-        if !path.exists() {
-            match config.save() {
-                Ok(()) => {
-                    info!(
-                        "Default config written to {} (debug_mode = {})",
-                        path.display(),
-                        config.debug_mode
-                    );
-                }
-                Err(e) => {
-                    warn!("Failed to save default config {}: {}", path.display(), e);
-                }
-            }
+        ProfileResolution::Fallback { name, error } => {
+            warn!(
+                "Config profile {} unavailable, using main config {}: {}",
+                name,
+                path.display(),
+                error
+            );
         }
-        info!(
-            "Using default config: notifier_host = {}, timeout_ms = {}, target_fps = {}, vsync_count = {}, debug_mode = {}, debug_output_dir = {:?}, fans_enabled = {}, fans_output_dir = {:?}",
-            config.notifier_host,
-            config.timeout_ms,
-            config.target_fps,
-            config.vsync_count,
-            config.debug_mode,
-            config.debug_output_dir,
-            config.fans_enabled,
-            config.fans_output_dir
-        );
-        config
     }
+    config
+}
 
-    /// 尝试从磁盘加载配置，失败时返回错误，不覆盖现有文件。
-    pub fn try_load() -> Result<Config, String> {
-        let path = Self::config_path();
-        info!("Loading config from: {}", path.display());
-        let config = load_from_path(&path)?;
-        info!(
-            "Config loaded from {}: notifier_host = {}, timeout_ms = {}, target_fps = {}, vsync_count = {}, debug_mode = {}, debug_output_dir = {:?}, fans_enabled = {}, fans_output_dir = {:?}",
+fn log_migration(path: &std::path::Path, migration: &ConfigMigration) {
+    info!(
+        "Config {} migrated from v{} to v{}, backup = {:?}",
+        path.display(),
+        migration.report.from_version,
+        migration.report.to_version,
+        migration.backup_path
+    );
+    if let Some(e) = migration.rewrite_error.as_ref() {
+        warn!(
+            "Config {} migrated in memory only, rewrite failed: {}",
             path.display(),
-            config.notifier_host,
-            config.timeout_ms,
-            config.target_fps,
-            config.vsync_count,
-            config.debug_mode,
-            config.debug_output_dir,
-            config.fans_enabled,
-            config.fans_output_dir
+            e
         );
-        Ok(config)
-    }
-
-    /// 保存配置
-    pub fn save(&self) -> Result<(), String> {
-        let path = Self::config_path();
-        let json =
-            serde_json::to_string_pretty(self).map_err(|e| format!("Serialize error: {}", e))?;
-        fs::write(&path, json).map_err(|e| format!("Write error: {}", e))?;
-        Ok(())
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Config {
-            notifier_host: Self::default_notifier_host(),
-            receiver_listen_addr: Self::default_receiver_listen_addr(),
-            timeout_ms: Self::default_timeout_ms(),
-            target_fps: Self::default_target_fps(),
-            vsync_count: Self::default_vsync_count(),
-            debug_mode: false,
-            debug_output_dir: None,
-            fans_output_dir: None,
-            fans_enabled: Self::default_fans_enabled(),
-            relay_enabled: Self::default_relay_enabled(),
-            relay_target_host: None,
-            stallion_output_enabled: Self::default_stallion_output_enabled(),
-            stallion_output_dir: None,
+/// 记录配置校验结果；错误字段在运行时会失效（例如发送失败），这里明确写入日志
+fn log_validation(path: &std::path::Path, config: &Config) {
+    for issue in config.validate().issues {
+        if issue.is_error() {
+            error!("Config {} invalid field {}", path.display(), issue);
+        } else {
+            warn!("Config {} suspicious field {}", path.display(), issue);
         }
     }
 }
 
-fn load_from_path(path: &Path) -> Result<Config, String> {
-    let content =
-        fs::read_to_string(path).map_err(|e| format!("read {} failed: {}", path.display(), e))?;
-    parse_config_json(&content).map_err(|e| format!("parse {} failed: {}", path.display(), e))
-}
-
-fn parse_config_json(content: &str) -> Result<Config, serde_json::Error> {
-    serde_json::from_str(content).or_else(|_| {
-        let trimmed = content.trim_start_matches('\u{feff}');
-        serde_json::from_str(trimmed)
-    })
-}
-
-#[cfg(test)]
-mod tests {
-    use super::{parse_config_json, Config};
-
-    #[test]
-    fn default_values_should_include_receiver_and_relay_fields() {
-        let config = Config::default();
-
-        assert_eq!(config.notifier_host, "http://127.0.0.1:4693");
-        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
-        assert_eq!(config.timeout_ms, 100);
-        assert_eq!(config.target_fps, -1);
-        assert_eq!(config.vsync_count, -1);
-        assert!(!config.debug_mode);
-        assert_eq!(config.debug_output_dir, None);
-        assert_eq!(config.fans_output_dir, None);
-        assert!(config.fans_enabled);
-        assert!(!config.relay_enabled);
-        assert_eq!(config.relay_target_host, None);
-    }
-
-    #[test]
-    fn parse_config_json_should_support_bom_and_backfill_new_fields() {
-        let content = "\u{feff}{\"timeout_ms\":250,\"fans_enabled\":false}";
-
-        let config = parse_config_json(content).expect("BOM 配置解析失败");
-
-        assert_eq!(config.notifier_host, "http://127.0.0.1:4693");
-        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
-        assert_eq!(config.timeout_ms, 250);
-        assert!(!config.fans_enabled);
-        assert!(!config.relay_enabled);
-        assert_eq!(config.relay_target_host, None);
-    }
-
-    #[test]
-    fn legacy_config_should_remain_compatible() {
-        let content = r#"{"notifier_host":"http://127.0.0.1:4800","timeout_ms":180}"#;
-
-        let config = parse_config_json(content).expect("旧配置解析失败");
-
-        assert_eq!(config.notifier_host, "http://127.0.0.1:4800");
-        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
-        assert_eq!(config.timeout_ms, 180);
-        assert!(!config.relay_enabled);
-        assert_eq!(config.relay_target_host, None);
-    }
-
-    #[test]
-    fn new_fields_should_round_trip() {
-        let config = Config {
-            receiver_listen_addr: "127.0.0.1:4700".to_string(),
-            relay_enabled: true,
-            relay_target_host: Some("http://127.0.0.1:4800".to_string()),
-            fans_enabled: false,
-            ..Config::default()
-        };
-
-        let json = serde_json::to_string(&config).expect("序列化配置失败");
-        let reparsed = parse_config_json(&json).expect("round-trip 解析失败");
-
-        assert_eq!(reparsed.receiver_listen_addr, "127.0.0.1:4700");
-        assert!(reparsed.relay_enabled);
-        assert_eq!(
-            reparsed.relay_target_host.as_deref(),
-            Some("http://127.0.0.1:4800")
-        );
-        assert!(!reparsed.fans_enabled);
-    }
+fn log_config(prefix: &str, path: &std::path::Path, config: &Config) {
+    info!(
+        "{} {}: active_profile = {:?}, notifier_host = {}, timeout_ms = {}, target_fps = {}, vsync_count = {}, debug_mode = {}, debug_output_dir = {:?}, fans_enabled = {}, fans_output_dir = {:?}, signing = {}",
+        prefix,
+        path.display(),
+        config.active_profile,
+        config.notifier_host,
+        config.timeout_ms,
+        config.target_fps,
+        config.vsync_count,
+        config.debug_mode,
+        config.debug_output_dir,
+        config.fans_enabled,
+        config.fans_output_dir,
+        signing::effective_secret(config.shared_secret.as_deref()).is_some()
+    );
 }
@@ -1,8 +1,16 @@
 //! Debug 模块
 //!
 //! 将拦截到的 msgpack 数据转换为 JSON 并保存到本地文件，
-//! 用于分析游戏通信数据结构。
+//! 用于分析游戏通信数据结构。文件命名与包装结构与 Receiver 输出一致
+//! （见 [`guga_ura_transport::capture::CapturedPayloadFile`]）；开启
+//! `debug_keep_raw` 或解码失败时，同名 `.msgpack` 保留原始数据。
+//! `received_at` 按配置的时间输出格式与时区写出，与 Receiver 相同。
 
+use guga_ura_config::time::TimeSettings;
+use guga_ura_transport::capture::{
+    self, CaptureDirection, CaptureRoute, CapturedPayloadFile, DECODED_AS_RAW,
+};
+use serde_json::{json, Value};
 use std::fs;
 use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
@@ -12,6 +20,9 @@ use std::sync::atomic::{AtomicU64, Ordering};
 static REQUEST_SEQ: AtomicU64 = AtomicU64::new(0);
 static RESPONSE_SEQ: AtomicU64 = AtomicU64::new(0);
 
+/// 写入包装结构 `source.plugin` 的来源标识
+const DEBUG_SOURCE_PLUGIN: &str = "guga_ura_dll";
+
 /// 获取 debug 输出目录
 /// - 优先使用 config 传入的绝对路径
 /// - 未配置时回退到当前进程 EXE 同级 debug/
@@ -39,16 +50,22 @@ fn ensure_debug_dir(custom_output_dir: Option<&str>) -> Result<PathBuf, String>
 
 /// 将 msgpack 数据转换为 JSON 并保存
 ///
-/// direction: "request" 或 "response"
-pub fn save_msgpack_as_json(data: &[u8], direction: &str, output_dir: Option<&str>) {
+/// 默认通道的文件以方向开头，其它通道加上通道名前缀
+pub fn save_msgpack_as_json(
+    data: &[u8],
+    route: &CaptureRoute,
+    output_dir: Option<&str>,
+    keep_raw: bool,
+    time: TimeSettings,
+) {
     // 在后台线程中处理，避免阻塞游戏
     let data = data.to_vec();
-    let direction = direction.to_string();
+    let route = route.clone();
     let output_dir = output_dir.map(|s| s.to_string());
 
     std::thread::spawn(move || {
         match panic::catch_unwind(AssertUnwindSafe(|| {
-            save_impl(&data, &direction, output_dir.as_deref())
+            save_impl(&data, &route, output_dir.as_deref(), keep_raw, &time)
         })) {
             Ok(Ok(())) => {}
             Ok(Err(e)) => {
@@ -61,51 +78,62 @@ pub fn save_msgpack_as_json(data: &[u8], direction: &str, output_dir: Option<&st
     });
 }
 
-fn save_impl(data: &[u8], direction: &str, output_dir: Option<&str>) -> Result<(), String> {
+fn save_impl(
+    data: &[u8],
+    route: &CaptureRoute,
+    output_dir: Option<&str>,
+    keep_raw: bool,
+    time: &TimeSettings,
+) -> Result<(), String> {
     let dir = ensure_debug_dir(output_dir)?;
 
-    // 生成文件名：方向_序号_时间戳.json
-    let seq = match direction {
-        "request" => REQUEST_SEQ.fetch_add(1, Ordering::Relaxed),
-        _ => RESPONSE_SEQ.fetch_add(1, Ordering::Relaxed),
+    // 生成文件名：[通道_]方向_序号_时间戳
+    let seq = match route.direction {
+        CaptureDirection::Request => REQUEST_SEQ.fetch_add(1, Ordering::Relaxed),
+        CaptureDirection::Response => RESPONSE_SEQ.fetch_add(1, Ordering::Relaxed),
     };
-
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
-        .as_millis();
-
-    let file_stem = format!("{}_{:04}_{}", direction, seq, timestamp);
+        .as_millis() as u64;
+    let file_stem = capture::capture_file_stem(route, seq, timestamp);
 
-    // 尝试将 msgpack 解码为 JSON Value；失败时仅记录日志
-    match rmp_serde::from_slice::<serde_json::Value>(data) {
-        Ok(json_value) => {
-            let json_filename = format!("{}.json", file_stem);
-            let json_path = dir.join(&json_filename);
+    let received_at = time.format_unix_ms(timestamp);
+    let wrapper = CapturedPayloadFile::new(route, &route.path(), timestamp, received_at)
+        .with_extra("source", json!({ "plugin": DEBUG_SOURCE_PLUGIN }));
 
-            match serde_json::to_string_pretty(&json_value) {
-                Ok(json_str) => {
-                    if let Err(e) = fs::write(&json_path, &json_str) {
-                        warn!("Debug JSON write failed ({}): {}", json_path.display(), e);
-                    } else {
-                        info!(
-                            "Debug: saved {} ({} bytes -> {} bytes JSON) to {}",
-                            json_filename,
-                            data.len(),
-                            json_str.len(),
-                            dir.display()
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!("Debug JSON serialize failed: {}", e);
-                }
-            }
-        }
+    // 解码失败时只能依靠原始数据，无论是否开启 keep_raw 都保留
+    let wrapper = match rmp_serde::from_slice::<Value>(data) {
+        Ok(json_value) => wrapper.with_payload(data.len(), "msgpack", json_value),
         Err(e) => {
-            warn!("Debug msgpack decode failed: {}", e);
+            warn!("Debug msgpack decode failed, keeping raw payload: {}", e);
+            wrapper.with_decode_error(data.len(), e.to_string())
         }
-    }
+    };
+    let wrapper = if keep_raw || wrapper.decoded_as == DECODED_AS_RAW {
+        let raw_filename = capture::raw_payload_file_name(&file_stem);
+        let raw_path = dir.join(&raw_filename);
+        fs::write(&raw_path, data)
+            .map_err(|e| format!("Debug raw write failed ({}): {}", raw_path.display(), e))?;
+        wrapper.with_raw_file(raw_filename)
+    } else {
+        wrapper
+    };
+
+    let json_filename = format!("{}.json", file_stem);
+    wrapper.write_json(&dir.join(&json_filename))?;
+    info!(
+        "Debug: saved {} ({} bytes, decoded as {}{}) to {}",
+        json_filename,
+        data.len(),
+        wrapper.decoded_as,
+        wrapper
+            .raw_file
+            .as_deref()
+            .map(|raw| format!(", raw {}", raw))
+            .unwrap_or_default(),
+        dir.display()
+    );
 
     Ok(())
 }
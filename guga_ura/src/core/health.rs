@@ -0,0 +1,154 @@
+//! 捕获状态上报
+//!
+//! 汇总代理入口、Steam/DMM 判定、符号映射、Hook 安装结果与 payload 计数，
+//! 启动后定期发送到 Receiver 的 `/status`，便于排查捕获失效而不必翻找
+//! `guga_ura_runtime.log`。是否上报由 `status_heartbeat` 决定，默认只发给本工具的
+//! Receiver，避免向第三方 notifier 发送未知路由。
+
+use guga_ura_transport::health::{
+    CaptureHealth, HookHealth, HookInstallHealth, HEALTH_SCHEMA_VERSION, HEARTBEAT_INTERVAL_MS,
+    STATUS_ROUTE,
+};
+use guga_ura_transport::signing;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{http, GugaURA, CAPTURE_COUNTERS};
+use crate::il2cpp;
+
+static STATE: Lazy<Mutex<CaptureHealth>> = Lazy::new(|| {
+    Mutex::new(CaptureHealth {
+        schema_version: HEALTH_SCHEMA_VERSION,
+        dll_version: env!("CARGO_PKG_VERSION").to_string(),
+        process_id: std::process::id(),
+        started_at_unix_ms: signing::now_unix_ms(),
+        proxy_entry: "unknown".to_string(),
+        game_release: "unknown".to_string(),
+        hooks: HookHealth {
+            state: "pending".to_string(),
+            ..HookHealth::default()
+        },
+        ..CaptureHealth::default()
+    })
+});
+
+static HEARTBEAT_SEQ: AtomicU64 = AtomicU64::new(0);
+static HEARTBEAT_STARTED: AtomicBool = AtomicBool::new(false);
+static HEARTBEAT_FAILURE_LOGGED: AtomicBool = AtomicBool::new(false);
+
+fn update(apply: impl FnOnce(&mut CaptureHealth)) {
+    if let Ok(mut state) = STATE.lock() {
+        apply(&mut state);
+    }
+}
+
+/// 记录实际使用的代理入口
+pub fn set_proxy_entry(entry: &str) {
+    update(|state| state.proxy_entry = entry.to_string());
+}
+
+/// 记录发行版本判定结果与依据
+pub fn set_game_release(is_steam: bool, evidence: Vec<String>) {
+    update(|state| {
+        state.game_release = if is_steam { "steam" } else { "dmm" }.to_string();
+        state.release_evidence = evidence;
+    });
+}
+
+/// 记录一次 Hook 安装尝试，并立即上报一次
+pub fn record_hook_attempt(
+    manifest_source: String,
+    result: &Result<Vec<HookInstallHealth>, String>,
+) {
+    update(|state| {
+        let hooks = &mut state.hooks;
+        hooks.attempts += 1;
+        hooks.manifest_source = manifest_source;
+        match result {
+            Ok(installed) => {
+                hooks.state = "installed".to_string();
+                hooks.hooks = installed.clone();
+                hooks.error = None;
+            }
+            Err(e) => {
+                hooks.state = "failed".to_string();
+                hooks.hooks.clear();
+                hooks.error = Some(e.clone());
+            }
+        }
+    });
+    std::thread::spawn(send_heartbeat);
+}
+
+/// 记录一次 payload 发送结果
+pub fn record_delivery(result: Result<(), String>) {
+    update(|state| {
+        let delivery = &mut state.delivery;
+        match result {
+            Ok(()) => {
+                delivery.sent += 1;
+                delivery.last_sent_at_unix_ms = Some(signing::now_unix_ms());
+            }
+            Err(e) => {
+                delivery.failed += 1;
+                delivery.last_error = Some(e);
+            }
+        }
+    });
+}
+
+/// 启动心跳线程：立即上报一次，之后按固定间隔上报
+pub fn start_heartbeat() {
+    if HEARTBEAT_STARTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    std::thread::spawn(|| loop {
+        send_heartbeat();
+        std::thread::sleep(Duration::from_millis(HEARTBEAT_INTERVAL_MS));
+    });
+}
+
+fn snapshot() -> CaptureHealth {
+    let mut health = STATE.lock().map(|state| state.clone()).unwrap_or_default();
+    health.sent_at_unix_ms = signing::now_unix_ms();
+    health.heartbeat_seq = HEARTBEAT_SEQ.fetch_add(1, Ordering::Relaxed);
+    health.symbol_map = il2cpp::symbol_map_health();
+    health.capture = CAPTURE_COUNTERS.snapshot();
+    health
+}
+
+fn send_heartbeat() {
+    let config = GugaURA::instance().config.load();
+    if !config.status_heartbeat_enabled() {
+        return;
+    }
+    let body = match serde_json::to_vec(&snapshot()) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Status serialize failed: {}", e);
+            return;
+        }
+    };
+    let url = format!("{}{}", config.notifier_host, STATUS_ROUTE);
+
+    match http::post_json(
+        &url,
+        &body,
+        config.timeout_ms,
+        config.shared_secret.as_deref(),
+    ) {
+        Ok(()) => {
+            if HEARTBEAT_FAILURE_LOGGED.swap(false, Ordering::Relaxed) {
+                info!("Status heartbeat delivered to {}", url);
+            }
+        }
+        Err(e) => {
+            // Receiver 未启动时每次都会失败，只记录第一次
+            if !HEARTBEAT_FAILURE_LOGGED.swap(true, Ordering::Relaxed) {
+                warn!("Status heartbeat to {} failed: {}", url, e);
+            }
+        }
+    }
+}
@@ -1,12 +1,34 @@
 //! HTTP客户端模块
 
+use guga_ura_transport::signing::{self, SIGNATURE_REJECTED_STATUS};
+use guga_ura_transport::transport::{post_payload, sign_post, ContentEncoding};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+use super::health;
+
+/// Receiver 返回 415 后置位，本次进程内不再压缩
+static COMPRESSION_REJECTED: AtomicBool = AtomicBool::new(false);
+
+/// 签名被拒绝的提示只输出一次
+static SIGNATURE_REJECTED_LOGGED: AtomicBool = AtomicBool::new(false);
+
 /// POST字节数据到指定URL
-pub fn post_bytes(url: &str, data: &[u8], timeout_ms: u64) {
+///
+/// 数据足够大且配置了压缩时按 `encoding` 压缩并声明 `Content-Encoding`；
+/// Receiver 不支持该编码时改为原样重发。配置了 `shared_secret` 时按实际发送的
+/// 请求体签名
+pub fn post_bytes(
+    url: &str,
+    data: &[u8],
+    timeout_ms: u64,
+    encoding: ContentEncoding,
+    shared_secret: Option<&str>,
+) {
     // 使用单独的线程发送，避免阻塞游戏
     let url = url.to_string();
     let data = data.to_vec();
+    let shared_secret = signing::effective_secret(shared_secret).map(str::to_string);
     let timeout = Duration::from_millis(timeout_ms.max(1));
 
     std::thread::spawn(move || {
@@ -16,15 +38,70 @@ pub fn post_bytes(url: &str, data: &[u8], timeout_ms: u64) {
             .timeout_write(timeout)
             .build();
 
-        match agent.post(&url).send_bytes(&data) {
+        let delivery = post_payload(
+            &agent,
+            &url,
+            &data,
+            encoding,
+            shared_secret.as_deref(),
+            &COMPRESSION_REJECTED,
+        );
+        if let Some(e) = &delivery.compress_error {
+            warn!("Compress body failed, sending raw: {}", e);
+        }
+        if delivery.compression_disabled {
+            warn!(
+                "{} rejected {} body, compression disabled for this session",
+                url,
+                encoding.as_str()
+            );
+        }
+
+        match delivery.result {
             Ok(_) => {
                 // 发送成功，静默
+                health::record_delivery(Ok(()));
+            }
+            Err(ureq::Error::Status(status, _)) if status == SIGNATURE_REJECTED_STATUS => {
+                if !SIGNATURE_REJECTED_LOGGED.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "{} rejected request signature, check shared_secret on both sides",
+                        url
+                    );
+                }
+                health::record_delivery(Err(format!("HTTP {}: signature rejected", status)));
             }
             Err(e) => {
                 // 发送失败，只在调试模式下打印
                 #[cfg(debug_assertions)]
                 warn!("Failed to send data to {}: {}", url, e);
+                health::record_delivery(Err(e.to_string()));
             }
         }
     });
 }
+
+/// 在当前线程同步 POST 一份 JSON（状态心跳使用，不计入 payload 发送计数）
+pub fn post_json(
+    url: &str,
+    body: &[u8],
+    timeout_ms: u64,
+    shared_secret: Option<&str>,
+) -> Result<(), String> {
+    let timeout = Duration::from_millis(timeout_ms.max(1));
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .timeout_write(timeout)
+        .build();
+
+    sign_post(
+        agent.post(url).set("Content-Type", "application/json"),
+        url,
+        body,
+        signing::effective_secret(shared_secret),
+    )
+    .send_bytes(body)
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
@@ -2,8 +2,10 @@
 
 mod config;
 pub mod debug;
+pub mod health;
 mod http;
 mod interceptor;
+pub mod observer;
 mod watcher;
 // 注意：反检测功能已移至独立的 Cellar (apphelp.dll)
 
@@ -11,6 +13,10 @@ pub use config::Config;
 pub use interceptor::Interceptor;
 
 use arc_swap::ArcSwap;
+use guga_ura_config::time::receive_time_settings;
+use guga_ura_transport::capture::CaptureRoute;
+use guga_ura_transport::capture_rules::{CaptureCounters, CaptureDecision};
+use guga_ura_transport::transport::ContentEncoding;
 use once_cell::sync::OnceCell;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::Arc;
@@ -26,6 +32,10 @@ static INSTANCE: OnceCell<Arc<GugaURA>> = OnceCell::new();
 static HOOKING_FINISHED: AtomicBool = AtomicBool::new(false);
 static DEBUG_MODE_STATE: AtomicU8 = AtomicU8::new(2);
 static DEBUG_MODE_DISABLED_HINT_LOGGED: AtomicBool = AtomicBool::new(false);
+static CAPTURE_COUNTERS: CaptureCounters = CaptureCounters::new();
+
+/// 每处理多少个 payload 输出一次捕获计数
+const CAPTURE_STATS_LOG_INTERVAL: u64 = 100;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum ProxyEntry {
@@ -34,6 +44,16 @@ enum ProxyEntry {
     Unknown,
 }
 
+impl ProxyEntry {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::UnityPlayer => "unity_player",
+            Self::CriManaVpx => "cri_mana_vpx",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 fn log_debug_mode_state(debug_mode: bool) {
     let next = if debug_mode { 1 } else { 0 };
     let prev = DEBUG_MODE_STATE.swap(next, Ordering::Relaxed);
@@ -53,15 +73,18 @@ pub struct GugaURA {
 
 impl GugaURA {
     pub fn init() -> Result<(), String> {
-        let config = Config::load();
-        let config_path = Config::config_path();
-        trace::append_runtime_log(&format!(
-            "[core] init config_path={} notifier_host={} timeout_ms={} debug_mode={}",
-            config_path.display(),
-            config.notifier_host,
-            config.timeout_ms,
-            config.debug_mode
-        ));
+        let config = config::load();
+        let config_path = config::config_path();
+        trace::info(
+            "core",
+            &format!(
+                "init config_path={} notifier_host={} timeout_ms={} debug_mode={}",
+                config_path.display(),
+                config.notifier_host,
+                config.timeout_ms,
+                config.debug_mode
+            ),
+        );
         info!(
             "Config loaded: path = {}, notifier_host = {}, timeout_ms = {}, target_fps = {}, vsync_count = {}, debug_mode = {}, debug_output_dir = {:?}",
             config_path.display(),
@@ -73,6 +96,7 @@ impl GugaURA {
             config.debug_output_dir
         );
         log_debug_mode_state(config.debug_mode);
+        config::record_startup_status(&config);
 
         let instance = Arc::new(GugaURA {
             config: ArcSwap::new(Arc::new(config)),
@@ -81,12 +105,18 @@ impl GugaURA {
 
         INSTANCE.set(instance).map_err(|_| "Already initialized")?;
 
+        // 订阅需要热更新的配置字段（须在监控启动前完成）
+        Self::subscribe_config_observers();
+
         // 初始化代理和Hook
         Self::setup_hooks()?;
 
         // 启动配置文件监控
         watcher::start_config_watcher();
 
+        // 定期向 Receiver 上报捕获状态
+        health::start_heartbeat();
+
         Ok(())
     }
 
@@ -95,18 +125,16 @@ impl GugaURA {
     }
 
     /// 重载配置
-    /// 从文件重新加载配置，并应用 FPS/VSync 等运行时设置
-    pub fn reload_config() {
+    /// 从文件重新加载并校验配置，生效后按字段通知订阅者（FPS/VSync 等运行时设置）；
+    /// 新配置无效时保留当前配置
+    pub fn reload_config(trigger: &str) {
         let instance = Self::instance();
-        let new_config = match Config::try_load() {
-            Ok(config) => config,
-            Err(e) => {
-                warn!("Config reload skipped: {}", e);
-                return;
-            }
+        let current_profile = instance.config.load().active_profile.clone();
+        let Some(new_config) = config::reload(trigger, current_profile.as_deref()) else {
+            return;
         };
 
-        let config_path = Config::config_path();
+        let config_path = config::config_path();
         info!(
             "Config reloaded: path = {}, notifier_host = {}, timeout_ms = {}, target_fps = {}, vsync_count = {}, debug_mode = {}, debug_output_dir = {:?}, fans_enabled = {}, fans_output_dir = {:?}",
             config_path.display(),
@@ -119,22 +147,25 @@ impl GugaURA {
             new_config.fans_enabled,
             new_config.fans_output_dir
         );
-        log_debug_mode_state(new_config.debug_mode);
-
-        // 更新 FPS/VSync 原子变量（即使 hook 未初始化也安全）
-        il2cpp::fps_hook::TARGET_FPS.store(new_config.target_fps, Ordering::Relaxed);
-        il2cpp::fps_hook::VSYNC_COUNT.store(new_config.vsync_count, Ordering::Relaxed);
 
-        // 原子交换配置
-        instance.config.store(Arc::new(new_config));
+        // 原子交换配置，再把变更分发给订阅者
+        let old_config = instance.config.swap(Arc::new(new_config));
+        observer::dispatch(&old_config, &instance.config.load());
 
         info!("Config reloaded successfully");
     }
 
+    fn subscribe_config_observers() {
+        observer::subscribe("debug_mode", &["debug_mode"], |change| {
+            log_debug_mode_state(change.new.debug_mode);
+        });
+        il2cpp::fps_hook::subscribe_config();
+    }
+
     fn setup_hooks() -> Result<(), String> {
         let instance = Self::instance();
         let proxy_entry = Self::current_proxy_entry();
-        trace::append_runtime_log(&format!("[core] proxy entry = {:?}", proxy_entry));
+        trace::info("core", &format!("proxy entry = {:?}", proxy_entry));
 
         // 检查是否已经加载了 GameAssembly.dll (游戏可能已经启动)
         let game_assembly = unsafe { GetModuleHandleW(w!("GameAssembly.dll")) };
@@ -142,18 +173,20 @@ impl GugaURA {
         if let Ok(handle) = game_assembly {
             if !handle.is_invalid() {
                 info!("Late loading detected, GameAssembly already loaded");
-                trace::append_runtime_log(&format!(
-                    "[core] late loading detected, GameAssembly handle=0x{:X}",
-                    handle.0 as usize
-                ));
+                trace::info(
+                    "core",
+                    &format!(
+                        "late loading detected, GameAssembly handle=0x{:X}",
+                        handle.0 as usize
+                    ),
+                );
                 il2cpp::set_handle(handle.0 as usize);
+                Self::is_steam_release();
+                health::set_proxy_entry(proxy_entry.as_str());
 
                 if let Err(e) = Self::init_proxy(proxy_entry) {
                     warn!("proxy init failed during late loading: {}", e);
-                    trace::append_runtime_log(&format!(
-                        "[core] late proxy init failed: {}",
-                        e
-                    ));
+                    trace::warn("core", &format!("late proxy init failed: {}", e));
                 }
 
                 // 延迟初始化HTTP hooks
@@ -165,19 +198,24 @@ impl GugaURA {
         // 正常流程：判断是 Steam 版还是 DMM 版
         let is_steam = Self::is_steam_release();
         info!("Game version: {}", if is_steam { "Steam" } else { "DMM" });
-        trace::append_runtime_log(&format!(
-            "[core] runtime game version = {}",
-            if is_steam { "Steam" } else { "DMM" }
-        ));
+        trace::info(
+            "core",
+            &format!(
+                "runtime game version = {}",
+                if is_steam { "Steam" } else { "DMM" }
+            ),
+        );
 
-        Self::init_proxy(match proxy_entry {
+        let proxy_entry = match proxy_entry {
             ProxyEntry::Unknown if is_steam => ProxyEntry::CriManaVpx,
             ProxyEntry::Unknown => ProxyEntry::UnityPlayer,
             known => known,
-        })?;
+        };
+        health::set_proxy_entry(proxy_entry.as_str());
+        Self::init_proxy(proxy_entry)?;
 
         info!("Hooking LoadLibraryW");
-        trace::append_runtime_log("[core] hooking LoadLibraryW");
+        trace::info("core", "hooking LoadLibraryW");
         instance.interceptor.hook_load_library()?;
 
         Ok(())
@@ -201,8 +239,18 @@ impl GugaURA {
 
         let is_steam =
             steam_api_exists || cri_backup_exists || path_looks_like_steam || exe_looks_like_steam;
-        trace::append_runtime_log(&format!(
-            "[core] steam detection: exe={} steam_api64={} cri_backup={} path_looks_like_steam={} exe_looks_like_steam={} => {}",
+        health::set_game_release(
+            is_steam,
+            vec![
+                format!("exe={}", exec_path.display()),
+                format!("steam_api64={}", steam_api_exists),
+                format!("cri_backup={}", cri_backup_exists),
+                format!("path_looks_like_steam={}", path_looks_like_steam),
+                format!("exe_looks_like_steam={}", exe_looks_like_steam),
+            ],
+        );
+        trace::info("core", &format!(
+            "steam detection: exe={} steam_api64={} cri_backup={} path_looks_like_steam={} exe_looks_like_steam={} => {}",
             exec_path.display(),
             steam_api_exists,
             cri_backup_exists,
@@ -238,17 +286,18 @@ impl GugaURA {
         match proxy_entry {
             ProxyEntry::UnityPlayer => {
                 info!("Setting up UnityPlayer proxy");
-                trace::append_runtime_log("[core] setting up UnityPlayer proxy");
+                trace::info("core", "setting up UnityPlayer proxy");
                 proxy::unityplayer::init()
             }
             ProxyEntry::CriManaVpx => {
                 info!("Setting up cri_mana_vpx proxy");
-                trace::append_runtime_log("[core] setting up cri_mana_vpx proxy");
+                trace::info("core", "setting up cri_mana_vpx proxy");
                 proxy::cri_mana_vpx::init()
             }
             ProxyEntry::Unknown => {
-                trace::append_runtime_log(
-                    "[core] proxy entry unknown, skipping explicit proxy initialization",
+                trace::info(
+                    "core",
+                    "proxy entry unknown, skipping explicit proxy initialization",
                 );
                 Ok(())
             }
@@ -258,7 +307,10 @@ impl GugaURA {
     /// 当 GameAssembly.dll 加载后调用
     pub fn on_game_assembly_loaded(handle: usize) {
         info!("GameAssembly.dll loaded at 0x{:X}", handle);
-        trace::append_runtime_log(&format!("[core] GameAssembly loaded handle=0x{:X}", handle));
+        trace::info(
+            "core",
+            &format!("GameAssembly loaded handle=0x{:X}", handle),
+        );
         il2cpp::set_handle(handle);
     }
 
@@ -271,7 +323,7 @@ impl GugaURA {
         }
 
         info!("Game ready, initializing HTTP hooks (first time only)");
-        trace::append_runtime_log("[core] on_game_ready fired");
+        trace::info("core", "on_game_ready fired");
         Self::try_init_http_hooks();
     }
 
@@ -279,10 +331,13 @@ impl GugaURA {
     fn try_init_http_hooks() {
         let instance = Self::instance();
         let config = instance.config.load();
-        trace::append_runtime_log(&format!(
-            "[core] try_init_http_hooks target_fps={} vsync_count={} notifier_host={}",
-            config.target_fps, config.vsync_count, config.notifier_host
-        ));
+        trace::info(
+            "core",
+            &format!(
+                "try_init_http_hooks target_fps={} vsync_count={} notifier_host={}",
+                config.target_fps, config.vsync_count, config.notifier_host
+            ),
+        );
 
         // 初始化IL2CPP符号
         il2cpp::init();
@@ -293,15 +348,15 @@ impl GugaURA {
         // 初始化帧数限制Hook
         il2cpp::fps_hook::init(config.target_fps, config.vsync_count);
 
-        // Hook HTTP请求/响应
-        if let Err(e) = il2cpp::http_hook::init() {
+        // Hook 各捕获通道的请求/响应
+        if let Err(e) = il2cpp::capture::init() {
             error!("Failed to hook HTTP: {}", e);
-            trace::append_runtime_log(&format!("[core] http hook init failed: {}", e));
+            trace::warn("core", &format!("http hook init failed: {}", e));
             // 标记为未完成，以便下次重试
             HOOKING_FINISHED.store(false, Ordering::Relaxed);
         } else {
             info!("HTTP hooks installed successfully!");
-            trace::append_runtime_log("[core] http hooks installed successfully");
+            trace::info("core", "http hooks installed successfully");
         }
     }
 
@@ -312,41 +367,78 @@ impl GugaURA {
     }
 }
 
-/// 发送数据到notifier服务
-pub fn notify_request(data: &[u8]) {
+/// 发送数据到notifier服务：`{notifier_host}/notify/{channel}/{direction}`
+pub fn notify(route: &CaptureRoute, data: &[u8]) {
     let config = GugaURA::instance().config.load();
     log_debug_mode_state(config.debug_mode);
 
     // Debug 模式：保存 msgpack 数据为 JSON
     if config.debug_mode {
-        debug::save_msgpack_as_json(data, "request", config.debug_output_dir.as_deref());
+        debug::save_msgpack_as_json(
+            data,
+            route,
+            config.debug_output_dir.as_deref(),
+            config.debug_keep_raw,
+            receive_time_settings(&config),
+        );
     } else if !DEBUG_MODE_DISABLED_HINT_LOGGED.swap(true, Ordering::Relaxed) {
         warn!(
-            "Debug mode is DISABLED while request interception is active. \
-            If you enabled it in Config UI, click '保存配置' (or use auto-save toggle) and check logs for loaded config path."
+            "Debug mode is DISABLED while {} interception is active. \
+            If you enabled it in Config UI, click '保存配置' (or use auto-save toggle) and check logs for loaded config path.",
+            route.direction.as_str()
         );
     }
 
-    let url = format!("{}/notify/request", config.notifier_host);
-    info!("Sending request data ({} bytes) to {}", data.len(), url);
-    http::post_bytes(&url, data, config.timeout_ms);
-}
-
-pub fn notify_response(data: &[u8]) {
-    let config = GugaURA::instance().config.load();
-    log_debug_mode_state(config.debug_mode);
-
-    // Debug 模式：保存 msgpack 数据为 JSON
-    if config.debug_mode {
-        debug::save_msgpack_as_json(data, "response", config.debug_output_dir.as_deref());
-    } else if !DEBUG_MODE_DISABLED_HINT_LOGGED.swap(true, Ordering::Relaxed) {
-        warn!(
-            "Debug mode is DISABLED while response interception is active. \
-            If you enabled it in Config UI, click '保存配置' (or use auto-save toggle) and check logs for loaded config path."
+    // 捕获规则：只影响发送，不影响 Debug 保存
+    let decision = config
+        .capture_rules
+        .decide(route.direction, data, &CAPTURE_COUNTERS);
+    log_capture_stats();
+    if let CaptureDecision::Skip(reason) = decision {
+        debug!(
+            "Skipped {} {} data ({} bytes): {}",
+            route.channel,
+            route.direction.as_str(),
+            data.len(),
+            reason.as_str()
         );
+        return;
     }
 
-    let url = format!("{}/notify/response", config.notifier_host);
-    info!("Sending response data ({} bytes) to {}", data.len(), url);
-    http::post_bytes(&url, data, config.timeout_ms);
+    let url = format!("{}{}", config.notifier_host, route.path());
+    info!(
+        "Sending {} {} data ({} bytes) to {}",
+        route.channel,
+        route.direction.as_str(),
+        data.len(),
+        url
+    );
+    let encoding = ContentEncoding::from_config(config.transport_compression.as_deref())
+        .unwrap_or(ContentEncoding::Identity);
+    http::post_bytes(
+        &url,
+        data,
+        config.timeout_ms,
+        encoding,
+        config.shared_secret.as_deref(),
+    );
+}
+
+/// 按固定间隔输出捕获计数
+fn log_capture_stats() {
+    let stats = CAPTURE_COUNTERS.snapshot();
+    if stats.total() % CAPTURE_STATS_LOG_INTERVAL != 0 {
+        return;
+    }
+    info!(
+        "Capture stats: matched={} skipped={} (direction={} too_small={} too_large={} missing_key={} excluded_key={} sampled={})",
+        stats.matched,
+        stats.skipped,
+        stats.direction_disabled,
+        stats.too_small,
+        stats.too_large,
+        stats.missing_key,
+        stats.excluded_key,
+        stats.sampled
+    );
 }
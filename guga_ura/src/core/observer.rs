@@ -0,0 +1,46 @@
+//! 配置变更订阅
+//!
+//! 各子系统在初始化时订阅关心的配置字段，热重载成功后由 `GugaURA::reload_config`
+//! 统一分发新旧值。比较与分发逻辑在共享的 `guga_ura_config::observer` 中实现。
+
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+pub use guga_ura_config::observer::ConfigChange;
+use guga_ura_config::observer::ConfigObservers;
+
+use super::Config;
+
+static OBSERVERS: Lazy<RwLock<ConfigObservers>> = Lazy::new(|| RwLock::new(ConfigObservers::new()));
+
+/// 订阅配置字段变更
+///
+/// 回调在配置监控线程中执行，应保持轻量；回调执行时新配置已经生效。
+pub fn subscribe(
+    name: &'static str,
+    fields: &'static [&'static str],
+    callback: impl Fn(&ConfigChange<'_>) + Send + Sync + 'static,
+) {
+    match OBSERVERS.write() {
+        Ok(mut observers) => {
+            observers.subscribe(name, fields, callback);
+            info!("Config observer registered: {} -> {:?}", name, fields);
+        }
+        Err(_) => error!("Config observer registry poisoned, {} not registered", name),
+    }
+}
+
+/// 分发一次配置变更
+pub(super) fn dispatch(old: &Config, new: &Config) {
+    let notified = match OBSERVERS.read() {
+        Ok(observers) => observers.dispatch(old, new),
+        Err(_) => {
+            error!("Config observer registry poisoned, change not dispatched");
+            return;
+        }
+    };
+
+    if !notified.is_empty() {
+        info!("Config change dispatched to: {:?}", notified);
+    }
+}
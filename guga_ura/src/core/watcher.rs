@@ -1,21 +1,28 @@
 //! 配置文件监控
 //!
-//! 使用 notify crate 监控 guga_ura_config.json 文件变更，
-//! 文件修改后自动触发配置热重载。
+//! 使用 notify crate 监控 guga_ura_config.json 与配置方案目录的变更，
+//! 文件修改后自动触发配置热重载（包括切换激活方案）。
+//!
+//! 除直接写入外，编辑器与配置工具常用“写临时文件再重命名”的方式保存，
+//! 因此创建、重命名与删除事件同样会触发重载；重载结果见 `config::reload`。
 
+use notify::event::ModifyKind;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
-use std::time::Duration;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
-use super::config::Config;
+use super::config;
 use super::GugaURA;
 
 /// 启动配置文件监控线程
 ///
-/// 在后台线程中监控配置文件，当文件被修改时自动重载配置。
-/// 包含 200ms 的 debounce 防止短时间内多次重载。
+/// 在后台线程中监控配置文件，当文件被修改、创建、重命名或删除时自动重载配置。
+/// 事件静默 200ms 后才重载（最长等待 2s），避免保存过程中的中间状态被读取。
 pub fn start_config_watcher() {
     std::thread::spawn(|| {
-        let config_path = Config::config_path();
+        let config_path = config::config_path();
 
         // 监控配置文件所在的目录（notify 需要监控目录）
         let watch_dir = match config_path.parent() {
@@ -30,6 +37,7 @@ pub fn start_config_watcher() {
             .file_name()
             .map(|n| n.to_os_string())
             .unwrap_or_default();
+        let profiles_dir = config::profiles_dir();
 
         let (tx, rx) = std::sync::mpsc::channel();
 
@@ -51,43 +59,119 @@ pub fn start_config_watcher() {
             return;
         }
 
+        // 方案目录可能在运行中才被配置工具创建，未监控时每次事件后重试
+        let mut watching_profiles = watch_profiles_dir(&mut watcher, &profiles_dir);
+
         info!(
-            "Config file watcher started, watching: {}",
-            config_path.display()
+            "Config file watcher started, watching: {} (profiles = {}, watching = {})",
+            config_path.display(),
+            profiles_dir.display(),
+            watching_profiles
         );
 
+        let targets = WatchTargets {
+            config_filename,
+            profiles_dir: profiles_dir.clone(),
+        };
+
         loop {
-            match rx.recv() {
-                Ok(event) => {
-                    // 只处理修改事件
-                    if !matches!(event.kind, EventKind::Modify(_)) {
-                        continue;
-                    }
-
-                    // 只处理目标配置文件
-                    let is_config_file = event
-                        .paths
-                        .iter()
-                        .any(|p| p.file_name().map(|n| n == config_filename).unwrap_or(false));
-
-                    if !is_config_file {
-                        continue;
-                    }
-
-                    // Debounce: 等待 200ms 让写入完成
-                    std::thread::sleep(Duration::from_millis(200));
-
-                    // 排空后续的重复事件
-                    while rx.try_recv().is_ok() {}
-
-                    info!("Config file changed, reloading...");
-                    GugaURA::reload_config();
-                }
+            let event = match rx.recv() {
+                Ok(event) => event,
                 Err(e) => {
                     error!("Config watcher channel error: {}", e);
                     break;
                 }
+            };
+
+            if !watching_profiles {
+                watching_profiles = watch_profiles_dir(&mut watcher, &profiles_dir);
             }
+
+            let Some(trigger) = targets.trigger_of(&event) else {
+                continue;
+            };
+
+            let Some(trigger) = debounce(&rx, &targets, trigger) else {
+                error!("Config watcher channel closed");
+                break;
+            };
+
+            info!("Config file changed ({}), reloading...", trigger);
+            GugaURA::reload_config(trigger);
         }
     });
 }
+
+/// 静默期：事件停止 200ms 后返回最后一次相关事件的触发原因
+const DEBOUNCE_QUIET: Duration = Duration::from_millis(200);
+/// 持续有事件时的最长等待
+const DEBOUNCE_MAX: Duration = Duration::from_secs(2);
+
+struct WatchTargets {
+    config_filename: OsString,
+    profiles_dir: PathBuf,
+}
+
+impl WatchTargets {
+    /// 与配置相关的事件返回触发原因，其余返回 `None`
+    fn trigger_of(&self, event: &Event) -> Option<&'static str> {
+        let trigger = match event.kind {
+            EventKind::Create(_) => "create",
+            EventKind::Modify(ModifyKind::Name(_)) => "rename",
+            EventKind::Modify(_) => "modify",
+            EventKind::Remove(_) => "remove",
+            _ => return None,
+        };
+
+        // 只处理主配置文件与方案文件
+        let is_config_file = event.paths.iter().any(|p| {
+            p.file_name()
+                .map(|n| n == self.config_filename)
+                .unwrap_or(false)
+                || p.parent() == Some(self.profiles_dir.as_path())
+        });
+        is_config_file.then_some(trigger)
+    }
+}
+
+/// 合并一段时间内的连续事件；通道关闭时返回 `None`
+fn debounce(
+    rx: &Receiver<Event>,
+    targets: &WatchTargets,
+    mut trigger: &'static str,
+) -> Option<&'static str> {
+    let started = Instant::now();
+    while started.elapsed() < DEBOUNCE_MAX {
+        match rx.recv_timeout(DEBOUNCE_QUIET) {
+            Ok(event) => {
+                if let Some(next) = targets.trigger_of(&event) {
+                    trigger = next;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+    Some(trigger)
+}
+
+fn watch_profiles_dir(watcher: &mut impl Watcher, profiles_dir: &Path) -> bool {
+    if !profiles_dir.is_dir() {
+        return false;
+    }
+
+    match watcher.watch(profiles_dir, RecursiveMode::NonRecursive) {
+        Ok(()) => {
+            info!("Watching config profiles: {}", profiles_dir.display());
+            true
+        }
+        Err(e) => {
+            warn!(
+                "Failed to watch config profiles {}: {}",
+                profiles_dir.display(),
+                e
+            );
+            false
+        }
+    }
+}
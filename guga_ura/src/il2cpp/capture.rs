@@ -0,0 +1,191 @@
+//! 捕获通道模块
+//!
+//! 核心功能：拦截游戏方法中的明文数据并按通道转发
+//! 要 Hook 的方法由清单描述（默认 `http` 通道，即 Gallop.HttpHelper 的
+//! CompressRequest 和 DecompressResponse），可通过
+//! `guga_ura_data/guga_ura_hooks.json` 覆盖或增加新通道。
+//! 每个 Hook 占用一个预先生成的跳板，拦截到的 `byte[]` 发送到
+//! `/notify/{channel}/{direction}`。
+
+use minhook::MinHook;
+use once_cell::sync::OnceCell;
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use guga_ura_symbols::{
+    resolve_hook, HookManifest, HookManifestSource, HookTarget, ResolvedHook,
+    HOOK_MANIFEST_FILE_NAME, MAX_HOOK_COUNT,
+};
+use guga_ura_transport::capture::{CaptureDirection, CaptureRoute};
+use guga_ura_transport::health::HookInstallHealth;
+
+use super::symbols;
+use super::types::Il2CppArray;
+use crate::core;
+
+// 函数类型定义：x64 下前 4 个整数参数走寄存器，原样转发即可覆盖
+// 不超过 3 个参数的方法（最后一个寄存器是 IL2CPP 的 MethodInfo*）
+type PayloadFn = extern "C" fn(usize, usize, usize, usize) -> usize;
+
+/// 一个 Hook 跳板的状态
+struct CaptureSlot {
+    /// 原始函数指针
+    orig: AtomicUsize,
+    /// 请求明文所在的参数下标
+    payload_arg: AtomicUsize,
+    route: OnceCell<CaptureRoute>,
+}
+
+static SLOTS: [CaptureSlot; MAX_HOOK_COUNT] = [const {
+    CaptureSlot {
+        orig: AtomicUsize::new(0),
+        payload_arg: AtomicUsize::new(0),
+        route: OnceCell::new(),
+    }
+}; MAX_HOOK_COUNT];
+
+/// 为每个跳板生成独立的 detour，数量必须与 `MAX_HOOK_COUNT` 一致
+macro_rules! capture_detours {
+    ($($index:literal),* $(,)?) => {
+        [$({
+            extern "C" fn detour(a0: usize, a1: usize, a2: usize, a3: usize) -> usize {
+                on_call(&SLOTS[$index], [a0, a1, a2, a3])
+            }
+            detour as PayloadFn
+        }),*]
+    };
+}
+
+static DETOURS: [PayloadFn; MAX_HOOK_COUNT] = capture_detours!(0, 1, 2, 3, 4, 5, 6, 7);
+
+/// Hook 公共入口
+/// 请求：调用原方法前读取参数（压缩前的明文）
+/// 响应：调用原方法后读取返回值（解压后的明文）
+fn on_call(slot: &CaptureSlot, args: [usize; 4]) -> usize {
+    let orig_fn: PayloadFn = unsafe { std::mem::transmute(slot.orig.load(Ordering::Relaxed)) };
+    let call = || orig_fn(args[0], args[1], args[2], args[3]);
+
+    let Some(route) = slot.route.get() else {
+        return call();
+    };
+
+    match route.direction {
+        CaptureDirection::Request => {
+            capture_array(route, args[slot.payload_arg.load(Ordering::Relaxed)]);
+            call()
+        }
+        CaptureDirection::Response => {
+            let result = call();
+            capture_array(route, result);
+            result
+        }
+    }
+}
+
+/// 读取 `byte[]` 并转发到notifier服务
+fn capture_array(route: &CaptureRoute, ptr: usize) {
+    let data = ptr as *mut Il2CppArray;
+    if data.is_null() {
+        return;
+    }
+
+    unsafe {
+        let array = &*data;
+        core::notify(route, array.as_slice::<u8>());
+    }
+}
+
+/// 在 IL2CPP 中查找候选方法地址
+fn find_method(target: &HookTarget) -> Result<usize, String> {
+    let image = symbols::get_assembly_image(&target.assembly)
+        .ok_or_else(|| format!("assembly {} not found", target.assembly))?;
+    let klass = symbols::get_class(image, &target.namespace, &target.class)
+        .ok_or_else(|| format!("class {}.{} not found", target.namespace, target.class))?;
+    symbols::get_method_addr(klass, &target.method, target.arity as i32)
+        .ok_or_else(|| format!("method {} not found", target.method))
+}
+
+/// 把 Hook 安装到指定跳板
+unsafe fn install(index: usize, hook: &ResolvedHook<'_, usize>) -> Result<(), String> {
+    let slot = &SLOTS[index];
+    slot.payload_arg
+        .store(hook.target.payload_arg as usize, Ordering::Relaxed);
+    let _ = slot
+        .route
+        .set(CaptureRoute::new(hook.channel, hook.role.direction()));
+
+    let trampoline = MinHook::create_hook(hook.value as *mut c_void, DETOURS[index] as *mut c_void)
+        .map_err(|e| format!("Failed to create {} hook: {:?}", hook.target, e))?;
+    slot.orig.store(trampoline as usize, Ordering::Relaxed);
+
+    MinHook::enable_hook(hook.value as *mut c_void)
+        .map_err(|e| format!("Failed to enable {} hook: {:?}", hook.target, e))
+}
+
+/// 初始化捕获 Hook
+pub fn init() -> Result<(), String> {
+    let loaded = HookManifest::load(&crate::trace::data_dir().join(HOOK_MANIFEST_FILE_NAME));
+    if let Some(warning) = &loaded.warning {
+        warn!("{}", warning);
+    }
+    let source = match &loaded.source {
+        HookManifestSource::Embedded => {
+            info!("Using embedded hook manifest");
+            "embedded".to_string()
+        }
+        HookManifestSource::Override(path) => {
+            info!("Using hook manifest {}", path.display());
+            path.display().to_string()
+        }
+    };
+
+    let result = install_all(&loaded.manifest);
+    core::health::record_hook_attempt(source, &result);
+    result.map(|_| ())
+}
+
+/// 解析并安装清单中的全部 Hook，返回实际安装结果
+fn install_all(manifest: &HookManifest) -> Result<Vec<HookInstallHealth>, String> {
+    // 先解析全部目标，任何一个找不到都不安装，便于稍后整体重试
+    let resolved = manifest
+        .hooks
+        .iter()
+        .map(|hook| resolve_hook(hook, find_method))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    for hook in &resolved {
+        if hook.candidate_index > 0 {
+            warn!(
+                "{}/{} using fallback candidate #{}",
+                hook.channel,
+                hook.role.as_str(),
+                hook.candidate_index
+            );
+        }
+        info!(
+            "Found {}/{} {} at 0x{:X}",
+            hook.channel,
+            hook.role.as_str(),
+            hook.target,
+            hook.value
+        );
+    }
+
+    // 安装 Hook
+    for (index, hook) in resolved.iter().enumerate() {
+        unsafe { install(index, hook)? };
+        info!("{} hooked on channel {}", hook.target, hook.channel);
+    }
+
+    info!("Capture hooks installed successfully!");
+
+    Ok(resolved
+        .iter()
+        .map(|hook| HookInstallHealth {
+            channel: hook.channel.to_string(),
+            role: hook.role.as_str().to_string(),
+            target: hook.target.to_string(),
+            candidate_index: hook.candidate_index,
+        })
+        .collect())
+}
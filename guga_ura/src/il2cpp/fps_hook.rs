@@ -10,10 +10,10 @@ use std::sync::atomic::{AtomicI32, Ordering};
 use crate::il2cpp::symbols::il2cpp_resolve_icall;
 
 /// 目标帧数 (-1 表示使用游戏默认)
-pub static TARGET_FPS: AtomicI32 = AtomicI32::new(-1);
+static TARGET_FPS: AtomicI32 = AtomicI32::new(-1);
 
 /// VSync设置 (-1 表示使用游戏默认)
-pub static VSYNC_COUNT: AtomicI32 = AtomicI32::new(-1);
+static VSYNC_COUNT: AtomicI32 = AtomicI32::new(-1);
 
 // 原始函数指针
 static mut SET_TARGET_FRAME_RATE_ORIG: usize = 0;
@@ -55,6 +55,16 @@ extern "C" fn hooked_set_vsync_count(mut value: i32) {
     }
 }
 
+/// 订阅 FPS/VSync 配置变更
+///
+/// 热重载后更新静态变量；Hook 尚未安装时同样安全，安装后立即使用最新值。
+pub fn subscribe_config() {
+    crate::core::observer::subscribe("fps_hook", &["target_fps", "vsync_count"], |change| {
+        TARGET_FPS.store(change.new.target_fps, Ordering::Relaxed);
+        VSYNC_COUNT.store(change.new.vsync_count, Ordering::Relaxed);
+    });
+}
+
 /// 初始化帧数Hook
 ///
 /// 注意：无论配置值是什么，都会安装 Hook。
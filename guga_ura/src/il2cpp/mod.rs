@@ -1,11 +1,12 @@
 //! IL2CPP交互模块
 
+pub mod capture;
 pub mod fps_hook;
-pub mod http_hook;
 pub mod symbols;
 mod symbols_impl;
 pub mod types;
 
+use guga_ura_transport::health::SymbolMapHealth;
 use std::os::raw::c_void;
 use std::ptr::null_mut;
 
@@ -28,3 +29,8 @@ pub fn get_handle() -> *mut c_void {
 pub fn init() {
     symbols::init();
 }
+
+/// 符号映射状态
+pub fn symbol_map_health() -> SymbolMapHealth {
+    symbols_impl::symbol_map_health()
+}
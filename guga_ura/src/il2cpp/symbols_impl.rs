@@ -1,15 +1,24 @@
 //! IL2CPP符号解析模块
 //! 通过解析UnityPlayer.dll的PE结构获取混淆后的符号名称
+//! 符号表位置由 `guga_ura_symbols` 在代码节中按 lea-rip 特征扫描得到，
+//! 结果按 UnityPlayer.dll 的哈希缓存在 `guga_ura_data/`，并输出一份文本清单
 //!
 //! 本文件基于 Hachimi 项目的代码改编
 //! 原始项目: https://github.com/Hachimi-Hachimi/Hachimi
 //! 许可证: GPL-3.0
 
 use fnv::FnvHashMap;
+use guga_ura_symbols::{
+    DllFingerprint, SymbolCache, SymbolCacheLookup, SymbolDumpEntry, SymbolResolution, SymbolTable,
+    SYMBOL_CACHE_FILE_NAME, SYMBOL_DUMP_FILE_NAME,
+};
+use guga_ura_transport::health::SymbolMapHealth;
 use once_cell::sync::Lazy;
-use pelite::{pe::Pe, pe64::PeFile, FileMap};
+use pelite::FileMap;
+use std::collections::{BTreeMap, HashSet};
 use std::ffi::{CStr, CString};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
 use widestring::Utf16Str;
 use windows::core::PCSTR;
 use windows::Win32::Foundation::{HMODULE, MAX_PATH};
@@ -253,8 +262,11 @@ const SYMBOL_LIST: &[&'static str] = &[
     "il2cpp_unity_set_android_network_up_state_func",
 ];
 
-/// UnityPlayer.dll中符号表的起始RVA
-const START_RVA: u32 = 0x782c92;
+/// 旧版UnityPlayer.dll中符号表的起始RVA，仅在特征扫描失败时尝试
+const LEGACY_START_RVA: u32 = 0x782c92;
+
+/// 旧版符号表第一项与其余各项的间距
+const LEGACY_STRIDES: (u32, u32) = (0x28, 0x26);
 
 /// 获取游戏目录
 fn get_game_dir() -> PathBuf {
@@ -275,49 +287,240 @@ fn get_proc_address(hmodule: HMODULE, name: &CStr) -> usize {
     }
 }
 
-/// 从UnityPlayer.dll解析符号映射
-fn generate_symbol_map() -> Result<FnvHashMap<&'static str, CString>, String> {
-    let mut map = FnvHashMap::default();
-    map.reserve(SYMBOL_LIST.len());
+/// 读取GameAssembly.dll的导出名，用于校验扫描结果；读取失败时跳过该项校验
+fn load_game_assembly_exports(game_dir: &Path) -> Option<HashSet<String>> {
+    let path = game_dir.join("GameAssembly.dll");
+    let exports = FileMap::open(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|file_map| guga_ura_symbols::export_names(file_map.as_ref()));
+
+    match exports {
+        Ok(exports) => {
+            info!("Loaded {} exports from {:?}", exports.len(), path);
+            Some(exports)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to read exports from {:?}, skipping export check: {}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// 解析结果：映射本身、来源与UnityPlayer.dll指纹（供清单使用）
+struct SymbolMapState {
+    fingerprint: Option<DllFingerprint>,
+    source: String,
+    map: Result<FnvHashMap<&'static str, CString>, String>,
+}
+
+impl SymbolMapState {
+    fn failed(fingerprint: Option<DllFingerprint>, error: String) -> Self {
+        error!("Symbol map generation failed: {}", error);
+        Self {
+            fingerprint,
+            source: "none".to_string(),
+            map: Err(error),
+        }
+    }
+
+    fn from_symbols(
+        fingerprint: DllFingerprint,
+        source: String,
+        symbols: &BTreeMap<String, String>,
+    ) -> Self {
+        let mut map = FnvHashMap::default();
+        map.reserve(SYMBOL_LIST.len());
+        for symbol in SYMBOL_LIST {
+            let Some(name) = symbols.get(*symbol) else {
+                continue;
+            };
+            match CString::new(name.as_str()) {
+                Ok(name) => {
+                    map.insert(*symbol, name);
+                }
+                Err(e) => warn!("Invalid mangled name for {}: {}", symbol, e),
+            }
+        }
+
+        info!(
+            "Symbol map ready with {} entries (source: {})",
+            map.len(),
+            source
+        );
+        Self {
+            fingerprint: Some(fingerprint),
+            source,
+            map: Ok(map),
+        }
+    }
+}
+
+/// 在UnityPlayer.dll中定位符号表，特征扫描失败时尝试旧版固定偏移
+fn scan_symbol_table(game_dir: &Path, image: &[u8]) -> Result<(SymbolTable, &'static str), String> {
+    let exports = load_game_assembly_exports(game_dir);
 
-    let mut path = get_game_dir();
-    path.push("UnityPlayer.dll");
+    let table =
+        match guga_ura_symbols::scan_symbol_table(image, SYMBOL_LIST.len(), exports.as_ref()) {
+            Ok(table) => (table, "scan"),
+            Err(scan_error) => {
+                warn!(
+                    "Symbol table scan failed, trying legacy RVA 0x{:X}: {}",
+                    LEGACY_START_RVA, scan_error
+                );
+                let table = guga_ura_symbols::decode_symbol_table(
+                    image,
+                    LEGACY_START_RVA,
+                    LEGACY_STRIDES.0,
+                    LEGACY_STRIDES.1,
+                    SYMBOL_LIST.len(),
+                    exports.as_ref(),
+                )
+                .map_err(|legacy_error| {
+                    format!(
+                        "Symbol table not found (scan: {}; legacy: {})",
+                        scan_error, legacy_error
+                    )
+                })?;
+                (table, "legacy")
+            }
+        };
+
+    info!(
+        "Symbol table at RVA 0x{:X} (strides 0x{:X}/0x{:X})",
+        table.0.start_rva, table.0.first_stride, table.0.stride
+    );
+    Ok(table)
+}
+
+/// 从UnityPlayer.dll解析符号映射；UnityPlayer.dll未变化时直接使用缓存
+fn generate_symbol_map() -> SymbolMapState {
+    let game_dir = get_game_dir();
+    let path = game_dir.join("UnityPlayer.dll");
 
     info!("Parsing symbol map from: {:?}", path);
 
-    let file_map =
-        FileMap::open(&path).map_err(|e| format!("Failed to open UnityPlayer.dll: {}", e))?;
-    let pe = PeFile::from_bytes(&file_map).map_err(|e| format!("Failed to parse PE: {}", e))?;
+    let file_map = match FileMap::open(&path) {
+        Ok(file_map) => file_map,
+        Err(e) => {
+            return SymbolMapState::failed(None, format!("Failed to open UnityPlayer.dll: {}", e))
+        }
+    };
     let image = file_map.as_ref();
+    let fingerprint = DllFingerprint::of(image);
+    let cache_path = crate::trace::data_dir().join(SYMBOL_CACHE_FILE_NAME);
 
-    let mut rva = START_RVA;
-    for symbol in SYMBOL_LIST {
-        let offset = pe
-            .rva_to_file_offset(rva)
-            .map_err(|e| format!("Failed to convert RVA 0x{:X}: {}", rva, e))?;
-        let rip_offset = u32::from_le_bytes(image[offset..offset + 4].try_into().unwrap());
-        let name_offset = pe
-            .rva_to_file_offset(rva + 0x4 + rip_offset)
-            .map_err(|e| format!("Failed to get name offset: {}", e))?;
-        let name = unsafe { CStr::from_ptr(image[name_offset..].as_ptr() as _) };
-        map.insert(*symbol, name.to_owned());
-        rva += if rva == START_RVA { 0x28 } else { 0x26 };
+    match SymbolCache::lookup(&cache_path, &fingerprint, SYMBOL_LIST) {
+        SymbolCacheLookup::Hit(cache) => {
+            let source = format!("cache ({})", cache.source);
+            return SymbolMapState::from_symbols(fingerprint, source, &cache.symbols);
+        }
+        SymbolCacheLookup::Miss(reason) => {
+            info!("Symbol cache miss ({}), scanning UnityPlayer.dll", reason)
+        }
     }
 
-    info!("Symbol map generated with {} entries", map.len());
+    let (table, source) = match scan_symbol_table(&game_dir, image) {
+        Ok(found) => found,
+        Err(e) => return SymbolMapState::failed(Some(fingerprint), e),
+    };
+
+    let cache = SymbolCache::from_table(fingerprint.clone(), source, SYMBOL_LIST, &table);
+    match cache.save(&cache_path) {
+        Ok(()) => info!("Symbol cache written to {:?}", cache_path),
+        Err(e) => warn!("Failed to write symbol cache: {}", e),
+    }
 
-    Ok(map)
+    SymbolMapState::from_symbols(fingerprint, source.to_string(), &cache.symbols)
+}
+
+/// 写入符号映射清单，逐个查询地址以列出无法解析的符号
+fn write_symbol_dump(hmodule: HMODULE, state: &SymbolMapState) {
+    let empty = FnvHashMap::default();
+    let map = state.map.as_ref().unwrap_or(&empty);
+    let mangled_names: Vec<Option<String>> = SYMBOL_LIST
+        .iter()
+        .map(|symbol| {
+            map.get(symbol)
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .collect();
+
+    let entries: Vec<SymbolDumpEntry<'_>> = SYMBOL_LIST
+        .iter()
+        .zip(&mangled_names)
+        .map(|(symbol, mangled)| {
+            let resolution = match map.get(symbol) {
+                None => SymbolResolution::NotInMap,
+                Some(name) => match get_proc_address(hmodule, name) {
+                    0 => SymbolResolution::NotExported,
+                    address => SymbolResolution::Address(address),
+                },
+            };
+            SymbolDumpEntry {
+                symbol,
+                mangled: mangled.as_deref(),
+                resolution,
+            }
+        })
+        .collect();
+
+    let text = guga_ura_symbols::render_symbol_dump(
+        state.fingerprint.as_ref(),
+        &state.source,
+        state.map.as_ref().err().map(String::as_str),
+        &entries,
+    );
+
+    let dir = crate::trace::data_dir();
+    let path = dir.join(SYMBOL_DUMP_FILE_NAME);
+    let result = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, text));
+    match result {
+        Ok(()) => info!("Symbol map dump written to {:?}", path),
+        Err(e) => warn!("Failed to write symbol map dump {:?}: {}", path, e),
+    }
 }
 
 /// 全局符号映射表
-static SYMBOL_MAP: Lazy<Result<FnvHashMap<&'static str, CString>, String>> =
-    Lazy::new(|| generate_symbol_map());
+static SYMBOL_MAP: Lazy<SymbolMapState> = Lazy::new(generate_symbol_map);
+
+/// 首次查询符号时写一次清单
+static SYMBOL_DUMP: Once = Once::new();
+
+/// 符号映射状态（供状态上报）；尚未解析时不触发解析
+pub fn symbol_map_health() -> SymbolMapHealth {
+    let Some(state) = Lazy::get(&SYMBOL_MAP) else {
+        return SymbolMapHealth {
+            state: "pending".to_string(),
+            ..SymbolMapHealth::default()
+        };
+    };
+    match &state.map {
+        Ok(map) => SymbolMapHealth {
+            state: "ready".to_string(),
+            source: state.source.clone(),
+            entries: map.len(),
+            error: None,
+        },
+        Err(e) => SymbolMapHealth {
+            state: "failed".to_string(),
+            source: state.source.clone(),
+            entries: 0,
+            error: Some(e.clone()),
+        },
+    }
+}
 
 /// 从GameAssembly.dll获取函数地址（使用符号映射）
 pub unsafe fn dlsym(handle: *mut std::ffi::c_void, name: &str) -> usize {
     debug_assert!(!handle.is_null());
 
-    let map = match SYMBOL_MAP.as_ref() {
+    let state = &*SYMBOL_MAP;
+    SYMBOL_DUMP.call_once(|| write_symbol_dump(HMODULE(handle as _), state));
+
+    let map = match state.map.as_ref() {
         Ok(m) => m,
         Err(e) => {
             error!("Symbol map not available: {}", e);
@@ -26,7 +26,7 @@ const DLL_PROCESS_DETACH: c_ulong = 0;
 pub static mut DLL_HMODULE: HMODULE = HMODULE(std::ptr::null_mut());
 
 fn raw_debug_output(msg: &str) {
-    trace::append_runtime_log(msg);
+    trace::info("dll", msg.trim_start_matches("[GugaURA] "));
     let mut bytes: Vec<u8> = msg.as_bytes().iter().copied().filter(|b| *b != 0).collect();
     bytes.push(b'\n');
     bytes.push(0);
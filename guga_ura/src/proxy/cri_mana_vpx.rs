@@ -40,17 +40,21 @@ pub fn init() -> Result<(), String> {
     let data_dir = get_data_dir();
     // 使用 _orig 后缀与 installer.rs 保持一致
     let dll_path = data_dir.join("cri_mana_vpx_orig.dll");
-    trace::append_runtime_log(&format!(
-        "[cri_proxy] init data_dir={} dll_path={}",
-        data_dir.display(),
-        dll_path.display()
-    ));
+    trace::info(
+        "cri_proxy",
+        &format!(
+            "init data_dir={} dll_path={}",
+            data_dir.display(),
+            dll_path.display()
+        ),
+    );
 
     if !dll_path.exists() {
         // 原始 DLL 不存在，跳过（可能是 DMM 版或未正确安装）
         warn!("cri_mana_vpx_orig.dll not found in data dir, skipping cri proxy init");
-        trace::append_runtime_log(
-            "[cri_proxy] cri_mana_vpx_orig.dll not found, skipping proxy init",
+        trace::warn(
+            "cri_proxy",
+            "cri_mana_vpx_orig.dll not found, skipping proxy init",
         );
         return Ok(());
     }
@@ -62,10 +66,10 @@ pub fn init() -> Result<(), String> {
 
         let handle = LoadLibraryW(PCWSTR(dll_path_wide.as_ptr()))
             .map_err(|e| format!("Failed to load cri_mana_vpx.dll: {}", e))?;
-        trace::append_runtime_log(&format!(
-            "[cri_proxy] LoadLibraryW success handle=0x{:X}",
-            handle.0 as usize
-        ));
+        trace::info(
+            "cri_proxy",
+            &format!("LoadLibraryW success handle=0x{:X}", handle.0 as usize),
+        );
 
         let mut missing_exports = Vec::new();
 
@@ -93,7 +97,7 @@ pub fn init() -> Result<(), String> {
                 "cri_mana_vpx_orig.dll 缺少导出: {}",
                 missing_exports.join(", ")
             );
-            trace::append_runtime_log(&format!("[cri_proxy] {}", error));
+            trace::warn("cri_proxy", &error);
             return Err(error);
         }
 
@@ -107,12 +111,15 @@ pub fn init() -> Result<(), String> {
             "  criVvp9_SetUserAllocator: 0x{:X}",
             criVvp9_SetUserAllocator_orig
         );
-        trace::append_runtime_log(&format!(
-            "[cri_proxy] exports resolved alpha=0x{:X} interface=0x{:X} allocator=0x{:X}",
-            criVvp9_GetAlphaInterface_orig,
-            criVvp9_GetInterface_orig,
-            criVvp9_SetUserAllocator_orig
-        ));
+        trace::info(
+            "cri_proxy",
+            &format!(
+                "exports resolved alpha=0x{:X} interface=0x{:X} allocator=0x{:X}",
+                criVvp9_GetAlphaInterface_orig,
+                criVvp9_GetInterface_orig,
+                criVvp9_SetUserAllocator_orig
+            ),
+        );
     }
 
     Ok(())
@@ -38,11 +38,14 @@ fn prepare_orig_dll() -> Result<PathBuf, String> {
     let src_dll = get_game_dir().join("UnityPlayer.dll");
     let data_dir = get_data_dir();
     let dest_dll = data_dir.join("UnityPlayer_orig.dll");
-    trace::append_runtime_log(&format!(
-        "[unity_proxy] prepare_orig_dll src={} dest={}",
-        src_dll.display(),
-        dest_dll.display()
-    ));
+    trace::info(
+        "unity_proxy",
+        &format!(
+            "prepare_orig_dll src={} dest={}",
+            src_dll.display(),
+            dest_dll.display()
+        ),
+    );
 
     // 确保数据目录存在
     std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
@@ -55,9 +58,7 @@ fn prepare_orig_dll() -> Result<PathBuf, String> {
         if let (Some(s), Some(d)) = (src_time, dest_time) {
             if d >= s {
                 // 已经是最新的
-                trace::append_runtime_log(
-                    "[unity_proxy] existing UnityPlayer_orig.dll is up to date",
-                );
+                trace::info("unity_proxy", "existing UnityPlayer_orig.dll is up to date");
                 return Ok(dest_dll);
             }
         }
@@ -66,7 +67,10 @@ fn prepare_orig_dll() -> Result<PathBuf, String> {
     // 复制文件
     std::fs::copy(&src_dll, &dest_dll)
         .map_err(|e| format!("Failed to copy UnityPlayer.dll: {}", e))?;
-    trace::append_runtime_log("[unity_proxy] copied UnityPlayer.dll to UnityPlayer_orig.dll");
+    trace::info(
+        "unity_proxy",
+        "copied UnityPlayer.dll to UnityPlayer_orig.dll",
+    );
 
     Ok(dest_dll)
 }
@@ -75,10 +79,10 @@ fn prepare_orig_dll() -> Result<PathBuf, String> {
 pub fn init() -> Result<(), String> {
     unsafe {
         let dll_path = prepare_orig_dll()?;
-        trace::append_runtime_log(&format!(
-            "[unity_proxy] init dll_path={}",
-            dll_path.display()
-        ));
+        trace::info(
+            "unity_proxy",
+            &format!("init dll_path={}", dll_path.display()),
+        );
 
         let dll_path_str = dll_path.to_str().ok_or("Invalid path")?;
         let dll_path_wide = U16CString::from_str(dll_path_str)
@@ -86,10 +90,10 @@ pub fn init() -> Result<(), String> {
 
         let handle = LoadLibraryW(PCWSTR(dll_path_wide.as_ptr()))
             .map_err(|e| format!("Failed to load UnityPlayer_orig.dll: {}", e))?;
-        trace::append_runtime_log(&format!(
-            "[unity_proxy] LoadLibraryW success handle=0x{:X}",
-            handle.0 as usize
-        ));
+        trace::info(
+            "unity_proxy",
+            &format!("LoadLibraryW success handle=0x{:X}", handle.0 as usize),
+        );
 
         // 获取 UnityMain 地址
         let unity_main = GetProcAddress(handle, windows::core::s!("UnityMain"))
@@ -101,10 +105,10 @@ pub fn init() -> Result<(), String> {
             "UnityPlayer proxy initialized, UnityMain at 0x{:X}",
             UnityMain_orig
         );
-        trace::append_runtime_log(&format!(
-            "[unity_proxy] UnityMain resolved at 0x{:X}",
-            UnityMain_orig
-        ));
+        trace::info(
+            "unity_proxy",
+            &format!("UnityMain resolved at 0x{:X}", UnityMain_orig),
+        );
 
         Ok(())
     }
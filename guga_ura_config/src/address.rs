@@ -0,0 +1,157 @@
+//! 地址解析
+//!
+//! Receiver 监听地址与 Relay/Notifier 目标地址的规范化规则。配置校验、
+//! 配置工具与 Receiver 运行时共用这里的实现，保证“能保存”与“能运行”一致。
+
+use std::net::IpAddr;
+use url::Url;
+
+/// 规范化监听地址为 `host:port`
+///
+/// 支持 `host:port` 与 `http(s)://host:port[/path]` 两种写法；
+/// host 仅允许 localhost 或 IP，端口不能为 0。
+pub fn normalize_listen_addr(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Some(normalized) = normalize_listen_addr_from_url(value) {
+        return Some(normalized);
+    }
+
+    let (host, port) = value.rsplit_once(':')?;
+    let host = host.trim();
+    let port = port.trim().parse::<u16>().ok()?;
+
+    if host.is_empty() || port == 0 || !is_valid_listen_host(host) {
+        return None;
+    }
+
+    Some(format!("{}:{}", host, port))
+}
+
+/// 拆分监听地址为 host 与端口
+pub fn parse_listen_addr(listen_addr: &str) -> Option<(String, u16)> {
+    let normalized = normalize_listen_addr(listen_addr)?;
+    let (host, port) = normalized.rsplit_once(':')?;
+    let port = port.parse::<u16>().ok()?;
+    Some((host.to_string(), port))
+}
+
+/// 是否为 http:// 或 https:// 开头的地址
+pub fn has_http_scheme(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+/// 规范化目标基地址：去掉 query/fragment，路径统一以 `/` 结尾
+pub fn normalize_target_base_url(value: &str) -> Option<Url> {
+    let mut url = Url::parse(value.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+
+    url.set_query(None);
+    url.set_fragment(None);
+
+    let path = if url.path().is_empty() {
+        "/".to_string()
+    } else {
+        format!("{}/", url.path().trim_end_matches('/'))
+    };
+    url.set_path(&path);
+    Some(url)
+}
+
+/// 目标地址是否指向监听地址自身（localhost 与 127.0.0.1 视为相同）
+pub fn relay_target_would_loop(listen_addr: &str, relay_target_host: &str) -> bool {
+    let Some(self_origin) = normalized_origin_from_listen_addr(listen_addr) else {
+        return false;
+    };
+    let Some(target_origin) = normalized_origin_from_target(relay_target_host) else {
+        return false;
+    };
+    self_origin == target_origin
+}
+
+fn normalize_listen_addr_from_url(value: &str) -> Option<String> {
+    let url = Url::parse(value).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host = url.host_str()?;
+    let port = url.port()?;
+    if !is_valid_listen_host(host) {
+        return None;
+    }
+
+    Some(format!("{}:{}", host, port))
+}
+
+fn is_valid_listen_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok()
+}
+
+fn normalized_origin_from_target(target_host: &str) -> Option<String> {
+    let base_url = normalize_target_base_url(target_host)?;
+    normalized_origin_from_url(&base_url)
+}
+
+fn normalized_origin_from_listen_addr(listen_addr: &str) -> Option<String> {
+    let (host, port) = parse_listen_addr(listen_addr)?;
+    let url = Url::parse(&format!("http://{}:{}/", host, port)).ok()?;
+    normalized_origin_from_url(&url)
+}
+
+fn normalized_origin_from_url(url: &Url) -> Option<String> {
+    let scheme = url.scheme().to_ascii_lowercase();
+    let host = canonical_host(url.host_str()?);
+    let port = url.port_or_known_default()?;
+    Some(format!("{}://{}:{}", scheme, host, port))
+}
+
+fn canonical_host(host: &str) -> String {
+    if host.eq_ignore_ascii_case("localhost") {
+        "127.0.0.1".to_string()
+    } else {
+        host.to_ascii_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_listen_addr_should_accept_host_port_and_url() {
+        assert_eq!(
+            normalize_listen_addr(" 127.0.0.1:4700 ").as_deref(),
+            Some("127.0.0.1:4700")
+        );
+        assert_eq!(
+            normalize_listen_addr("http://localhost:4700/runtime").as_deref(),
+            Some("localhost:4700")
+        );
+        assert_eq!(normalize_listen_addr("example.com:4700"), None);
+        assert_eq!(normalize_listen_addr("127.0.0.1:0"), None);
+        assert_eq!(normalize_listen_addr("ftp://127.0.0.1:4700"), None);
+    }
+
+    #[test]
+    fn relay_target_would_loop_should_treat_localhost_as_loopback() {
+        assert!(relay_target_would_loop(
+            "127.0.0.1:4693",
+            "http://localhost:4693/base"
+        ));
+        assert!(!relay_target_would_loop(
+            "127.0.0.1:4693",
+            "http://127.0.0.1:4800"
+        ));
+        assert!(!relay_target_would_loop(
+            "127.0.0.1:4693",
+            "https://127.0.0.1:4693"
+        ));
+    }
+}
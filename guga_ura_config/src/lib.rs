@@ -0,0 +1,625 @@
+//! GugaURA 共享配置
+//!
+//! DLL、配置工具与独立 Receiver 共用同一份配置结构、默认值与读写逻辑。
+//! 本 crate 保持平台无关，只处理“给定路径”的读写；配置文件所在目录
+//! （游戏目录、EXE 目录等）由调用方决定。捕获、压缩、签名与健康上报在
+//! `guga_ura_transport` 中。
+
+pub mod address;
+pub mod migration;
+pub mod observer;
+pub mod profiles;
+pub mod reload;
+pub mod time;
+pub mod validation;
+
+pub use validation::{ConfigIssue, IssueSeverity, ValidationReport};
+
+use guga_ura_transport::capture_rules::CaptureRules;
+use migration::{MigrationReport, CURRENT_CONFIG_VERSION};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 配置文件名
+pub const CONFIG_FILE_NAME: &str = "guga_ura_config.json";
+
+/// [`Config::load_or_create_file`] 的加载结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLoadOutcome {
+    /// 从已有文件读取
+    Loaded,
+    /// 从旧版本文件读取并完成迁移
+    Migrated(ConfigMigration),
+    /// 文件不存在，已写入默认配置
+    CreatedDefault,
+    /// 文件不存在，写入默认配置失败
+    CreateFailed(String),
+    /// 文件存在但读取/解析失败，使用默认值且不覆盖原文件
+    InvalidKeptFile(String),
+}
+
+/// 加载时执行的版本迁移
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigMigration {
+    pub report: MigrationReport,
+    /// 原文件备份路径（备份失败时为 None，此时不会改写原文件）
+    pub backup_path: Option<PathBuf>,
+    /// 备份或改写失败的原因；失败时本次仍使用迁移后的配置，下次加载会重试
+    pub rewrite_error: Option<String>,
+}
+
+/// 配置结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// 配置结构版本，加载时据此执行迁移
+    #[serde(default = "Config::default_config_version")]
+    pub config_version: u32,
+
+    /// Notifier服务地址
+    #[serde(default = "Config::default_notifier_host")]
+    pub notifier_host: String,
+
+    /// Receiver监听地址（接收端使用）
+    #[serde(default = "Config::default_receiver_listen_addr")]
+    pub receiver_listen_addr: String,
+
+    /// HTTP超时时间(毫秒)
+    #[serde(default = "Config::default_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// DLL 发送时的压缩方式：none / gzip / zstd（为空时不压缩，见 [`guga_ura_transport::transport`]）
+    #[serde(default)]
+    pub transport_compression: Option<String>,
+
+    /// DLL、Receiver 与 Relay 共用的签名密钥（为空时不签名也不校验，见 [`guga_ura_transport::signing`]）
+    #[serde(default)]
+    pub shared_secret: Option<String>,
+
+    /// DLL 状态心跳开关（为空时仅在 `notifier_host` 指向本机 Receiver 时上报）
+    #[serde(default)]
+    pub status_heartbeat: Option<bool>,
+
+    /// 目标帧数 (-1 表示使用游戏默认, 30/60/120/240 等)
+    #[serde(default = "Config::default_target_fps")]
+    pub target_fps: i32,
+
+    /// VSync 设置 (-1 表示使用游戏默认, 0=关闭, 1=开启)
+    #[serde(default = "Config::default_vsync_count")]
+    pub vsync_count: i32,
+
+    /// DLL 发送前的捕获规则（方向、大小、顶层键、采样，见 [`guga_ura_transport::capture_rules`]）
+    #[serde(default)]
+    pub capture_rules: CaptureRules,
+
+    /// Debug 模式：将拦截的 msgpack 数据转为 JSON 保存到本地
+    #[serde(default)]
+    pub debug_mode: bool,
+
+    /// Debug 输出目录（绝对路径，优先使用）
+    #[serde(default)]
+    pub debug_output_dir: Option<String>,
+
+    /// Debug 模式下同时保留原始 `.msgpack`；无法解码的 payload 总是保留原始数据
+    #[serde(default)]
+    pub debug_keep_raw: bool,
+
+    /// 社团Fans 输出目录（绝对路径优先，接收端使用）
+    #[serde(default)]
+    pub fans_output_dir: Option<String>,
+
+    /// Fans 数据保存开关（接收端聚合）
+    #[serde(default = "Config::default_fans_enabled")]
+    pub fans_enabled: bool,
+
+    /// Relay 开关（接收端二次转发）
+    #[serde(default = "Config::default_relay_enabled")]
+    pub relay_enabled: bool,
+
+    /// Relay 目标地址（接收端二次转发）
+    #[serde(default)]
+    pub relay_target_host: Option<String>,
+
+    /// 种马/玩家数据输出开关（接收端处理）
+    #[serde(default = "Config::default_stallion_output_enabled")]
+    pub stallion_output_enabled: bool,
+
+    /// 种马/玩家数据输出目录（绝对路径优先，接收端使用）
+    #[serde(default)]
+    pub stallion_output_dir: Option<String>,
+
+    /// 种马/玩家数据输出的 schema 版本（为空时使用最新版本，接收端使用）
+    #[serde(default)]
+    pub stallion_output_schema_version: Option<u32>,
+
+    /// 输出时间格式：rfc3339 / unix_ms / local（为空时 rfc3339，接收端使用）
+    #[serde(default)]
+    pub time_output_format: Option<String>,
+
+    /// 输出时区：game / local / utc / ±HH:MM（为空时按各输出的默认时区，接收端使用）
+    #[serde(default)]
+    pub time_output_timezone: Option<String>,
+
+    /// 当前激活的配置方案（为空时使用主配置本身，见 [`profiles`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+impl Config {
+    fn default_config_version() -> u32 {
+        CURRENT_CONFIG_VERSION
+    }
+
+    fn default_notifier_host() -> String {
+        "http://127.0.0.1:4693".to_string()
+    }
+
+    fn default_receiver_listen_addr() -> String {
+        "127.0.0.1:4693".to_string()
+    }
+
+    fn default_timeout_ms() -> u64 {
+        100
+    }
+
+    fn default_target_fps() -> i32 {
+        -1 // -1 表示使用游戏默认
+    }
+
+    fn default_vsync_count() -> i32 {
+        -1 // -1 表示使用游戏默认
+    }
+
+    fn default_fans_enabled() -> bool {
+        true
+    }
+
+    fn default_relay_enabled() -> bool {
+        false
+    }
+
+    fn default_stallion_output_enabled() -> bool {
+        true
+    }
+
+    /// DLL 是否上报状态心跳：显式配置优先，未配置时仅在 `notifier_host`
+    /// 指向 `receiver_listen_addr`（即本工具的 Receiver）时上报
+    pub fn status_heartbeat_enabled(&self) -> bool {
+        self.status_heartbeat.unwrap_or_else(|| {
+            address::relay_target_would_loop(&self.receiver_listen_addr, &self.notifier_host)
+        })
+    }
+
+    /// 获取配置文件路径（相对于游戏目录）
+    pub fn config_path(game_dir: &Path) -> PathBuf {
+        game_dir.join(CONFIG_FILE_NAME)
+    }
+
+    /// 获取配置文件路径（相对于当前进程 EXE 目录）
+    ///
+    /// DLL 运行在游戏进程内时即为游戏目录；配置工具与 Receiver 中为各自 EXE 目录。
+    pub fn exe_config_path() -> PathBuf {
+        if let Ok(mut exe_path) = std::env::current_exe() {
+            exe_path.pop();
+            return exe_path.join(CONFIG_FILE_NAME);
+        }
+        PathBuf::from(CONFIG_FILE_NAME)
+    }
+
+    /// 从指定文件加载配置，读取或解析失败时返回错误
+    pub fn load_file(path: &Path) -> Result<Config, String> {
+        Self::load_file_with_migration(path).map(|(config, _)| config)
+    }
+
+    /// 从指定文件加载配置；旧版本文件会先备份原文件，再以迁移后的文档改写
+    pub fn load_file_with_migration(
+        path: &Path,
+    ) -> Result<(Config, Option<ConfigMigration>), String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("read {} failed: {}", path.display(), e))?;
+        let mut document = parse_config_document(&content)
+            .map_err(|e| format!("parse {} failed: {}", path.display(), e))?;
+
+        let migration = migration::migrate_document(&mut document).map(|report| {
+            let mut applied = ConfigMigration {
+                report,
+                backup_path: None,
+                rewrite_error: None,
+            };
+            match backup_original(path, &content, applied.report.from_version) {
+                Ok(backup_path) => {
+                    applied.backup_path = Some(backup_path);
+                    if let Err(e) = write_document(path, &document) {
+                        applied.rewrite_error = Some(e);
+                    }
+                }
+                Err(e) => applied.rewrite_error = Some(e),
+            }
+            applied
+        });
+
+        let config = serde_json::from_value(document)
+            .map_err(|e| format!("parse {} failed: {}", path.display(), e))?;
+        Ok((config, migration))
+    }
+
+    /// 从指定文件加载生效配置：应用激活的方案，失败时回落默认值（不写文件）
+    pub fn load_file_or_default(path: &Path) -> Config {
+        match Self::load_file(path) {
+            Ok(config) => profiles::apply_active_profile(path, config).0,
+            Err(_) => Config::default(),
+        }
+    }
+
+    /// 从指定文件加载配置；文件不存在时写入默认配置，文件损坏时保留原文件
+    pub fn load_or_create_file(path: &Path) -> (Config, ConfigLoadOutcome) {
+        match Self::load_file_with_migration(path) {
+            Ok((config, None)) => (config, ConfigLoadOutcome::Loaded),
+            Ok((config, Some(migration))) => (config, ConfigLoadOutcome::Migrated(migration)),
+            Err(e) if path.exists() => (Config::default(), ConfigLoadOutcome::InvalidKeptFile(e)),
+            Err(_) => {
+                let config = Config::default();
+                let outcome = match config.save_file(path) {
+                    Ok(()) => ConfigLoadOutcome::CreatedDefault,
+                    Err(e) => ConfigLoadOutcome::CreateFailed(e),
+                };
+                (config, outcome)
+            }
+        }
+    }
+
+    /// 从游戏目录加载配置
+    pub fn load_from(game_dir: &Path) -> Config {
+        Self::load_file_or_default(&Self::config_path(game_dir))
+    }
+
+    /// 从游戏目录加载配置，并以 EXE 侧配置回填 Receiver 使用的字段
+    pub fn load_from_with_exe_side(game_dir: &Path, exe_config: &Config) -> Config {
+        let has_fans_enabled = Self::game_config_has_key(game_dir, "fans_enabled");
+        let mut config = Self::load_from(game_dir);
+        migration::backfill_exe_side_fields(&mut config, exe_config, has_fans_enabled);
+        config
+    }
+
+    /// 判断游戏目录配置文件中是否显式包含某个顶层字段
+    pub fn game_config_has_key(game_dir: &Path, key: &str) -> bool {
+        let path = Self::config_path(game_dir);
+        json_file_has_key(&path, key)
+    }
+
+    /// 判断生效配置文件（激活方案时为方案文件）中是否显式包含某个顶层字段
+    pub fn effective_file_has_key(path: &Path, key: &str) -> bool {
+        let profile_path = profiles::read_active_profile(path)
+            .and_then(|name| profiles::profile_path(path, &name).ok())
+            .filter(|profile_path| profile_path.exists());
+        match profile_path {
+            Some(profile_path) => json_file_has_key(&profile_path, key),
+            None => json_file_has_key(path, key),
+        }
+    }
+
+    /// 从当前进程 EXE 目录加载配置
+    pub fn load_from_exe_dir() -> Config {
+        Self::load_file_or_default(&Self::exe_config_path())
+    }
+
+    /// 保存配置到指定文件
+    pub fn save_file(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("序列化错误: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("写入错误: {}", e))?;
+        Ok(())
+    }
+
+    /// 保存生效配置：激活了方案时写入方案文件并保持主配置的选择，否则写入主配置
+    pub fn save_active_file(&self, path: &Path) -> Result<(), String> {
+        match self.active_profile.as_deref() {
+            Some(name) => {
+                profiles::save_profile(path, name, self)?;
+                profiles::set_active_profile(path, Some(name))
+            }
+            None => self.save_file(path),
+        }
+    }
+
+    /// 保存配置到游戏目录
+    pub fn save_to(&self, game_dir: &Path) -> Result<(), String> {
+        self.save_active_file(&Self::config_path(game_dir))
+    }
+
+    /// 保存配置到当前进程 EXE 目录
+    pub fn save_to_exe_dir(&self) -> Result<(), String> {
+        self.save_active_file(&Self::exe_config_path())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            config_version: Self::default_config_version(),
+            notifier_host: Self::default_notifier_host(),
+            receiver_listen_addr: Self::default_receiver_listen_addr(),
+            timeout_ms: Self::default_timeout_ms(),
+            transport_compression: None,
+            shared_secret: None,
+            status_heartbeat: None,
+            target_fps: Self::default_target_fps(),
+            vsync_count: Self::default_vsync_count(),
+            capture_rules: CaptureRules::default(),
+            debug_mode: false,
+            debug_output_dir: None,
+            debug_keep_raw: false,
+            fans_output_dir: None,
+            fans_enabled: Self::default_fans_enabled(),
+            relay_enabled: Self::default_relay_enabled(),
+            relay_target_host: None,
+            stallion_output_enabled: Self::default_stallion_output_enabled(),
+            stallion_output_dir: None,
+            stallion_output_schema_version: None,
+            time_output_format: None,
+            time_output_timezone: None,
+            active_profile: None,
+        }
+    }
+}
+
+/// 解析配置 JSON，兼容带 BOM 的文件；旧版本文档会在内存中迁移到当前版本
+pub fn parse_config_json(content: &str) -> Result<Config, serde_json::Error> {
+    let mut document = parse_config_document(content)?;
+    migration::migrate_document(&mut document);
+    serde_json::from_value(document)
+}
+
+fn parse_config_document(content: &str) -> Result<Value, serde_json::Error> {
+    serde_json::from_str(content).or_else(|_| {
+        let trimmed = content.trim_start_matches('\u{feff}');
+        serde_json::from_str(trimmed)
+    })
+}
+
+/// 迁移前备份原文件：`guga_ura_config.json.v{版本}.bak`，已存在时附加时间戳
+fn backup_original(path: &Path, content: &str, from_version: u32) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| CONFIG_FILE_NAME.to_string());
+    let mut backup_path = path.with_file_name(format!("{}.v{}.bak", file_name, from_version));
+    if backup_path.exists() {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        backup_path =
+            path.with_file_name(format!("{}.v{}.{}.bak", file_name, from_version, now_ms));
+    }
+
+    fs::write(&backup_path, content)
+        .map_err(|e| format!("备份 {} 失败: {}", backup_path.display(), e))?;
+    Ok(backup_path)
+}
+
+fn write_document(path: &Path, document: &Value) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(document).map_err(|e| format!("序列化错误: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("写入错误: {}", e))
+}
+
+fn json_file_has_key(path: &Path, key: &str) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    let trimmed = content.trim_start_matches('\u{feff}');
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) else {
+        return false;
+    };
+    value
+        .as_object()
+        .map(|obj| obj.contains_key(key))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_config_json, Config, ConfigLoadOutcome, CURRENT_CONFIG_VERSION};
+    use std::fs;
+
+    #[test]
+    fn default_values_should_match_current_behavior() {
+        let config = Config::default();
+
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4693");
+        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
+        assert_eq!(config.timeout_ms, 100);
+        assert_eq!(config.target_fps, -1);
+        assert_eq!(config.vsync_count, -1);
+        assert!(!config.debug_mode);
+        assert_eq!(config.debug_output_dir, None);
+        assert!(!config.debug_keep_raw);
+        assert_eq!(config.fans_output_dir, None);
+        assert!(config.fans_enabled);
+        assert!(!config.relay_enabled);
+        assert_eq!(config.relay_target_host, None);
+        assert_eq!(config.status_heartbeat, None);
+    }
+
+    #[test]
+    fn status_heartbeat_should_default_to_local_receiver_only() {
+        let local = Config::default();
+        let third_party = Config {
+            notifier_host: "http://127.0.0.1:4800".to_string(),
+            ..Config::default()
+        };
+        let forced = Config {
+            status_heartbeat: Some(true),
+            ..third_party.clone()
+        };
+        let disabled = Config {
+            status_heartbeat: Some(false),
+            ..Config::default()
+        };
+
+        assert!(local.status_heartbeat_enabled());
+        assert!(!third_party.status_heartbeat_enabled());
+        assert!(forced.status_heartbeat_enabled());
+        assert!(!disabled.status_heartbeat_enabled());
+    }
+
+    #[test]
+    fn parse_config_json_should_support_bom() {
+        let content = "\u{feff}{\"timeout_ms\":250,\"fans_enabled\":false}";
+
+        let config = parse_config_json(content).expect("BOM 配置解析失败");
+
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4693");
+        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
+        assert_eq!(config.timeout_ms, 250);
+        assert!(!config.fans_enabled);
+        assert!(!config.relay_enabled);
+        assert_eq!(config.relay_target_host, None);
+    }
+
+    #[test]
+    fn legacy_config_should_remain_compatible() {
+        let content = r#"{"notifier_host":"http://127.0.0.1:4800","timeout_ms":180}"#;
+
+        let config = parse_config_json(content).expect("旧配置解析失败");
+
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4800");
+        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
+        assert_eq!(config.timeout_ms, 180);
+        assert!(!config.relay_enabled);
+        assert_eq!(config.relay_target_host, None);
+    }
+
+    #[test]
+    fn new_fields_should_round_trip() {
+        let config = Config {
+            receiver_listen_addr: "127.0.0.1:4700".to_string(),
+            relay_enabled: true,
+            relay_target_host: Some("http://127.0.0.1:4800".to_string()),
+            fans_enabled: false,
+            ..Config::default()
+        };
+
+        let json = serde_json::to_string(&config).expect("序列化配置失败");
+        let reparsed = parse_config_json(&json).expect("round-trip 解析失败");
+
+        assert_eq!(reparsed.receiver_listen_addr, "127.0.0.1:4700");
+        assert!(reparsed.relay_enabled);
+        assert_eq!(
+            reparsed.relay_target_host.as_deref(),
+            Some("http://127.0.0.1:4800")
+        );
+        assert!(!reparsed.fans_enabled);
+    }
+
+    #[test]
+    fn load_or_create_file_should_write_default_when_missing() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = Config::config_path(dir.path());
+
+        let (config, outcome) = Config::load_or_create_file(&path);
+
+        assert_eq!(outcome, ConfigLoadOutcome::CreatedDefault);
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4693");
+        let written = Config::load_file(&path).expect("读取默认配置失败");
+        assert_eq!(written.timeout_ms, config.timeout_ms);
+    }
+
+    #[test]
+    fn load_or_create_file_should_keep_invalid_file() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = Config::config_path(dir.path());
+        fs::write(&path, "{ broken").expect("写入损坏配置失败");
+
+        let (config, outcome) = Config::load_or_create_file(&path);
+
+        assert!(matches!(outcome, ConfigLoadOutcome::InvalidKeptFile(_)));
+        assert_eq!(config.timeout_ms, 100);
+        assert_eq!(fs::read_to_string(&path).expect("读取配置失败"), "{ broken");
+    }
+
+    #[test]
+    fn load_from_should_not_create_file_for_tool_side_reads() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+
+        let config = Config::load_from(dir.path());
+
+        assert_eq!(config.timeout_ms, 100);
+        assert!(!Config::config_path(dir.path()).exists());
+    }
+
+    #[test]
+    fn save_to_and_game_config_has_key_should_round_trip() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let config = Config {
+            target_fps: 120,
+            ..Config::default()
+        };
+
+        config.save_to(dir.path()).expect("保存配置失败");
+
+        assert_eq!(Config::load_from(dir.path()).target_fps, 120);
+        assert!(Config::game_config_has_key(dir.path(), "fans_enabled"));
+        assert!(!Config::game_config_has_key(dir.path(), "missing_key"));
+    }
+
+    #[test]
+    fn load_file_should_backup_and_rewrite_legacy_config() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = Config::config_path(dir.path());
+        let legacy = r#"{"notifier_host":"http://127.0.0.1:4800","fans_output_dir":""}"#;
+        fs::write(&path, legacy).expect("写入旧配置失败");
+
+        let (config, migration) = Config::load_file_with_migration(&path).expect("加载旧配置失败");
+
+        let migration = migration.expect("应执行迁移");
+        assert_eq!(migration.report.from_version, 0);
+        assert_eq!(migration.rewrite_error, None);
+        let backup_path = migration.backup_path.expect("应生成备份");
+        assert_eq!(backup_path, dir.path().join("guga_ura_config.json.v0.bak"));
+        assert_eq!(
+            fs::read_to_string(&backup_path).expect("读取备份失败"),
+            legacy
+        );
+
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4800");
+        assert_eq!(config.fans_output_dir, None);
+        let rewritten: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("读取改写后配置失败"))
+                .expect("解析改写后配置失败");
+        assert_eq!(rewritten["config_version"], CURRENT_CONFIG_VERSION);
+        assert!(rewritten.get("fans_output_dir").is_none());
+        assert!(
+            rewritten.get("fans_enabled").is_none(),
+            "迁移不应补全缺省字段"
+        );
+
+        let (_, second) = Config::load_file_with_migration(&path).expect("再次加载失败");
+        assert_eq!(second, None);
+    }
+
+    #[test]
+    fn load_file_should_keep_existing_backup() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = Config::config_path(dir.path());
+        let first_backup = dir.path().join("guga_ura_config.json.v0.bak");
+        fs::write(&first_backup, "older").expect("写入已有备份失败");
+        fs::write(&path, r#"{"timeout_ms":180}"#).expect("写入旧配置失败");
+
+        let (_, migration) = Config::load_file_with_migration(&path).expect("加载旧配置失败");
+
+        let backup_path = migration.and_then(|m| m.backup_path).expect("应生成备份");
+        assert_ne!(backup_path, first_backup);
+        assert_eq!(
+            fs::read_to_string(&first_backup).expect("读取备份失败"),
+            "older"
+        );
+    }
+
+    #[test]
+    fn default_config_should_carry_current_version() {
+        let json = serde_json::to_value(Config::default()).expect("序列化配置失败");
+
+        assert_eq!(json["config_version"], CURRENT_CONFIG_VERSION);
+    }
+}
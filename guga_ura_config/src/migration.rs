@@ -0,0 +1,313 @@
+//! 配置版本迁移
+//!
+//! 配置文件通过顶层 `config_version` 标记结构版本。加载时若版本低于
+//! [`CURRENT_CONFIG_VERSION`]，按顺序执行迁移步骤，把旧文档升级到当前结构。
+//! 版本迁移只作用于 JSON 文档本身：未出现的字段保持缺省，由 serde 默认值继续处理。
+//!
+//! 游戏目录配置另有一步 EXE 侧回填（[`backfill_exe_side_fields`]）：Receiver 使用的
+//! 字段以 EXE 同级配置为准。它依赖第二份配置，不改写文件，在每次加载时执行。
+//!
+//! 新增迁移时：提升 `CURRENT_CONFIG_VERSION`，在 `MIGRATIONS` 末尾追加一步，
+//! 并在测试中补充对应历史结构的样例。
+
+use crate::Config;
+use serde_json::{Map, Value};
+
+/// 当前配置结构版本
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// 配置版本字段名
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// 单个迁移步骤：把 `from` 版本的文档升级到 `from + 1`
+struct MigrationStep {
+    from: u32,
+    apply: fn(&mut Map<String, Value>),
+}
+
+const MIGRATIONS: &[MigrationStep] = &[MigrationStep {
+    from: 0,
+    apply: migrate_v0_to_v1,
+}];
+
+/// 一次迁移的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// 读取文档中的配置版本，缺失时视为 0（引入版本号之前的配置）
+pub fn document_version(document: &Value) -> u32 {
+    document
+        .get(CONFIG_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// 将文档就地升级到当前版本
+///
+/// 已是当前版本、来自更新版本或根节点不是对象时不做修改并返回 None。
+pub fn migrate_document(document: &mut Value) -> Option<MigrationReport> {
+    let from_version = document_version(document);
+    if from_version >= CURRENT_CONFIG_VERSION {
+        return None;
+    }
+
+    let map = document.as_object_mut()?;
+    let mut version = from_version;
+    for step in MIGRATIONS.iter().filter(|step| step.from >= from_version) {
+        debug_assert_eq!(step.from, version, "迁移步骤必须连续");
+        (step.apply)(map);
+        version = step.from + 1;
+    }
+    map.insert(CONFIG_VERSION_KEY.to_string(), Value::from(version));
+
+    Some(MigrationReport {
+        from_version,
+        to_version: version,
+    })
+}
+
+/// v0 -> v1：引入 `config_version`
+///
+/// v0 时期配置工具会把留空的可选地址/目录保存为空字符串，各处读取时再按
+/// `trim().is_empty()` 视为未设置；这里统一移除这些空值，使其回到缺省语义。
+fn migrate_v0_to_v1(map: &mut Map<String, Value>) {
+    const OPTIONAL_TEXT_FIELDS: &[&str] = &[
+        "debug_output_dir",
+        "fans_output_dir",
+        "relay_target_host",
+        "stallion_output_dir",
+    ];
+
+    for key in OPTIONAL_TEXT_FIELDS {
+        let is_blank = map
+            .get(*key)
+            .and_then(Value::as_str)
+            .is_some_and(|s| s.trim().is_empty());
+        if is_blank {
+            map.remove(*key);
+        }
+    }
+}
+
+/// EXE 侧回填：以 EXE 同级配置补齐游戏目录配置中由 Receiver 使用的字段
+///
+/// `fans_enabled` 仅在游戏目录配置未显式写出时回填；`fans_output_dir` 缺省时回填
+/// （空字符串已由 v0 -> v1 移除）；Stallion 输出与时间格式字段始终取 EXE 侧的值。
+pub fn backfill_exe_side_fields(config: &mut Config, exe_config: &Config, has_fans_enabled: bool) {
+    if !has_fans_enabled {
+        config.fans_enabled = exe_config.fans_enabled;
+    }
+    if config.fans_output_dir.is_none() {
+        config.fans_output_dir = exe_config.fans_output_dir.clone();
+    }
+
+    config.stallion_output_enabled = exe_config.stallion_output_enabled;
+    config.stallion_output_dir = exe_config.stallion_output_dir.clone();
+    config.time_output_format = exe_config.time_output_format.clone();
+    config.time_output_timezone = exe_config.time_output_timezone.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn migrate_to_config(mut document: Value) -> (Config, Option<MigrationReport>) {
+        let report = migrate_document(&mut document);
+        assert_eq!(
+            document[CONFIG_VERSION_KEY], CURRENT_CONFIG_VERSION,
+            "迁移后应写入当前版本"
+        );
+        let config = serde_json::from_value(document).expect("迁移后解析失败");
+        (config, report)
+    }
+
+    fn v0_report() -> Option<MigrationReport> {
+        Some(MigrationReport {
+            from_version: 0,
+            to_version: CURRENT_CONFIG_VERSION,
+        })
+    }
+
+    #[test]
+    fn v0_notifier_only_shape_should_migrate() {
+        let (config, report) =
+            migrate_to_config(json!({"notifier_host": "http://127.0.0.1:4800", "timeout_ms": 180}));
+
+        assert_eq!(report, v0_report());
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4800");
+        assert_eq!(config.timeout_ms, 180);
+        assert_eq!(config.receiver_listen_addr, "127.0.0.1:4693");
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+    }
+
+    #[test]
+    fn v0_fps_and_debug_shape_should_migrate() {
+        let (config, report) = migrate_to_config(json!({
+            "notifier_host": "http://127.0.0.1:4693",
+            "timeout_ms": 100,
+            "target_fps": 120,
+            "vsync_count": 0,
+            "debug_mode": true,
+            "debug_output_dir": ""
+        }));
+
+        assert_eq!(report, v0_report());
+        assert_eq!(config.target_fps, 120);
+        assert_eq!(config.vsync_count, 0);
+        assert!(config.debug_mode);
+        assert_eq!(config.debug_output_dir, None);
+    }
+
+    #[test]
+    fn v0_fans_shape_should_drop_blank_output_dir() {
+        let (config, _) = migrate_to_config(json!({
+            "notifier_host": "http://127.0.0.1:4693",
+            "fans_enabled": false,
+            "fans_output_dir": "  "
+        }));
+
+        assert!(!config.fans_enabled);
+        assert_eq!(config.fans_output_dir, None);
+    }
+
+    #[test]
+    fn v0_relay_shape_should_keep_target_and_drop_blank_target() {
+        let (enabled, _) = migrate_to_config(json!({
+            "receiver_listen_addr": "127.0.0.1:4700",
+            "notifier_host": "http://127.0.0.1:4700",
+            "relay_enabled": true,
+            "relay_target_host": "http://127.0.0.1:4800"
+        }));
+        let (blank, _) = migrate_to_config(json!({
+            "relay_enabled": false,
+            "relay_target_host": ""
+        }));
+
+        assert_eq!(enabled.receiver_listen_addr, "127.0.0.1:4700");
+        assert!(enabled.relay_enabled);
+        assert_eq!(
+            enabled.relay_target_host.as_deref(),
+            Some("http://127.0.0.1:4800")
+        );
+        assert_eq!(blank.relay_target_host, None);
+    }
+
+    #[test]
+    fn v0_stallion_shape_should_migrate() {
+        let (config, report) = migrate_to_config(json!({
+            "stallion_output_enabled": false,
+            "stallion_output_dir": "D:\\stallion",
+            "fans_enabled": true
+        }));
+
+        assert_eq!(report, v0_report());
+        assert!(!config.stallion_output_enabled);
+        assert_eq!(config.stallion_output_dir.as_deref(), Some("D:\\stallion"));
+    }
+
+    #[test]
+    fn v0_migration_should_not_add_missing_fields() {
+        let mut document = json!({"notifier_host": "http://127.0.0.1:4693"});
+
+        migrate_document(&mut document);
+
+        let keys: Vec<&String> = document.as_object().expect("应为对象").keys().collect();
+        assert_eq!(keys.len(), 2);
+        assert!(document.get("fans_enabled").is_none());
+    }
+
+    #[test]
+    fn current_and_future_versions_should_be_left_alone() {
+        let mut current = json!({"config_version": CURRENT_CONFIG_VERSION, "fans_output_dir": ""});
+        let mut future = json!({"config_version": CURRENT_CONFIG_VERSION + 1});
+
+        assert_eq!(migrate_document(&mut current), None);
+        assert_eq!(migrate_document(&mut future), None);
+        assert_eq!(current["fans_output_dir"], "");
+        assert_eq!(future[CONFIG_VERSION_KEY], CURRENT_CONFIG_VERSION + 1);
+    }
+
+    #[test]
+    fn migrations_should_cover_every_version() {
+        let froms: Vec<u32> = MIGRATIONS.iter().map(|step| step.from).collect();
+        let expected: Vec<u32> = (0..CURRENT_CONFIG_VERSION).collect();
+
+        assert_eq!(froms, expected);
+    }
+
+    #[test]
+    fn backfill_exe_side_fields_should_preserve_exe_stallion_runtime_settings() {
+        let mut game_config = Config {
+            fans_enabled: true,
+            fans_output_dir: Some("C:\\game\\fans".to_string()),
+            stallion_output_enabled: true,
+            stallion_output_dir: Some("C:\\game\\stallion".to_string()),
+            ..Config::default()
+        };
+        let exe_config = Config {
+            fans_enabled: false,
+            fans_output_dir: Some("C:\\exe\\fans".to_string()),
+            stallion_output_enabled: false,
+            stallion_output_dir: Some("C:\\exe\\stallion".to_string()),
+            ..Config::default()
+        };
+
+        backfill_exe_side_fields(&mut game_config, &exe_config, true);
+
+        assert!(game_config.fans_enabled);
+        assert_eq!(
+            game_config.fans_output_dir.as_deref(),
+            Some("C:\\game\\fans")
+        );
+        assert!(!game_config.stallion_output_enabled);
+        assert_eq!(
+            game_config.stallion_output_dir.as_deref(),
+            Some("C:\\exe\\stallion")
+        );
+    }
+
+    #[test]
+    fn backfill_exe_side_fields_should_use_exe_stallion_defaults() {
+        let mut game_config = Config {
+            stallion_output_enabled: true,
+            stallion_output_dir: Some("C:\\game\\stallion".to_string()),
+            ..Config::default()
+        };
+        let exe_config = Config {
+            stallion_output_enabled: false,
+            stallion_output_dir: None,
+            ..Config::default()
+        };
+
+        backfill_exe_side_fields(&mut game_config, &exe_config, true);
+
+        assert!(!game_config.stallion_output_enabled);
+        assert_eq!(game_config.stallion_output_dir, None);
+    }
+
+    #[test]
+    fn load_from_with_exe_side_should_fill_fields_missing_from_v0_game_config() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        std::fs::write(
+            Config::config_path(dir.path()),
+            r#"{"notifier_host": "http://127.0.0.1:4693", "fans_output_dir": ""}"#,
+        )
+        .expect("写入配置失败");
+        let exe_config = Config {
+            fans_enabled: false,
+            fans_output_dir: Some("C:\\exe\\fans".to_string()),
+            ..Config::default()
+        };
+
+        let config = Config::load_from_with_exe_side(dir.path(), &exe_config);
+
+        assert!(!config.fans_enabled);
+        assert_eq!(config.fans_output_dir.as_deref(), Some("C:\\exe\\fans"));
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+    }
+}
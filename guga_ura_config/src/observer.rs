@@ -0,0 +1,219 @@
+//! 配置变更订阅
+//!
+//! 热重载时比较新旧配置，按字段把变更分发给订阅者。订阅者只声明关心的字段，
+//! 每次重载最多收到一次回调，回调中带有本次涉及的字段及其新旧值；新增运行时
+//! 设置只需新增一个订阅，无需改动重载流程。
+
+use crate::Config;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// 单个字段的变更（值为 JSON 表示，字段名与 JSON 键一致）
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// 分发给订阅者的一次变更
+#[derive(Debug)]
+pub struct ConfigChange<'a> {
+    pub old: &'a Config,
+    pub new: &'a Config,
+    /// 本次变更中属于该订阅者关心的字段
+    pub fields: Vec<&'a FieldChange>,
+}
+
+impl ConfigChange<'_> {
+    /// 指定字段是否在本次变更中
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields.iter().any(|change| change.field == field)
+    }
+}
+
+type ObserverCallback = Box<dyn Fn(&ConfigChange<'_>) + Send + Sync>;
+
+struct Subscription {
+    name: &'static str,
+    fields: &'static [&'static str],
+    callback: ObserverCallback,
+}
+
+/// 订阅者列表
+#[derive(Default)]
+pub struct ConfigObservers {
+    subscriptions: Vec<Subscription>,
+}
+
+/// 比较两份配置，返回按字段名排序的变更列表
+pub fn diff_configs(old: &Config, new: &Config) -> Vec<FieldChange> {
+    let old = to_object(old);
+    let new = to_object(new);
+    let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange {
+                field: field.clone(),
+                old: old_value,
+                new: new_value,
+            })
+        })
+        .collect()
+}
+
+impl ConfigObservers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅指定字段；`name` 仅用于日志
+    pub fn subscribe(
+        &mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        callback: impl Fn(&ConfigChange<'_>) + Send + Sync + 'static,
+    ) {
+        self.subscriptions.push(Subscription {
+            name,
+            fields,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// 分发一次变更，返回收到回调的订阅者名称
+    pub fn dispatch(&self, old: &Config, new: &Config) -> Vec<&'static str> {
+        let changes = diff_configs(old, new);
+        if changes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut notified = Vec::new();
+        for subscription in &self.subscriptions {
+            let fields: Vec<&FieldChange> = changes
+                .iter()
+                .filter(|change| subscription.fields.contains(&change.field.as_str()))
+                .collect();
+            if fields.is_empty() {
+                continue;
+            }
+
+            (subscription.callback)(&ConfigChange { old, new, fields });
+            notified.push(subscription.name);
+        }
+        notified
+    }
+}
+
+fn to_object(config: &Config) -> Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn diff_should_report_changed_fields_with_old_and_new_values() {
+        let old = Config::default();
+        let new = Config {
+            target_fps: 120,
+            fans_output_dir: Some("D:/fans".to_string()),
+            active_profile: Some("team".to_string()),
+            ..Config::default()
+        };
+
+        let changes = diff_configs(&old, &new);
+
+        let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["active_profile", "fans_output_dir", "target_fps"]
+        );
+        assert_eq!(changes[2].old, Value::from(-1));
+        assert_eq!(changes[2].new, Value::from(120));
+        assert_eq!(changes[0].old, Value::Null);
+        assert!(diff_configs(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn dispatch_should_notify_only_matching_subscribers_once() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let mut observers = ConfigObservers::new();
+
+        let sink = Arc::clone(&received);
+        observers.subscribe("fps", &["target_fps", "vsync_count"], move |change| {
+            let fields: Vec<String> = change.fields.iter().map(|f| f.field.clone()).collect();
+            sink.lock()
+                .expect("锁异常")
+                .push((change.new.target_fps, fields));
+        });
+        observers.subscribe("relay", &["relay_enabled"], |_| {
+            panic!("relay 字段未变更，不应收到回调");
+        });
+
+        let old = Config::default();
+        let new = Config {
+            target_fps: 60,
+            vsync_count: 0,
+            timeout_ms: 500,
+            ..Config::default()
+        };
+        let notified = observers.dispatch(&old, &new);
+
+        assert_eq!(notified, vec!["fps"]);
+        assert_eq!(
+            *received.lock().expect("锁异常"),
+            vec![(
+                60,
+                vec!["target_fps".to_string(), "vsync_count".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn dispatch_without_changes_should_not_call_subscribers() {
+        let mut observers = ConfigObservers::new();
+        observers.subscribe("any", &["target_fps"], |_| panic!("不应收到回调"));
+
+        assert!(observers
+            .dispatch(&Config::default(), &Config::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn change_contains_should_match_field_names() {
+        let mut observers = ConfigObservers::new();
+        let seen = Arc::new(Mutex::new(None));
+        let sink = Arc::clone(&seen);
+        observers.subscribe(
+            "debug",
+            &["debug_mode", "debug_output_dir"],
+            move |change| {
+                *sink.lock().expect("锁异常") = Some((
+                    change.contains("debug_mode"),
+                    change.contains("debug_output_dir"),
+                    change.old.debug_mode,
+                ));
+            },
+        );
+
+        observers.dispatch(
+            &Config::default(),
+            &Config {
+                debug_mode: true,
+                ..Config::default()
+            },
+        );
+
+        assert_eq!(*seen.lock().expect("锁异常"), Some((true, false, false)));
+    }
+}
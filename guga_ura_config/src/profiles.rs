@@ -0,0 +1,383 @@
+//! 配置方案（profile）
+//!
+//! 方案保存在配置文件同级的 `guga_ura_profiles/` 目录中，每个方案是一份完整配置
+//! （`<名称>.json`）。主配置的 `active_profile` 字段选择当前方案：设置后各读取方
+//! 使用方案内容，未设置时使用主配置本身。切换方案只改写主配置中的这一个字段，
+//! 因此监控主配置文件的进程（例如 DLL）可以直接热应用。
+
+use crate::{write_document, Config};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 方案目录名（与配置文件同级）
+pub const PROFILES_DIR_NAME: &str = "guga_ura_profiles";
+
+/// 主配置中的激活方案字段名
+pub const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
+const PROFILE_FILE_EXTENSION: &str = "json";
+const PROFILE_NAME_MAX_CHARS: usize = 64;
+
+/// [`apply_active_profile`] 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileResolution {
+    /// 未设置激活方案，使用主配置
+    NotActive,
+    /// 已应用激活方案
+    Applied(String),
+    /// 激活方案读取失败，回落到主配置
+    Fallback { name: String, error: String },
+}
+
+/// 方案目录路径
+pub fn profiles_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(|dir| dir.join(PROFILES_DIR_NAME))
+        .unwrap_or_else(|| PathBuf::from(PROFILES_DIR_NAME))
+}
+
+/// 规范化方案名：去掉首尾空白，禁止路径字符与过长名称
+pub fn normalize_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("方案名称不能为空".to_string());
+    }
+    if name.chars().count() > PROFILE_NAME_MAX_CHARS {
+        return Err(format!(
+            "方案名称不能超过 {} 个字符",
+            PROFILE_NAME_MAX_CHARS
+        ));
+    }
+    if name.starts_with('.')
+        || name.chars().any(|c| {
+            c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+        })
+    {
+        return Err(format!("方案名称包含不允许的字符: {}", name));
+    }
+    Ok(name.to_string())
+}
+
+/// 方案文件路径
+pub fn profile_path(config_path: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = normalize_profile_name(name)?;
+    Ok(profiles_dir(config_path).join(format!("{}.{}", name, PROFILE_FILE_EXTENSION)))
+}
+
+/// 方案是否存在
+pub fn profile_exists(config_path: &Path, name: &str) -> bool {
+    profile_path(config_path, name)
+        .map(|path| path.is_file())
+        .unwrap_or(false)
+}
+
+/// 列出全部方案名（按名称排序）
+pub fn list_profiles(config_path: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(profiles_dir(config_path)) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(PROFILE_FILE_EXTENSION))
+        })
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .filter(|name| normalize_profile_name(name).is_ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// 读取方案内容；返回的配置会标记 `active_profile` 为该方案
+pub fn load_profile(config_path: &Path, name: &str) -> Result<Config, String> {
+    let name = normalize_profile_name(name)?;
+    let path = profile_path(config_path, &name)?;
+    if !path.is_file() {
+        return Err(format!("方案不存在: {}", name));
+    }
+    let mut config = Config::load_file(&path)?;
+    config.active_profile = Some(name);
+    Ok(config)
+}
+
+/// 写入方案内容（已存在时覆盖）
+pub fn save_profile(config_path: &Path, name: &str, config: &Config) -> Result<(), String> {
+    let path = profile_path(config_path, name)?;
+    let dir = profiles_dir(config_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("创建方案目录 {} 失败: {}", dir.display(), e))?;
+
+    let mut content = config.clone();
+    content.active_profile = None;
+    content.save_file(&path)
+}
+
+/// 新建方案，同名方案已存在时报错
+pub fn create_profile(config_path: &Path, name: &str, config: &Config) -> Result<String, String> {
+    let name = normalize_profile_name(name)?;
+    if profile_exists(config_path, &name) {
+        return Err(format!("方案已存在: {}", name));
+    }
+    save_profile(config_path, &name, config)?;
+    Ok(name)
+}
+
+/// 复制方案
+pub fn clone_profile(config_path: &Path, source: &str, target: &str) -> Result<String, String> {
+    let config = load_profile(config_path, source)?;
+    create_profile(config_path, target, &config)
+}
+
+/// 重命名方案；被重命名的方案处于激活状态时同步更新主配置
+pub fn rename_profile(config_path: &Path, from: &str, to: &str) -> Result<String, String> {
+    let from = normalize_profile_name(from)?;
+    let to = normalize_profile_name(to)?;
+    if from == to {
+        return Ok(to);
+    }
+
+    let from_path = profile_path(config_path, &from)?;
+    if !from_path.is_file() {
+        return Err(format!("方案不存在: {}", from));
+    }
+    // 仅大小写不同时目标路径在大小写不敏感的文件系统上指向同一文件
+    if !from.eq_ignore_ascii_case(&to) && profile_exists(config_path, &to) {
+        return Err(format!("方案已存在: {}", to));
+    }
+
+    let to_path = profile_path(config_path, &to)?;
+    fs::rename(&from_path, &to_path).map_err(|e| {
+        format!(
+            "重命名方案 {} -> {} 失败: {}",
+            from_path.display(),
+            to_path.display(),
+            e
+        )
+    })?;
+
+    if read_active_profile(config_path).as_deref() == Some(from.as_str()) {
+        write_active_profile(config_path, Some(&to))?;
+    }
+    Ok(to)
+}
+
+/// 删除方案；被删除的方案处于激活状态时清除主配置中的选择
+pub fn delete_profile(config_path: &Path, name: &str) -> Result<(), String> {
+    let name = normalize_profile_name(name)?;
+    let path = profile_path(config_path, &name)?;
+    if !path.is_file() {
+        return Err(format!("方案不存在: {}", name));
+    }
+    fs::remove_file(&path).map_err(|e| format!("删除方案 {} 失败: {}", path.display(), e))?;
+
+    if read_active_profile(config_path).as_deref() == Some(name.as_str()) {
+        write_active_profile(config_path, None)?;
+    }
+    Ok(())
+}
+
+/// 读取主配置中的激活方案名
+pub fn read_active_profile(config_path: &Path) -> Option<String> {
+    let document = read_document(config_path).ok()?;
+    document
+        .get(ACTIVE_PROFILE_KEY)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// 切换激活方案（`None` 表示回到主配置）
+///
+/// 只改写主配置中的 `active_profile` 字段，其余字段保持原样。
+pub fn set_active_profile(config_path: &Path, name: Option<&str>) -> Result<(), String> {
+    let name = name.map(normalize_profile_name).transpose()?;
+    if let Some(name) = name.as_deref() {
+        if !profile_exists(config_path, name) {
+            return Err(format!("方案不存在: {}", name));
+        }
+    }
+    write_active_profile(config_path, name.as_deref())
+}
+
+/// 若主配置选择了方案，则以方案内容替换主配置
+pub fn apply_active_profile(config_path: &Path, config: Config) -> (Config, ProfileResolution) {
+    let Some(name) = config
+        .active_profile
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+    else {
+        return (config, ProfileResolution::NotActive);
+    };
+
+    match load_profile(config_path, &name) {
+        Ok(profile) => (profile, ProfileResolution::Applied(name)),
+        Err(error) => {
+            let mut config = config;
+            config.active_profile = None;
+            (config, ProfileResolution::Fallback { name, error })
+        }
+    }
+}
+
+fn write_active_profile(config_path: &Path, name: Option<&str>) -> Result<(), String> {
+    let mut document = if config_path.exists() {
+        read_document(config_path)?
+    } else {
+        serde_json::to_value(Config::default()).map_err(|e| format!("序列化错误: {}", e))?
+    };
+
+    let map = match document.as_object_mut() {
+        Some(map) => map,
+        None => {
+            document = Value::Object(Map::new());
+            document.as_object_mut().expect("刚创建的对象")
+        }
+    };
+    match name {
+        Some(name) => {
+            map.insert(ACTIVE_PROFILE_KEY.to_string(), Value::from(name));
+        }
+        None => {
+            map.remove(ACTIVE_PROFILE_KEY);
+        }
+    }
+    write_document(config_path, &document)
+}
+
+fn read_document(path: &Path) -> Result<Value, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("read {} failed: {}", path.display(), e))?;
+    crate::parse_config_document(&content)
+        .map_err(|e| format!("parse {} failed: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let config_path = Config::config_path(dir.path());
+        Config::default()
+            .save_file(&config_path)
+            .expect("写入主配置失败");
+        (dir, config_path)
+    }
+
+    fn fps_only() -> Config {
+        Config {
+            target_fps: 120,
+            notifier_host: "http://127.0.0.1:9".to_string(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn normalize_profile_name_should_reject_path_like_names() {
+        assert_eq!(
+            normalize_profile_name("  本地 URA ").as_deref(),
+            Ok("本地 URA")
+        );
+        assert!(normalize_profile_name("").is_err());
+        assert!(normalize_profile_name("../escape").is_err());
+        assert!(normalize_profile_name("a\\b").is_err());
+        assert!(normalize_profile_name(".hidden").is_err());
+        assert!(normalize_profile_name(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn create_clone_and_list_should_round_trip() {
+        let (_dir, config_path) = setup();
+
+        create_profile(&config_path, "fps_only", &fps_only()).expect("新建方案失败");
+        clone_profile(&config_path, "fps_only", "fps_copy").expect("复制方案失败");
+
+        assert_eq!(list_profiles(&config_path), vec!["fps_copy", "fps_only"]);
+        assert!(create_profile(&config_path, "fps_only", &Config::default()).is_err());
+        let copy = load_profile(&config_path, "fps_copy").expect("读取方案失败");
+        assert_eq!(copy.target_fps, 120);
+        assert_eq!(copy.active_profile.as_deref(), Some("fps_copy"));
+    }
+
+    #[test]
+    fn set_active_profile_should_only_touch_selector() {
+        let (_dir, config_path) = setup();
+        let base = Config {
+            timeout_ms: 321,
+            ..Config::default()
+        };
+        base.save_file(&config_path).expect("写入主配置失败");
+        create_profile(&config_path, "fps_only", &fps_only()).expect("新建方案失败");
+
+        set_active_profile(&config_path, Some("fps_only")).expect("切换方案失败");
+        let (effective, resolution) = apply_active_profile(
+            &config_path,
+            Config::load_file(&config_path).expect("读取失败"),
+        );
+
+        assert_eq!(
+            resolution,
+            ProfileResolution::Applied("fps_only".to_string())
+        );
+        assert_eq!(effective.target_fps, 120);
+        assert_eq!(
+            Config::load_file(&config_path)
+                .expect("读取失败")
+                .timeout_ms,
+            321
+        );
+
+        set_active_profile(&config_path, None).expect("取消方案失败");
+        assert_eq!(read_active_profile(&config_path), None);
+        assert!(set_active_profile(&config_path, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn rename_and_delete_should_follow_active_selector() {
+        let (_dir, config_path) = setup();
+        create_profile(&config_path, "team", &fps_only()).expect("新建方案失败");
+        set_active_profile(&config_path, Some("team")).expect("切换方案失败");
+
+        rename_profile(&config_path, "team", "team_relay").expect("重命名失败");
+        assert_eq!(
+            read_active_profile(&config_path).as_deref(),
+            Some("team_relay")
+        );
+        assert_eq!(list_profiles(&config_path), vec!["team_relay"]);
+
+        delete_profile(&config_path, "team_relay").expect("删除失败");
+        assert_eq!(read_active_profile(&config_path), None);
+        assert!(list_profiles(&config_path).is_empty());
+    }
+
+    #[test]
+    fn missing_active_profile_should_fall_back_to_main_config() {
+        let (_dir, config_path) = setup();
+        let config = Config {
+            active_profile: Some("gone".to_string()),
+            timeout_ms: 222,
+            ..Config::default()
+        };
+
+        let (effective, resolution) = apply_active_profile(&config_path, config);
+
+        assert!(matches!(
+            resolution,
+            ProfileResolution::Fallback { ref name, .. } if name == "gone"
+        ));
+        assert_eq!(effective.timeout_ms, 222);
+        assert_eq!(effective.active_profile, None);
+    }
+}
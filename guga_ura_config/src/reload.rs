@@ -0,0 +1,260 @@
+//! 热重载结果
+//!
+//! DLL 监控到配置变更后按这里的规则决定是否应用新配置：文件缺失、解析失败
+//! 或校验存在错误时保留上一次有效配置。每次判定的结果写入配置文件同级的
+//! `guga_ura_reload_status.json`，配置工具据此向用户展示重载是否生效。
+
+use crate::{Config, IssueSeverity, ValidationReport};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 重载状态文件名（配置文件同级）
+pub const RELOAD_STATUS_FILE_NAME: &str = "guga_ura_reload_status.json";
+
+/// 重载结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloadState {
+    /// 新配置已生效
+    Applied,
+    /// 新配置校验失败，继续使用上一次有效配置
+    Rejected,
+    /// 新配置无法读取或解析，继续使用上一次有效配置
+    Failed,
+    /// 配置文件已删除，继续使用上一次有效配置
+    Missing,
+}
+
+/// 状态中的校验问题（可反序列化的 [`crate::ConfigIssue`]）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReloadIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// 一次加载或重载的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReloadStatus {
+    pub state: ReloadState,
+    /// 触发原因，例如 `startup`、`modify`、`create`、`rename`、`remove`
+    pub trigger: String,
+    pub at_unix_ms: u64,
+    pub message: String,
+    #[serde(default)]
+    pub issues: Vec<ReloadIssue>,
+    /// 当前生效配置所用的方案
+    #[serde(default)]
+    pub active_profile: Option<String>,
+}
+
+/// 判定是否应用新加载的配置
+///
+/// 返回 `Some(config)` 表示应当应用；`None` 表示保留上一次有效配置。
+/// `current_active_profile` 用于在拒绝时记录仍在生效的方案。
+pub fn evaluate_reload(
+    config_path: &Path,
+    trigger: &str,
+    loaded: Result<Config, String>,
+    current_active_profile: Option<&str>,
+) -> (Option<Config>, ReloadStatus) {
+    let kept_profile = current_active_profile.map(str::to_string);
+
+    let config = match loaded {
+        Ok(config) => config,
+        Err(_) if !config_path.exists() => {
+            let status = ReloadStatus::new(
+                ReloadState::Missing,
+                trigger,
+                "配置文件不存在，继续使用上一次有效配置",
+                Vec::new(),
+                kept_profile,
+            );
+            return (None, status);
+        }
+        Err(error) => {
+            let status = ReloadStatus::new(
+                ReloadState::Failed,
+                trigger,
+                format!("配置读取失败，继续使用上一次有效配置: {}", error),
+                Vec::new(),
+                kept_profile,
+            );
+            return (None, status);
+        }
+    };
+
+    let report = config.validate();
+    let issues = issues_of(&report);
+
+    if !report.is_valid() {
+        let status = ReloadStatus::new(
+            ReloadState::Rejected,
+            trigger,
+            "配置校验失败，继续使用上一次有效配置",
+            issues,
+            kept_profile,
+        );
+        return (None, status);
+    }
+
+    let status = ReloadStatus::new(
+        ReloadState::Applied,
+        trigger,
+        "配置已生效",
+        issues,
+        config.active_profile.clone(),
+    );
+    (Some(config), status)
+}
+
+/// 启动时的加载结果：启动阶段没有可保留的旧配置，配置总会生效，问题一并记录
+pub fn startup_status(config: &Config) -> ReloadStatus {
+    ReloadStatus::new(
+        ReloadState::Applied,
+        "startup",
+        "启动时已加载配置",
+        issues_of(&config.validate()),
+        config.active_profile.clone(),
+    )
+}
+
+fn issues_of(report: &ValidationReport) -> Vec<ReloadIssue> {
+    report
+        .issues
+        .iter()
+        .map(|issue| ReloadIssue {
+            field: issue.field.to_string(),
+            severity: issue.severity,
+            message: issue.message.clone(),
+        })
+        .collect()
+}
+
+impl ReloadStatus {
+    fn new(
+        state: ReloadState,
+        trigger: &str,
+        message: impl Into<String>,
+        issues: Vec<ReloadIssue>,
+        active_profile: Option<String>,
+    ) -> Self {
+        Self {
+            state,
+            trigger: trigger.to_string(),
+            at_unix_ms: now_unix_ms(),
+            message: message.into(),
+            issues,
+            active_profile,
+        }
+    }
+}
+
+/// 状态文件路径（配置文件同级）
+pub fn reload_status_path(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(RELOAD_STATUS_FILE_NAME)
+}
+
+/// 写入状态文件
+pub fn write_reload_status(config_path: &Path, status: &ReloadStatus) -> Result<(), String> {
+    let path = reload_status_path(config_path);
+    let json =
+        serde_json::to_string_pretty(status).map_err(|e| format!("序列化重载状态失败: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+}
+
+/// 读取状态文件；文件不存在时返回 `Ok(None)`
+pub fn read_reload_status(config_path: &Path) -> Result<Option<ReloadStatus>, String> {
+    let path = reload_status_path(config_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map(Some)
+        .map_err(|e| format!("解析 {} 失败: {}", path.display(), e))
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_path() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join(crate::CONFIG_FILE_NAME);
+        (dir, path)
+    }
+
+    #[test]
+    fn valid_config_should_be_applied_with_warnings() {
+        let (_dir, path) = config_path();
+        Config::default().save_file(&path).expect("写入配置失败");
+        let loaded = Config {
+            timeout_ms: 30_000,
+            ..Config::default()
+        };
+
+        let (config, status) = evaluate_reload(&path, "modify", Ok(loaded), None);
+
+        assert_eq!(config.map(|config| config.timeout_ms), Some(30_000));
+        assert_eq!(status.state, ReloadState::Applied);
+        assert_eq!(status.issues.len(), 1);
+        assert_eq!(status.issues[0].severity, IssueSeverity::Warning);
+    }
+
+    #[test]
+    fn invalid_config_should_be_rejected_and_keep_current_profile() {
+        let (_dir, path) = config_path();
+        Config::default().save_file(&path).expect("写入配置失败");
+        let loaded = Config {
+            notifier_host: "127.0.0.1:4693".to_string(),
+            ..Config::default()
+        };
+
+        let (config, status) = evaluate_reload(&path, "rename", Ok(loaded), Some("team"));
+
+        assert!(config.is_none());
+        assert_eq!(status.state, ReloadState::Rejected);
+        assert_eq!(status.trigger, "rename");
+        assert_eq!(status.issues[0].field, "notifier_host");
+        assert_eq!(status.active_profile.as_deref(), Some("team"));
+    }
+
+    #[test]
+    fn load_errors_should_distinguish_missing_and_broken_files() {
+        let (_dir, path) = config_path();
+
+        let (_, missing) = evaluate_reload(&path, "remove", Err("not found".to_string()), None);
+        std::fs::write(&path, "{ broken").expect("写入配置失败");
+        let (_, failed) = evaluate_reload(&path, "modify", Err("EOF".to_string()), None);
+
+        assert_eq!(missing.state, ReloadState::Missing);
+        assert_eq!(failed.state, ReloadState::Failed);
+        assert!(failed.message.contains("EOF"));
+    }
+
+    #[test]
+    fn status_file_should_round_trip() {
+        let (_dir, path) = config_path();
+        assert_eq!(read_reload_status(&path), Ok(None));
+
+        let status = startup_status(&Config::default());
+        write_reload_status(&path, &status).expect("写入状态失败");
+
+        assert_eq!(read_reload_status(&path), Ok(Some(status)));
+    }
+}
@@ -0,0 +1,325 @@
+//! 时间转换公共模块
+//!
+//! 游戏服务器返回的时间统一为 JST（+09:00）的 `"YYYY-MM-DD HH:MM:SS"` 字符串。
+//! 本模块按格式识别游戏时间（不依赖字段名），并按配置的输出时区与格式转换，
+//! 供 fans、种马输出、提取规则、debug 包装与 DLL 共用。
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+
+/// 游戏时间格式
+pub const GAME_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 本地可读格式（不带时区后缀）
+const LOCAL_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 游戏服务器时区偏移（JST，秒）
+const GAME_UTC_OFFSET_SECS: i32 = 9 * 3600;
+
+/// 时间输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOutputFormat {
+    /// RFC3339，带输出时区偏移
+    Rfc3339,
+    /// Unix 毫秒时间戳（数字）
+    UnixMs,
+    /// 输出时区下的 `YYYY-MM-DD HH:MM:SS`
+    Local,
+}
+
+/// 时间输出时区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputTimeZone {
+    /// 游戏服务器时区（+09:00）
+    Game,
+    /// 本机时区
+    Local,
+    Utc,
+    /// 固定偏移，如 `+08:00`
+    Fixed(FixedOffset),
+}
+
+/// 时间输出设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSettings {
+    pub format: TimeOutputFormat,
+    pub timezone: OutputTimeZone,
+}
+
+impl TimeOutputFormat {
+    /// 解析配置值：`rfc3339` / `unix_ms` / `local`
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "rfc3339" => Some(Self::Rfc3339),
+            "unix_ms" => Some(Self::UnixMs),
+            "local" => Some(Self::Local),
+            _ => None,
+        }
+    }
+}
+
+impl OutputTimeZone {
+    /// 解析配置值：`game` / `local` / `utc` / `±HH:MM`
+    pub fn parse(raw: &str) -> Option<Self> {
+        let trimmed = raw.trim();
+        match trimmed.to_ascii_lowercase().as_str() {
+            "game" | "jst" => return Some(Self::Game),
+            "local" => return Some(Self::Local),
+            "utc" | "z" => return Some(Self::Utc),
+            _ => {}
+        }
+
+        let (sign, rest) = match trimmed.as_bytes().first()? {
+            b'+' => (1, &trimmed[1..]),
+            b'-' => (-1, &trimmed[1..]),
+            _ => return None,
+        };
+        let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).map(Self::Fixed)
+    }
+
+    fn offset_at(&self, utc: &DateTime<Utc>) -> FixedOffset {
+        match self {
+            Self::Game => game_offset(),
+            Self::Local => *utc.with_timezone(&Local).offset(),
+            Self::Utc => FixedOffset::east_opt(0).expect("UTC offset is valid"),
+            Self::Fixed(offset) => *offset,
+        }
+    }
+}
+
+impl TimeSettings {
+    pub const fn new(format: TimeOutputFormat, timezone: OutputTimeZone) -> Self {
+        Self { format, timezone }
+    }
+
+    /// 从配置值构建设置；为空或无法识别时使用调用方给出的默认值
+    pub fn from_config(
+        format: Option<&str>,
+        timezone: Option<&str>,
+        default_timezone: OutputTimeZone,
+    ) -> Self {
+        Self {
+            format: format
+                .and_then(TimeOutputFormat::parse)
+                .unwrap_or(TimeOutputFormat::Rfc3339),
+            timezone: timezone
+                .and_then(OutputTimeZone::parse)
+                .unwrap_or(default_timezone),
+        }
+    }
+
+    /// 按设置格式化某个时刻
+    pub fn format_datetime<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> Value {
+        let utc = datetime.with_timezone(&Utc);
+        match self.format {
+            TimeOutputFormat::UnixMs => Value::from(utc.timestamp_millis()),
+            TimeOutputFormat::Rfc3339 => Value::String(self.in_output_zone(&utc).to_rfc3339()),
+            TimeOutputFormat::Local => Value::String(
+                self.in_output_zone(&utc)
+                    .format(LOCAL_TIME_FORMAT)
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// 按设置格式化 Unix 毫秒时间戳
+    pub fn format_unix_ms(&self, unix_ms: u64) -> Value {
+        match datetime_from_unix_ms(unix_ms) {
+            Some(datetime) => self.format_datetime(&datetime),
+            None => Value::from(unix_ms),
+        }
+    }
+
+    /// 始终以 RFC3339 输出（用于 schema 要求固定为 date-time 的元数据字段）
+    pub fn format_rfc3339<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> String {
+        self.in_output_zone(&datetime.with_timezone(&Utc))
+            .to_rfc3339()
+    }
+
+    /// 输出时区下的日期键（`YYYYMMDD`）
+    pub fn date_key(&self, unix_ms: u64) -> String {
+        let utc = datetime_from_unix_ms(unix_ms).unwrap_or_else(Utc::now);
+        self.in_output_zone(&utc).format("%Y%m%d").to_string()
+    }
+
+    /// 转换单个游戏时间字符串；不符合游戏格式或为空时间时返回 None
+    pub fn convert_game_time_str(&self, raw: &str) -> Option<Value> {
+        parse_game_time(raw).map(|datetime| self.format_datetime(&datetime))
+    }
+
+    /// 递归转换 Value 中所有符合游戏时间格式的字符串（就地修改）
+    pub fn convert_game_times(&self, value: &mut Value) {
+        match value {
+            Value::String(raw) => {
+                if let Some(converted) = self.convert_game_time_str(raw) {
+                    *value = converted;
+                }
+            }
+            Value::Array(items) => {
+                for item in items.iter_mut() {
+                    self.convert_game_times(item);
+                }
+            }
+            Value::Object(map) => {
+                for item in map.values_mut() {
+                    self.convert_game_times(item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn in_output_zone(&self, utc: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        utc.with_timezone(&self.timezone.offset_at(utc))
+    }
+}
+
+/// 接收时间等本地元数据的时间设置：未配置时区时使用本机时区
+///
+/// Receiver 与 DLL 的 debug 输出共用，保证两边 `received_at` 的格式一致。
+pub fn receive_time_settings(config: &crate::Config) -> TimeSettings {
+    TimeSettings::from_config(
+        config.time_output_format.as_deref(),
+        config.time_output_timezone.as_deref(),
+        OutputTimeZone::Local,
+    )
+}
+
+/// 判断字符串是否为游戏时间格式（含 `0000-00-00 00:00:00` 空时间）
+pub fn is_game_time_str(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    bytes.len() == 19
+        && bytes.iter().enumerate().all(|(index, byte)| match index {
+            4 | 7 => *byte == b'-',
+            10 => *byte == b' ',
+            13 | 16 => *byte == b':',
+            _ => byte.is_ascii_digit(),
+        })
+}
+
+/// 解析游戏时间为 JST 时刻；空时间（`0000` 开头）与非法值返回 None
+pub fn parse_game_time(raw: &str) -> Option<DateTime<FixedOffset>> {
+    let trimmed = raw.trim();
+    if !is_game_time_str(trimmed) || trimmed.starts_with("0000") {
+        return None;
+    }
+    let naive = NaiveDateTime::parse_from_str(trimmed, GAME_TIME_FORMAT).ok()?;
+    game_offset().from_local_datetime(&naive).single()
+}
+
+fn game_offset() -> FixedOffset {
+    FixedOffset::east_opt(GAME_UTC_OFFSET_SECS).expect("JST offset is valid")
+}
+
+fn datetime_from_unix_ms(unix_ms: u64) -> Option<DateTime<Utc>> {
+    let ms = i64::try_from(unix_ms).ok()?;
+    Utc.timestamp_millis_opt(ms).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GAME_RFC3339: TimeSettings =
+        TimeSettings::new(TimeOutputFormat::Rfc3339, OutputTimeZone::Game);
+
+    #[test]
+    fn is_game_time_str_should_match_pattern_only() {
+        assert!(is_game_time_str("2026-04-06 22:40:05"));
+        assert!(is_game_time_str("0000-00-00 00:00:00"));
+        assert!(!is_game_time_str("2026-04-06T22:40:05"));
+        assert!(!is_game_time_str("2026-04-06 22:40"));
+        assert!(!is_game_time_str("20260406"));
+    }
+
+    #[test]
+    fn convert_game_time_str_should_support_all_formats() {
+        let raw = "2026-04-06 22:40:05";
+
+        assert_eq!(
+            GAME_RFC3339.convert_game_time_str(raw),
+            Some(json!("2026-04-06T22:40:05+09:00"))
+        );
+        assert_eq!(
+            TimeSettings::new(TimeOutputFormat::Rfc3339, OutputTimeZone::Utc)
+                .convert_game_time_str(raw),
+            Some(json!("2026-04-06T13:40:05+00:00"))
+        );
+        assert_eq!(
+            TimeSettings::new(TimeOutputFormat::UnixMs, OutputTimeZone::Game)
+                .convert_game_time_str(raw),
+            Some(json!(1_775_482_805_000_i64))
+        );
+        assert_eq!(
+            TimeSettings::new(
+                TimeOutputFormat::Local,
+                OutputTimeZone::parse("+08:00").expect("解析时区失败")
+            )
+            .convert_game_time_str(raw),
+            Some(json!("2026-04-06 21:40:05"))
+        );
+    }
+
+    #[test]
+    fn convert_game_time_str_should_skip_zero_and_invalid() {
+        assert!(GAME_RFC3339
+            .convert_game_time_str("0000-00-00 00:00:00")
+            .is_none());
+        assert!(GAME_RFC3339.convert_game_time_str("").is_none());
+        assert!(GAME_RFC3339
+            .convert_game_time_str("2026-13-40 99:99:99")
+            .is_none());
+    }
+
+    #[test]
+    fn convert_game_times_should_convert_unknown_fields_by_pattern() {
+        let mut value = json!({
+            "some_new_time": "2026-04-06 22:40:05",
+            "nested": [{"whatever": "2026-04-06 22:40:05", "name": "2026"}],
+            "empty_time": "0000-00-00 00:00:00",
+            "count": 3
+        });
+
+        GAME_RFC3339.convert_game_times(&mut value);
+
+        assert_eq!(value["some_new_time"], "2026-04-06T22:40:05+09:00");
+        assert_eq!(value["nested"][0]["whatever"], "2026-04-06T22:40:05+09:00");
+        assert_eq!(value["nested"][0]["name"], "2026");
+        assert_eq!(value["empty_time"], "0000-00-00 00:00:00");
+        assert_eq!(value["count"], 3);
+    }
+
+    #[test]
+    fn date_key_should_follow_output_timezone() {
+        // 2026-04-06T15:30:00Z
+        let unix_ms = 1_775_489_400_000;
+
+        let utc = TimeSettings::new(TimeOutputFormat::Rfc3339, OutputTimeZone::Utc);
+        let game = TimeSettings::new(TimeOutputFormat::Rfc3339, OutputTimeZone::Game);
+
+        assert_eq!(utc.date_key(unix_ms), "20260406");
+        assert_eq!(game.date_key(unix_ms), "20260407");
+    }
+
+    #[test]
+    fn from_config_should_fallback_on_unknown_values() {
+        let settings = TimeSettings::from_config(Some("bogus"), Some("mars"), OutputTimeZone::Game);
+
+        assert_eq!(settings, GAME_RFC3339);
+        assert_eq!(
+            TimeSettings::from_config(Some("UNIX_MS"), Some("utc"), OutputTimeZone::Game),
+            TimeSettings::new(TimeOutputFormat::UnixMs, OutputTimeZone::Utc)
+        );
+        assert_eq!(
+            OutputTimeZone::parse("-05:30"),
+            FixedOffset::east_opt(-(5 * 3600 + 30 * 60)).map(OutputTimeZone::Fixed)
+        );
+    }
+}
@@ -0,0 +1,459 @@
+//! 配置校验
+//!
+//! DLL、独立 Receiver 与配置工具共用同一套校验规则。校验不修改配置，
+//! 只返回按字段归属的问题列表：错误表示该字段在运行时无法正常工作，
+//! 警告表示配置可用但大概率不是用户想要的效果。
+
+use crate::address;
+use crate::Config;
+use guga_ura_transport::signing;
+use guga_ura_transport::transport::ContentEncoding;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 超过该值的超时会让每次抓取都可能明显拖慢游戏请求
+const SLOW_TIMEOUT_WARNING_MS: u64 = 5_000;
+
+/// 问题级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// 单个字段的校验问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    /// 配置字段名（与 JSON 键一致）
+    pub field: &'static str,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// [`Config::validate`] 的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigIssue {
+    fn error(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            severity: IssueSeverity::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl ValidationReport {
+    /// 没有错误（可以有警告）
+    pub fn is_valid(&self) -> bool {
+        !self.issues.iter().any(ConfigIssue::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().filter(|issue| issue.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().filter(|issue| !issue.is_error())
+    }
+
+    /// 指定字段中的第一个错误（用于只保存部分字段的场景）
+    pub fn first_error_in(&self, fields: &[&str]) -> Option<&ConfigIssue> {
+        self.errors().find(|issue| fields.contains(&issue.field))
+    }
+
+    /// 指定字段中的第一个错误转为 `Err(message)`
+    pub fn ensure_fields(&self, fields: &[&str]) -> Result<(), String> {
+        match self.first_error_in(fields) {
+            Some(issue) => Err(issue.message.clone()),
+            None => Ok(()),
+        }
+    }
+
+    /// 仅保留指定字段的问题
+    pub fn only_fields(&self, fields: &[&str]) -> ValidationReport {
+        ValidationReport {
+            issues: self
+                .issues
+                .iter()
+                .filter(|issue| fields.contains(&issue.field))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// DLL 发送侧字段
+pub const DLL_INJECTION_FIELDS: &[&str] = &[
+    "notifier_host",
+    "timeout_ms",
+    "transport_compression",
+    "shared_secret",
+];
+
+/// DLL 捕获规则字段
+pub const CAPTURE_RULE_FIELDS: &[&str] = &["capture_rules"];
+
+/// Receiver 运行时字段
+pub const RECEIVER_RUNTIME_FIELDS: &[&str] =
+    &["receiver_listen_addr", "relay_target_host", "shared_secret"];
+
+/// 游戏设置字段
+pub const GAME_SETTINGS_FIELDS: &[&str] = &["target_fps", "vsync_count"];
+
+impl Config {
+    /// 校验整份配置
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+        self.validate_dll_injection(&mut issues);
+        self.validate_transport(&mut issues);
+        self.validate_capture_rules(&mut issues);
+        self.validate_game_settings(&mut issues);
+        self.validate_receiver_runtime(&mut issues);
+        ValidationReport { issues }
+    }
+
+    fn validate_dll_injection(&self, issues: &mut Vec<ConfigIssue>) {
+        let notifier_host = self.notifier_host.trim();
+        if !address::has_http_scheme(notifier_host) {
+            issues.push(ConfigIssue::error(
+                "notifier_host",
+                "转发地址必须以 http:// 或 https:// 开头",
+            ));
+        } else if address::normalize_target_base_url(notifier_host).is_none() {
+            issues.push(ConfigIssue::error(
+                "notifier_host",
+                "转发地址不是有效的 URL",
+            ));
+        }
+
+        if self.timeout_ms == 0 {
+            issues.push(ConfigIssue::error("timeout_ms", "超时必须大于 0"));
+        } else if self.timeout_ms > SLOW_TIMEOUT_WARNING_MS {
+            issues.push(ConfigIssue::warning(
+                "timeout_ms",
+                format!(
+                    "超时超过 {} 毫秒，接收端无响应时可能拖慢游戏",
+                    SLOW_TIMEOUT_WARNING_MS
+                ),
+            ));
+        }
+    }
+
+    fn validate_transport(&self, issues: &mut Vec<ConfigIssue>) {
+        if let Err(message) = ContentEncoding::from_config(self.transport_compression.as_deref()) {
+            issues.push(ConfigIssue::error("transport_compression", message));
+        }
+
+        if let Some(secret) = signing::effective_secret(self.shared_secret.as_deref()) {
+            if secret.chars().count() < signing::MIN_SECRET_LEN {
+                issues.push(ConfigIssue::warning(
+                    "shared_secret",
+                    format!("签名密钥过短，建议至少 {} 个字符", signing::MIN_SECRET_LEN),
+                ));
+            }
+        }
+    }
+
+    fn validate_capture_rules(&self, issues: &mut Vec<ConfigIssue>) {
+        let rules = &self.capture_rules;
+        if !(0.0..=1.0).contains(&rules.sample_rate) {
+            issues.push(ConfigIssue::error(
+                "capture_rules",
+                "采样率必须在 0 到 1 之间",
+            ));
+        }
+        if let (Some(min), Some(max)) = (rules.min_size_bytes, rules.max_size_bytes) {
+            if min > max {
+                issues.push(ConfigIssue::error(
+                    "capture_rules",
+                    "最小大小不能超过最大大小",
+                ));
+            }
+        }
+        if !rules.request_enabled && !rules.response_enabled {
+            issues.push(ConfigIssue::warning(
+                "capture_rules",
+                "请求与响应均已关闭，DLL 不会发送任何数据",
+            ));
+        } else if rules.sample_rate == 0.0 {
+            issues.push(ConfigIssue::warning(
+                "capture_rules",
+                "采样率为 0，DLL 不会发送任何数据",
+            ));
+        }
+    }
+
+    fn validate_game_settings(&self, issues: &mut Vec<ConfigIssue>) {
+        if self.target_fps != -1 && self.target_fps <= 0 {
+            issues.push(ConfigIssue::error(
+                "target_fps",
+                "目标 FPS 必须是默认值或正整数",
+            ));
+        }
+
+        if !matches!(self.vsync_count, -1..=1) {
+            issues.push(ConfigIssue::error(
+                "vsync_count",
+                "VSync 取值必须是默认、关闭或开启",
+            ));
+        }
+    }
+
+    fn validate_receiver_runtime(&self, issues: &mut Vec<ConfigIssue>) {
+        let listen_addr = self.receiver_listen_addr.trim();
+        let normalized_listen_addr = address::normalize_listen_addr(listen_addr);
+        if listen_addr.is_empty() {
+            issues.push(ConfigIssue::error(
+                "receiver_listen_addr",
+                "监听地址不能为空",
+            ));
+        } else if normalized_listen_addr.is_none() {
+            issues.push(ConfigIssue::error(
+                "receiver_listen_addr",
+                "监听地址必须使用 host:port 格式，host 仅支持 localhost 或 IP；也可填写 http://host:port",
+            ));
+        }
+
+        let relay_target = self
+            .relay_target_host
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        let Some(target) = relay_target else {
+            if self.relay_enabled {
+                issues.push(ConfigIssue::warning(
+                    "relay_target_host",
+                    "已开启 Relay 但未填写目标地址，Relay 不会生效",
+                ));
+            }
+            return;
+        };
+
+        if !address::has_http_scheme(target) {
+            issues.push(ConfigIssue::error(
+                "relay_target_host",
+                "Relay 目标地址必须以 http:// 或 https:// 开头",
+            ));
+            return;
+        }
+
+        if self.relay_enabled {
+            if let Some(listen_addr) = normalized_listen_addr.as_deref() {
+                if address::relay_target_would_loop(listen_addr, target) {
+                    issues.push(ConfigIssue::error(
+                        "relay_target_host",
+                        "Relay 目标地址不能指向当前 Receiver 自身",
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use guga_ura_transport::capture_rules::CaptureRules;
+
+    fn fields_of(report: &ValidationReport, severity: IssueSeverity) -> Vec<&'static str> {
+        report
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .map(|issue| issue.field)
+            .collect()
+    }
+
+    #[test]
+    fn default_config_should_be_valid_without_warnings() {
+        let report = Config::default().validate();
+
+        assert!(report.is_valid());
+        assert!(report.issues.is_empty(), "默认配置不应有问题: {}", report);
+    }
+
+    #[test]
+    fn hand_edited_config_should_report_each_bad_field() {
+        let config = Config {
+            notifier_host: "127.0.0.1:4693".to_string(),
+            timeout_ms: 0,
+            target_fps: 0,
+            vsync_count: 2,
+            receiver_listen_addr: "example.com:4693".to_string(),
+            ..Config::default()
+        };
+
+        let report = config.validate();
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            fields_of(&report, IssueSeverity::Error),
+            vec![
+                "notifier_host",
+                "timeout_ms",
+                "target_fps",
+                "vsync_count",
+                "receiver_listen_addr"
+            ]
+        );
+    }
+
+    #[test]
+    fn transport_compression_should_accept_known_values_only() {
+        for value in [None, Some("none"), Some("gzip"), Some("ZSTD")] {
+            let config = Config {
+                transport_compression: value.map(str::to_string),
+                ..Config::default()
+            };
+            assert!(config.validate().is_valid(), "{:?}", value);
+        }
+
+        let config = Config {
+            transport_compression: Some("brotli".to_string()),
+            ..Config::default()
+        };
+        let error = config
+            .validate()
+            .ensure_fields(DLL_INJECTION_FIELDS)
+            .expect_err("未知压缩方式应报错");
+        assert!(error.contains("brotli"));
+    }
+
+    #[test]
+    fn short_shared_secret_should_only_warn() {
+        let short = Config {
+            shared_secret: Some("abc".to_string()),
+            ..Config::default()
+        };
+        let report = short.validate();
+        assert!(report.is_valid());
+        assert_eq!(
+            fields_of(&report, IssueSeverity::Warning),
+            vec!["shared_secret"]
+        );
+
+        let strong = Config {
+            shared_secret: Some("0123456789abcdef0123".to_string()),
+            ..Config::default()
+        };
+        assert!(strong.validate().issues.is_empty());
+    }
+
+    #[test]
+    fn capture_rules_should_report_bad_ranges_and_silent_rules() {
+        let mut config = Config::default();
+        config.capture_rules.sample_rate = 1.5;
+        config.capture_rules.min_size_bytes = Some(10);
+        config.capture_rules.max_size_bytes = Some(5);
+
+        let report = config.validate();
+        assert_eq!(
+            fields_of(&report, IssueSeverity::Error),
+            vec!["capture_rules", "capture_rules"]
+        );
+        assert!(report.ensure_fields(DLL_INJECTION_FIELDS).is_ok());
+
+        config.capture_rules = CaptureRules {
+            request_enabled: false,
+            response_enabled: false,
+            ..CaptureRules::default()
+        };
+        let report = config.validate();
+        assert!(report.is_valid());
+        assert_eq!(
+            fields_of(&report, IssueSeverity::Warning),
+            CAPTURE_RULE_FIELDS.to_vec()
+        );
+    }
+
+    #[test]
+    fn relay_self_loop_should_only_error_when_enabled() {
+        let mut config = Config {
+            receiver_listen_addr: "127.0.0.1:4700".to_string(),
+            relay_target_host: Some("http://localhost:4700/".to_string()),
+            relay_enabled: true,
+            ..Config::default()
+        };
+
+        let enabled = config.validate();
+        config.relay_enabled = false;
+        let disabled = config.validate();
+
+        let issue = enabled
+            .first_error_in(RECEIVER_RUNTIME_FIELDS)
+            .expect("自环应报错");
+        assert_eq!(issue.field, "relay_target_host");
+        assert!(issue.message.contains("当前 Receiver 自身"));
+        assert!(disabled.is_valid());
+    }
+
+    #[test]
+    fn relay_without_target_and_slow_timeout_should_warn() {
+        let config = Config {
+            relay_enabled: true,
+            relay_target_host: Some("  ".to_string()),
+            timeout_ms: 30_000,
+            ..Config::default()
+        };
+
+        let report = config.validate();
+
+        assert!(report.is_valid());
+        assert_eq!(
+            fields_of(&report, IssueSeverity::Warning),
+            vec!["timeout_ms", "relay_target_host"]
+        );
+    }
+
+    #[test]
+    fn ensure_fields_should_ignore_errors_outside_scope() {
+        let config = Config {
+            timeout_ms: 0,
+            ..Config::default()
+        };
+
+        let report = config.validate();
+
+        assert!(report.ensure_fields(GAME_SETTINGS_FIELDS).is_ok());
+        assert_eq!(
+            report.ensure_fields(DLL_INJECTION_FIELDS),
+            Err("超时必须大于 0".to_string())
+        );
+        assert_eq!(report.only_fields(GAME_SETTINGS_FIELDS).issues.len(), 0);
+    }
+}
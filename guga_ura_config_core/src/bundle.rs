@@ -0,0 +1,655 @@
+//! 配置导入导出
+//!
+//! 把主配置与全部配置方案打包为一个 JSON 文件，便于迁移到另一台电脑。
+//! 导出时，输出目录等绝对路径若位于游戏目录、配置工具目录或“文档”目录下，
+//! 会改写为 `{game_dir}`、`{exe_dir}`、`{documents}` 占位符；导入时按新环境
+//! 还原。导入前先生成预览（与当前配置的逐字段差异），确认后再写入。
+//! 签名密钥只属于本机，导出时清空，导入时保留本机的值。
+
+use crate::config::observer::{diff_configs, FieldChange};
+use crate::config::{profiles as config_profiles, Config, ValidationReport};
+use crate::profiles::profile_targets;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 导出文件格式标识
+pub const BUNDLE_FORMAT: &str = "guga_ura_config_bundle";
+
+/// 当前导出格式版本
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 会被改写为占位符的路径字段
+pub const PATH_FIELDS: &[&str] = &["debug_output_dir", "fans_output_dir", "stallion_output_dir"];
+
+/// 只属于本机的字段：导出时清空，导入时保留本机值，也不出现在预览差异中
+pub const LOCAL_ONLY_FIELDS: &[&str] = &["shared_secret"];
+
+/// 导出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at_unix_ms: u64,
+    /// 主配置（未应用方案）
+    pub config: Config,
+    /// 配置方案，键为方案名
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Config>,
+}
+
+/// 占位符对应的本机目录
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathPlaceholders {
+    pub game_dir: Option<PathBuf>,
+    pub exe_dir: Option<PathBuf>,
+    pub documents: Option<PathBuf>,
+}
+
+/// 单个方案的导入预览
+#[derive(Debug, Clone)]
+pub struct ProfileImportPreview {
+    pub name: String,
+    /// 本机是否已有同名方案（导入会覆盖）
+    pub exists: bool,
+    pub changes: Vec<FieldChange>,
+    pub config: Config,
+}
+
+/// 导入预览
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    /// 还原占位符后的主配置
+    pub config: Config,
+    /// 与当前主配置的差异
+    pub changes: Vec<FieldChange>,
+    pub profiles: Vec<ProfileImportPreview>,
+    /// 无法还原的路径（对应字段导入后为空，使用默认目录）
+    pub unresolved: Vec<String>,
+    /// 主配置的校验结果
+    pub validation: ValidationReport,
+}
+
+impl PathPlaceholders {
+    /// 当前环境：可选的游戏目录、配置工具 EXE 目录与用户“文档”目录
+    pub fn current(game_dir: Option<&Path>) -> Self {
+        Self {
+            game_dir: game_dir.map(Path::to_path_buf),
+            exe_dir: std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+            documents: documents_dir(),
+        }
+    }
+
+    fn entries(&self) -> [(&'static str, Option<&Path>); 3] {
+        [
+            ("{game_dir}", self.game_dir.as_deref()),
+            ("{exe_dir}", self.exe_dir.as_deref()),
+            ("{documents}", self.documents.as_deref()),
+        ]
+    }
+
+    /// 绝对路径改写为占位符形式；不在任何已知目录下时原样返回
+    pub fn to_portable(&self, value: &str) -> String {
+        let path = Path::new(value.trim());
+        if !path.is_absolute() {
+            return value.to_string();
+        }
+
+        // 选择最深的匹配目录，例如游戏目录位于“文档”下时优先 {game_dir}
+        let best = self
+            .entries()
+            .into_iter()
+            .filter_map(|(placeholder, base)| {
+                let base = base?;
+                let rest = strip_base(path, base)?;
+                Some((placeholder, base.components().count(), rest))
+            })
+            .max_by_key(|(_, depth, _)| *depth);
+
+        match best {
+            Some((placeholder, _, rest)) if rest.is_empty() => placeholder.to_string(),
+            Some((placeholder, _, rest)) => format!("{}/{}", placeholder, rest.join("/")),
+            None => value.to_string(),
+        }
+    }
+
+    /// 还原占位符；占位符对应目录未知时返回错误
+    pub fn resolve(&self, value: &str) -> Result<String, String> {
+        let trimmed = value.trim();
+        let Some((placeholder, base)) = self
+            .entries()
+            .into_iter()
+            .find(|(placeholder, _)| trimmed.starts_with(placeholder))
+        else {
+            return Ok(value.to_string());
+        };
+
+        let rest = trimmed[placeholder.len()..].trim_start_matches(['/', '\\']);
+        let base = base.ok_or_else(|| format!("{} 在本机没有对应目录", placeholder))?;
+        let resolved = rest
+            .split(['/', '\\'])
+            .filter(|segment| !segment.is_empty())
+            .fold(base.to_path_buf(), |path, segment| path.join(segment));
+        Ok(resolved.display().to_string())
+    }
+}
+
+/// 由给定配置生成导出文件
+pub fn export_bundle(
+    config: &Config,
+    profiles: &BTreeMap<String, Config>,
+    placeholders: &PathPlaceholders,
+) -> ConfigBundle {
+    let portable = |config: &Config| {
+        let mut config = config.clone();
+        for field in PATH_FIELDS {
+            if let Some(value) = path_field_mut(&mut config, field).as_mut() {
+                *value = placeholders.to_portable(value);
+            }
+        }
+        config.shared_secret = None;
+        config
+    };
+
+    ConfigBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at_unix_ms: now_unix_ms(),
+        config: portable(config),
+        profiles: profiles
+            .iter()
+            .map(|(name, profile)| {
+                let mut profile = portable(profile);
+                profile.active_profile = None;
+                (name.clone(), profile)
+            })
+            .collect(),
+    }
+}
+
+/// 读取当前配置并导出到文件
+pub fn export_to_file(game_dir: Option<&Path>, file: &Path) -> Result<ConfigBundle, String> {
+    let bundle = export_from_targets(
+        &profile_targets(game_dir),
+        &PathPlaceholders::current(game_dir),
+    )?;
+    write_bundle(file, &bundle)?;
+    Ok(bundle)
+}
+
+/// 写入导出文件
+pub fn write_bundle(file: &Path, bundle: &ConfigBundle) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(bundle).map_err(|e| format!("序列化导出文件失败: {}", e))?;
+    fs::write(file, json).map_err(|e| format!("写入 {} 失败: {}", file.display(), e))
+}
+
+/// 读取导出文件
+pub fn read_bundle(file: &Path) -> Result<ConfigBundle, String> {
+    let content =
+        fs::read_to_string(file).map_err(|e| format!("读取 {} 失败: {}", file.display(), e))?;
+    parse_bundle(&content)
+}
+
+/// 解析导出文件内容
+pub fn parse_bundle(content: &str) -> Result<ConfigBundle, String> {
+    let bundle: ConfigBundle = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("导出文件格式错误: {}", e))?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(format!(
+            "不是 GugaURA 配置导出文件: format={}",
+            bundle.format
+        ));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "导出文件版本 {} 高于当前支持的版本 {}，请升级配置工具",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+    Ok(bundle)
+}
+
+/// 生成导入预览：还原占位符，并与当前配置逐字段比较
+pub fn preview_import(
+    bundle: &ConfigBundle,
+    game_dir: Option<&Path>,
+) -> Result<ImportPreview, String> {
+    preview_import_in(
+        bundle,
+        &profile_targets(game_dir),
+        &PathPlaceholders::current(game_dir),
+    )
+}
+
+/// 按预览写入主配置与方案（游戏目录与 EXE 同级保持一致）
+pub fn apply_import(preview: &ImportPreview, game_dir: Option<&Path>) -> Result<(), String> {
+    apply_import_in(preview, &profile_targets(game_dir))
+}
+
+fn export_from_targets(
+    targets: &[PathBuf],
+    placeholders: &PathPlaceholders,
+) -> Result<ConfigBundle, String> {
+    let primary = targets
+        .first()
+        .ok_or_else(|| "没有可用的配置目录".to_string())?;
+    let config = load_main_config(primary)?;
+
+    let mut profiles = BTreeMap::new();
+    for name in config_profiles::list_profiles(primary) {
+        let profile = config_profiles::load_profile(primary, &name)?;
+        profiles.insert(name, profile);
+    }
+
+    Ok(export_bundle(&config, &profiles, placeholders))
+}
+
+fn preview_import_in(
+    bundle: &ConfigBundle,
+    targets: &[PathBuf],
+    placeholders: &PathPlaceholders,
+) -> Result<ImportPreview, String> {
+    let primary = targets
+        .first()
+        .ok_or_else(|| "没有可用的配置目录".to_string())?;
+    let mut unresolved = Vec::new();
+
+    let mut config = resolve_paths(&bundle.config, placeholders, "主配置", &mut unresolved);
+    let keeps_active_profile = config
+        .active_profile
+        .as_deref()
+        .is_some_and(|name| bundle.profiles.contains_key(name));
+    if !keeps_active_profile {
+        config.active_profile = None;
+    }
+    let current = load_main_config(primary).unwrap_or_default();
+    config.shared_secret = current.shared_secret.clone();
+
+    let mut profiles = Vec::with_capacity(bundle.profiles.len());
+    for (name, profile) in &bundle.profiles {
+        let name = config_profiles::normalize_profile_name(name)?;
+        let mut resolved = resolve_paths(profile, placeholders, &name, &mut unresolved);
+        let existing = config_profiles::load_profile(primary, &name).ok();
+        // 新方案沿用本机主配置的密钥，激活后签名不会被关闭
+        resolved.shared_secret = existing
+            .as_ref()
+            .map_or(&current.shared_secret, |existing| &existing.shared_secret)
+            .clone();
+        let changes = existing
+            .as_ref()
+            .map(|existing| {
+                let mut existing = existing.clone();
+                existing.active_profile = None;
+                diff_local_configs(&existing, &resolved)
+            })
+            .unwrap_or_default();
+        profiles.push(ProfileImportPreview {
+            exists: existing.is_some(),
+            name,
+            changes,
+            config: resolved,
+        });
+    }
+
+    Ok(ImportPreview {
+        changes: diff_local_configs(&current, &config),
+        validation: config.validate(),
+        config,
+        profiles,
+        unresolved,
+    })
+}
+
+fn diff_local_configs(old: &Config, new: &Config) -> Vec<FieldChange> {
+    let mut changes = diff_configs(old, new);
+    changes.retain(|change| !LOCAL_ONLY_FIELDS.contains(&change.field.as_str()));
+    changes
+}
+
+fn apply_import_in(preview: &ImportPreview, targets: &[PathBuf]) -> Result<(), String> {
+    if let Some(issue) = preview.validation.errors().next() {
+        return Err(format!("导入的配置无效: {}", issue));
+    }
+
+    for target in targets {
+        // 先写方案，保证主配置引用的方案在 DLL 热重载时已经存在
+        for profile in &preview.profiles {
+            config_profiles::save_profile(target, &profile.name, &profile.config)?;
+        }
+        preview.config.save_file(target)?;
+    }
+    Ok(())
+}
+
+fn resolve_paths(
+    config: &Config,
+    placeholders: &PathPlaceholders,
+    scope: &str,
+    unresolved: &mut Vec<String>,
+) -> Config {
+    let mut config = config.clone();
+    for field in PATH_FIELDS {
+        let slot = path_field_mut(&mut config, field);
+        let Some(value) = slot.as_deref() else {
+            continue;
+        };
+        match placeholders.resolve(value) {
+            Ok(resolved) => *slot = Some(resolved),
+            Err(error) => {
+                unresolved.push(format!("{} {} = {}: {}", scope, field, value, error));
+                *slot = None;
+            }
+        }
+    }
+    config
+}
+
+fn load_main_config(path: &Path) -> Result<Config, String> {
+    if path.exists() {
+        Config::load_file(path)
+    } else {
+        Ok(Config::default())
+    }
+}
+
+fn path_field_mut<'a>(config: &'a mut Config, field: &str) -> &'a mut Option<String> {
+    match field {
+        "debug_output_dir" => &mut config.debug_output_dir,
+        "fans_output_dir" => &mut config.fans_output_dir,
+        "stallion_output_dir" => &mut config.stallion_output_dir,
+        _ => unreachable!("未知的路径字段: {}", field),
+    }
+}
+
+/// `path` 位于 `base` 下时返回剩余的路径段（Windows 下不区分大小写）
+fn strip_base(path: &Path, base: &Path) -> Option<Vec<String>> {
+    let mut path_components = path.components();
+    for base_component in base.components() {
+        let path_component = path_components.next()?;
+        if !same_component(path_component, base_component) {
+            return None;
+        }
+    }
+
+    Some(
+        path_components
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect(),
+    )
+}
+
+fn same_component(left: Component<'_>, right: Component<'_>) -> bool {
+    if cfg!(windows) {
+        left.as_os_str()
+            .to_string_lossy()
+            .eq_ignore_ascii_case(&right.as_os_str().to_string_lossy())
+    } else {
+        left == right
+    }
+}
+
+fn documents_dir() -> Option<PathBuf> {
+    let home = if cfg!(windows) {
+        std::env::var_os("USERPROFILE")
+    } else {
+        std::env::var_os("HOME")
+    }?;
+    Some(PathBuf::from(home).join("Documents"))
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(root: &Path) -> PathPlaceholders {
+        PathPlaceholders {
+            game_dir: Some(root.join("Documents").join("Umamusume")),
+            exe_dir: Some(root.join("tools").join("gugaura")),
+            documents: Some(root.join("Documents")),
+        }
+    }
+
+    fn setup() -> (tempfile::TempDir, Vec<PathBuf>) {
+        let root = tempfile::tempdir().expect("创建临时目录失败");
+        let game_dir = root.path().join("game");
+        let exe_dir = root.path().join("exe");
+        fs::create_dir_all(&game_dir).expect("创建游戏目录失败");
+        fs::create_dir_all(&exe_dir).expect("创建 EXE 目录失败");
+        let targets = vec![
+            Config::config_path(&game_dir),
+            Config::config_path(&exe_dir),
+        ];
+        (root, targets)
+    }
+
+    #[test]
+    fn to_portable_should_prefer_deepest_known_dir() {
+        let root = Path::new("/home/trainer");
+        let placeholders = placeholders(root);
+
+        let game = root.join("Documents").join("Umamusume").join("fans");
+        let docs = root.join("Documents").join("uma").join("stallion");
+        let exe = root.join("tools").join("gugaura");
+
+        assert_eq!(
+            placeholders.to_portable(&game.display().to_string()),
+            "{game_dir}/fans"
+        );
+        assert_eq!(
+            placeholders.to_portable(&docs.display().to_string()),
+            "{documents}/uma/stallion"
+        );
+        assert_eq!(
+            placeholders.to_portable(&exe.display().to_string()),
+            "{exe_dir}"
+        );
+        assert_eq!(placeholders.to_portable("/srv/data"), "/srv/data");
+        assert_eq!(placeholders.to_portable("fans"), "fans");
+    }
+
+    #[test]
+    fn resolve_should_map_placeholders_to_new_machine() {
+        let placeholders = placeholders(Path::new("/mnt/new"));
+
+        assert_eq!(
+            placeholders.resolve("{game_dir}/fans").map(PathBuf::from),
+            Ok(Path::new("/mnt/new/Documents/Umamusume/fans").to_path_buf())
+        );
+        assert_eq!(
+            placeholders.resolve("/srv/data"),
+            Ok("/srv/data".to_string())
+        );
+        assert!(PathPlaceholders::default()
+            .resolve("{exe_dir}/debug")
+            .is_err());
+    }
+
+    #[test]
+    fn export_and_import_should_round_trip_between_machines() {
+        let old_root = Path::new("/old");
+        let old = placeholders(old_root);
+        let config = Config {
+            fans_output_dir: Some(
+                old_root
+                    .join("Documents")
+                    .join("Umamusume")
+                    .join("fans")
+                    .display()
+                    .to_string(),
+            ),
+            relay_enabled: true,
+            relay_target_host: Some("http://10.0.0.2:4693".to_string()),
+            active_profile: Some("team".to_string()),
+            ..Config::default()
+        };
+        let mut profiles = BTreeMap::new();
+        profiles.insert(
+            "team".to_string(),
+            Config {
+                stallion_output_dir: Some(
+                    old_root
+                        .join("tools")
+                        .join("gugaura")
+                        .join("out")
+                        .display()
+                        .to_string(),
+                ),
+                active_profile: Some("team".to_string()),
+                ..Config::default()
+            },
+        );
+
+        let bundle = export_bundle(&config, &profiles, &old);
+        let content = serde_json::to_string(&bundle).expect("序列化失败");
+        assert!(!content.contains("/old/"), "导出文件不应包含本机绝对路径");
+        assert_eq!(bundle.profiles["team"].active_profile, None);
+
+        let (new_root, targets) = setup();
+        let new = PathPlaceholders {
+            game_dir: targets[0].parent().map(Path::to_path_buf),
+            exe_dir: targets[1].parent().map(Path::to_path_buf),
+            documents: None,
+        };
+        let bundle = parse_bundle(&content).expect("解析失败");
+        let preview = preview_import_in(&bundle, &targets, &new).expect("预览失败");
+
+        assert!(preview.unresolved.is_empty(), "{:?}", preview.unresolved);
+        assert_eq!(
+            preview.config.fans_output_dir.as_deref().map(PathBuf::from),
+            Some(new_root.path().join("game").join("fans"))
+        );
+        let changed: Vec<&str> = preview.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            changed,
+            vec![
+                "active_profile",
+                "fans_output_dir",
+                "relay_enabled",
+                "relay_target_host"
+            ]
+        );
+        assert!(!preview.profiles[0].exists);
+
+        apply_import_in(&preview, &targets).expect("导入失败");
+        for target in &targets {
+            let effective = Config::load_file_or_default(target);
+            assert_eq!(effective.active_profile.as_deref(), Some("team"));
+            assert_eq!(
+                effective.stallion_output_dir.as_deref().map(PathBuf::from),
+                Some(new_root.path().join("exe").join("out"))
+            );
+        }
+    }
+
+    #[test]
+    fn shared_secret_should_stay_on_local_machine() {
+        let with_secret = |secret: &str| Config {
+            shared_secret: Some(secret.to_string()),
+            ..Config::default()
+        };
+        let mut profiles = BTreeMap::new();
+        profiles.insert("team".to_string(), with_secret("profile-secret-0123456"));
+
+        let bundle = export_bundle(
+            &with_secret("exported-secret-012345"),
+            &profiles,
+            &PathPlaceholders::default(),
+        );
+        let content = serde_json::to_string(&bundle).expect("序列化失败");
+        assert!(!content.contains("secret-0"), "导出文件不应包含签名密钥");
+
+        let (_root, targets) = setup();
+        with_secret("local-secret-0123456789")
+            .save_file(&targets[0])
+            .expect("保存本机配置失败");
+        // 手工编辑过的导出文件即使带有密钥也不会覆盖本机值
+        let mut bundle = parse_bundle(&content).expect("解析失败");
+        bundle.config.shared_secret = Some("imported-secret-012345".to_string());
+        let preview =
+            preview_import_in(&bundle, &targets, &PathPlaceholders::default()).expect("预览失败");
+
+        assert_eq!(
+            preview.config.shared_secret.as_deref(),
+            Some("local-secret-0123456789")
+        );
+        assert_eq!(
+            preview.profiles[0].config.shared_secret.as_deref(),
+            Some("local-secret-0123456789")
+        );
+        assert!(preview
+            .changes
+            .iter()
+            .all(|change| change.field != "shared_secret"));
+    }
+
+    #[test]
+    fn unresolved_placeholder_should_clear_field_and_be_reported() {
+        let (_root, targets) = setup();
+        let bundle = ConfigBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at_unix_ms: 0,
+            config: Config {
+                debug_output_dir: Some("{documents}/uma_debug".to_string()),
+                active_profile: Some("missing".to_string()),
+                ..Config::default()
+            },
+            profiles: BTreeMap::new(),
+        };
+
+        let preview =
+            preview_import_in(&bundle, &targets, &PathPlaceholders::default()).expect("预览失败");
+
+        assert_eq!(preview.config.debug_output_dir, None);
+        assert_eq!(preview.config.active_profile, None);
+        assert_eq!(preview.unresolved.len(), 1);
+        assert!(preview.unresolved[0].contains("debug_output_dir"));
+    }
+
+    #[test]
+    fn parse_bundle_should_reject_foreign_or_newer_files() {
+        assert!(parse_bundle(r#"{"notifier_host":"http://127.0.0.1:4693"}"#).is_err());
+
+        let mut bundle = export_bundle(
+            &Config::default(),
+            &BTreeMap::new(),
+            &PathPlaceholders::default(),
+        );
+        bundle.version = BUNDLE_VERSION + 1;
+        let content = serde_json::to_string(&bundle).expect("序列化失败");
+        assert!(parse_bundle(&content)
+            .expect_err("应拒绝新版本")
+            .contains("升级配置工具"));
+    }
+
+    #[test]
+    fn apply_should_refuse_invalid_config() {
+        let (_root, targets) = setup();
+        let bundle = export_bundle(
+            &Config {
+                timeout_ms: 0,
+                ..Config::default()
+            },
+            &BTreeMap::new(),
+            &PathPlaceholders::default(),
+        );
+
+        let preview =
+            preview_import_in(&bundle, &targets, &PathPlaceholders::default()).expect("预览失败");
+
+        assert!(apply_import_in(&preview, &targets).is_err());
+        assert!(!targets[0].exists());
+    }
+}
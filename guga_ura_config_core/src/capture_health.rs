@@ -0,0 +1,130 @@
+//! DLL 捕获状态
+//!
+//! 内置 Receiver 与独立 Receiver 共用：保存 DLL 通过 `POST /status` 上报的最近
+//! 一份状态，供 `GET /status` 与配置工具读取。状态只保存在内存中。
+
+use guga_ura_transport::health::CaptureHealth;
+use guga_ura_transport::signing;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+
+pub use guga_ura_transport::health::STATUS_ROUTE;
+
+static STORE: OnceLock<CaptureHealthStore> = OnceLock::new();
+
+/// 对外展示的状态：DLL 未上报过时 `report` 为空
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaptureHealthView {
+    /// 最近一次心跳未超时
+    pub online: bool,
+    pub received_at_unix_ms: Option<u64>,
+    /// 距最近一次心跳的时长
+    pub age_ms: Option<u64>,
+    pub report: Option<CaptureHealth>,
+}
+
+/// 最近一份状态
+#[derive(Debug, Default)]
+pub struct CaptureHealthStore {
+    latest: Mutex<Option<(CaptureHealth, u64)>>,
+}
+
+impl CaptureHealthStore {
+    /// 解析并保存一份状态
+    pub fn record(&self, body: &[u8], received_at_unix_ms: u64) -> Result<(), String> {
+        let report = CaptureHealth::parse(body)?;
+        if let Ok(mut guard) = self.latest.lock() {
+            *guard = Some((report, received_at_unix_ms));
+        }
+        Ok(())
+    }
+
+    pub fn view(&self, now_unix_ms: u64) -> CaptureHealthView {
+        let latest = self.latest.lock().ok().and_then(|guard| guard.clone());
+        match latest {
+            Some((report, received_at)) => CaptureHealthView {
+                online: !CaptureHealth::is_stale(received_at, now_unix_ms),
+                received_at_unix_ms: Some(received_at),
+                age_ms: Some(now_unix_ms.saturating_sub(received_at)),
+                report: Some(report),
+            },
+            None => CaptureHealthView {
+                online: false,
+                received_at_unix_ms: None,
+                age_ms: None,
+                report: None,
+            },
+        }
+    }
+}
+
+/// 是否为状态路由（忽略查询串与末尾斜杠）
+pub fn is_status_route(route: &str) -> bool {
+    route
+        .split(['?', '#'])
+        .next()
+        .map(|path| path.trim_end_matches('/') == STATUS_ROUTE)
+        .unwrap_or(false)
+}
+
+/// 保存本进程 Receiver 收到的状态
+pub fn record_capture_health(body: &[u8]) -> Result<(), String> {
+    store().record(body, signing::now_unix_ms())
+}
+
+/// 本进程 Receiver 收到的最近一份状态
+pub fn capture_health_view() -> CaptureHealthView {
+    store().view(signing::now_unix_ms())
+}
+
+fn store() -> &'static CaptureHealthStore {
+    STORE.get_or_init(CaptureHealthStore::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use guga_ura_transport::health::HEARTBEAT_STALE_AFTER_MS;
+
+    #[test]
+    fn store_should_keep_latest_report_and_track_staleness() {
+        let store = CaptureHealthStore::default();
+        assert_eq!(store.view(1_000).report, None);
+        assert!(!store.view(1_000).online);
+
+        store
+            .record(br#"{"dll_version":"2.0.5","heartbeat_seq":1}"#, 1_000)
+            .expect("保存状态失败");
+        store
+            .record(br#"{"dll_version":"2.0.6","heartbeat_seq":2}"#, 2_000)
+            .expect("保存状态失败");
+
+        let view = store.view(2_500);
+        assert!(view.online);
+        assert_eq!(view.age_ms, Some(500));
+        let report = view.report.expect("缺少状态");
+        assert_eq!(report.dll_version, "2.0.6");
+        assert_eq!(report.heartbeat_seq, 2);
+
+        assert!(!store.view(2_001 + HEARTBEAT_STALE_AFTER_MS).online);
+    }
+
+    #[test]
+    fn invalid_report_should_not_replace_latest() {
+        let store = CaptureHealthStore::default();
+        store
+            .record(br#"{"dll_version":"2.0.6"}"#, 1_000)
+            .expect("保存状态失败");
+
+        assert!(store.record(b"garbage", 2_000).is_err());
+        assert_eq!(store.view(2_000).received_at_unix_ms, Some(1_000));
+    }
+
+    #[test]
+    fn status_route_should_ignore_query_and_trailing_slash() {
+        assert!(is_status_route("/status"));
+        assert!(is_status_route("/status/?from=ui"));
+        assert!(!is_status_route("/status/extra"));
+        assert!(!is_status_route("/notify/status"));
+    }
+}
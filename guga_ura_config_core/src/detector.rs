@@ -1,6 +1,9 @@
 //! 游戏版本检测
 
+pub mod vdf;
+
 use std::path::{Path, PathBuf};
+use vdf::VdfValue;
 
 /// 游戏版本
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +14,15 @@ pub enum GameVersion {
 }
 
 impl GameVersion {
+    /// 写入安装清单等文件时使用的标识
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameVersion::Steam => "steam",
+            GameVersion::DMM => "dmm",
+            GameVersion::Unknown => "unknown",
+        }
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             GameVersion::Steam => "Steam 版",
@@ -189,131 +201,181 @@ pub fn scan_installed_games() -> Vec<DetectedGame> {
     games
 }
 
-/// Steam 游戏可能的目录名列表
-const STEAM_GAME_DIRS: &[&str] = &[
-    "Umamusume Pretty Derby",
-    "UmamusumePrettyDerby",
-    "UmamusumePrettyDerby_Jpn",
-    "umamusume",
-];
+/// 已知的 Steam 版 app id；不在列表中的 app 按 appmanifest 中的名称识别
+const STEAM_APP_IDS: &[&str] = &["3224770"];
 
-/// 在指定的 Steam 库路径中查找游戏
-fn find_game_in_steam_library(lib_path: &Path, games: &mut Vec<DetectedGame>) {
-    let common_dir = lib_path.join("steamapps").join("common");
-    if !common_dir.exists() {
-        return;
-    }
+/// Steam 库目录，来自 `libraryfolders.vdf`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteamLibrary {
+    pub path: PathBuf,
+    /// 库中已安装的 app id；旧格式的 `libraryfolders.vdf` 不含这一项
+    pub app_ids: Vec<String>,
+}
 
-    // 首先尝试已知的目录名
-    for dir_name in STEAM_GAME_DIRS {
-        let game_path = common_dir.join(dir_name);
-        if is_valid_game_dir(&game_path) {
-            if !games.iter().any(|g: &DetectedGame| g.path == game_path) {
-                games.push(DetectedGame {
-                    path: game_path,
-                    version: GameVersion::Steam,
-                });
-            }
-            return;
-        }
-    }
+/// `appmanifest_<appid>.acf` 中用到的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteamAppManifest {
+    pub app_id: String,
+    pub name: String,
+    /// `steamapps/common/` 下的目录名
+    pub install_dir: String,
+}
 
-    // 如果已知目录名都没找到，遍历 common 目录查找
-    if let Ok(entries) = std::fs::read_dir(&common_dir) {
-        for entry in entries.filter_map(Result::ok) {
-            let dir_name = entry.file_name().to_string_lossy().to_lowercase();
-            // 匹配包含 "umamusume" 或 "uma" + "derby" 的目录
-            if dir_name.contains("umamusume")
-                || (dir_name.contains("uma") && dir_name.contains("derby"))
-            {
-                let game_path = entry.path();
-                if is_valid_game_dir(&game_path) {
-                    if !games.iter().any(|g: &DetectedGame| g.path == game_path) {
-                        games.push(DetectedGame {
-                            path: game_path,
-                            version: GameVersion::Steam,
-                        });
-                    }
-                }
-            }
-        }
-    }
+/// 解析 `libraryfolders.vdf`，兼容新格式（`"0" { "path" ... "apps" {...} }`）
+/// 与旧格式（`"1" "D:\\SteamLibrary"`）
+pub fn parse_library_folders(text: &str) -> Result<Vec<SteamLibrary>, String> {
+    let root = vdf::parse(text)?;
+    let folders = root
+        .get("libraryfolders")
+        .ok_or_else(|| "libraryfolders.vdf 缺少 libraryfolders 节点".to_string())?;
+
+    let libraries = folders
+        .entries()
+        .iter()
+        .filter(|(key, _)| key.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(_, value)| match value {
+            VdfValue::String(path) => Some(SteamLibrary {
+                path: PathBuf::from(path),
+                app_ids: Vec::new(),
+            }),
+            VdfValue::Object(_) => value.get_str("path").map(|path| SteamLibrary {
+                path: PathBuf::from(path),
+                app_ids: value
+                    .get("apps")
+                    .map(|apps| apps.entries().iter().map(|(id, _)| id.clone()).collect())
+                    .unwrap_or_default(),
+            }),
+        })
+        .collect();
+    Ok(libraries)
 }
 
-/// 扫描 Steam 游戏库
-fn scan_steam_games() -> Option<Vec<DetectedGame>> {
-    let mut games = Vec::new();
-    let mut library_paths: Vec<PathBuf> = Vec::new();
+/// 解析 `appmanifest_<appid>.acf`
+pub fn parse_app_manifest(text: &str) -> Result<SteamAppManifest, String> {
+    let root = vdf::parse(text)?;
+    let state = root
+        .get("AppState")
+        .ok_or_else(|| "appmanifest 缺少 AppState 节点".to_string())?;
+    let field = |key: &str| {
+        state
+            .get_str(key)
+            .map(str::to_string)
+            .ok_or_else(|| format!("appmanifest 缺少 {} 字段", key))
+    };
 
-    // 从注册表获取 Steam 安装路径
-    #[cfg(windows)]
+    Ok(SteamAppManifest {
+        app_id: field("appid")?,
+        name: state.get_str("name").unwrap_or_default().to_string(),
+        install_dir: field("installdir")?,
+    })
+}
+
+fn steam_app_matches(manifest: &SteamAppManifest) -> bool {
+    STEAM_APP_IDS.contains(&manifest.app_id.as_str()) || dmm_keyword_matches(&manifest.name)
+}
+
+/// 读取 Steam 根目录下的库列表；Steam 根目录本身总是第一个库
+fn steam_libraries(steam_root: &Path) -> Vec<SteamLibrary> {
+    let vdf_path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    let mut libraries = std::fs::read_to_string(vdf_path)
+        .ok()
+        .and_then(|text| parse_library_folders(&text).ok())
+        .unwrap_or_default();
+
+    let root = normalize_path_for_compare(steam_root);
+    if !libraries
+        .iter()
+        .any(|library| normalize_path_for_compare(&library.path) == root)
     {
-        use winreg::enums::*;
-        use winreg::RegKey;
-
-        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-
-        // 尝试 64 位和 32 位路径
-        let steam_paths = [
-            hklm.open_subkey(r"SOFTWARE\Valve\Steam"),
-            hklm.open_subkey(r"SOFTWARE\WOW6432Node\Valve\Steam"),
-            hkcu.open_subkey(r"SOFTWARE\Valve\Steam"),
-        ];
-
-        for result in steam_paths {
-            if let Ok(key) = result {
-                let path: String = match key.get_value("InstallPath") {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-                let steam_path = PathBuf::from(&path);
-
-                // 添加默认库路径
-                library_paths.push(steam_path.clone());
-
-                // 解析 libraryfolders.vdf 获取其他库路径
-                let vdf_path = steam_path.join("steamapps").join("libraryfolders.vdf");
-                if let Ok(content) = std::fs::read_to_string(&vdf_path) {
-                    for line in content.lines() {
-                        // 解析 "path" 行
-                        if line.contains("\"path\"") {
-                            // 格式: "path"		"G:\\SteamLibrary"
-                            let parts: Vec<&str> = line.split('"').collect();
-                            if parts.len() >= 4 {
-                                let lib_path = parts[3].replace("\\\\", "\\");
-                                let lib_pathbuf = PathBuf::from(&lib_path);
-                                if lib_pathbuf.exists() && !library_paths.contains(&lib_pathbuf) {
-                                    library_paths.push(lib_pathbuf);
-                                }
-                            }
-                        }
-                    }
-                }
+        libraries.insert(
+            0,
+            SteamLibrary {
+                path: steam_root.to_path_buf(),
+                app_ids: Vec::new(),
+            },
+        );
+    }
+    libraries
+}
+
+/// 按 app id 读取库中的 appmanifest，返回匹配游戏的安装目录
+fn find_games_in_steam_library(library: &SteamLibrary) -> Vec<PathBuf> {
+    let steamapps = library.path.join("steamapps");
+    let app_ids = if library.app_ids.is_empty() {
+        app_ids_from_manifest_files(&steamapps)
+    } else {
+        library.app_ids.clone()
+    };
+
+    app_ids
+        .iter()
+        .filter_map(|app_id| {
+            let path = steamapps.join(format!("appmanifest_{}.acf", app_id));
+            let text = std::fs::read_to_string(path).ok()?;
+            parse_app_manifest(&text).ok()
+        })
+        .filter(steam_app_matches)
+        .map(|manifest| steamapps.join("common").join(manifest.install_dir))
+        .collect()
+}
 
-                break;
+fn app_ids_from_manifest_files(steamapps: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(steamapps) else {
+        return Vec::new();
+    };
+    let mut app_ids: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix("appmanifest_")?
+                .strip_suffix(".acf")
+                .map(str::to_string)
+        })
+        .collect();
+    app_ids.sort();
+    app_ids
+}
+
+/// 从注册表获取 Steam 安装路径
+#[cfg(windows)]
+fn steam_install_paths() -> Vec<PathBuf> {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+
+    // 尝试 64 位和 32 位路径
+    let steam_keys = [
+        hklm.open_subkey(r"SOFTWARE\Valve\Steam"),
+        hklm.open_subkey(r"SOFTWARE\WOW6432Node\Valve\Steam"),
+        hkcu.open_subkey(r"SOFTWARE\Valve\Steam"),
+    ];
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for key in steam_keys.into_iter().flatten() {
+        for name in ["InstallPath", "SteamPath"] {
+            if let Ok(path) = key.get_value::<String, _>(name) {
+                push_path_candidate(&mut paths, &path);
             }
         }
     }
+    paths
+}
 
-    // 在所有库路径中查找游戏
-    for lib_path in library_paths {
-        find_game_in_steam_library(&lib_path, &mut games);
-    }
-
-    // 额外扫描常见 Steam 库位置
-    let common_steam_libs = [
-        r"C:\SteamLibrary",
-        r"D:\SteamLibrary",
-        r"E:\SteamLibrary",
-        r"F:\SteamLibrary",
-        r"G:\SteamLibrary",
-    ];
+#[cfg(not(windows))]
+fn steam_install_paths() -> Vec<PathBuf> {
+    Vec::new()
+}
 
-    for lib in common_steam_libs {
-        let lib_path = PathBuf::from(lib);
-        if lib_path.exists() {
-            find_game_in_steam_library(&lib_path, &mut games);
+/// 扫描 Steam 游戏库
+fn scan_steam_games() -> Option<Vec<DetectedGame>> {
+    let mut games = Vec::new();
+    for steam_root in steam_install_paths() {
+        for library in steam_libraries(&steam_root) {
+            for game_path in find_games_in_steam_library(&library) {
+                add_game_if_new(&mut games, game_path, GameVersion::Steam);
+            }
         }
     }
 
@@ -718,7 +780,8 @@ fn push_parent_path_candidate(paths: &mut Vec<PathBuf>, path: &str) {
 mod tests {
     use super::{
         collect_dmm_config_candidates, detect_game_version, extract_registry_path_candidates,
-        is_valid_game_dir, scan_game_dirs_under, DetectedGame, GameVersion,
+        find_games_in_steam_library, is_valid_game_dir, parse_app_manifest, parse_library_folders,
+        scan_game_dirs_under, steam_libraries, DetectedGame, GameVersion, SteamLibrary,
     };
     use std::fs;
     use std::path::PathBuf;
@@ -800,4 +863,131 @@ mod tests {
         assert_eq!(games[0].path, game_dir);
         assert_eq!(games[0].version, GameVersion::DMM);
     }
+
+    const LIBRARY_FOLDERS_VDF: &str = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"6102034961434406339"
+		"totalsize"		"0"
+		"apps"
+		{
+			"228980"		"459478541"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"label"		"Games \"SSD\""
+		"apps"
+		{
+			"3224770"		"23621672960"
+			"1245620"		"60453920512"
+		}
+	}
+}
+"#;
+
+    const APP_MANIFEST_ACF: &str = r#"
+"AppState"
+{
+	"appid"		"3224770"
+	"universe"		"1"
+	"name"		"Umamusume: Pretty Derby"
+	"StateFlags"		"4"
+	"installdir"		"UmamusumePrettyDerby"
+	"InstalledDepots"
+	{
+		"3224771"
+		{
+			"manifest"		"8125046154839409541"
+			"size"		"23621672960"
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn parse_library_folders_should_read_paths_and_apps() {
+        let libraries = parse_library_folders(LIBRARY_FOLDERS_VDF).expect("解析失败");
+
+        assert_eq!(
+            libraries,
+            vec![
+                SteamLibrary {
+                    path: PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                    app_ids: vec!["228980".to_string()],
+                },
+                SteamLibrary {
+                    path: PathBuf::from(r"D:\SteamLibrary"),
+                    app_ids: vec!["3224770".to_string(), "1245620".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_library_folders_should_accept_legacy_format() {
+        let text = r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1700000000"
+	"ContentStatsID"		"-123"
+	"1"		"E:\\Games\\Steam"
+}
+"#;
+        let libraries = parse_library_folders(text).expect("解析失败");
+
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].path, PathBuf::from(r"E:\Games\Steam"));
+        assert!(libraries[0].app_ids.is_empty());
+    }
+
+    #[test]
+    fn parse_app_manifest_should_read_install_dir() {
+        let manifest = parse_app_manifest(APP_MANIFEST_ACF).expect("解析失败");
+
+        assert_eq!(manifest.app_id, "3224770");
+        assert_eq!(manifest.name, "Umamusume: Pretty Derby");
+        assert_eq!(manifest.install_dir, "UmamusumePrettyDerby");
+        assert!(parse_app_manifest("\"AppState\" { \"appid\" \"1\" }").is_err());
+    }
+
+    #[test]
+    fn steam_library_should_resolve_install_dir_by_app_id() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let steamapps = dir.path().join("steamapps");
+        fs::create_dir_all(&steamapps).expect("创建 steamapps 失败");
+        fs::write(steamapps.join("appmanifest_3224770.acf"), APP_MANIFEST_ACF)
+            .expect("写入 appmanifest 失败");
+        fs::write(
+            steamapps.join("appmanifest_1245620.acf"),
+            "\"AppState\" { \"appid\" \"1245620\" \"name\" \"ELDEN RING\" \"installdir\" \"ELDEN RING\" }",
+        )
+        .expect("写入 appmanifest 失败");
+        // 目录名与游戏无关的库也能按 manifest 找到
+        let vdf = format!(
+            "\"libraryfolders\" {{ \"0\" {{ \"path\" \"{}\" }} }}",
+            dir.path().display().to_string().replace('\\', "\\\\")
+        );
+        fs::write(steamapps.join("libraryfolders.vdf"), vdf).expect("写入 vdf 失败");
+
+        let libraries = steam_libraries(dir.path());
+        assert_eq!(libraries.len(), 1);
+
+        let expected = steamapps.join("common").join("UmamusumePrettyDerby");
+        assert_eq!(
+            find_games_in_steam_library(&libraries[0]),
+            vec![expected.clone()]
+        );
+
+        let listed = SteamLibrary {
+            path: dir.path().to_path_buf(),
+            app_ids: vec!["1245620".to_string()],
+        };
+        assert!(find_games_in_steam_library(&listed).is_empty());
+    }
 }
@@ -0,0 +1,245 @@
+//! Valve KeyValues 文本格式（VDF / ACF）解析
+//!
+//! 只覆盖 Steam 客户端文件用到的部分：带引号或不带引号的键值、嵌套的 `{}`、
+//! `\\` `\"` `\n` `\t` 转义、`//` 注释，以及值后面的 `[$WIN32]` 条件标记（忽略）。
+//! 键按 KeyValues 的约定不区分大小写。
+
+/// 键值节点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfValue {
+    String(String),
+    /// 保留原始顺序；同名键可重复出现
+    Object(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+    /// 查找第一个同名子节点（不区分大小写）
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        self.entries()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(VdfValue::as_str)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::String(value) => Some(value),
+            VdfValue::Object(_) => None,
+        }
+    }
+
+    /// 子节点；字符串节点没有子节点
+    pub fn entries(&self) -> &[(String, VdfValue)] {
+        match self {
+            VdfValue::Object(entries) => entries,
+            VdfValue::String(_) => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+/// 解析整个文件，返回顶层键值组成的对象
+pub fn parse(text: &str) -> Result<VdfValue, String> {
+    let mut tokens = Tokenizer::new(text);
+    let entries = parse_entries(&mut tokens, false)?;
+    Ok(VdfValue::Object(entries))
+}
+
+fn parse_entries(tokens: &mut Tokenizer, nested: bool) -> Result<Vec<(String, VdfValue)>, String> {
+    let mut entries = Vec::new();
+    loop {
+        let key = match tokens.next()? {
+            Some(Token::Text(key)) => key,
+            Some(Token::Close) if nested => return Ok(entries),
+            Some(Token::Close) => return Err(tokens.error("多余的 '}'")),
+            Some(Token::Open) => return Err(tokens.error("'{' 前缺少键名")),
+            None if nested => return Err(tokens.error("缺少 '}'")),
+            None => return Ok(entries),
+        };
+
+        let value = match tokens.next()? {
+            Some(Token::Text(value)) => VdfValue::String(value),
+            Some(Token::Open) => VdfValue::Object(parse_entries(tokens, true)?),
+            Some(Token::Close) | None => {
+                return Err(tokens.error(&format!("键 \"{}\" 缺少值", key)));
+            }
+        };
+        entries.push((key, value));
+    }
+}
+
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.trim_start_matches('\u{feff}').chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("VDF 第 {} 行: {}", self.line, message)
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, String> {
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek().copied() {
+                None => return Ok(None),
+                Some('{') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                Some('"') => {
+                    self.chars.next();
+                    return self.quoted().map(|text| Some(Token::Text(text)));
+                }
+                Some('/') => {
+                    self.chars.next();
+                    if self.chars.peek() == Some(&'/') {
+                        self.skip_line();
+                        continue;
+                    }
+                    return Ok(Some(Token::Text(self.unquoted("/"))));
+                }
+                Some('[') => {
+                    // 条件标记，如 [$WIN32]
+                    self.skip_condition();
+                    continue;
+                }
+                Some(_) => return Ok(Some(Token::Text(self.unquoted("")))),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.peek().copied() {
+            if !c.is_whitespace() {
+                break;
+            }
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                self.line += 1;
+                break;
+            }
+        }
+    }
+
+    fn skip_condition(&mut self) {
+        for c in self.chars.by_ref() {
+            if c == ']' {
+                break;
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        let mut text = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok(text),
+                '\\' => match self.chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('\\') => text.push('\\'),
+                    Some('"') => text.push('"'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => break,
+                },
+                '\n' => {
+                    self.line += 1;
+                    text.push(c);
+                }
+                _ => text.push(c),
+            }
+        }
+        Err(self.error("字符串缺少结束引号"))
+    }
+
+    fn unquoted(&mut self, prefix: &str) -> String {
+        let mut text = prefix.to_string();
+        while let Some(c) = self.chars.peek().copied() {
+            if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_handle_nesting_escapes_and_comments() {
+        let text = r#"
+// Steam 生成的文件
+"Root"
+{
+    "path"      "D:\\SteamLibrary"
+    "quote"     "say \"hi\""
+    Unquoted    value   [$WIN32]
+    "empty"     {}
+    "child"
+    {
+        "Key"   "1"
+    }
+}
+"#;
+        let root = parse(text).expect("解析失败");
+        let node = root.get("root").expect("缺少 Root");
+
+        assert_eq!(node.get_str("PATH"), Some(r"D:\SteamLibrary"));
+        assert_eq!(node.get_str("quote"), Some(r#"say "hi""#));
+        assert_eq!(node.get_str("unquoted"), Some("value"));
+        assert_eq!(node.get("empty"), Some(&VdfValue::Object(Vec::new())));
+        assert_eq!(
+            node.get("child").and_then(|child| child.get_str("key")),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn parse_should_report_line_of_malformed_input() {
+        let unclosed = parse("\"a\"\n{\n\"b\" \"c\"\n").expect_err("缺少 } 应报错");
+        assert!(unclosed.contains("缺少 '}'"), "{}", unclosed);
+
+        let missing_value = parse("\"a\"\n{\n\"b\"\n}").expect_err("缺少值应报错");
+        assert!(missing_value.contains("第 4 行"), "{}", missing_value);
+
+        assert!(parse("\"a\" \"unterminated").is_err());
+        assert!(parse("}").is_err());
+    }
+}
@@ -0,0 +1,903 @@
+//! 声明式提取规则
+//!
+//! 从 EXE 同级的 `guga_ura_extractors.json` 读取用户自定义规则，与内置的
+//! fans / 种马提取并行运行。每条规则由三部分组成：
+//!
+//! - `when`：基于 JSON Pointer 的匹配条件（字段存在 / 等于某值）
+//! - `fields`：字段投影，支持提取、重命名和扁平化
+//! - `output`：输出路径模板与写入模式（append / upsert / new_file）
+//!
+//! 示例：
+//!
+//! ```json
+//! {
+//!   "rules": [{
+//!     "name": "circle_members",
+//!     "when": [{ "pointer": "/data/circle_info" }],
+//!     "each": "/data/circle_user_array",
+//!     "fields": [
+//!       { "from": "/viewer_id" },
+//!       { "from": "/membership", "to": "role" }
+//!     ],
+//!     "output": { "path": "circle/{date}.json", "mode": "upsert", "key": "viewer_id" }
+//!   }]
+//! }
+//! ```
+
+use guga_ura_config::time::TimeSettings;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub const EXTRACTOR_RULES_FILE_NAME: &str = "guga_ura_extractors.json";
+const DEFAULT_OUTPUT_DIR_NAME: &str = "extractor_output";
+
+static OUTPUT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+static RULES_CACHE: Mutex<Option<CachedRuleSet>> = Mutex::new(None);
+
+type Record = Map<String, Value>;
+type RecordGroup<'a> = (PathBuf, Vec<&'a Record>);
+
+/// 上次解析的规则文件；路径、修改时间与大小都未变化时直接复用
+struct CachedRuleSet {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    result: Result<Arc<ExtractorRuleSet>, String>,
+}
+
+/// 规则文件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtractorRuleSet {
+    /// 相对输出路径的根目录（为空时默认 EXE 同级 extractor_output/）
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    #[serde(default)]
+    pub rules: Vec<ExtractorRule>,
+}
+
+/// 单条提取规则
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtractorRule {
+    pub name: String,
+
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+
+    /// 全部满足时规则才会触发
+    #[serde(default)]
+    pub when: Vec<RuleCondition>,
+
+    /// 指向数组时按元素逐条生成记录，字段投影相对于数组元素
+    #[serde(default)]
+    pub each: Option<String>,
+
+    /// 为空时直接输出整个来源对象
+    #[serde(default)]
+    pub fields: Vec<FieldProjection>,
+
+    /// 按配置时间格式转换记录中的游戏时间字段（按格式识别，默认开启）
+    #[serde(default = "default_convert_times")]
+    pub convert_times: bool,
+
+    pub output: RuleOutput,
+}
+
+/// 匹配条件：未指定 `equals` 时按 `exists` 判断（默认要求存在）
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleCondition {
+    pub pointer: String,
+
+    #[serde(default)]
+    pub exists: Option<bool>,
+
+    #[serde(default)]
+    pub equals: Option<Value>,
+}
+
+/// 字段投影
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldProjection {
+    /// 来源 JSON Pointer，空字符串表示来源本身
+    pub from: String,
+
+    /// 输出字段名，默认取 pointer 最后一段
+    #[serde(default)]
+    pub to: Option<String>,
+
+    /// 将对象字段展开到记录顶层
+    #[serde(default)]
+    pub flatten: bool,
+
+    /// 展开时附加的字段名前缀
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// 缺失时丢弃整条记录
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// 输出设置
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleOutput {
+    /// 路径模板，支持 `{rule}` `{date}` `{timestamp}` `{seq}` 与记录字段占位符
+    pub path: String,
+
+    #[serde(default)]
+    pub mode: RuleWriteMode,
+
+    /// upsert 模式下作为对象键的记录字段
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+/// 写入模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleWriteMode {
+    /// 以 JSON Lines 追加
+    Append,
+    /// 合并到以 key 为键的 JSON 对象
+    Upsert,
+    /// 每条记录写入一个新文件
+    #[default]
+    NewFile,
+}
+
+/// 单条规则的执行结果
+#[derive(Debug, Clone, Default)]
+pub struct ExtractorRuleOutcome {
+    pub rule: String,
+    pub output_paths: Vec<PathBuf>,
+    pub error: Option<String>,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+fn default_convert_times() -> bool {
+    true
+}
+
+/// 规则文件路径（EXE 同级）
+pub fn extractor_rules_path() -> PathBuf {
+    exe_dir().join(EXTRACTOR_RULES_FILE_NAME)
+}
+
+/// 默认输出根目录
+pub fn default_extractor_output_dir() -> PathBuf {
+    exe_dir().join(DEFAULT_OUTPUT_DIR_NAME)
+}
+
+/// 读取规则文件；文件不存在时返回空规则集
+pub fn load_extractor_rules(path: &Path) -> Result<ExtractorRuleSet, String> {
+    if !path.exists() {
+        return Ok(ExtractorRuleSet::default());
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("read {} failed: {}", path.display(), e))?;
+    parse_extractor_rules(&content).map_err(|e| format!("parse {} failed: {}", path.display(), e))
+}
+
+/// 读取规则文件并缓存解析结果（含解析错误），文件修改后重新读取
+pub fn load_extractor_rules_cached(path: &Path) -> Result<Arc<ExtractorRuleSet>, String> {
+    let stamp = fs::metadata(path)
+        .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+        .ok();
+    let Ok(mut cache) = RULES_CACHE.lock() else {
+        return load_extractor_rules(path).map(Arc::new);
+    };
+    if let Some(cached) = cache
+        .as_ref()
+        .filter(|cached| cached.path == path && cached.stamp == stamp)
+    {
+        return cached.result.clone();
+    }
+
+    let result = load_extractor_rules(path).map(Arc::new);
+    *cache = Some(CachedRuleSet {
+        path: path.to_path_buf(),
+        stamp,
+        result: result.clone(),
+    });
+    result
+}
+
+pub fn parse_extractor_rules(content: &str) -> Result<ExtractorRuleSet, String> {
+    let trimmed = content.trim_start_matches('\u{feff}');
+    let rule_set: ExtractorRuleSet = serde_json::from_str(trimmed).map_err(|e| e.to_string())?;
+
+    for rule in &rule_set.rules {
+        if rule.output.mode == RuleWriteMode::Upsert && rule.output.key.is_none() {
+            return Err(format!(
+                "rule {}: upsert 模式必须指定 output.key",
+                rule.name
+            ));
+        }
+    }
+
+    Ok(rule_set)
+}
+
+/// 读取 EXE 同级规则文件并对 payload 执行
+///
+/// 规则文件只在修改后重新解析；解析失败时返回一条以文件名命名的错误结果。
+pub fn run_configured_rules(
+    payload: &Value,
+    received_at_unix_ms: u64,
+    time: &TimeSettings,
+) -> Vec<ExtractorRuleOutcome> {
+    let path = extractor_rules_path();
+    match load_extractor_rules_cached(&path) {
+        Ok(rule_set) => {
+            let output_dir = resolve_output_dir(rule_set.output_dir.as_deref());
+            run_rules(&rule_set, payload, received_at_unix_ms, time, &output_dir)
+        }
+        Err(error) => vec![ExtractorRuleOutcome {
+            rule: EXTRACTOR_RULES_FILE_NAME.to_string(),
+            output_paths: Vec::new(),
+            error: Some(error),
+        }],
+    }
+}
+
+/// 执行规则集，只返回命中的规则结果
+pub fn run_rules(
+    rule_set: &ExtractorRuleSet,
+    payload: &Value,
+    received_at_unix_ms: u64,
+    time: &TimeSettings,
+    output_dir: &Path,
+) -> Vec<ExtractorRuleOutcome> {
+    rule_set
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled && rule_matches(rule, payload))
+        .map(|rule| {
+            let mut outcome = ExtractorRuleOutcome {
+                rule: rule.name.clone(),
+                ..ExtractorRuleOutcome::default()
+            };
+            match apply_rule(rule, payload, received_at_unix_ms, time, output_dir) {
+                Ok(paths) => outcome.output_paths = paths,
+                Err(error) => outcome.error = Some(error),
+            }
+            outcome
+        })
+        .collect()
+}
+
+fn rule_matches(rule: &ExtractorRule, payload: &Value) -> bool {
+    rule.when
+        .iter()
+        .all(|condition| condition_matches(condition, payload))
+}
+
+fn condition_matches(condition: &RuleCondition, payload: &Value) -> bool {
+    let target = payload.pointer(&condition.pointer);
+    if let Some(expected) = condition.equals.as_ref() {
+        return target == Some(expected);
+    }
+
+    target.is_some() == condition.exists.unwrap_or(true)
+}
+
+fn apply_rule(
+    rule: &ExtractorRule,
+    payload: &Value,
+    received_at_unix_ms: u64,
+    time: &TimeSettings,
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let mut records = project_records(rule, payload)?;
+    if records.is_empty() {
+        return Ok(Vec::new());
+    }
+    if rule.convert_times {
+        for record in records.iter_mut() {
+            for value in record.values_mut() {
+                time.convert_game_times(value);
+            }
+        }
+    }
+
+    let context = TemplateContext {
+        rule: &rule.name,
+        date: time.date_key(received_at_unix_ms),
+        timestamp: received_at_unix_ms,
+    };
+
+    match rule.output.mode {
+        RuleWriteMode::NewFile => records
+            .iter()
+            .map(|record| {
+                let path = render_output_path(&rule.output.path, &context, record, output_dir)?;
+                write_new_file(&path, record)
+            })
+            .collect(),
+        RuleWriteMode::Append => group_by_path(&rule.output.path, &context, &records, output_dir)?
+            .into_iter()
+            .map(|(path, group)| append_json_lines(&path, &group).map(|()| path))
+            .collect(),
+        RuleWriteMode::Upsert => {
+            let key = rule.output.key.as_deref().unwrap_or_default();
+            group_by_path(&rule.output.path, &context, &records, output_dir)?
+                .into_iter()
+                .map(|(path, group)| upsert_json_object(&path, key, &group).map(|()| path))
+                .collect()
+        }
+    }
+}
+
+/// 按规则投影出输出记录
+fn project_records(rule: &ExtractorRule, payload: &Value) -> Result<Vec<Record>, String> {
+    let sources: Vec<&Value> = match rule.each.as_deref() {
+        Some(pointer) => match payload.pointer(pointer) {
+            Some(Value::Array(items)) => items.iter().collect(),
+            Some(_) => return Err(format!("each {} 不是数组", pointer)),
+            None => Vec::new(),
+        },
+        None => vec![payload],
+    };
+
+    let mut records = Vec::with_capacity(sources.len());
+    for source in sources {
+        if let Some(record) = project_record(&rule.fields, source)? {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+fn project_record(fields: &[FieldProjection], source: &Value) -> Result<Option<Record>, String> {
+    if fields.is_empty() {
+        return Ok(source.as_object().cloned());
+    }
+
+    let mut record = Record::new();
+    for field in fields {
+        let Some(value) = source.pointer(&field.from) else {
+            if field.required {
+                return Ok(None);
+            }
+            continue;
+        };
+
+        if field.flatten {
+            let Some(object) = value.as_object() else {
+                return Err(format!("flatten {} 不是对象", field.from));
+            };
+            let prefix = field.prefix.as_deref().unwrap_or_default();
+            for (key, value) in object {
+                record.insert(format!("{}{}", prefix, key), value.clone());
+            }
+            continue;
+        }
+
+        let name = field
+            .to
+            .clone()
+            .or_else(|| pointer_last_segment(&field.from))
+            .ok_or_else(|| format!("字段 {} 需要指定 to", field.from))?;
+        record.insert(name, value.clone());
+    }
+
+    Ok(Some(record))
+}
+
+fn pointer_last_segment(pointer: &str) -> Option<String> {
+    let segment = pointer.rsplit('/').next()?;
+    if segment.is_empty() {
+        return None;
+    }
+    Some(segment.replace("~1", "/").replace("~0", "~"))
+}
+
+struct TemplateContext<'a> {
+    rule: &'a str,
+    date: String,
+    timestamp: u64,
+}
+
+fn render_output_path(
+    template: &str,
+    context: &TemplateContext<'_>,
+    record: &Record,
+    output_dir: &Path,
+) -> Result<PathBuf, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("路径模板 {} 缺少 }}", template))?;
+        let name = &after[..end];
+        let value = match name {
+            "rule" => context.rule.to_string(),
+            "date" => context.date.clone(),
+            "timestamp" => context.timestamp.to_string(),
+            "seq" => OUTPUT_SEQUENCE.fetch_add(1, Ordering::Relaxed).to_string(),
+            field => match record.get(field) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                Some(Value::Bool(b)) => b.to_string(),
+                _ => return Err(format!("路径模板字段 {} 不存在或不是标量", field)),
+            },
+        };
+        let component = sanitize_path_component(&value);
+        if component == "." || component == ".." {
+            return Err(format!(
+                "路径模板字段 {} 的值 {} 不能用作路径",
+                name, component
+            ));
+        }
+        rendered.push_str(&component);
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    // 输出只能落在 output_dir 之内：拒绝绝对路径、盘符与 `..`
+    let relative = PathBuf::from(&rendered);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!(
+            "输出路径 {} 必须是 output_dir 下的相对路径，不能包含 ..",
+            rendered
+        ));
+    }
+    let path = output_dir.join(relative);
+    if !path.starts_with(output_dir) {
+        return Err(format!("输出路径 {} 超出 output_dir", path.display()));
+    }
+    Ok(path)
+}
+
+fn sanitize_path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect()
+}
+
+fn group_by_path<'a>(
+    template: &str,
+    context: &TemplateContext<'_>,
+    records: &'a [Record],
+    output_dir: &Path,
+) -> Result<Vec<RecordGroup<'a>>, String> {
+    let mut groups: Vec<RecordGroup<'a>> = Vec::new();
+    for record in records {
+        let path = render_output_path(template, context, record, output_dir)?;
+        match groups.iter_mut().find(|(existing, _)| *existing == path) {
+            Some((_, group)) => group.push(record),
+            None => groups.push((path, vec![record])),
+        }
+    }
+    Ok(groups)
+}
+
+fn ensure_parent_dir(path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("create dir {} failed: {}", parent.display(), e))?;
+    }
+    Ok(())
+}
+
+fn write_new_file(path: &Path, record: &Record) -> Result<PathBuf, String> {
+    ensure_parent_dir(path)?;
+
+    let mut target = path.to_path_buf();
+    if target.exists() {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = path
+            .extension()
+            .map(|s| format!(".{}", s.to_string_lossy()))
+            .unwrap_or_default();
+        let seq = OUTPUT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        target = path.with_file_name(format!("{}_{}{}", stem, seq, extension));
+    }
+
+    let json = serde_json::to_string_pretty(record)
+        .map_err(|e| format!("serialize {} failed: {}", target.display(), e))?;
+    fs::write(&target, json).map_err(|e| format!("write {} failed: {}", target.display(), e))?;
+    Ok(target)
+}
+
+fn append_json_lines(path: &Path, records: &[&Record]) -> Result<(), String> {
+    ensure_parent_dir(path)?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("open {} failed: {}", path.display(), e))?;
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("serialize {} failed: {}", path.display(), e))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("append {} failed: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn upsert_json_object(path: &Path, key: &str, records: &[&Record]) -> Result<(), String> {
+    ensure_parent_dir(path)?;
+
+    let mut merged = if path.exists() {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("read {} failed: {}", path.display(), e))?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => return Err(format!("{} is not a JSON object", path.display())),
+            Err(e) => return Err(format!("parse {} failed: {}", path.display(), e)),
+        }
+    } else {
+        Map::new()
+    };
+
+    for record in records {
+        let record_key = match record.get(key) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => return Err(format!("记录缺少 upsert key {}", key)),
+        };
+        merged.insert(record_key, Value::Object((*record).clone()));
+    }
+
+    let json = serde_json::to_string_pretty(&Value::Object(merged))
+        .map_err(|e| format!("serialize {} failed: {}", path.display(), e))?;
+    let temp_path = path.with_extension(format!("json.tmp.{}", std::process::id()));
+    fs::write(&temp_path, json)
+        .map_err(|e| format!("write temp {} failed: {}", temp_path.display(), e))?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!(
+            "rename {} -> {} failed: {}",
+            temp_path.display(),
+            path.display(),
+            e
+        )
+    })
+}
+
+fn resolve_output_dir(configured: Option<&str>) -> PathBuf {
+    if let Some(dir) = configured {
+        let trimmed = dir.trim();
+        if !trimmed.is_empty() {
+            let path = PathBuf::from(trimmed);
+            return if path.is_absolute() {
+                path
+            } else {
+                exe_dir().join(path)
+            };
+        }
+    }
+    default_extractor_output_dir()
+}
+
+fn exe_dir() -> PathBuf {
+    if let Ok(mut exe_path) = std::env::current_exe() {
+        exe_path.pop();
+        return exe_path;
+    }
+    PathBuf::from(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use guga_ura_config::time::{OutputTimeZone, TimeOutputFormat};
+    use serde_json::json;
+
+    fn utc_time() -> TimeSettings {
+        TimeSettings::new(TimeOutputFormat::Rfc3339, OutputTimeZone::Utc)
+    }
+
+    fn circle_payload() -> Value {
+        json!({
+            "data_headers": {"result_code": 1},
+            "data": {
+                "circle_info": {"circle_id": 77, "name": "current-circle"},
+                "circle_user_array": [
+                    {"viewer_id": 100, "membership": 1},
+                    {"viewer_id": 200, "membership": 2}
+                ]
+            }
+        })
+    }
+
+    fn rule_set(json: Value) -> ExtractorRuleSet {
+        parse_extractor_rules(&json.to_string()).expect("解析规则失败")
+    }
+
+    #[test]
+    fn conditions_should_support_exists_and_equals() {
+        let payload = circle_payload();
+        let exists = RuleCondition {
+            pointer: "/data/circle_info".to_string(),
+            exists: None,
+            equals: None,
+        };
+        let missing = RuleCondition {
+            pointer: "/data/partner_chara_info_array".to_string(),
+            exists: Some(false),
+            equals: None,
+        };
+        let equals = RuleCondition {
+            pointer: "/data_headers/result_code".to_string(),
+            exists: None,
+            equals: Some(json!(1)),
+        };
+        let not_equals = RuleCondition {
+            pointer: "/data_headers/result_code".to_string(),
+            exists: None,
+            equals: Some(json!(2)),
+        };
+
+        assert!(condition_matches(&exists, &payload));
+        assert!(condition_matches(&missing, &payload));
+        assert!(condition_matches(&equals, &payload));
+        assert!(!condition_matches(&not_equals, &payload));
+    }
+
+    #[test]
+    fn project_record_should_pull_rename_and_flatten() {
+        let fields: Vec<FieldProjection> = serde_json::from_value(json!([
+            {"from": "/data/circle_info/circle_id"},
+            {"from": "/data/circle_info/name", "to": "circle_name"},
+            {"from": "/data_headers", "flatten": true, "prefix": "header_"}
+        ]))
+        .expect("解析字段失败");
+
+        let record = project_record(&fields, &circle_payload())
+            .expect("投影失败")
+            .expect("应生成记录");
+
+        assert_eq!(record["circle_id"], 77);
+        assert_eq!(record["circle_name"], "current-circle");
+        assert_eq!(record["header_result_code"], 1);
+        assert_eq!(record.len(), 3);
+    }
+
+    #[test]
+    fn project_record_should_drop_record_when_required_field_missing() {
+        let fields: Vec<FieldProjection> =
+            serde_json::from_value(json!([{"from": "/missing", "required": true}]))
+                .expect("解析字段失败");
+
+        assert!(project_record(&fields, &circle_payload())
+            .expect("投影失败")
+            .is_none());
+    }
+
+    #[test]
+    fn parse_extractor_rules_should_require_key_for_upsert() {
+        let error = parse_extractor_rules(
+            r#"{"rules":[{"name":"r","output":{"path":"a.json","mode":"upsert"}}]}"#,
+        )
+        .expect_err("缺少 key 应报错");
+
+        assert!(error.contains("output.key"));
+    }
+
+    #[test]
+    fn load_extractor_rules_cached_should_reparse_only_after_change() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join(EXTRACTOR_RULES_FILE_NAME);
+        let rule = r#"{"name":"a","output":{"path":"a.json"}}"#;
+        fs::write(&path, format!(r#"{{"rules":[{}]}}"#, rule)).expect("写入规则失败");
+
+        let first = load_extractor_rules_cached(&path).expect("读取规则失败");
+        let second = load_extractor_rules_cached(&path).expect("读取规则失败");
+        assert!(Arc::ptr_eq(&first, &second), "文件未变化时应复用解析结果");
+
+        fs::write(&path, format!(r#"{{"rules":[{},{}]}}"#, rule, rule)).expect("写入规则失败");
+        let changed = load_extractor_rules_cached(&path).expect("读取规则失败");
+        assert_eq!(changed.rules.len(), 2);
+
+        fs::write(&path, "{").expect("写入规则失败");
+        assert!(load_extractor_rules_cached(&path).is_err());
+    }
+
+    #[test]
+    fn run_rules_should_upsert_each_item_by_key() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let rules = rule_set(json!({
+            "rules": [{
+                "name": "circle_members",
+                "when": [{"pointer": "/data/circle_info"}],
+                "each": "/data/circle_user_array",
+                "fields": [{"from": "/viewer_id"}, {"from": "/membership", "to": "role"}],
+                "output": {"path": "circle/{rule}.json", "mode": "upsert", "key": "viewer_id"}
+            }]
+        }));
+
+        let first = run_rules(
+            &rules,
+            &circle_payload(),
+            1_772_641_517_934,
+            &utc_time(),
+            dir.path(),
+        );
+        let mut updated = circle_payload();
+        updated["data"]["circle_user_array"][0]["membership"] = json!(9);
+        run_rules(&rules, &updated, 1_772_641_517_935, &utc_time(), dir.path());
+
+        assert_eq!(first.len(), 1);
+        assert!(first[0].error.is_none(), "{:?}", first[0].error);
+        let path = dir.path().join("circle").join("circle_members.json");
+        assert_eq!(first[0].output_paths, vec![path.clone()]);
+
+        let stored: Value = serde_json::from_str(&fs::read_to_string(path).expect("读取输出失败"))
+            .expect("解析输出失败");
+        assert_eq!(stored["100"]["role"], 9);
+        assert_eq!(stored["200"]["role"], 2);
+    }
+
+    #[test]
+    fn run_rules_should_append_json_lines_and_skip_unmatched() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let rules = rule_set(json!({
+            "rules": [
+                {
+                    "name": "headers",
+                    "when": [{"pointer": "/data_headers/result_code", "equals": 1}],
+                    "fields": [{"from": "/data_headers", "flatten": true}],
+                    "output": {"path": "headers.jsonl", "mode": "append"}
+                },
+                {
+                    "name": "profile",
+                    "when": [{"pointer": "/data/partner_chara_info_array"}],
+                    "output": {"path": "profile.json"}
+                }
+            ]
+        }));
+
+        run_rules(&rules, &circle_payload(), 1, &utc_time(), dir.path());
+        let outcomes = run_rules(&rules, &circle_payload(), 2, &utc_time(), dir.path());
+
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].rule, "headers");
+        let content = fs::read_to_string(dir.path().join("headers.jsonl")).expect("读取输出失败");
+        assert_eq!(content.lines().count(), 2);
+        assert!(!dir.path().join("profile.json").exists());
+    }
+
+    #[test]
+    fn run_rules_should_render_record_fields_into_new_files() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let rules = rule_set(json!({
+            "rules": [{
+                "name": "member",
+                "each": "/data/circle_user_array",
+                "output": {"path": "members/{viewer_id}_{timestamp}.json", "mode": "new_file"}
+            }]
+        }));
+
+        let outcomes = run_rules(&rules, &circle_payload(), 42, &utc_time(), dir.path());
+
+        assert_eq!(
+            outcomes[0].output_paths,
+            vec![
+                dir.path().join("members").join("100_42.json"),
+                dir.path().join("members").join("200_42.json"),
+            ]
+        );
+        let written = run_rules(&rules, &circle_payload(), 42, &utc_time(), dir.path());
+        assert_ne!(written[0].output_paths[0], outcomes[0].output_paths[0]);
+    }
+
+    #[test]
+    fn run_rules_should_convert_game_times_unless_disabled() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let payload = json!({"data": {"join_time": "2026-04-06 22:40:05"}});
+        let rules = rule_set(json!({
+            "rules": [
+                {
+                    "name": "converted",
+                    "fields": [{"from": "/data/join_time"}],
+                    "output": {"path": "{rule}_{date}.json"}
+                },
+                {
+                    "name": "raw",
+                    "convert_times": false,
+                    "fields": [{"from": "/data/join_time"}],
+                    "output": {"path": "{rule}.json"}
+                }
+            ]
+        }));
+
+        // 2026-04-06T15:30:00Z
+        let outcomes = run_rules(&rules, &payload, 1_775_489_400_000, &utc_time(), dir.path());
+
+        let read = |path: &Path| -> Value {
+            serde_json::from_str(&fs::read_to_string(path).expect("读取输出失败"))
+                .expect("解析输出失败")
+        };
+        assert_eq!(
+            outcomes[0].output_paths,
+            vec![dir.path().join("converted_20260406.json")]
+        );
+        assert_eq!(
+            read(&outcomes[0].output_paths[0])["join_time"],
+            "2026-04-06T13:40:05+00:00"
+        );
+        assert_eq!(
+            read(&outcomes[1].output_paths[0])["join_time"],
+            "2026-04-06 22:40:05"
+        );
+    }
+
+    #[test]
+    fn render_output_path_should_report_unknown_placeholder() {
+        let context = TemplateContext {
+            rule: "r",
+            date: "20260101".to_string(),
+            timestamp: 1,
+        };
+
+        let error = render_output_path(
+            "{missing}.json",
+            &context,
+            &Record::new(),
+            Path::new("/tmp"),
+        )
+        .expect_err("未知占位符应报错");
+
+        assert!(error.contains("missing"));
+    }
+
+    #[test]
+    fn render_output_path_should_stay_inside_output_dir() {
+        let context = TemplateContext {
+            rule: "r",
+            date: "20260101".to_string(),
+            timestamp: 1,
+        };
+        let output_dir = Path::new("/tmp/extractor");
+        let record = |id: &str| {
+            let mut record = Record::new();
+            record.insert("id".to_string(), Value::from(id));
+            record
+        };
+
+        for id in ["..", "."] {
+            let error = render_output_path("{id}/out.json", &context, &record(id), output_dir)
+                .expect_err("字段值为 . 或 .. 时应报错");
+            assert!(error.contains("id"), "{}", error);
+        }
+        for template in ["../out.json", "a/../../out.json", "/etc/out.json"] {
+            render_output_path(template, &context, &record("1"), output_dir)
+                .expect_err("越出 output_dir 的模板应报错");
+        }
+
+        let error = render_output_path("{id}/../{rule}.json", &context, &record("a"), output_dir)
+            .expect_err("模板中的 .. 应报错");
+        assert!(error.contains(".."), "{}", error);
+        assert_eq!(
+            render_output_path("./{id}/..x.json", &context, &record("a/b"), output_dir),
+            Ok(output_dir.join("a_b").join("..x.json"))
+        );
+    }
+}
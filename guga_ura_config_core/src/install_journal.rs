@@ -0,0 +1,449 @@
+//! 安装事务日志
+//!
+//! 安装与卸载对游戏目录的每一步文件操作都先写入游戏目录下
+//! `.guga_ura_txn/journal.json`，再真正执行；被覆盖或删除的内容先保存到同一
+//! 目录。任何一步失败时按相反顺序撤销已完成的步骤；配置工具中途崩溃时，
+//! 下次打开同一游戏目录会发现遗留的日志并自动回滚。
+
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// 事务目录名，位于游戏目录下
+pub const INSTALL_TXN_DIR_NAME: &str = ".guga_ura_txn";
+
+/// 事务日志文件名
+pub const INSTALL_JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// 当前日志格式版本
+pub const INSTALL_JOURNAL_VERSION: u32 = 1;
+
+/// 一次事务的日志
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallJournal {
+    pub version: u32,
+    /// `install` / `uninstall`
+    pub operation: String,
+    pub started_at: String,
+    pub steps: Vec<JournalStep>,
+}
+
+/// 已记录的文件操作及撤销所需的信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalStep {
+    /// 新建目录；撤销时目录为空才删除
+    CreateDir { path: PathBuf },
+    /// 写入文件；`backup` 为原内容的副本，原先不存在时为空
+    WriteFile {
+        path: PathBuf,
+        backup: Option<PathBuf>,
+    },
+    /// 删除文件或目录：整体移动到 `backup`，撤销时移回
+    Remove { path: PathBuf, backup: PathBuf },
+    /// 移动文件或目录；撤销时移回
+    Move { from: PathBuf, to: PathBuf },
+}
+
+/// 一次回滚的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackReport {
+    pub operation: String,
+    /// 撤销的步骤数
+    pub undone: usize,
+    /// 未能撤销的步骤
+    pub errors: Vec<String>,
+}
+
+impl JournalStep {
+    fn undo(&self) -> Result<(), String> {
+        match self {
+            JournalStep::CreateDir { path } => match fs::remove_dir(path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                // 目录中还有事务外写入的文件（如安装日志），保留
+                Err(_) if path.is_dir() => Ok(()),
+                Err(e) => Err(format!("删除目录 {} 失败: {}", path.display(), e)),
+            },
+            JournalStep::WriteFile {
+                path,
+                backup: Some(backup),
+            } => {
+                if !backup.exists() {
+                    return Err(format!("{} 的备份缺失，无法恢复", path.display()));
+                }
+                fs::copy(backup, path)
+                    .map(|_| ())
+                    .map_err(|e| format!("恢复 {} 失败: {}", path.display(), e))
+            }
+            JournalStep::WriteFile { path, backup: None } => remove_path(path),
+            JournalStep::Remove { path, backup } => {
+                if !backup.exists() {
+                    return Ok(());
+                }
+                if path.exists() {
+                    remove_path(path)?;
+                }
+                fs::rename(backup, path).map_err(|e| format!("恢复 {} 失败: {}", path.display(), e))
+            }
+            JournalStep::Move { from, to } => {
+                if !to.exists() || from.exists() {
+                    return Ok(());
+                }
+                fs::rename(to, from).map_err(|e| format!("移回 {} 失败: {}", from.display(), e))
+            }
+        }
+    }
+}
+
+/// 正在进行的事务
+#[derive(Debug)]
+pub struct InstallTransaction {
+    txn_dir: PathBuf,
+    journal: InstallJournal,
+}
+
+impl InstallTransaction {
+    /// 开始事务；存在未完成的事务时拒绝开始
+    pub fn begin(game_dir: &Path, operation: &str) -> Result<Self, String> {
+        let txn_dir = txn_dir(game_dir);
+        if journal_path(game_dir).exists() {
+            return Err(format!(
+                "存在未完成的安装事务: {}",
+                journal_path(game_dir).display()
+            ));
+        }
+        if txn_dir.exists() {
+            // 没有日志的事务目录只可能是已提交但未清理完的残留
+            remove_path(&txn_dir)?;
+        }
+        fs::create_dir_all(&txn_dir).map_err(|e| format!("创建事务目录失败: {}", e))?;
+
+        let transaction = Self {
+            txn_dir,
+            journal: InstallJournal {
+                version: INSTALL_JOURNAL_VERSION,
+                operation: operation.to_string(),
+                started_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+                steps: Vec::new(),
+            },
+        };
+        transaction.save_journal()?;
+        Ok(transaction)
+    }
+
+    /// 已记录的步骤
+    pub fn steps(&self) -> &[JournalStep] {
+        &self.journal.steps
+    }
+
+    /// 逐级创建目录，只记录原先不存在的层级
+    pub fn create_dir_all(&mut self, path: &Path) -> Result<(), String> {
+        let mut missing = Vec::new();
+        let mut current = Some(path);
+        while let Some(dir) = current {
+            if dir.exists() {
+                break;
+            }
+            missing.push(dir.to_path_buf());
+            current = dir.parent();
+        }
+
+        for dir in missing.into_iter().rev() {
+            self.record(JournalStep::CreateDir { path: dir.clone() })?;
+            fs::create_dir(&dir).map_err(|e| format!("创建目录 {} 失败: {}", dir.display(), e))?;
+        }
+        Ok(())
+    }
+
+    /// 写入文件，覆盖前保存原内容
+    pub fn write_file(&mut self, path: &Path, data: &[u8]) -> Result<(), String> {
+        let backup = if path.exists() {
+            let backup = self.next_backup_path();
+            fs::copy(path, &backup).map_err(|e| format!("备份 {} 失败: {}", path.display(), e))?;
+            Some(backup)
+        } else {
+            None
+        };
+
+        self.record(JournalStep::WriteFile {
+            path: path.to_path_buf(),
+            backup,
+        })?;
+        fs::write(path, data).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+    }
+
+    /// 复制文件
+    pub fn copy_file(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        let data = fs::read(from).map_err(|e| format!("读取 {} 失败: {}", from.display(), e))?;
+        self.write_file(to, &data)
+    }
+
+    /// 删除文件或整个目录；不存在时什么也不做
+    pub fn remove(&mut self, path: &Path) -> Result<(), String> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let backup = self.next_backup_path();
+        self.record(JournalStep::Remove {
+            path: path.to_path_buf(),
+            backup: backup.clone(),
+        })?;
+        fs::rename(path, &backup).map_err(|e| format!("删除 {} 失败: {}", path.display(), e))
+    }
+
+    /// 移动文件或目录，目标已存在时拒绝
+    pub fn move_path(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        if to.exists() {
+            return Err(format!("{} 已存在", to.display()));
+        }
+        self.record(JournalStep::Move {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        })?;
+        fs::rename(from, to).map_err(|e| format!("移动 {} 失败: {}", from.display(), e))
+    }
+
+    /// 提交事务：清理日志与备份
+    pub fn commit(self) -> Result<(), String> {
+        fs::remove_file(self.txn_dir.join(INSTALL_JOURNAL_FILE_NAME))
+            .map_err(|e| format!("删除事务日志失败: {}", e))?;
+        // 日志已删除，事务已生效；备份清理失败只会留下残留目录，下次开始时再清理
+        let _ = fs::remove_dir_all(&self.txn_dir);
+        Ok(())
+    }
+
+    /// 按相反顺序撤销已记录的步骤
+    pub fn rollback(self) -> RollbackReport {
+        rollback_journal(&self.txn_dir, self.journal)
+    }
+
+    fn record(&mut self, step: JournalStep) -> Result<(), String> {
+        self.journal.steps.push(step);
+        self.save_journal()
+    }
+
+    fn save_journal(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&self.journal)
+            .map_err(|e| format!("序列化事务日志失败: {}", e))?;
+        let path = self.txn_dir.join(INSTALL_JOURNAL_FILE_NAME);
+        let tmp = self
+            .txn_dir
+            .join(format!("{}.tmp", INSTALL_JOURNAL_FILE_NAME));
+        fs::write(&tmp, json).map_err(|e| format!("写入事务日志失败: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("写入事务日志失败: {}", e))
+    }
+
+    fn next_backup_path(&self) -> PathBuf {
+        self.txn_dir
+            .join(format!("backup_{:03}", self.journal.steps.len()))
+    }
+}
+
+/// 在事务中执行 `apply`：成功则提交，失败则回滚并在错误中附上回滚结果
+pub fn run_transaction<T>(
+    game_dir: &Path,
+    operation: &str,
+    apply: impl FnOnce(&mut InstallTransaction) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut transaction = InstallTransaction::begin(game_dir, operation)?;
+    match apply(&mut transaction) {
+        Ok(value) => {
+            transaction.commit()?;
+            Ok(value)
+        }
+        Err(error) => {
+            let report = transaction.rollback();
+            if report.errors.is_empty() {
+                Err(format!("{}（已撤销 {} 步更改）", error, report.undone))
+            } else {
+                Err(format!(
+                    "{}（回滚未完成: {}）",
+                    error,
+                    report.errors.join("; ")
+                ))
+            }
+        }
+    }
+}
+
+/// 回滚上次中断的事务；没有遗留事务时返回 `None`
+pub fn recover_pending(game_dir: &Path) -> Result<Option<RollbackReport>, String> {
+    let path = journal_path(game_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("读取事务日志失败 ({}): {}", path.display(), e))?;
+    let journal: InstallJournal = serde_json::from_str(&text)
+        .map_err(|e| format!("解析事务日志失败 ({}): {}", path.display(), e))?;
+    Ok(Some(rollback_journal(&txn_dir(game_dir), journal)))
+}
+
+/// 是否存在未完成的事务
+pub fn has_pending(game_dir: &Path) -> bool {
+    journal_path(game_dir).exists()
+}
+
+fn rollback_journal(txn_dir: &Path, journal: InstallJournal) -> RollbackReport {
+    let mut report = RollbackReport {
+        operation: journal.operation,
+        undone: 0,
+        errors: Vec::new(),
+    };
+    for step in journal.steps.iter().rev() {
+        match step.undo() {
+            Ok(()) => report.undone += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+    // 有步骤未能撤销时保留日志与备份，便于再次恢复或人工处理
+    if report.errors.is_empty() {
+        let _ = fs::remove_dir_all(txn_dir);
+    }
+    report
+}
+
+fn txn_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join(INSTALL_TXN_DIR_NAME)
+}
+
+fn journal_path(game_dir: &Path) -> PathBuf {
+    txn_dir(game_dir).join(INSTALL_JOURNAL_FILE_NAME)
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("删除 {} 失败: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// 准备一个含已有文件与目录的游戏目录
+    fn game_fixture() -> tempfile::TempDir {
+        let dir = tempdir().expect("创建临时目录失败");
+        fs::write(dir.path().join("game.exe"), b"launcher").expect("写入 exe 失败");
+        fs::create_dir_all(dir.path().join("old.local")).expect("创建目录失败");
+        fs::write(dir.path().join("old.local").join("a.dll"), b"a").expect("写入 DLL 失败");
+        dir
+    }
+
+    fn apply_changes(txn: &mut InstallTransaction, game_dir: &Path) -> Result<(), String> {
+        txn.create_dir_all(&game_dir.join("data").join("nested"))?;
+        txn.copy_file(
+            &game_dir.join("game.exe"),
+            &game_dir.join("data").join("orig.exe"),
+        )?;
+        txn.write_file(&game_dir.join("game.exe"), b"proxy")?;
+        txn.move_path(
+            &game_dir.join("old.local").join("a.dll"),
+            &game_dir.join("data").join("nested").join("a.dll"),
+        )?;
+        txn.remove(&game_dir.join("old.local"))?;
+        Ok(())
+    }
+
+    fn assert_untouched(game_dir: &Path) {
+        assert_eq!(
+            fs::read(game_dir.join("game.exe")).expect("读取 exe 失败"),
+            b"launcher"
+        );
+        assert!(game_dir.join("old.local").join("a.dll").exists());
+        assert!(!game_dir.join("data").exists());
+        assert!(!game_dir.join(INSTALL_TXN_DIR_NAME).exists());
+    }
+
+    #[test]
+    fn commit_should_keep_changes_and_clear_journal() {
+        let dir = game_fixture();
+        let game_dir = dir.path();
+
+        run_transaction(game_dir, "install", |txn| apply_changes(txn, game_dir))
+            .expect("事务应成功");
+
+        assert_eq!(
+            fs::read(game_dir.join("game.exe")).expect("读取失败"),
+            b"proxy"
+        );
+        assert_eq!(
+            fs::read(game_dir.join("data").join("orig.exe")).expect("读取失败"),
+            b"launcher"
+        );
+        assert!(game_dir.join("data").join("nested").join("a.dll").exists());
+        assert!(!game_dir.join("old.local").exists());
+        assert!(!game_dir.join(INSTALL_TXN_DIR_NAME).exists());
+        assert!(!has_pending(game_dir));
+    }
+
+    #[test]
+    fn failure_should_undo_completed_steps() {
+        let dir = game_fixture();
+        let game_dir = dir.path();
+
+        let error = run_transaction(game_dir, "install", |txn| {
+            apply_changes(txn, game_dir)?;
+            Err::<(), _>("写入启动器失败".to_string())
+        })
+        .expect_err("事务应失败");
+
+        assert!(error.contains("写入启动器失败"));
+        assert!(error.contains("已撤销"));
+        assert_untouched(game_dir);
+    }
+
+    #[test]
+    fn interrupted_transaction_should_be_recovered_on_next_run() {
+        let dir = game_fixture();
+        let game_dir = dir.path();
+
+        let mut txn = InstallTransaction::begin(game_dir, "uninstall").expect("开始事务失败");
+        apply_changes(&mut txn, game_dir).expect("执行步骤失败");
+        // 模拟进程中途退出：既不提交也不回滚
+        drop(txn);
+
+        assert!(has_pending(game_dir));
+        assert!(InstallTransaction::begin(game_dir, "install").is_err());
+
+        let report = recover_pending(game_dir)
+            .expect("恢复失败")
+            .expect("应存在遗留事务");
+        assert_eq!(report.operation, "uninstall");
+        assert!(report.errors.is_empty());
+        assert_untouched(game_dir);
+        assert_eq!(recover_pending(game_dir), Ok(None));
+    }
+
+    #[test]
+    fn step_recorded_before_execution_should_undo_safely() {
+        let dir = game_fixture();
+        let game_dir = dir.path();
+        let mut txn = InstallTransaction::begin(game_dir, "install").expect("开始事务失败");
+        // 记录了删除但尚未移动时中断
+        let backup = txn.next_backup_path();
+        txn.record(JournalStep::Remove {
+            path: game_dir.join("old.local"),
+            backup,
+        })
+        .expect("记录步骤失败");
+        drop(txn);
+
+        let report = recover_pending(game_dir)
+            .expect("恢复失败")
+            .expect("应存在遗留事务");
+        assert_eq!(report.undone, 1);
+        assert_untouched(game_dir);
+    }
+}
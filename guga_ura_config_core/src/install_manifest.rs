@@ -0,0 +1,370 @@
+//! 安装清单
+//!
+//! 安装时在 `guga_ura_data/install_manifest.json` 记录每个部署文件的相对路径、
+//! 大小、SHA-256、payload 来源以及被覆盖原文件的备份位置。状态检查据此区分
+//! 已安装、需要更新（当前 payload 与已部署的不一致）、文件被修改与安装不完整；
+//! 卸载时也只撤销清单中记录的文件。
+
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 清单文件名，位于 `guga_ura_data/`
+pub const INSTALL_MANIFEST_FILE_NAME: &str = "install_manifest.json";
+
+/// 当前清单格式版本
+pub const INSTALL_MANIFEST_VERSION: u32 = 1;
+
+/// 安装清单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub version: u32,
+    /// 安装时的配置工具版本，内嵌 payload 与之同版本发布
+    pub payload_version: String,
+    pub installed_at: String,
+    /// `steam` / `dmm`
+    pub game_version: String,
+    pub files: Vec<InstalledFile>,
+}
+
+/// 安装器部署的单个文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledFile {
+    /// 相对游戏目录的路径，`/` 分隔
+    pub path: String,
+    /// 对应的 payload，如 `UnityPlayer.dll`
+    pub payload: String,
+    pub size: u64,
+    pub sha256: String,
+    /// `embedded` 或 `external:<路径>`
+    pub source: String,
+    /// 被覆盖原文件的备份，相对游戏目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+}
+
+/// 单个文件的检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    Ok,
+    /// 与当前 payload 不一致
+    Outdated,
+    /// 与清单记录的哈希不一致
+    Modified,
+    Missing,
+    /// 原文件备份缺失，卸载时无法恢复
+    BackupMissing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCheck {
+    pub path: String,
+    pub state: FileState,
+}
+
+impl FileCheck {
+    /// 用于日志与界面展示的说明
+    pub fn describe(&self) -> String {
+        let state = match self.state {
+            FileState::Ok => "正常",
+            FileState::Outdated => "与当前 payload 不一致，需要更新",
+            FileState::Modified => "已被其它程序修改",
+            FileState::Missing => "文件缺失",
+            FileState::BackupMissing => "原文件备份缺失",
+        };
+        format!("{}: {}", self.path, state)
+    }
+}
+
+impl InstalledFile {
+    /// 记录一个刚写入的文件；`target` 与 `backup` 为绝对路径，必须位于游戏目录内
+    pub fn new(
+        game_dir: &Path,
+        target: &Path,
+        payload: &str,
+        data: &[u8],
+        source: &str,
+        backup: Option<&Path>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            path: relative_path(game_dir, target)?,
+            payload: payload.to_string(),
+            size: data.len() as u64,
+            sha256: sha256_hex(data),
+            source: source.to_string(),
+            backup: backup
+                .map(|path| relative_path(game_dir, path))
+                .transpose()?,
+        })
+    }
+
+    /// 清单中的路径来自磁盘文件，可能被改写；拒绝指向游戏目录之外的路径
+    pub fn absolute_path(&self, game_dir: &Path) -> Result<PathBuf, String> {
+        resolve_relative(game_dir, &self.path)
+    }
+
+    pub fn backup_path(&self, game_dir: &Path) -> Result<Option<PathBuf>, String> {
+        self.backup
+            .as_deref()
+            .map(|backup| resolve_relative(game_dir, backup))
+            .transpose()
+    }
+
+    /// 磁盘上的文件是否仍是安装器写入的内容；文件不存在时为 `None`
+    pub fn matches_disk(&self, game_dir: &Path) -> Result<Option<bool>, String> {
+        match fs::read(self.absolute_path(game_dir)?) {
+            Ok(data) => Ok(Some(sha256_hex(&data) == self.sha256)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// 对照磁盘检查该文件；`current_sha256` 返回当前可用 payload 的哈希，未知时为 `None`
+    ///
+    /// 路径越出游戏目录说明清单被改写，按被修改处理。
+    pub fn check(
+        &self,
+        game_dir: &Path,
+        current_sha256: &dyn Fn(&str) -> Option<String>,
+    ) -> FileState {
+        match self.matches_disk(game_dir) {
+            Ok(Some(true)) => {}
+            Ok(Some(false)) | Err(_) => return FileState::Modified,
+            Ok(None) => return FileState::Missing,
+        }
+        match self.backup_path(game_dir) {
+            Ok(Some(backup)) if !backup.exists() => return FileState::BackupMissing,
+            Ok(_) => {}
+            Err(_) => return FileState::Modified,
+        }
+        match current_sha256(&self.payload) {
+            Some(current) if current != self.sha256 => FileState::Outdated,
+            _ => FileState::Ok,
+        }
+    }
+}
+
+impl InstallManifest {
+    pub fn new(game_version: &str, files: Vec<InstalledFile>) -> Self {
+        Self {
+            version: INSTALL_MANIFEST_VERSION,
+            payload_version: env!("CARGO_PKG_VERSION").to_string(),
+            installed_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            game_version: game_version.to_string(),
+            files,
+        }
+    }
+
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(INSTALL_MANIFEST_FILE_NAME)
+    }
+
+    /// 读取清单；不存在时返回 `None`
+    pub fn load(data_dir: &Path) -> Result<Option<Self>, String> {
+        let path = Self::path(data_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path)
+            .map_err(|e| format!("读取安装清单失败 ({}): {}", path.display(), e))?;
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("解析安装清单失败 ({}): {}", path.display(), e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("序列化安装清单失败: {}", e))
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<(), String> {
+        let path = Self::path(data_dir);
+        fs::write(&path, self.to_json()?)
+            .map_err(|e| format!("写入安装清单失败 ({}): {}", path.display(), e))
+    }
+
+    /// 逐个检查清单中的文件
+    pub fn verify(
+        &self,
+        game_dir: &Path,
+        current_sha256: &dyn Fn(&str) -> Option<String>,
+    ) -> Vec<FileCheck> {
+        self.files
+            .iter()
+            .map(|file| FileCheck {
+                path: file.path.clone(),
+                state: file.check(game_dir, current_sha256),
+            })
+            .collect()
+    }
+}
+
+/// 小写十六进制 SHA-256
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn relative_path(game_dir: &Path, path: &Path) -> Result<String, String> {
+    let relative = path
+        .strip_prefix(game_dir)
+        .map_err(|_| format!("{} 不在游戏目录内", path.display()))?;
+    if relative.as_os_str().is_empty() || !is_plain_relative(relative) {
+        return Err(format!("{} 不在游戏目录内", path.display()));
+    }
+    Ok(relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+fn resolve_relative(game_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    // Windows 的 `\` 根路径与盘符在其它平台上不算绝对路径，单独拒绝
+    if path.is_empty()
+        || path.starts_with(['/', '\\'])
+        || path.contains(':')
+        || relative.is_absolute()
+        || !is_plain_relative(relative)
+    {
+        return Err(format!("安装清单中的路径 {} 不在游戏目录内", path));
+    }
+    Ok(game_dir.join(relative))
+}
+
+/// 只包含普通路径段：没有根、盘符与 `..`
+fn is_plain_relative(path: &Path) -> bool {
+    path.components()
+        .all(|part| matches!(part, Component::Normal(_) | Component::CurDir))
+        && !path
+            .to_string_lossy()
+            .split(['/', '\\'])
+            .any(|part| part == "..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn deploy(game_dir: &Path, relative: &str, data: &[u8], backup: Option<&str>) -> InstalledFile {
+        let target = game_dir.join(relative);
+        fs::create_dir_all(target.parent().expect("缺少父目录")).expect("创建目录失败");
+        fs::write(&target, data).expect("写入文件失败");
+        let backup = backup.map(|path| game_dir.join(path));
+        if let Some(backup) = &backup {
+            fs::create_dir_all(backup.parent().expect("缺少父目录")).expect("创建目录失败");
+            fs::write(backup, b"orig").expect("写入备份失败");
+        }
+        InstalledFile::new(
+            game_dir,
+            &target,
+            "UnityPlayer.dll",
+            data,
+            "embedded",
+            backup.as_deref(),
+        )
+        .expect("记录文件失败")
+    }
+
+    #[test]
+    fn manifest_should_round_trip_with_relative_paths() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let file = deploy(
+            dir.path(),
+            "umamusume.exe.local/UnityPlayer.dll",
+            b"proxy",
+            None,
+        );
+        assert_eq!(file.path, "umamusume.exe.local/UnityPlayer.dll");
+        assert_eq!(file.sha256, sha256_hex(b"proxy"));
+
+        let data_dir = dir.path().join("guga_ura_data");
+        fs::create_dir_all(&data_dir).expect("创建数据目录失败");
+        assert_eq!(InstallManifest::load(&data_dir), Ok(None));
+
+        let manifest = InstallManifest::new("dmm", vec![file]);
+        manifest.save(&data_dir).expect("保存清单失败");
+        assert_eq!(InstallManifest::load(&data_dir), Ok(Some(manifest)));
+    }
+
+    #[test]
+    fn verify_should_report_each_file_state() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        let files = vec![
+            deploy(game_dir, "a/ok.dll", b"v2", None),
+            deploy(game_dir, "a/old.dll", b"v1", None),
+            deploy(game_dir, "a/changed.dll", b"v2", None),
+            deploy(game_dir, "a/gone.dll", b"v2", None),
+            deploy(
+                game_dir,
+                "a/backed.dll",
+                b"v2",
+                Some("guga_ura_data/orig.dll"),
+            ),
+        ];
+        fs::write(game_dir.join("a/changed.dll"), b"patched").expect("修改文件失败");
+        fs::remove_file(game_dir.join("a/gone.dll")).expect("删除文件失败");
+        fs::remove_file(game_dir.join("guga_ura_data/orig.dll")).expect("删除备份失败");
+
+        let current = sha256_hex(b"v2");
+        let checks =
+            InstallManifest::new("steam", files).verify(game_dir, &|_| Some(current.clone()));
+        let states: Vec<FileState> = checks.iter().map(|check| check.state).collect();
+
+        assert_eq!(
+            states,
+            vec![
+                FileState::Ok,
+                FileState::Outdated,
+                FileState::Modified,
+                FileState::Missing,
+                FileState::BackupMissing,
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_current_payload_should_not_flag_update() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let file = deploy(dir.path(), "x.dll", b"v1", None);
+
+        assert_eq!(file.check(dir.path(), &|_| None), FileState::Ok);
+    }
+
+    #[test]
+    fn paths_outside_game_dir_should_be_rejected() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path().join("game");
+        let outside = dir.path().join("other.dll");
+
+        assert!(InstalledFile::new(&game_dir, &outside, "x.dll", b"x", "embedded", None).is_err());
+        assert!(InstalledFile::new(
+            &game_dir,
+            &game_dir.join("..").join("other.dll"),
+            "x.dll",
+            b"x",
+            "embedded",
+            None
+        )
+        .is_err());
+
+        let mut file = deploy(&game_dir, "a/ok.dll", b"v1", None);
+        for path in [
+            outside.display().to_string(),
+            "../other.dll".to_string(),
+            "a/../../other.dll".to_string(),
+            "..\\other.dll".to_string(),
+            "C:\\Windows\\other.dll".to_string(),
+            "\\\\server\\share\\x.dll".to_string(),
+        ] {
+            file.path = path.clone();
+            assert!(file.absolute_path(&game_dir).is_err(), "{}", path);
+            assert_eq!(file.check(&game_dir, &|_| None), FileState::Modified);
+        }
+    }
+}
@@ -2,9 +2,23 @@
 
 use crate::detector::GameVersion;
 use crate::embedded_dlls;
+use crate::install_journal::{self, InstallTransaction, RollbackReport};
+use crate::install_manifest::{self, FileCheck, FileState, InstallManifest, InstalledFile};
+use chrono::Local;
+use guga_ura_config::profiles::PROFILES_DIR_NAME;
+use guga_ura_config::CONFIG_FILE_NAME;
+use guga_ura_diagnostics::{DiagnosticsLog, Level, INSTALL_LOG_FILE_NAME};
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// 卸载归档目录名，位于游戏目录下；每次归档建一个时间戳子目录
+pub const UNINSTALL_ARCHIVE_DIR_NAME: &str = "guga_ura_archive";
+
+/// 安装器写入数据目录、但不在清单文件列表中的文件
+const INSTALLER_DATA_FILES: &[&str] = &[
+    "install_info.txt",
+    install_manifest::INSTALL_MANIFEST_FILE_NAME,
+];
 
 /// 安装状态
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,6 +29,10 @@ pub enum InstallStatus {
     NotInstalled,
     /// 需要更新
     NeedsUpdate,
+    /// 部署的文件被其它程序修改
+    Modified,
+    /// 部分文件或备份缺失
+    Incomplete,
     /// 无法确定
     Unknown,
 }
@@ -25,37 +43,151 @@ impl InstallStatus {
             InstallStatus::Installed => "✅ 已安装",
             InstallStatus::NotInstalled => "❌ 未安装",
             InstallStatus::NeedsUpdate => "🔄 需要更新",
+            InstallStatus::Modified => "⚠️ 文件被修改",
+            InstallStatus::Incomplete => "⚠️ 安装不完整",
             InstallStatus::Unknown => "❓ 未知",
         }
     }
 }
 
+/// 安装检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallReport {
+    pub status: InstallStatus,
+    /// 安装清单；旧版本安装或未安装时为空
+    pub manifest: Option<InstallManifest>,
+    /// 状态不是正常安装时的具体原因
+    pub issues: Vec<String>,
+}
+
+impl InstallReport {
+    fn new(status: InstallStatus) -> Self {
+        Self {
+            status,
+            manifest: None,
+            issues: Vec::new(),
+        }
+    }
+}
+
 /// 检查安装状态
 pub fn check_install_status(game_dir: &Path, version: GameVersion) -> InstallStatus {
+    inspect_install(game_dir, version).status
+}
+
+/// 对照安装清单与当前 payload 检查已部署的文件
+pub fn inspect_install(game_dir: &Path, version: GameVersion) -> InstallReport {
+    inspect_install_with(game_dir, version, &current_payload_sha256)
+}
+
+fn inspect_install_with(
+    game_dir: &Path,
+    version: GameVersion,
+    current_sha256: &dyn Fn(&str) -> Option<String>,
+) -> InstallReport {
     let exe_name = match find_game_exe(game_dir) {
         Some(name) => name,
-        None => return InstallStatus::Unknown,
+        None => return InstallReport::new(InstallStatus::Unknown),
     };
 
     let data_dir = game_dir.join("guga_ura_data");
+    match InstallManifest::load(&data_dir) {
+        Ok(Some(manifest)) => {
+            let checks = manifest.verify(game_dir, current_sha256);
+            InstallReport {
+                status: status_from_checks(&checks),
+                issues: checks
+                    .iter()
+                    .filter(|check| check.state != FileState::Ok)
+                    .map(FileCheck::describe)
+                    .collect(),
+                manifest: Some(manifest),
+            }
+        }
+        Ok(None) => inspect_legacy_install(game_dir, version, &exe_name, current_sha256),
+        Err(e) => {
+            let mut report = inspect_legacy_install(game_dir, version, &exe_name, current_sha256);
+            report.issues.insert(0, e);
+            report
+        }
+    }
+}
+
+fn status_from_checks(checks: &[FileCheck]) -> InstallStatus {
+    let has = |state: FileState| checks.iter().any(|check| check.state == state);
+
+    if checks.is_empty() || checks.iter().all(|check| check.state == FileState::Missing) {
+        InstallStatus::NotInstalled
+    } else if has(FileState::Missing) || has(FileState::BackupMissing) {
+        InstallStatus::Incomplete
+    } else if has(FileState::Modified) {
+        InstallStatus::Modified
+    } else if has(FileState::Outdated) {
+        InstallStatus::NeedsUpdate
+    } else {
+        InstallStatus::Installed
+    }
+}
 
-    if version == GameVersion::Steam {
-        let backup_dll = data_dir.join("cri_mana_vpx_orig.dll");
-        if backup_dll.exists() {
-            return InstallStatus::Installed;
+/// 没有安装清单的旧版本安装：按约定位置判断，并与当前 payload 比较
+fn inspect_legacy_install(
+    game_dir: &Path,
+    version: GameVersion,
+    exe_name: &str,
+    current_sha256: &dyn Fn(&str) -> Option<String>,
+) -> InstallReport {
+    let data_dir = game_dir.join("guga_ura_data");
+
+    let deployed: Vec<(std::path::PathBuf, &str)> = if version == GameVersion::Steam {
+        if !data_dir.join("cri_mana_vpx_orig.dll").exists() {
+            return InstallReport::new(InstallStatus::NotInstalled);
+        }
+        match find_steam_plugins_dir(game_dir) {
+            Some(plugins_dir) => vec![(plugins_dir.join("cri_mana_vpx.dll"), "UnityPlayer.dll")],
+            None => {
+                let mut report = InstallReport::new(InstallStatus::Incomplete);
+                report
+                    .issues
+                    .push("找不到 Plugins/x86_64/cri_mana_vpx.dll".to_string());
+                return report;
+            }
         }
     } else {
         let local_dir = game_dir.join(format!("{}.local", exe_name));
-        if local_dir.exists() {
-            let unity_dll = local_dir.join("UnityPlayer.dll");
-            let apphelp_dll = local_dir.join("apphelp.dll");
-            if unity_dll.exists() && apphelp_dll.exists() {
-                return InstallStatus::Installed;
-            }
+        if !local_dir.exists() {
+            return InstallReport::new(InstallStatus::NotInstalled);
+        }
+        vec![
+            (local_dir.join("UnityPlayer.dll"), "UnityPlayer.dll"),
+            (local_dir.join("apphelp.dll"), "apphelp.dll"),
+        ]
+    };
+
+    let mut report = InstallReport::new(InstallStatus::Installed);
+    for (path, payload) in deployed {
+        let Ok(data) = fs::read(&path) else {
+            report.status = InstallStatus::Incomplete;
+            report.issues.push(format!("{}: 文件缺失", path.display()));
+            continue;
+        };
+        let outdated = current_sha256(payload)
+            .is_some_and(|current| current != install_manifest::sha256_hex(&data));
+        if outdated && report.status == InstallStatus::Installed {
+            report.status = InstallStatus::NeedsUpdate;
+            report.issues.push(format!(
+                "{}: 与当前 payload 不一致，需要更新",
+                path.display()
+            ));
         }
     }
+    report
+}
 
-    InstallStatus::NotInstalled
+/// 当前可用 payload（外部文件优先，其次内嵌）的 SHA-256
+fn current_payload_sha256(file_name: &str) -> Option<String> {
+    get_payload_data(file_name)
+        .ok()
+        .map(|(data, _)| install_manifest::sha256_hex(&data))
 }
 
 /// 获取当前可执行文件所在目录（用于查找编译好的DLL）
@@ -96,25 +228,15 @@ fn get_payload_data(file_name: &str) -> Result<(Vec<u8>, String), String> {
         return Ok((data, format!("external:{}", path.display())));
     }
 
-    if embedded_dlls::has_embedded_dlls() || embedded_dlls::has_embedded_funny_honey() {
-        match file_name {
-            "UnityPlayer.dll" => {
-                return Ok((
-                    embedded_dlls::UNITY_PLAYER_DLL.to_vec(),
-                    "embedded".to_string(),
-                ));
-            }
-            "apphelp.dll" => {
-                return Ok((embedded_dlls::APPHELP_DLL.to_vec(), "embedded".to_string()));
-            }
-            "FunnyHoney.exe" => {
-                return Ok((
-                    embedded_dlls::FUNNY_HONEY_EXE.to_vec(),
-                    "embedded".to_string(),
-                ));
-            }
-            _ => {}
-        }
+    // 只嵌入了部分资源时，未嵌入的资源为空
+    let embedded: &[u8] = match file_name {
+        "UnityPlayer.dll" => embedded_dlls::UNITY_PLAYER_DLL,
+        "apphelp.dll" => embedded_dlls::APPHELP_DLL,
+        "FunnyHoney.exe" => embedded_dlls::FUNNY_HONEY_EXE,
+        _ => &[],
+    };
+    if !embedded.is_empty() {
+        return Ok((embedded.to_vec(), "embedded".to_string()));
     }
 
     Err(format!(
@@ -123,22 +245,12 @@ fn get_payload_data(file_name: &str) -> Result<(Vec<u8>, String), String> {
     ))
 }
 
-fn append_install_log(game_dir: &Path, message: &str) {
-    let data_dir = game_dir.join("guga_ura_data");
-    if fs::create_dir_all(&data_dir).is_err() {
-        return;
-    }
-
-    let log_path = data_dir.join("install_trace.log");
-    let Ok(mut file) = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-    else {
-        return;
-    };
-
-    let _ = writeln!(file, "{}", message.replace('\r', " ").replace('\n', " "));
+fn install_log(game_dir: &Path, level: Level, message: &str) {
+    DiagnosticsLog::new(game_dir.join("guga_ura_data").join(INSTALL_LOG_FILE_NAME)).write(
+        level,
+        "installer",
+        message,
+    );
 }
 
 /// 安装 DLL
@@ -150,38 +262,69 @@ pub fn install_dll(game_dir: &Path, version: GameVersion) -> Result<(), String>
         return Err("未知的游戏版本".to_string());
     }
 
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install] start version={:?} game_dir={}",
+            "start version={:?} game_dir={}",
             version,
             game_dir.display()
         ),
     );
 
-    // 创建数据目录
-    let data_dir = game_dir.join("guga_ura_data");
-    fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    recover_interrupted_transaction(game_dir)?;
 
-    if version == GameVersion::Steam {
-        install_steam(game_dir, &data_dir)
-    } else {
-        install_dmm(game_dir, &data_dir)
+    let data_dir = game_dir.join("guga_ura_data");
+    let result = install_journal::run_transaction(game_dir, "install", |txn| {
+        // 创建数据目录
+        txn.create_dir_all(&data_dir)
+            .map_err(|e| format!("创建数据目录失败: {}", e))?;
+
+        let files = if version == GameVersion::Steam {
+            install_steam(txn, game_dir, &data_dir)?
+        } else {
+            install_dmm(txn, game_dir, &data_dir)?
+        };
+
+        let manifest = InstallManifest::new(version.as_str(), files);
+        txn.write_file(
+            &InstallManifest::path(&data_dir),
+            manifest.to_json()?.as_bytes(),
+        )?;
+        install_log(
+            game_dir,
+            Level::Info,
+            &format!(
+                "manifest written payload_version={} files={}",
+                manifest.payload_version,
+                manifest.files.len()
+            ),
+        );
+        Ok(())
+    });
+    if let Err(e) = &result {
+        install_log(game_dir, Level::Error, &format!("install failed: {}", e));
     }
+    result
 }
 
 /// Steam 版安装：直接替换 cri_mana_vpx.dll
 /// 注意：Steam版的cri_mana_vpx.dll位于 *_Data/Plugins/x86_64/ 目录下
-fn install_steam(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
-    cleanup_local_proxy_if_present(game_dir)?;
+fn install_steam(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+) -> Result<Vec<InstalledFile>, String> {
+    cleanup_local_proxy_if_present(txn, game_dir)?;
     let exe_name = find_game_exe(game_dir).ok_or_else(|| "找不到游戏可执行文件".to_string())?;
 
     let plugins_dir = find_steam_plugins_dir(game_dir)
         .ok_or("找不到 Plugins/x86_64 目录，可能不是正确的Steam游戏目录")?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][steam] plugins_dir={} data_dir={}",
+            "[steam] plugins_dir={} data_dir={}",
             plugins_dir.display(),
             data_dir.display()
         ),
@@ -198,42 +341,55 @@ fn install_steam(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
     }
 
     if !backup_dll.exists() {
-        fs::copy(&orig_dll, &backup_dll)
+        txn.copy_file(&orig_dll, &backup_dll)
             .map_err(|e| format!("备份 cri_mana_vpx.dll 失败: {}", e))?;
-        append_install_log(
+        install_log(
             game_dir,
+            Level::Info,
             &format!(
-                "[install][steam] backup created src={} dest={}",
+                "[steam] backup created src={} dest={}",
                 orig_dll.display(),
                 backup_dll.display()
             ),
         );
     } else {
-        append_install_log(
+        install_log(
             game_dir,
+            Level::Info,
             &format!(
-                "[install][steam] backup already exists path={}",
+                "[steam] backup already exists path={}",
                 backup_dll.display()
             ),
         );
     }
 
     let (dll_data, dll_source) = get_payload_data("UnityPlayer.dll")?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][steam] proxy payload source={} bytes={} target={}",
+            "[steam] proxy payload source={} bytes={} target={}",
             dll_source,
             dll_data.len(),
             orig_dll.display()
         ),
     );
 
-    fs::write(&orig_dll, &dll_data).map_err(|e| format!("替换 cri_mana_vpx.dll 失败: {}", e))?;
-    append_install_log(
+    txn.write_file(&orig_dll, &dll_data)
+        .map_err(|e| format!("替换 cri_mana_vpx.dll 失败: {}", e))?;
+    let mut files = vec![InstalledFile::new(
         game_dir,
+        &orig_dll,
+        "UnityPlayer.dll",
+        &dll_data,
+        &dll_source,
+        Some(&backup_dll),
+    )?];
+    install_log(
+        game_dir,
+        Level::Info,
         &format!(
-            "[install][steam] wrote proxy dll target={} bytes={}",
+            "[steam] wrote proxy dll target={} bytes={}",
             orig_dll.display(),
             dll_data.len()
         ),
@@ -241,15 +397,15 @@ fn install_steam(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
 
     // 记录安装信息
     let info = format!("steam\ncri_mana_vpx.dll\n{}", plugins_dir.display());
-    fs::write(data_dir.join("install_info.txt"), info)
+    txn.write_file(&data_dir.join("install_info.txt"), info.as_bytes())
         .map_err(|e| format!("保存安装信息失败: {}", e))?;
-    append_install_log(game_dir, "[install][steam] install completed");
+    install_log(game_dir, Level::Info, "[steam] install completed");
 
     if exe_name.eq_ignore_ascii_case("UmamusumePrettyDerby_Jpn.exe") {
-        install_funny_honey(game_dir, data_dir, &exe_name)?;
+        files.push(install_funny_honey(txn, game_dir, data_dir, &exe_name)?);
     }
 
-    Ok(())
+    Ok(files)
 }
 
 /// 查找Steam版游戏的Plugins/x86_64目录
@@ -285,47 +441,57 @@ fn find_steam_plugins_dir(game_dir: &Path) -> Option<std::path::PathBuf> {
 }
 
 /// DMM 版安装：使用 .local 文件夹
-fn install_dmm(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
+fn install_dmm(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+) -> Result<Vec<InstalledFile>, String> {
     let exe_name = find_game_exe(game_dir).ok_or_else(|| "找不到游戏可执行文件".to_string())?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][dmm] exe_name={} data_dir={}",
+            "[dmm] exe_name={} data_dir={}",
             exe_name,
             data_dir.display()
         ),
     );
 
-    install_local_proxy(game_dir, &exe_name, "dmm")?;
+    let files = install_local_proxy(txn, game_dir, &exe_name, "dmm")?;
 
     // 记录安装信息
     let info = format!("dmm\n{}", exe_name);
-    fs::write(data_dir.join("install_info.txt"), info)
+    txn.write_file(&data_dir.join("install_info.txt"), info.as_bytes())
         .map_err(|e| format!("保存安装信息失败: {}", e))?;
-    append_install_log(game_dir, "[install][dmm] install completed");
+    install_log(game_dir, Level::Info, "[dmm] install completed");
 
-    Ok(())
+    Ok(files)
 }
 
-fn install_local_proxy(game_dir: &Path, exe_name: &str, channel: &str) -> Result<(), String> {
+fn install_local_proxy(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    exe_name: &str,
+    channel: &str,
+) -> Result<Vec<InstalledFile>, String> {
     let local_dir = game_dir.join(format!("{}.local", exe_name));
-    fs::create_dir_all(&local_dir).map_err(|e| format!("创建 .local 目录失败: {}", e))?;
-    append_install_log(
+    txn.create_dir_all(&local_dir)
+        .map_err(|e| format!("创建 .local 目录失败: {}", e))?;
+    install_log(
         game_dir,
-        &format!(
-            "[install][{}] local_dir={}",
-            channel,
-            local_dir.display()
-        ),
+        Level::Info,
+        &format!("[{}] local_dir={}", channel, local_dir.display()),
     );
 
     let (unity_data, unity_source) = get_payload_data("UnityPlayer.dll")?;
-    fs::write(local_dir.join("UnityPlayer.dll"), &unity_data)
+    let unity_path = local_dir.join("UnityPlayer.dll");
+    txn.write_file(&unity_path, &unity_data)
         .map_err(|e| format!("写入 UnityPlayer.dll 失败: {}", e))?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][{}] wrote UnityPlayer.dll source={} bytes={}",
+            "[{}] wrote UnityPlayer.dll source={} bytes={}",
             channel,
             unity_source,
             unity_data.len()
@@ -333,19 +499,38 @@ fn install_local_proxy(game_dir: &Path, exe_name: &str, channel: &str) -> Result
     );
 
     let (apphelp_data, apphelp_source) = get_payload_data("apphelp.dll")?;
-    fs::write(local_dir.join("apphelp.dll"), &apphelp_data)
+    let apphelp_path = local_dir.join("apphelp.dll");
+    txn.write_file(&apphelp_path, &apphelp_data)
         .map_err(|e| format!("写入 apphelp.dll 失败: {}", e))?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][{}] wrote apphelp.dll source={} bytes={}",
+            "[{}] wrote apphelp.dll source={} bytes={}",
             channel,
             apphelp_source,
             apphelp_data.len()
         ),
     );
 
-    Ok(())
+    Ok(vec![
+        InstalledFile::new(
+            game_dir,
+            &unity_path,
+            "UnityPlayer.dll",
+            &unity_data,
+            &unity_source,
+            None,
+        )?,
+        InstalledFile::new(
+            game_dir,
+            &apphelp_path,
+            "apphelp.dll",
+            &apphelp_data,
+            &apphelp_source,
+            None,
+        )?,
+    ])
 }
 
 /// 查找游戏可执行文件名
@@ -375,45 +560,346 @@ fn find_game_exe(game_dir: &Path) -> Option<String> {
     None
 }
 
+/// 卸载时如何处理配置、方案、日志等用户数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UninstallMode {
+    /// 保留在原位置
+    #[default]
+    Keep,
+    /// 移动到 `guga_ura_archive/<时间戳>/`
+    Archive,
+    /// 全部删除
+    Purge,
+}
+
+impl UninstallMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UninstallMode::Keep => "keep",
+            UninstallMode::Archive => "archive",
+            UninstallMode::Purge => "purge",
+        }
+    }
+}
+
+/// 卸载结果，路径均相对游戏目录
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UninstallReport {
+    /// 删除的安装文件
+    pub removed: Vec<String>,
+    /// 从备份恢复的原文件
+    pub restored: Vec<String>,
+    /// 内容已不是安装器写入的版本而跳过的文件（`Purge` 时仍会处理）
+    pub modified: Vec<String>,
+    /// 按卸载方式保留、归档或删除的用户数据
+    pub user_data: Vec<String>,
+    pub archive_dir: Option<PathBuf>,
+    /// 未能归档或删除、留在原位置的用户数据（如被占用的日志）
+    pub skipped: Vec<String>,
+}
+
 /// 卸载 DLL
-pub fn uninstall_dll(game_dir: &Path, version: GameVersion) -> Result<(), String> {
+///
+/// 有安装清单时只撤销清单中记录的文件，没有清单（旧版本安装）时按固定位置恢复；
+/// 配置与日志按 `mode` 处理。
+pub fn uninstall_dll(
+    game_dir: &Path,
+    version: GameVersion,
+    mode: UninstallMode,
+) -> Result<UninstallReport, String> {
     if version == GameVersion::Unknown {
         return Err("未知的游戏版本".to_string());
     }
 
+    recover_interrupted_transaction(game_dir)?;
+
     let data_dir = game_dir.join("guga_ura_data");
+    let manifest = InstallManifest::load(&data_dir)?;
+    install_log(
+        game_dir,
+        Level::Info,
+        &format!(
+            "uninstall start version={:?} mode={} manifest={}",
+            version,
+            mode.as_str(),
+            manifest.is_some()
+        ),
+    );
+
+    let result = install_journal::run_transaction(game_dir, "uninstall", |txn| {
+        let mut report = UninstallReport::default();
+        let mut retained = Vec::new();
+        match &manifest {
+            Some(manifest) => {
+                uninstall_from_manifest(txn, game_dir, manifest, mode, &mut report)?;
+                retained = retain_modified_files(txn, game_dir, &data_dir, manifest, &report)?;
+            }
+            None if version == GameVersion::Steam => {
+                uninstall_steam(txn, game_dir, &data_dir, &mut report)?
+            }
+            None => uninstall_dmm(txn, game_dir, &mut report)?,
+        }
+        for name in INSTALLER_DATA_FILES {
+            let path = data_dir.join(name);
+            if !retained.contains(&path) {
+                txn.remove(&path)?;
+            }
+        }
+
+        // 在归档或删除前写入，卸载记录随安装日志一起保留
+        install_log(
+            game_dir,
+            Level::Info,
+            &format!(
+                "uninstall completed removed={} restored={}",
+                report.removed.join(","),
+                report.restored.join(",")
+            ),
+        );
+        handle_user_data(txn, game_dir, &data_dir, mode, &retained, &mut report)?;
+        Ok(report)
+    });
+    if let Err(e) = &result {
+        install_log(game_dir, Level::Error, &format!("uninstall failed: {}", e));
+    }
+    result
+}
 
-    if version == GameVersion::Steam {
-        uninstall_steam(game_dir, &data_dir)
+/// 按清单撤销安装：有备份的文件从备份恢复，其余删除
+///
+/// 内容与清单哈希不一致的文件不是安装器写入的（例如其它 mod 或游戏更新覆盖），
+/// 除 `Purge` 外原样保留并记入 `modified`。
+fn uninstall_from_manifest(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    manifest: &InstallManifest,
+    mode: UninstallMode,
+    report: &mut UninstallReport,
+) -> Result<(), String> {
+    for file in manifest.files.iter().rev() {
+        let target = file.absolute_path(game_dir)?;
+        let backup = file.backup_path(game_dir)?;
+        match file.matches_disk(game_dir)? {
+            Some(false) if mode != UninstallMode::Purge => {
+                install_log(
+                    game_dir,
+                    Level::Warn,
+                    &format!("skip modified file path={}", file.path),
+                );
+                report.modified.push(file.path.clone());
+                continue;
+            }
+            None if backup.is_none() => continue,
+            _ => {}
+        }
+        match backup {
+            Some(backup) => {
+                if !backup.exists() {
+                    return Err(format!("找不到 {} 的原文件备份，无法恢复", file.path));
+                }
+                txn.copy_file(&backup, &target)
+                    .map_err(|e| format!("恢复 {} 失败: {}", file.path, e))?;
+                txn.remove(&backup)
+                    .map_err(|e| format!("删除备份失败: {}", e))?;
+                report.restored.push(file.path.clone());
+            }
+            None => {
+                txn.remove(&target)
+                    .map_err(|e| format!("删除 {} 失败: {}", file.path, e))?;
+                report.removed.push(file.path.clone());
+                remove_dir_if_empty(txn, game_dir, target.parent())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 跳过的文件仍属于这次安装：清单改写为只含这些文件，与它们的备份一起留在数据目录，
+/// 状态检查会继续报告“文件被修改”。返回需要保留的路径
+fn retain_modified_files(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+    manifest: &InstallManifest,
+    report: &UninstallReport,
+) -> Result<Vec<PathBuf>, String> {
+    if report.modified.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut remaining = manifest.clone();
+    remaining
+        .files
+        .retain(|file| report.modified.contains(&file.path));
+    let manifest_path = InstallManifest::path(data_dir);
+    txn.write_file(&manifest_path, remaining.to_json()?.as_bytes())?;
+
+    let mut retained = vec![manifest_path];
+    for file in &remaining.files {
+        retained.extend(file.backup_path(game_dir)?);
+    }
+    Ok(retained)
+}
+
+/// 删除安装器创建、现已清空的目录（如 `.local`）；目录里还有其它文件时保留
+fn remove_dir_if_empty(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    dir: Option<&Path>,
+) -> Result<(), String> {
+    let Some(dir) = dir else {
+        return Ok(());
+    };
+    if dir == game_dir || !dir.starts_with(game_dir) {
+        return Ok(());
+    }
+    let is_empty = fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+    if is_empty {
+        txn.remove(dir)?;
+    }
+    Ok(())
+}
+
+/// 卸载后仍留在游戏目录中的用户数据：数据目录剩余内容、配置及其迁移备份、配置方案
+fn user_data_paths(game_dir: &Path, data_dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(data_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Ok(entries) = fs::read_dir(game_dir) {
+        paths.extend(
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    name == CONFIG_FILE_NAME
+                        || name.starts_with(&format!("{}.", CONFIG_FILE_NAME))
+                        || name == PROFILES_DIR_NAME
+                }),
+        );
+    }
+    paths.sort();
+    paths
+}
+
+fn handle_user_data(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+    mode: UninstallMode,
+    retained: &[PathBuf],
+    report: &mut UninstallReport,
+) -> Result<(), String> {
+    let mut paths = user_data_paths(game_dir, data_dir);
+    paths.retain(|path| !retained.contains(path));
+    report.user_data = paths
+        .iter()
+        .map(|path| relative_display(game_dir, path))
+        .collect();
+
+    match mode {
+        UninstallMode::Keep => {}
+        UninstallMode::Archive if !paths.is_empty() => {
+            let archive_dir = game_dir
+                .join(UNINSTALL_ARCHIVE_DIR_NAME)
+                .join(Local::now().format("%Y%m%d_%H%M%S").to_string());
+            for path in &paths {
+                let target = archive_dir.join(path.strip_prefix(game_dir).unwrap_or(path));
+                if let Some(parent) = target.parent() {
+                    txn.create_dir_all(parent)?;
+                }
+                if let Err(e) = txn.move_path(path, &target) {
+                    report.skipped.push(e);
+                }
+            }
+            report.archive_dir = Some(archive_dir);
+        }
+        UninstallMode::Archive => {}
+        UninstallMode::Purge => {
+            for path in &paths {
+                if let Err(e) = txn.remove(path) {
+                    report.skipped.push(e);
+                }
+            }
+        }
+    }
+
+    remove_dir_if_empty(txn, game_dir, Some(data_dir))
+}
+
+fn relative_display(game_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(game_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// 回滚上次中断的安装或卸载（配置工具中途退出时遗留），结果写入安装日志
+pub fn recover_interrupted_transaction(game_dir: &Path) -> Result<Option<RollbackReport>, String> {
+    let Some(report) = install_journal::recover_pending(game_dir)? else {
+        return Ok(None);
+    };
+
+    if report.errors.is_empty() {
+        install_log(
+            game_dir,
+            Level::Warn,
+            &format!(
+                "rolled back interrupted {} steps={}",
+                report.operation, report.undone
+            ),
+        );
+        Ok(Some(report))
     } else {
-        uninstall_dmm(game_dir, &data_dir)
+        let message = format!(
+            "回滚上次中断的 {} 未完成: {}",
+            report.operation,
+            report.errors.join("; ")
+        );
+        install_log(game_dir, Level::Error, &message);
+        Err(message)
     }
 }
 
-/// Steam 版卸载：恢复原始 cri_mana_vpx.dll
-fn uninstall_steam(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
-    cleanup_local_proxy_if_present(game_dir)?;
-    restore_funny_honey_backup_if_present(game_dir, data_dir)?;
+/// 没有安装清单时的 Steam 版卸载：恢复原始 cri_mana_vpx.dll
+fn uninstall_steam(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+    report: &mut UninstallReport,
+) -> Result<(), String> {
+    cleanup_local_proxy_if_present(txn, game_dir)?;
+    restore_funny_honey_backup_if_present(txn, game_dir, data_dir, report)?;
 
     let backup_dll = data_dir.join("cri_mana_vpx_orig.dll");
     if backup_dll.exists() {
         let plugins_dir = find_steam_plugins_dir(game_dir).ok_or("找不到 Plugins/x86_64 目录")?;
         let orig_dll = plugins_dir.join("cri_mana_vpx.dll");
-        fs::copy(&backup_dll, &orig_dll)
+        txn.copy_file(&backup_dll, &orig_dll)
             .map_err(|e| format!("恢复 cri_mana_vpx.dll 失败: {}", e))?;
-        fs::remove_file(&backup_dll).map_err(|e| format!("删除备份失败: {}", e))?;
+        txn.remove(&backup_dll)
+            .map_err(|e| format!("删除备份失败: {}", e))?;
+        report.restored.push(relative_display(game_dir, &orig_dll));
     } else {
         return Err("找不到备份文件，无法恢复".to_string());
     }
 
-    if data_dir.exists() {
-        let _ = fs::remove_dir_all(data_dir);
-    }
-
     Ok(())
 }
 
-fn install_funny_honey(game_dir: &Path, data_dir: &Path, exe_name: &str) -> Result<(), String> {
+fn install_funny_honey(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+    exe_name: &str,
+) -> Result<InstalledFile, String> {
     let target_exe = game_dir.join(exe_name);
     let backup_exe = data_dir.join("FunnyHoney_orig.exe");
 
@@ -422,76 +908,82 @@ fn install_funny_honey(game_dir: &Path, data_dir: &Path, exe_name: &str) -> Resu
     }
 
     if !backup_exe.exists() {
-        fs::copy(&target_exe, &backup_exe).map_err(|e| format!("备份原始启动器失败: {}", e))?;
-        append_install_log(
+        txn.copy_file(&target_exe, &backup_exe)
+            .map_err(|e| format!("备份原始启动器失败: {}", e))?;
+        install_log(
             game_dir,
+            Level::Info,
             &format!(
-                "[install][steam-jp] launcher backup created src={} dest={}",
+                "[steam-jp] launcher backup created src={} dest={}",
                 target_exe.display(),
                 backup_exe.display()
             ),
         );
     } else {
-        append_install_log(
+        install_log(
             game_dir,
+            Level::Info,
             &format!(
-                "[install][steam-jp] launcher backup already exists path={}",
+                "[steam-jp] launcher backup already exists path={}",
                 backup_exe.display()
             ),
         );
     }
 
     let (funny_honey_data, funny_honey_source) = get_payload_data("FunnyHoney.exe")?;
-    fs::write(&target_exe, &funny_honey_data)
+    txn.write_file(&target_exe, &funny_honey_data)
         .map_err(|e| format!("写入 FunnyHoney 启动器失败: {}", e))?;
-    append_install_log(
+    install_log(
         game_dir,
+        Level::Info,
         &format!(
-            "[install][steam-jp] wrote FunnyHoney source={} bytes={} target={}",
+            "[steam-jp] wrote FunnyHoney source={} bytes={} target={}",
             funny_honey_source,
             funny_honey_data.len(),
             target_exe.display()
         ),
     );
 
-    Ok(())
+    InstalledFile::new(
+        game_dir,
+        &target_exe,
+        "FunnyHoney.exe",
+        &funny_honey_data,
+        &funny_honey_source,
+        Some(&backup_exe),
+    )
 }
 
-/// DMM 版卸载：删除 .local 文件夹
-fn uninstall_dmm(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
+/// 没有安装清单时的 DMM 版卸载：删除 .local 文件夹
+fn uninstall_dmm(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    report: &mut UninstallReport,
+) -> Result<(), String> {
     let exe_name = find_game_exe(game_dir).ok_or_else(|| "找不到游戏可执行文件".to_string())?;
 
     // 删除 .local 文件夹
     let local_dir = game_dir.join(format!("{}.local", exe_name));
-    if local_dir.exists() {
-        fs::remove_dir_all(&local_dir).map_err(|e| format!("删除 .local 目录失败: {}", e))?;
-    }
-
-    // 删除数据目录
-    if data_dir.exists() {
-        let _ = fs::remove_dir_all(data_dir);
-    }
-
-    // 删除配置文件
-    let config_file = game_dir.join("guga_ura_config.json");
-    if config_file.exists() {
-        let _ = fs::remove_file(&config_file);
-    }
+    txn.remove(&local_dir)
+        .map_err(|e| format!("删除 .local 目录失败: {}", e))?;
+    report.removed.push(relative_display(game_dir, &local_dir));
 
     Ok(())
 }
 
-fn cleanup_local_proxy_if_present(game_dir: &Path) -> Result<(), String> {
+fn cleanup_local_proxy_if_present(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+) -> Result<(), String> {
     if let Some(exe_name) = find_game_exe(game_dir) {
         let local_dir = game_dir.join(format!("{}.local", exe_name));
         if local_dir.exists() {
-            fs::remove_dir_all(&local_dir).map_err(|e| format!("删除 .local 目录失败: {}", e))?;
-            append_install_log(
+            txn.remove(&local_dir)
+                .map_err(|e| format!("删除 .local 目录失败: {}", e))?;
+            install_log(
                 game_dir,
-                &format!(
-                    "[install] removed stale local proxy dir={}",
-                    local_dir.display()
-                ),
+                Level::Info,
+                &format!("removed stale local proxy dir={}", local_dir.display()),
             );
         }
     }
@@ -499,13 +991,23 @@ fn cleanup_local_proxy_if_present(game_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
-fn restore_funny_honey_backup_if_present(game_dir: &Path, data_dir: &Path) -> Result<(), String> {
+fn restore_funny_honey_backup_if_present(
+    txn: &mut InstallTransaction,
+    game_dir: &Path,
+    data_dir: &Path,
+    report: &mut UninstallReport,
+) -> Result<(), String> {
     let backup_exe = data_dir.join("FunnyHoney_orig.exe");
     let target_exe = game_dir.join("UmamusumePrettyDerby_Jpn.exe");
 
     if backup_exe.exists() {
-        fs::copy(&backup_exe, &target_exe).map_err(|e| format!("恢复原始 Steam JP 启动器失败: {}", e))?;
-        fs::remove_file(&backup_exe).map_err(|e| format!("删除 FunnyHoney 备份失败: {}", e))?;
+        txn.copy_file(&backup_exe, &target_exe)
+            .map_err(|e| format!("恢复原始 Steam JP 启动器失败: {}", e))?;
+        txn.remove(&backup_exe)
+            .map_err(|e| format!("删除 FunnyHoney 备份失败: {}", e))?;
+        report
+            .restored
+            .push(relative_display(game_dir, &target_exe));
     }
 
     Ok(())
@@ -513,11 +1015,39 @@ fn restore_funny_honey_backup_if_present(game_dir: &Path, data_dir: &Path) -> Re
 
 #[cfg(test)]
 mod tests {
-    use super::{check_install_status, InstallStatus};
+    use super::{
+        check_install_status, inspect_install_with, recover_interrupted_transaction, uninstall_dll,
+        InstallStatus, UninstallMode, UNINSTALL_ARCHIVE_DIR_NAME,
+    };
     use crate::detector::GameVersion;
+    use crate::install_journal::{InstallTransaction, INSTALL_TXN_DIR_NAME};
+    use crate::install_manifest::{sha256_hex, InstallManifest, InstalledFile};
+    use guga_ura_diagnostics::INSTALL_LOG_FILE_NAME;
     use std::fs;
+    use std::path::Path;
     use tempfile::tempdir;
 
+    /// 模拟 DMM 版安装：写入 .local 代理并记录清单
+    fn install_dmm_fixture(game_dir: &Path, payload: &[u8]) {
+        fs::write(game_dir.join("umamusume.exe"), []).expect("创建 exe 失败");
+        let local_dir = game_dir.join("umamusume.exe.local");
+        fs::create_dir_all(&local_dir).expect("创建 .local 目录失败");
+        let files = ["UnityPlayer.dll", "apphelp.dll"]
+            .into_iter()
+            .map(|name| {
+                let target = local_dir.join(name);
+                fs::write(&target, payload).expect("写入代理 DLL 失败");
+                InstalledFile::new(game_dir, &target, name, payload, "embedded", None)
+                    .expect("记录文件失败")
+            })
+            .collect();
+        let data_dir = game_dir.join("guga_ura_data");
+        fs::create_dir_all(&data_dir).expect("创建数据目录失败");
+        InstallManifest::new("dmm", files)
+            .save(&data_dir)
+            .expect("保存安装清单失败");
+    }
+
     #[test]
     fn check_install_status_should_detect_dmm_installation() {
         let dir = tempdir().expect("创建临时目录失败");
@@ -551,4 +1081,300 @@ mod tests {
 
         assert_eq!(status, InstallStatus::Installed);
     }
+
+    #[test]
+    fn inspect_should_report_outdated_payload_from_manifest() {
+        let dir = tempdir().expect("创建临时目录失败");
+        install_dmm_fixture(dir.path(), b"v1");
+        let v1 = sha256_hex(b"v1");
+        let v2 = sha256_hex(b"v2");
+
+        let current = inspect_install_with(dir.path(), GameVersion::DMM, &|_| Some(v1.clone()));
+        assert_eq!(current.status, InstallStatus::Installed);
+        assert!(current.issues.is_empty());
+
+        let outdated = inspect_install_with(dir.path(), GameVersion::DMM, &|_| Some(v2.clone()));
+        assert_eq!(outdated.status, InstallStatus::NeedsUpdate);
+        assert_eq!(outdated.issues.len(), 2);
+        assert_eq!(
+            outdated.manifest.expect("缺少安装清单").payload_version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    #[test]
+    fn inspect_should_report_tampered_and_partial_installs() {
+        let dir = tempdir().expect("创建临时目录失败");
+        install_dmm_fixture(dir.path(), b"v1");
+        let local_dir = dir.path().join("umamusume.exe.local");
+
+        fs::write(local_dir.join("apphelp.dll"), b"patched").expect("修改文件失败");
+        let tampered = inspect_install_with(dir.path(), GameVersion::DMM, &|_| None);
+        assert_eq!(tampered.status, InstallStatus::Modified);
+        assert!(tampered.issues[0].contains("apphelp.dll"));
+
+        fs::remove_file(local_dir.join("UnityPlayer.dll")).expect("删除文件失败");
+        let partial = inspect_install_with(dir.path(), GameVersion::DMM, &|_| None);
+        assert_eq!(partial.status, InstallStatus::Incomplete);
+
+        fs::remove_dir_all(&local_dir).expect("删除 .local 目录失败");
+        let removed = inspect_install_with(dir.path(), GameVersion::DMM, &|_| None);
+        assert_eq!(removed.status, InstallStatus::NotInstalled);
+    }
+
+    #[test]
+    fn legacy_install_without_manifest_should_compare_current_payload() {
+        let dir = tempdir().expect("创建临时目录失败");
+        fs::write(dir.path().join("umamusume.exe"), []).expect("创建 exe 失败");
+        let local_dir = dir.path().join("umamusume.exe.local");
+        fs::create_dir_all(&local_dir).expect("创建 .local 目录失败");
+        fs::write(local_dir.join("UnityPlayer.dll"), b"old").expect("创建 UnityPlayer.dll 失败");
+
+        let partial = inspect_install_with(dir.path(), GameVersion::DMM, &|_| None);
+        assert_eq!(partial.status, InstallStatus::Incomplete);
+
+        fs::write(local_dir.join("apphelp.dll"), b"old").expect("创建 apphelp.dll 失败");
+        let current = sha256_hex(b"new");
+        let outdated =
+            inspect_install_with(dir.path(), GameVersion::DMM, &|_| Some(current.clone()));
+        assert_eq!(outdated.status, InstallStatus::NeedsUpdate);
+        assert_eq!(outdated.manifest, None);
+    }
+
+    #[cfg(not(has_embedded_dlls))]
+    #[test]
+    fn failed_install_should_roll_back_created_files() {
+        let dir = tempdir().expect("创建临时目录失败");
+        fs::write(dir.path().join("umamusume.exe"), []).expect("创建 exe 失败");
+
+        // 未内嵌 payload 时写入 UnityPlayer.dll 前失败，已创建的 .local 目录应被撤销
+        let error = super::install_dll(dir.path(), GameVersion::DMM).expect_err("安装应失败");
+
+        assert!(error.contains("已撤销"), "{}", error);
+        assert!(!dir.path().join("umamusume.exe.local").exists());
+        assert!(!dir.path().join(INSTALL_TXN_DIR_NAME).exists());
+        assert_eq!(
+            check_install_status(dir.path(), GameVersion::DMM),
+            InstallStatus::NotInstalled
+        );
+    }
+
+    #[test]
+    fn interrupted_uninstall_should_be_rolled_back_on_next_launch() {
+        let dir = tempdir().expect("创建临时目录失败");
+        install_dmm_fixture(dir.path(), b"v1");
+        let local_dir = dir.path().join("umamusume.exe.local");
+
+        let mut txn = InstallTransaction::begin(dir.path(), "uninstall").expect("开始事务失败");
+        txn.remove(&local_dir).expect("删除 .local 目录失败");
+        drop(txn);
+        assert!(!local_dir.exists());
+
+        let report = recover_interrupted_transaction(dir.path())
+            .expect("恢复失败")
+            .expect("应存在遗留事务");
+
+        assert_eq!(report.operation, "uninstall");
+        assert!(local_dir.join("UnityPlayer.dll").exists());
+        assert_eq!(
+            check_install_status(dir.path(), GameVersion::DMM),
+            InstallStatus::Installed
+        );
+        assert_eq!(recover_interrupted_transaction(dir.path()), Ok(None));
+    }
+
+    /// 在 DMM 安装之外写入运行日志、安装信息与配置
+    fn add_user_data(game_dir: &Path) {
+        let data_dir = game_dir.join("guga_ura_data");
+        fs::write(data_dir.join("install_info.txt"), "dmm\numamusume.exe")
+            .expect("写入安装信息失败");
+        fs::write(data_dir.join("guga_ura_runtime.log"), "{}\n").expect("写入日志失败");
+        fs::write(game_dir.join("guga_ura_config.json"), "{}").expect("写入配置失败");
+        fs::create_dir_all(game_dir.join("guga_ura_profiles")).expect("创建方案目录失败");
+    }
+
+    #[test]
+    fn uninstall_should_remove_only_installed_files_and_keep_user_data() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        install_dmm_fixture(game_dir, b"v1");
+        add_user_data(game_dir);
+        let data_dir = game_dir.join("guga_ura_data");
+
+        let report =
+            uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Keep).expect("卸载失败");
+
+        assert_eq!(report.removed.len(), 2);
+        assert!(!game_dir.join("umamusume.exe.local").exists());
+        assert!(!data_dir.join("install_info.txt").exists());
+        assert!(!InstallManifest::path(&data_dir).exists());
+        assert!(data_dir.join("guga_ura_runtime.log").exists());
+        assert!(game_dir.join("guga_ura_config.json").exists());
+        assert!(report
+            .user_data
+            .iter()
+            .any(|path| path.ends_with("guga_ura_runtime.log")));
+        assert_eq!(report.archive_dir, None);
+        assert_eq!(
+            check_install_status(game_dir, GameVersion::DMM),
+            InstallStatus::NotInstalled
+        );
+    }
+
+    #[test]
+    fn uninstall_should_keep_foreign_files_in_local_dir() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        install_dmm_fixture(game_dir, b"v1");
+        let local_dir = game_dir.join("umamusume.exe.local");
+        fs::write(local_dir.join("other_mod.dll"), b"mod").expect("写入其它文件失败");
+
+        uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Keep).expect("卸载失败");
+
+        assert!(local_dir.join("other_mod.dll").exists());
+        assert!(!local_dir.join("UnityPlayer.dll").exists());
+    }
+
+    #[test]
+    fn uninstall_should_archive_user_data_to_timestamped_dir() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        install_dmm_fixture(game_dir, b"v1");
+        add_user_data(game_dir);
+
+        let report =
+            uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Archive).expect("卸载失败");
+
+        let archive_dir = report.archive_dir.expect("缺少归档目录");
+        assert!(archive_dir.starts_with(game_dir.join(UNINSTALL_ARCHIVE_DIR_NAME)));
+        assert!(archive_dir
+            .join("guga_ura_data")
+            .join("guga_ura_runtime.log")
+            .exists());
+        assert!(archive_dir
+            .join("guga_ura_data")
+            .join(INSTALL_LOG_FILE_NAME)
+            .exists());
+        assert!(archive_dir.join("guga_ura_config.json").exists());
+        assert!(archive_dir.join("guga_ura_profiles").is_dir());
+        assert!(!archive_dir
+            .join("guga_ura_data")
+            .join("install_info.txt")
+            .exists());
+        assert!(!game_dir.join("guga_ura_data").exists());
+        assert!(!game_dir.join("guga_ura_config.json").exists());
+    }
+
+    #[test]
+    fn uninstall_purge_should_remove_all_user_data() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        install_dmm_fixture(game_dir, b"v1");
+        add_user_data(game_dir);
+
+        let report =
+            uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Purge).expect("卸载失败");
+
+        assert!(report.skipped.is_empty());
+        let mut remaining: Vec<_> = fs::read_dir(game_dir)
+            .expect("读取游戏目录失败")
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["umamusume.exe"]);
+    }
+
+    #[test]
+    fn uninstall_should_restore_backed_up_files_from_manifest() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        fs::write(game_dir.join("UmamusumePrettyDerby_Jpn.exe"), []).expect("创建 exe 失败");
+        let plugins_dir = game_dir
+            .join("UmamusumePrettyDerby_Jpn_Data")
+            .join("Plugins")
+            .join("x86_64");
+        fs::create_dir_all(&plugins_dir).expect("创建 Plugins/x86_64 目录失败");
+        let target = plugins_dir.join("cri_mana_vpx.dll");
+        fs::write(&target, b"proxy").expect("写入代理 DLL 失败");
+        let data_dir = game_dir.join("guga_ura_data");
+        fs::create_dir_all(&data_dir).expect("创建数据目录失败");
+        let backup = data_dir.join("cri_mana_vpx_orig.dll");
+        fs::write(&backup, b"original").expect("写入备份失败");
+        let file = InstalledFile::new(
+            game_dir,
+            &target,
+            "UnityPlayer.dll",
+            b"proxy",
+            "embedded",
+            Some(&backup),
+        )
+        .expect("记录文件失败");
+        InstallManifest::new("steam", vec![file])
+            .save(&data_dir)
+            .expect("保存安装清单失败");
+
+        let report =
+            uninstall_dll(game_dir, GameVersion::Steam, UninstallMode::Keep).expect("卸载失败");
+
+        assert_eq!(
+            report.restored,
+            vec!["UmamusumePrettyDerby_Jpn_Data/Plugins/x86_64/cri_mana_vpx.dll"]
+        );
+        assert_eq!(fs::read(&target).expect("读取 DLL 失败"), b"original");
+        assert!(!backup.exists());
+        assert!(plugins_dir.is_dir());
+    }
+
+    #[test]
+    fn uninstall_should_skip_files_replaced_by_other_programs() {
+        let dir = tempdir().expect("创建临时目录失败");
+        let game_dir = dir.path();
+        install_dmm_fixture(game_dir, b"v1");
+        let replaced = game_dir.join("umamusume.exe.local").join("apphelp.dll");
+        fs::write(&replaced, b"other mod").expect("覆盖文件失败");
+
+        let report =
+            uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Archive).expect("卸载失败");
+
+        assert_eq!(report.modified, vec!["umamusume.exe.local/apphelp.dll"]);
+        assert_eq!(fs::read(&replaced).expect("读取文件失败"), b"other mod");
+        assert_eq!(
+            check_install_status(game_dir, GameVersion::DMM),
+            InstallStatus::Modified
+        );
+
+        let purged =
+            uninstall_dll(game_dir, GameVersion::DMM, UninstallMode::Purge).expect("卸载失败");
+        assert!(purged.modified.is_empty());
+        assert!(!replaced.exists());
+        assert!(!game_dir.join("guga_ura_data").exists());
+    }
+
+    #[test]
+    fn uninstall_should_reject_manifest_paths_outside_game_dir() {
+        let root = tempdir().expect("创建临时目录失败");
+        let game_dir = root.path().join("game");
+        fs::create_dir_all(&game_dir).expect("创建游戏目录失败");
+        install_dmm_fixture(&game_dir, b"v1");
+        let victim = root.path().join("victim.dll");
+        fs::write(&victim, b"v1").expect("写入文件失败");
+
+        let data_dir = game_dir.join("guga_ura_data");
+        let mut manifest = InstallManifest::load(&data_dir)
+            .expect("读取清单失败")
+            .expect("缺少清单");
+        manifest.files[0].path = "../victim.dll".to_string();
+        manifest.save(&data_dir).expect("保存清单失败");
+
+        let error = uninstall_dll(&game_dir, GameVersion::DMM, UninstallMode::Purge)
+            .expect_err("越界路径应被拒绝");
+
+        assert!(error.contains("不在游戏目录内"), "{}", error);
+        assert!(victim.exists());
+        assert!(game_dir
+            .join("umamusume.exe.local")
+            .join("apphelp.dll")
+            .exists());
+    }
 }
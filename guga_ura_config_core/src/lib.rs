@@ -1,9 +1,17 @@
 //! GugaURA 配置工具核心能力
 
+pub mod bundle;
+pub mod capture_health;
 pub mod config;
 pub mod detector;
 pub mod embedded_dlls;
+pub mod extractor_rules;
+pub mod install_journal;
+pub mod install_manifest;
 pub mod installer;
+pub mod profiles;
 pub mod receiver;
 pub mod receiver_pipeline;
+pub mod receiver_settings;
 pub mod stallion_output;
+pub mod support_bundle;
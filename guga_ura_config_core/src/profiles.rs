@@ -0,0 +1,246 @@
+//! 配置方案管理
+//!
+//! 配置工具同时维护游戏目录与 EXE 同级两份配置（DLL 读游戏目录，Receiver 读
+//! EXE 同级），方案也在两处保持一致：读取以第一个目标（有游戏目录时为游戏目录）
+//! 为准，写入同步到全部目标。
+
+use crate::config::{profiles, Config};
+use std::path::{Path, PathBuf};
+
+/// 方案列表与当前激活方案
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileOverview {
+    pub profiles: Vec<String>,
+    pub active: Option<String>,
+}
+
+/// 需要同步方案的配置文件：游戏目录（可选）与 EXE 同级
+pub fn profile_targets(game_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut targets = Vec::new();
+    if let Some(game_dir) = game_dir {
+        targets.push(Config::config_path(game_dir));
+    }
+
+    let exe_config_path = Config::exe_config_path();
+    if !targets.contains(&exe_config_path) {
+        targets.push(exe_config_path);
+    }
+    targets
+}
+
+/// 读取方案列表
+pub fn list_profiles(game_dir: Option<&Path>) -> ProfileOverview {
+    overview_of(&profile_targets(game_dir))
+}
+
+/// 以给定配置新建方案
+pub fn create_profile(
+    game_dir: Option<&Path>,
+    name: &str,
+    config: &Config,
+) -> Result<ProfileOverview, String> {
+    create_profile_in(&profile_targets(game_dir), name, config)
+}
+
+/// 复制已有方案
+pub fn clone_profile(
+    game_dir: Option<&Path>,
+    source: &str,
+    target_name: &str,
+) -> Result<ProfileOverview, String> {
+    clone_profile_in(&profile_targets(game_dir), source, target_name)
+}
+
+/// 重命名方案；激活中的方案会同步更新选择
+pub fn rename_profile(
+    game_dir: Option<&Path>,
+    from: &str,
+    to: &str,
+) -> Result<ProfileOverview, String> {
+    rename_profile_in(&profile_targets(game_dir), from, to)
+}
+
+/// 删除方案；激活中的方案会回到主配置
+pub fn delete_profile(game_dir: Option<&Path>, name: &str) -> Result<ProfileOverview, String> {
+    delete_profile_in(&profile_targets(game_dir), name)
+}
+
+/// 切换激活方案（`None` 表示回到主配置）
+pub fn activate_profile(
+    game_dir: Option<&Path>,
+    name: Option<&str>,
+) -> Result<ProfileOverview, String> {
+    activate_profile_in(&profile_targets(game_dir), name)
+}
+
+fn create_profile_in(
+    targets: &[PathBuf],
+    name: &str,
+    config: &Config,
+) -> Result<ProfileOverview, String> {
+    let name = profiles::normalize_profile_name(name)?;
+    ensure_absent(targets, &name)?;
+
+    for target in targets {
+        profiles::create_profile(target, &name, config)?;
+    }
+    Ok(overview_of(targets))
+}
+
+fn clone_profile_in(
+    targets: &[PathBuf],
+    source: &str,
+    target_name: &str,
+) -> Result<ProfileOverview, String> {
+    let config = load_primary(targets, source)?;
+    create_profile_in(targets, target_name, &config)
+}
+
+fn rename_profile_in(targets: &[PathBuf], from: &str, to: &str) -> Result<ProfileOverview, String> {
+    let config = load_primary(targets, from)?;
+    let to = profiles::normalize_profile_name(to)?;
+    if profiles::normalize_profile_name(from)? != to {
+        ensure_absent(targets, &to)?;
+    }
+
+    for target in targets {
+        if !profiles::profile_exists(target, from) {
+            profiles::save_profile(target, from, &config)?;
+        }
+        profiles::rename_profile(target, from, &to)?;
+    }
+    Ok(overview_of(targets))
+}
+
+fn delete_profile_in(targets: &[PathBuf], name: &str) -> Result<ProfileOverview, String> {
+    load_primary(targets, name)?;
+
+    for target in targets {
+        if profiles::profile_exists(target, name) {
+            profiles::delete_profile(target, name)?;
+        }
+    }
+    Ok(overview_of(targets))
+}
+
+fn activate_profile_in(targets: &[PathBuf], name: Option<&str>) -> Result<ProfileOverview, String> {
+    if let Some(name) = name {
+        let config = load_primary(targets, name)?;
+        for target in targets {
+            if !profiles::profile_exists(target, name) {
+                profiles::save_profile(target, name, &config)?;
+            }
+        }
+    }
+
+    for target in targets {
+        profiles::set_active_profile(target, name)?;
+    }
+    Ok(overview_of(targets))
+}
+
+fn overview_of(targets: &[PathBuf]) -> ProfileOverview {
+    let Some(primary) = targets.first() else {
+        return ProfileOverview {
+            profiles: Vec::new(),
+            active: None,
+        };
+    };
+
+    ProfileOverview {
+        profiles: profiles::list_profiles(primary),
+        active: profiles::read_active_profile(primary),
+    }
+}
+
+fn load_primary(targets: &[PathBuf], name: &str) -> Result<Config, String> {
+    let primary = targets
+        .first()
+        .ok_or_else(|| "没有可用的配置目录".to_string())?;
+    profiles::load_profile(primary, name)
+}
+
+fn ensure_absent(targets: &[PathBuf], name: &str) -> Result<(), String> {
+    if targets
+        .iter()
+        .any(|target| profiles::profile_exists(target, name))
+    {
+        return Err(format!("方案已存在: {}", name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Vec<PathBuf>) {
+        let root = tempfile::tempdir().expect("创建临时目录失败");
+        let game_dir = root.path().join("game");
+        let exe_dir = root.path().join("exe");
+        std::fs::create_dir_all(&game_dir).expect("创建游戏目录失败");
+        std::fs::create_dir_all(&exe_dir).expect("创建 EXE 目录失败");
+        let targets = vec![
+            Config::config_path(&game_dir),
+            Config::config_path(&exe_dir),
+        ];
+        (root, targets)
+    }
+
+    fn relay_config() -> Config {
+        Config {
+            relay_enabled: true,
+            relay_target_host: Some("http://10.0.0.2:4693".to_string()),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn create_and_activate_should_sync_all_targets() {
+        let (_root, targets) = setup();
+
+        create_profile_in(&targets, "team", &relay_config()).expect("新建方案失败");
+        let overview = activate_profile_in(&targets, Some("team")).expect("切换方案失败");
+
+        assert_eq!(overview.profiles, vec!["team"]);
+        assert_eq!(overview.active.as_deref(), Some("team"));
+        for target in &targets {
+            let effective = Config::load_file_or_default(target);
+            assert!(effective.relay_enabled, "{} 未应用方案", target.display());
+            assert_eq!(effective.active_profile.as_deref(), Some("team"));
+        }
+    }
+
+    #[test]
+    fn activate_should_copy_profile_missing_in_secondary_target() {
+        let (_root, targets) = setup();
+        profiles::create_profile(&targets[0], "local", &Config::default()).expect("新建方案失败");
+
+        activate_profile_in(&targets, Some("local")).expect("切换方案失败");
+
+        assert!(profiles::profile_exists(&targets[1], "local"));
+        assert_eq!(
+            profiles::read_active_profile(&targets[1]).as_deref(),
+            Some("local")
+        );
+    }
+
+    #[test]
+    fn clone_rename_delete_should_keep_targets_consistent() {
+        let (_root, targets) = setup();
+        create_profile_in(&targets, "team", &relay_config()).expect("新建方案失败");
+        activate_profile_in(&targets, Some("team")).expect("切换方案失败");
+
+        clone_profile_in(&targets, "team", "team_backup").expect("复制方案失败");
+        rename_profile_in(&targets, "team", "team_relay").expect("重命名失败");
+        assert!(rename_profile_in(&targets, "team_relay", "team_backup").is_err());
+        let overview = delete_profile_in(&targets, "team_relay").expect("删除失败");
+
+        assert_eq!(overview.profiles, vec!["team_backup"]);
+        assert_eq!(overview.active, None);
+        for target in &targets {
+            assert_eq!(profiles::list_profiles(target), vec!["team_backup"]);
+            assert_eq!(profiles::read_active_profile(target), None);
+        }
+    }
+}
@@ -3,44 +3,30 @@
 //! 在配置工具进程内监听本地 HTTP 端口，接收插件转发的 msgpack/json，
 //! 并保存为 JSON 到配置工具 EXE 同级 debug/ 目录。
 
+use crate::capture_health;
+use crate::config::validation::RECEIVER_RUNTIME_FIELDS;
 use crate::config::Config;
 use crate::receiver_pipeline::{self, ReceiverHeader, ReceiverProcessOutcome, RelayOutcome};
-use serde_json::json;
+use crate::receiver_settings::{self, ReceiverSettingSource};
+use guga_ura_diagnostics::{DiagnosticsLog, Level, TOOL_LOG_FILE_NAME};
 use std::collections::VecDeque;
 use std::fs;
-use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread::JoinHandle;
 use std::time::Duration;
-use tiny_http::{Method, Request, Response, Server, StatusCode};
-use url::Url;
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
 
 const MAX_LOG_LINES: usize = 600;
 pub const DEFAULT_RECEIVER_LISTEN_ADDR: &str = "127.0.0.1:4693";
 
 static SEQ: AtomicU64 = AtomicU64::new(0);
 static LOG_BUFFER: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+static FILE_LOG: OnceLock<DiagnosticsLog> = OnceLock::new();
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ReceiverListenAddrSource {
-    Cli,
-    Env,
-    ExeConfig,
-    Default,
-}
-
-impl ReceiverListenAddrSource {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            Self::Cli => "cli",
-            Self::Env => "env",
-            Self::ExeConfig => "exeConfig",
-            Self::Default => "default",
-        }
-    }
-}
+/// 监听地址来源（与其他 Receiver 字段共用同一套来源）
+pub type ReceiverListenAddrSource = ReceiverSettingSource;
 
 #[derive(Debug, Clone)]
 pub struct ReceiverListenAddrResolution {
@@ -99,14 +85,16 @@ pub fn start_embedded_receiver_managed() -> StartedEmbeddedReceiver {
 pub fn start_embedded_receiver_with_resolution(
     resolution: ReceiverListenAddrResolution,
 ) -> StartedEmbeddedReceiver {
+    FILE_LOG.get_or_init(tool_log);
     log_info(format!(
         "准备启动内置接收器: {} (source={} configured={})",
         resolution.listen_addr,
         resolution.source.as_str(),
         resolution.configured_listen_addr
     ));
+    log_receiver_settings();
 
-    let output_dir = default_output_dir();
+    let (output_dir, _) = receiver_settings::resolve_receiver_output_dir(None);
     if let Err(e) = fs::create_dir_all(&output_dir) {
         let msg = format!(
             "启动失败: 无法创建输出目录 {} ({})",
@@ -196,6 +184,11 @@ pub fn snapshot_logs(limit: usize) -> Vec<String> {
     }
 }
 
+/// 配置工具诊断日志（内置 Receiver 启动后写入）
+pub fn tool_log() -> DiagnosticsLog {
+    DiagnosticsLog::for_exe(TOOL_LOG_FILE_NAME)
+}
+
 pub fn clear_logs() {
     if let Ok(mut guard) = log_buffer().lock() {
         guard.clear();
@@ -213,10 +206,7 @@ pub fn resolve_receiver_listen_addr(cli_override: Option<&str>) -> ReceiverListe
 }
 
 pub fn parse_receiver_listen_addr(listen_addr: &str) -> Option<(String, u16)> {
-    let normalized = normalize_listen_addr(Some(listen_addr))?;
-    let (host, port) = normalized.rsplit_once(':')?;
-    let port = port.parse::<u16>().ok()?;
-    Some((host.to_string(), port))
+    guga_ura_config::address::parse_listen_addr(listen_addr)
 }
 
 pub fn normalize_receiver_listen_addr_input(value: &str) -> Option<String> {
@@ -224,6 +214,29 @@ pub fn normalize_receiver_listen_addr_input(value: &str) -> Option<String> {
 }
 
 fn handle_request(mut request: Request, output_dir: &Path, self_listen_addr: &str) {
+    if request.method() == &Method::Get && capture_health::is_status_route(request.url()) {
+        // 配置了签名密钥时，查询状态同样需要签名（请求体为空）
+        let config = receiver_settings::load_receiver_config();
+        let route = request.url().to_string();
+        let headers = headers_to_relay_headers(&request);
+        if let Err(error) =
+            receiver_pipeline::verify_receiver_request(&config, "GET", &route, &[], &headers)
+        {
+            log_warn(format!(
+                "拒绝签名无效的状态查询: route={} error={}",
+                route,
+                error.message()
+            ));
+            let _ = request.respond(
+                Response::from_string(error.message())
+                    .with_status_code(StatusCode(error.status_code())),
+            );
+            return;
+        }
+        respond_capture_health(request);
+        return;
+    }
+
     if request.method() != &Method::Post {
         log_warn(format!(
             "拒绝非 POST 请求: method={} route={}",
@@ -249,100 +262,169 @@ fn handle_request(mut request: Request, output_dir: &Path, self_listen_addr: &st
 
     let headers_json = headers_to_json(&request);
     let relay_headers = headers_to_relay_headers(&request);
+    let config = receiver_settings::load_receiver_config();
 
-    let response =
-        match receiver_pipeline::prepare_receiver_payload(output_dir, &route, None, &body, || {
-            SEQ.fetch_add(1, Ordering::Relaxed)
-        }) {
-            Ok(ReceiverProcessOutcome::Ignored) => {
-                (StatusCode(200), "ignored: non-response payload".to_string())
+    // 配置了签名密钥时拒绝未签名、签名错误或过期的请求，也不再转发
+    if let Err(error) = receiver_pipeline::verify_receiver_request(
+        &config,
+        request.method().as_str(),
+        &route,
+        &body,
+        &relay_headers,
+    ) {
+        log_warn(format!(
+            "拒绝签名无效的请求: route={} error={}",
+            route,
+            error.message()
+        ));
+        let _ = request.respond(
+            Response::from_string(error.message())
+                .with_status_code(StatusCode(error.status_code())),
+        );
+        return;
+    }
+
+    // 按 Content-Encoding 解压；relay 仍透传原始请求体
+    let payload_body = match receiver_pipeline::decode_receiver_body(&body, &relay_headers) {
+        Ok(decoded) => decoded,
+        Err(error) => {
+            log_warn(format!(
+                "请求体解压失败: route={} error={}",
+                route,
+                error.message()
+            ));
+            let _ = request.respond(
+                Response::from_string(error.message())
+                    .with_status_code(StatusCode(error.status_code())),
+            );
+            return;
+        }
+    };
+
+    if capture_health::is_status_route(&route) {
+        let (status, message) = match capture_health::record_capture_health(&payload_body) {
+            Ok(()) => (StatusCode(200), "status recorded".to_string()),
+            Err(error) => {
+                log_warn(format!("DLL 状态解析失败: error={}", error));
+                (StatusCode(400), error)
             }
-            Ok(ReceiverProcessOutcome::Saved(prepared)) => {
-                if let Some(path) = prepared.fans_output_path.as_ref() {
+        };
+        let _ = request.respond(Response::from_string(message).with_status_code(status));
+        return;
+    }
+
+    let response = match receiver_pipeline::prepare_receiver_payload(
+        &config,
+        output_dir,
+        &route,
+        &payload_body,
+        || SEQ.fetch_add(1, Ordering::Relaxed),
+    ) {
+        Ok(ReceiverProcessOutcome::Ignored) => {
+            (StatusCode(200), "ignored: non-response payload".to_string())
+        }
+        Ok(ReceiverProcessOutcome::Saved(prepared)) => {
+            if let Some(path) = prepared.fans_output_path.as_ref() {
+                log_info(format!(
+                    "社团Fans 更新: route={} output={}",
+                    prepared.route,
+                    path.display()
+                ));
+            }
+            if let Some(error) = prepared.fans_error.as_ref() {
+                log_warn(format!(
+                    "社团Fans 失败: route={} error={}",
+                    prepared.route, error
+                ));
+            }
+
+            // 种马/玩家数据输出日志
+            if let Some(ref stallion) = prepared.stallion_output {
+                if let Some(path) = stallion.stallion_data_path.as_ref() {
+                    log_info(format!(
+                        "种马数据输出: route={} output={}",
+                        prepared.route,
+                        path.display()
+                    ));
+                }
+                if let Some(path) = stallion.player_profile_path.as_ref() {
                     log_info(format!(
-                        "社团Fans 更新: route={} output={}",
+                        "玩家资料输出: route={} output={}",
                         prepared.route,
                         path.display()
                     ));
                 }
-                if let Some(error) = prepared.fans_error.as_ref() {
+                if let Some(error) = stallion.error.as_ref() {
                     log_warn(format!(
-                        "社团Fans 失败: route={} error={}",
+                        "种马/玩家输出失败: route={} error={}",
                         prepared.route, error
                     ));
                 }
+            }
 
-                // 种马/玩家数据输出日志
-                if let Some(ref stallion) = prepared.stallion_output {
-                    if let Some(path) = stallion.stallion_data_path.as_ref() {
-                        log_info(format!(
-                            "种马数据输出: route={} output={}",
-                            prepared.route,
-                            path.display()
-                        ));
-                    }
-                    if let Some(path) = stallion.player_profile_path.as_ref() {
-                        log_info(format!(
-                            "玩家资料输出: route={} output={}",
-                            prepared.route,
-                            path.display()
-                        ));
-                    }
-                    if let Some(error) = stallion.error.as_ref() {
-                        log_warn(format!(
-                            "种马/玩家输出失败: route={} error={}",
-                            prepared.route, error
-                        ));
-                    }
+            // 自定义提取规则日志
+            for outcome in &prepared.extractor_outcomes {
+                for path in &outcome.output_paths {
+                    log_info(format!(
+                        "提取规则输出: rule={} output={}",
+                        outcome.rule,
+                        path.display()
+                    ));
                 }
-
-                let wrapper = json!({
-                    "direction": prepared.direction,
-                    "route": prepared.route,
-                    "received_at_unix_ms": prepared.now_ms,
-                    "payload_size": body.len(),
-                    "decoded_as": prepared.decoded_as,
-                    "headers": headers_json,
-                    "payload": prepared.payload
-                });
-
-                match receiver_pipeline::write_receiver_payload_json(&prepared.file_path, &wrapper)
-                {
-                    Ok(()) => {
-                        log_info(format!(
-                            "保存 payload 成功: route={} decoded={} output={}",
-                            route,
-                            wrapper["decoded_as"].as_str().unwrap_or("unknown"),
-                            prepared.file_path.display()
-                        ));
-                        (
-                            StatusCode(200),
-                            format!("saved: {}", prepared.file_path.display()),
-                        )
-                    }
-                    Err(error) => {
-                        log_error(format!(
-                            "写入 debug 文件失败: route={} output={} error={}",
-                            route,
-                            prepared.file_path.display(),
-                            error
-                        ));
-                        (StatusCode(500), error)
-                    }
+                if let Some(error) = outcome.error.as_ref() {
+                    log_warn(format!(
+                        "提取规则失败: rule={} route={} error={}",
+                        outcome.rule, prepared.route, error
+                    ));
                 }
             }
-            Err(error) => {
-                if error.contains("Empty request body") {
-                    log_warn(format!("收到空请求体: route={}", route));
-                } else {
-                    log_warn(format!("payload 解码失败: route={} error={}", route, error));
+
+            let wrapper = prepared
+                .payload_file(payload_body.len())
+                .with_extra("headers", headers_json);
+
+            match receiver_pipeline::write_receiver_payload_json(&prepared.file_path, &wrapper) {
+                Ok(()) => {
+                    log_info(format!(
+                        "保存 payload 成功: route={} decoded={} output={}",
+                        route,
+                        wrapper.decoded_as,
+                        prepared.file_path.display()
+                    ));
+                    (
+                        StatusCode(200),
+                        format!("saved: {}", prepared.file_path.display()),
+                    )
+                }
+                Err(error) => {
+                    log_error(format!(
+                        "写入 debug 文件失败: route={} output={} error={}",
+                        route,
+                        prepared.file_path.display(),
+                        error
+                    ));
+                    (StatusCode(500), error)
                 }
-                (StatusCode(400), error)
             }
-        };
+        }
+        Err(error) => {
+            if error.contains("Empty request body") {
+                log_warn(format!("收到空请求体: route={}", route));
+            } else {
+                log_warn(format!("payload 解码失败: route={} error={}", route, error));
+            }
+            (StatusCode(400), error)
+        }
+    };
 
     log_relay_outcome(
-        receiver_pipeline::relay_receiver_payload(self_listen_addr, &route, &body, &relay_headers),
+        receiver_pipeline::relay_receiver_payload(
+            &config,
+            self_listen_addr,
+            &route,
+            &body,
+            &relay_headers,
+        ),
         &route,
     );
 
@@ -360,6 +442,17 @@ fn headers_to_json(request: &Request) -> serde_json::Value {
     serde_json::Value::Object(map)
 }
 
+/// `GET /status`：返回最近一次 DLL 状态
+fn respond_capture_health(request: Request) {
+    let body = serde_json::to_string_pretty(&capture_health::capture_health_view())
+        .unwrap_or_else(|_| "{}".to_string());
+    let mut response = Response::from_string(body);
+    if let Ok(header) = "Content-Type: application/json".parse::<Header>() {
+        response = response.with_header(header);
+    }
+    let _ = request.respond(response);
+}
+
 fn headers_to_relay_headers(request: &Request) -> Vec<ReceiverHeader> {
     request
         .headers()
@@ -368,7 +461,7 @@ fn headers_to_relay_headers(request: &Request) -> Vec<ReceiverHeader> {
         .collect()
 }
 
-fn default_output_dir() -> PathBuf {
+pub(crate) fn default_output_dir() -> PathBuf {
     if let Ok(mut exe_path) = std::env::current_exe() {
         exe_path.pop();
         return exe_path.join("debug");
@@ -387,7 +480,7 @@ fn configured_receiver_listen_addr() -> String {
     }
 }
 
-fn resolve_receiver_listen_addr_with_inputs(
+pub(crate) fn resolve_receiver_listen_addr_with_inputs(
     cli_override: Option<&str>,
     env_override: Option<&str>,
     configured_listen_addr: &str,
@@ -424,43 +517,7 @@ fn resolve_receiver_listen_addr_with_inputs(
 }
 
 fn normalize_listen_addr(value: Option<&str>) -> Option<String> {
-    let value = value?.trim();
-    if value.is_empty() {
-        return None;
-    }
-
-    if let Some(normalized) = normalize_listen_addr_from_url(value) {
-        return Some(normalized);
-    }
-
-    let (host, port) = value.rsplit_once(':')?;
-    let host = host.trim();
-    let port = port.trim().parse::<u16>().ok()?;
-
-    if host.is_empty() || port == 0 || !is_valid_listen_host(host) {
-        return None;
-    }
-
-    Some(format!("{}:{}", host, port))
-}
-
-fn normalize_listen_addr_from_url(value: &str) -> Option<String> {
-    let url = Url::parse(value).ok()?;
-    if !matches!(url.scheme(), "http" | "https") {
-        return None;
-    }
-
-    let host = url.host_str()?;
-    let port = url.port()?;
-    if !is_valid_listen_host(host) {
-        return None;
-    }
-
-    Some(format!("{}:{}", host, port))
-}
-
-fn is_valid_listen_host(host: &str) -> bool {
-    host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok()
+    guga_ura_config::address::normalize_listen_addr(value?)
 }
 
 fn build_runtime_info(
@@ -495,25 +552,61 @@ fn log_relay_outcome(outcome: RelayOutcome, route: &str) {
     }
 }
 
+fn log_receiver_settings() {
+    let resolution = receiver_settings::resolve_receiver_settings();
+    for report in &resolution.fields {
+        if matches!(
+            report.source,
+            ReceiverSettingSource::Cli | ReceiverSettingSource::Env
+        ) {
+            log_info(format!(
+                "Receiver 字段 {} 使用 {} 覆盖: {}",
+                report.field,
+                report.source.as_str(),
+                report.value
+            ));
+        }
+    }
+    for rejected in &resolution.rejected {
+        log_warn(format!("忽略无效的 Receiver 覆盖值: {}", rejected));
+    }
+
+    for issue in resolution
+        .config
+        .validate()
+        .only_fields(RECEIVER_RUNTIME_FIELDS)
+        .issues
+    {
+        if issue.is_error() {
+            log_error(format!("Receiver 配置字段无效: {}", issue));
+        } else {
+            log_warn(format!("Receiver 配置字段可疑: {}", issue));
+        }
+    }
+}
+
 fn log_buffer() -> &'static Mutex<VecDeque<String>> {
     LOG_BUFFER.get_or_init(|| Mutex::new(VecDeque::with_capacity(MAX_LOG_LINES)))
 }
 
 fn log_info(message: impl AsRef<str>) {
-    push_log("INFO", message.as_ref());
+    push_log(Level::Info, message.as_ref());
 }
 
 fn log_warn(message: impl AsRef<str>) {
-    push_log("WARN", message.as_ref());
+    push_log(Level::Warn, message.as_ref());
 }
 
 fn log_error(message: impl AsRef<str>) {
-    push_log("ERROR", message.as_ref());
+    push_log(Level::Error, message.as_ref());
 }
 
-fn push_log(level: &str, message: &str) {
-    let line = format!("[{}][{}] {}", hms_now(), level, message);
+fn push_log(level: Level, message: &str) {
+    let line = format!("[{}][{}] {}", hms_now(), level.as_str(), message);
     eprintln!("{}", line);
+    if let Some(file_log) = FILE_LOG.get() {
+        file_log.write(level, "receiver", message);
+    }
 
     if let Ok(mut guard) = log_buffer().lock() {
         guard.push_back(line);
@@ -535,13 +628,14 @@ mod tests {
         resolve_receiver_listen_addr_with_inputs, snapshot_logs, ReceiverListenAddrSource,
         DEFAULT_RECEIVER_LISTEN_ADDR, MAX_LOG_LINES,
     };
+    use guga_ura_diagnostics::Level;
 
     #[test]
     fn log_buffer_should_keep_latest_lines() {
         clear_logs();
 
         for i in 0..(MAX_LOG_LINES + 5) {
-            push_log("INFO", &format!("line {}", i));
+            push_log(Level::Info, &format!("line {}", i));
         }
 
         let lines = snapshot_logs(0);
@@ -579,11 +673,8 @@ mod tests {
             ReceiverListenAddrSource::ExeConfig
         );
 
-        let url_resolution = resolve_receiver_listen_addr_with_inputs(
-            None,
-            None,
-            "http://127.0.0.1:4901/receiver",
-        );
+        let url_resolution =
+            resolve_receiver_listen_addr_with_inputs(None, None, "http://127.0.0.1:4901/receiver");
         assert_eq!(url_resolution.listen_addr, "127.0.0.1:4901");
         assert_eq!(url_resolution.source, ReceiverListenAddrSource::ExeConfig);
     }
@@ -624,6 +715,9 @@ mod tests {
             normalize_receiver_listen_addr_input("https://localhost:4692/path"),
             Some("localhost:4692".to_string())
         );
-        assert_eq!(normalize_receiver_listen_addr_input("http://bad-host:4692"), None);
+        assert_eq!(
+            normalize_receiver_listen_addr_input("http://bad-host:4692"),
+            None
+        );
     }
 }
@@ -2,8 +2,18 @@
 //!
 //! 该模块只抽取内置 Receiver 与独立 Receiver 共享的 payload 处理逻辑，
 //! 不引入新的 server 抽象，不改变各自 transport 壳。
+//! 生效配置由调用方在每个请求开始时解析一次，再传给这里的各个步骤。
 
 use crate::config::Config;
+use guga_ura_config::address;
+use guga_ura_config::time::receive_time_settings;
+use guga_ura_transport::capture::{
+    self, CaptureDirection, CaptureRoute, CapturedPayloadFile, DEFAULT_CAPTURE_CHANNEL,
+};
+use guga_ura_transport::signing::{self, SignatureError, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use guga_ura_transport::transport::{
+    self, sign_post, ContentEncoding, CONTENT_ENCODING_HEADER, UNSUPPORTED_ENCODING_STATUS,
+};
 use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -22,14 +32,31 @@ pub enum ReceiverProcessOutcome {
 #[derive(Debug)]
 pub struct PreparedReceiverPayload {
     pub route: String,
-    pub direction: String,
+    /// 捕获通道与方向，见 [`guga_ura_transport::capture`]
+    pub capture_route: CaptureRoute,
     pub now_ms: u64,
+    /// 按配置时间格式输出的接收时间
+    pub received_at: Value,
     pub decoded_as: String,
     pub payload: Value,
     pub file_path: PathBuf,
     pub fans_output_path: Option<PathBuf>,
     pub fans_error: Option<String>,
     pub stallion_output: Option<crate::stallion_output::StallionOutputResult>,
+    pub extractor_outcomes: Vec<crate::extractor_rules::ExtractorRuleOutcome>,
+}
+
+impl PreparedReceiverPayload {
+    /// 落盘的包装结构，与 DLL debug 输出相同；`headers` / `source` 等由调用方追加
+    pub fn payload_file(&self, payload_size: usize) -> CapturedPayloadFile {
+        CapturedPayloadFile::new(
+            &self.capture_route,
+            &self.route,
+            self.now_ms,
+            self.received_at.clone(),
+        )
+        .with_payload(payload_size, &self.decoded_as, self.payload.clone())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -51,6 +78,30 @@ impl ReceiverHeader {
     }
 }
 
+/// 请求体解码失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyDecodeError {
+    /// `Content-Encoding` 不受支持，应返回 415
+    UnsupportedEncoding(String),
+    /// 压缩数据损坏或超出大小上限，应返回 400
+    Corrupt(String),
+}
+
+impl BodyDecodeError {
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::UnsupportedEncoding(_) => UNSUPPORTED_ENCODING_STATUS,
+            Self::Corrupt(_) => 400,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::UnsupportedEncoding(message) | Self::Corrupt(message) => message,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RelayOutcome {
     Disabled,
@@ -66,12 +117,16 @@ struct ReceiverRelaySettings {
     target_host: Option<String>,
     timeout_ms: u64,
     self_listen_addr: String,
+    /// 转发时用于重新签名的密钥
+    shared_secret: Option<String>,
 }
 
+/// 处理一次 notify 请求：按 `/notify/{channel}/{direction}` 路由，
+/// 请求方向与无法识别的路由直接忽略（见 [`resolve_capture_route`]）
 pub fn prepare_receiver_payload<F>(
+    config: &Config,
     output_dir: &Path,
     route: &str,
-    fixed_direction: Option<&str>,
     body: &[u8],
     next_seq: F,
 ) -> Result<ReceiverProcessOutcome, String>
@@ -82,10 +137,13 @@ where
         return Err("Empty request body".to_string());
     }
 
-    let direction = resolve_direction(route, fixed_direction);
-    if !guga_ura_fans::should_persist_debug_payload(&direction, route) {
+    let Some(capture_route) = resolve_capture_route(route) else {
+        return Ok(ReceiverProcessOutcome::Ignored);
+    };
+    if capture_route.direction != CaptureDirection::Response {
         return Ok(ReceiverProcessOutcome::Ignored);
     }
+    let direction = capture_route.direction.as_str().to_string();
 
     fs::create_dir_all(output_dir).map_err(|e| format!("create_dir_all failed: {}", e))?;
 
@@ -93,17 +151,23 @@ where
     let now_ms = u64::try_from(guga_ura_fans::now_millis())
         .map_err(|_| "now_millis overflowed u64".to_string())?;
     let seq = next_seq();
-    let filename = format!("{}_{:06}_{}.json", sanitize_tag(&direction), seq, now_ms);
+    let filename = format!(
+        "{}.json",
+        capture::capture_file_stem(&capture_route, seq, now_ms)
+    );
     let file_path = output_dir.join(filename);
 
-    let fans_settings = guga_ura_fans::resolve_fans_settings_from_exe_config();
-    let (fans_output_path, fans_error) = if fans_settings.enabled {
-        match guga_ura_fans::upsert_fans_from_decoded_payload(
+    // 社团 Fans 与种马输出只解析游戏 HTTP API 的响应
+    let http_channel = capture_route.is_default_channel();
+    let fans_settings = guga_ura_fans::resolve_fans_settings(config);
+    let (fans_output_path, fans_error) = if fans_settings.enabled && http_channel {
+        match guga_ura_fans::upsert_fans_from_decoded_payload_with_time(
             &payload,
             &direction,
             route,
             now_ms.into(),
             &fans_settings.output_dir,
+            &fans_settings.time,
         ) {
             Ok(path) => (path, None),
             Err(error) => (None, Some(error)),
@@ -114,13 +178,15 @@ where
 
     // 种马/玩家数据输出
     let stallion_output = {
-        let settings = crate::stallion_output::resolve_stallion_output_settings();
-        if settings.enabled {
-            let result = crate::stallion_output::extract_and_write(
+        let settings = crate::stallion_output::resolve_stallion_output_settings_from(config);
+        if settings.enabled && http_channel {
+            let result = crate::stallion_output::extract_and_write_with_schema(
                 &payload,
                 &direction,
                 route,
                 &settings.output_dir,
+                settings.schema_version,
+                &settings.time,
             );
             if result.stallion_data_path.is_some()
                 || result.player_profile_path.is_some()
@@ -135,75 +201,127 @@ where
         }
     };
 
+    // 用户自定义提取规则
+    let receive_time = receive_time_settings(config);
+    let extractor_outcomes =
+        crate::extractor_rules::run_configured_rules(&payload, now_ms, &receive_time);
+
     Ok(ReceiverProcessOutcome::Saved(PreparedReceiverPayload {
         route: route.to_string(),
-        direction,
+        capture_route,
         now_ms,
+        received_at: receive_time.format_unix_ms(now_ms),
         decoded_as: decoded_as.to_string(),
         payload,
         file_path,
         fans_output_path,
         fans_error,
         stallion_output,
+        extractor_outcomes,
     }))
 }
 
-pub fn write_receiver_payload_json(file_path: &Path, wrapper: &Value) -> Result<(), String> {
-    let json_str = serde_json::to_string_pretty(wrapper)
-        .map_err(|e| format!("to_string_pretty failed: {}", e))?;
-    fs::write(file_path, json_str)
-        .map_err(|e| format!("write {} failed: {}", file_path.display(), e))
+/// 请求头中声明的传输编码
+pub fn content_encoding_of(headers: &[ReceiverHeader]) -> Result<ContentEncoding, String> {
+    ContentEncoding::from_header(header_value(headers, CONTENT_ENCODING_HEADER))
 }
 
-pub fn resolve_direction(route: &str, fixed_direction: Option<&str>) -> String {
-    fixed_direction
-        .map(str::to_string)
-        .unwrap_or_else(|| guga_ura_fans::infer_direction(route))
+/// 按 Receiver 生效配置中的 `shared_secret` 校验请求签名；`method` 与 `route`
+/// 为请求实际到达的路由
+pub fn verify_receiver_request(
+    config: &Config,
+    method: &str,
+    route: &str,
+    body: &[u8],
+    headers: &[ReceiverHeader],
+) -> Result<(), SignatureError> {
+    verify_receiver_signature(
+        config.shared_secret.as_deref(),
+        method,
+        route,
+        body,
+        headers,
+    )
 }
 
-pub fn relay_target_would_loop(listen_addr: &str, relay_target_host: &str) -> bool {
-    let Some(self_origin) = normalized_origin_from_listen_addr(listen_addr) else {
-        return false;
-    };
-    let Some(target_origin) = normalized_origin_from_target(relay_target_host) else {
-        return false;
+/// 配置了签名密钥时校验请求签名（针对线上原始请求体）；未配置时直接通过
+pub fn verify_receiver_signature(
+    shared_secret: Option<&str>,
+    method: &str,
+    route: &str,
+    body: &[u8],
+    headers: &[ReceiverHeader],
+) -> Result<(), SignatureError> {
+    let Some(secret) = signing::effective_secret(shared_secret) else {
+        return Ok(());
     };
-    self_origin == target_origin
+    signing::verify_request(
+        secret,
+        header_value(headers, TIMESTAMP_HEADER),
+        header_value(headers, SIGNATURE_HEADER),
+        method,
+        route,
+        body,
+        signing::now_unix_ms(),
+    )
+}
+
+fn header_value<'a>(headers: &'a [ReceiverHeader], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|header| header.matches(name))
+        .map(|header| header.value.as_str())
+}
+
+/// 按 `Content-Encoding` 解压请求体；未压缩时原样返回
+pub fn decode_receiver_body(
+    body: &[u8],
+    headers: &[ReceiverHeader],
+) -> Result<Vec<u8>, BodyDecodeError> {
+    let encoding = content_encoding_of(headers).map_err(BodyDecodeError::UnsupportedEncoding)?;
+    transport::decode_body(body, encoding).map_err(BodyDecodeError::Corrupt)
+}
+
+pub fn write_receiver_payload_json(
+    file_path: &Path,
+    wrapper: &CapturedPayloadFile,
+) -> Result<(), String> {
+    wrapper.write_json(file_path)
+}
+
+/// 解析 notify 路由中的通道与方向
+///
+/// 不是 notify 路由但路径含 `response` 的旧式路由（如 `/api/response`）
+/// 与旧版 Receiver 一样按 `http` 通道的响应保存
+pub fn resolve_capture_route(route: &str) -> Option<CaptureRoute> {
+    capture::parse_notify_route(route).or_else(|| {
+        route
+            .to_ascii_lowercase()
+            .contains("response")
+            .then(|| CaptureRoute::new(DEFAULT_CAPTURE_CHANNEL, CaptureDirection::Response))
+    })
+}
+
+pub fn relay_target_would_loop(listen_addr: &str, relay_target_host: &str) -> bool {
+    address::relay_target_would_loop(listen_addr, relay_target_host)
 }
 
 pub fn relay_receiver_payload(
+    config: &Config,
     self_listen_addr: &str,
     route: &str,
     body: &[u8],
     headers: &[ReceiverHeader],
 ) -> RelayOutcome {
     relay_receiver_payload_with_settings(
-        &load_receiver_relay_settings(self_listen_addr),
+        &receiver_relay_settings(config, self_listen_addr),
         route,
         body,
         headers,
     )
 }
 
-fn sanitize_tag(tag: &str) -> String {
-    let mut out = String::with_capacity(tag.len());
-    for ch in tag.chars() {
-        if ch.is_ascii_alphanumeric() {
-            out.push(ch.to_ascii_lowercase());
-        } else {
-            out.push('_');
-        }
-    }
-
-    if out.is_empty() {
-        "unknown".to_string()
-    } else {
-        out
-    }
-}
-
-fn load_receiver_relay_settings(self_listen_addr: &str) -> ReceiverRelaySettings {
-    let config = Config::load_from_exe_dir();
+fn receiver_relay_settings(config: &Config, self_listen_addr: &str) -> ReceiverRelaySettings {
     ReceiverRelaySettings {
         enabled: config.relay_enabled,
         target_host: config
@@ -213,6 +331,8 @@ fn load_receiver_relay_settings(self_listen_addr: &str) -> ReceiverRelaySettings
             .filter(|value| !value.is_empty()),
         timeout_ms: config.timeout_ms.max(1),
         self_listen_addr: self_listen_addr.to_string(),
+        shared_secret: signing::effective_secret(config.shared_secret.as_deref())
+            .map(str::to_string),
     }
 }
 
@@ -237,7 +357,7 @@ fn relay_receiver_payload_with_settings(
         return RelayOutcome::SelfLoopBlocked;
     }
 
-    let Some(base_url) = normalize_target_base_url(target_host) else {
+    let Some(base_url) = address::normalize_target_base_url(target_host) else {
         return RelayOutcome::Failed(format!("invalid relay target: {}", target_host));
     };
 
@@ -253,21 +373,48 @@ fn relay_receiver_payload_with_settings(
         .timeout_write(timeout)
         .build();
 
-    let mut request = agent.post(&relay_url);
-    for header in headers
-        .iter()
-        .filter(|header| should_forward_header(&header.name))
-    {
-        request = request.set(&header.name, &header.value);
-    }
-    request = request.set(RELAY_HEADER_NAME, RELAY_HEADER_VALUE);
-
-    match request.send_bytes(body) {
+    // 压缩的请求体原样透传；下游不支持该编码时解压后重发
+    let compressed = content_encoding_of(headers).is_ok_and(|encoding| !encoding.is_identity());
+    let secret = settings.shared_secret.as_deref();
+    match relay_request(&agent, &relay_url, headers, body, secret, true).send_bytes(body) {
+        Err(ureq::Error::Status(status, _))
+            if compressed && status == UNSUPPORTED_ENCODING_STATUS =>
+        {
+            let decoded = match decode_receiver_body(body, headers) {
+                Ok(decoded) => decoded,
+                Err(error) => return RelayOutcome::Failed(error.message().to_string()),
+            };
+            match relay_request(&agent, &relay_url, headers, &decoded, secret, false)
+                .send_bytes(&decoded)
+            {
+                Ok(_) => RelayOutcome::Forwarded(relay_url),
+                Err(error) => RelayOutcome::Failed(format_relay_error(&relay_url, error)),
+            }
+        }
         Ok(_) => RelayOutcome::Forwarded(relay_url),
         Err(error) => RelayOutcome::Failed(format_relay_error(&relay_url, error)),
     }
 }
 
+/// 构造转发请求；配置了密钥时按本次发送的请求体重新签名
+fn relay_request(
+    agent: &ureq::Agent,
+    relay_url: &str,
+    headers: &[ReceiverHeader],
+    body: &[u8],
+    shared_secret: Option<&str>,
+    keep_encoding: bool,
+) -> ureq::Request {
+    let mut request = agent.post(relay_url);
+    for header in headers.iter().filter(|header| {
+        should_forward_header(&header.name)
+            || (keep_encoding && header.matches(CONTENT_ENCODING_HEADER))
+    }) {
+        request = request.set(&header.name, &header.value);
+    }
+    sign_post(request, relay_url, body, shared_secret).set(RELAY_HEADER_NAME, RELAY_HEADER_VALUE)
+}
+
 fn should_forward_header(name: &str) -> bool {
     name.eq_ignore_ascii_case("content-type") || name.eq_ignore_ascii_case("x-plugin-name")
 }
@@ -288,24 +435,6 @@ fn format_relay_error(relay_url: &str, error: ureq::Error) -> String {
     format!("POST {} failed: {}{}", relay_url, raw_error, hint)
 }
 
-fn normalize_target_base_url(value: &str) -> Option<Url> {
-    let mut url = Url::parse(value.trim()).ok()?;
-    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
-        return None;
-    }
-
-    url.set_query(None);
-    url.set_fragment(None);
-
-    let path = if url.path().is_empty() {
-        "/".to_string()
-    } else {
-        format!("{}/", url.path().trim_end_matches('/'))
-    };
-    url.set_path(&path);
-    Some(url)
-}
-
 fn build_relay_url(base_url: &Url, route: &str) -> Result<String, String> {
     let relative = route.trim_start_matches('/');
     base_url
@@ -314,51 +443,36 @@ fn build_relay_url(base_url: &Url, route: &str) -> Result<String, String> {
         .map_err(|error| format!("build relay url failed for {}: {}", route, error))
 }
 
-fn normalized_origin_from_target(target_host: &str) -> Option<String> {
-    let base_url = normalize_target_base_url(target_host)?;
-    normalized_origin_from_url(&base_url)
-}
-
-fn normalized_origin_from_listen_addr(listen_addr: &str) -> Option<String> {
-    let (host, port) = crate::receiver::parse_receiver_listen_addr(listen_addr)?;
-    let url = Url::parse(&format!("http://{}:{}/", host, port)).ok()?;
-    normalized_origin_from_url(&url)
-}
-
-fn normalized_origin_from_url(url: &Url) -> Option<String> {
-    let scheme = url.scheme().to_ascii_lowercase();
-    let host = canonical_host(url.host_str()?);
-    let port = url.port_or_known_default()?;
-    Some(format!("{}://{}:{}", scheme, host, port))
-}
-
-fn canonical_host(host: &str) -> String {
-    if host.eq_ignore_ascii_case("localhost") {
-        "127.0.0.1".to_string()
-    } else {
-        host.to_ascii_lowercase()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
-        prepare_receiver_payload, relay_receiver_payload_with_settings, relay_target_would_loop,
-        resolve_direction, ReceiverHeader, ReceiverProcessOutcome, ReceiverRelaySettings,
-        RELAY_HEADER_NAME, RELAY_HEADER_VALUE,
+        decode_receiver_body, prepare_receiver_payload, relay_receiver_payload_with_settings,
+        relay_target_would_loop, resolve_capture_route, verify_receiver_signature, BodyDecodeError,
+        ReceiverHeader, ReceiverProcessOutcome, ReceiverRelaySettings, RELAY_HEADER_NAME,
+        RELAY_HEADER_VALUE,
     };
-    use std::net::TcpListener;
+    use crate::config::Config;
+    use guga_ura_transport::signing::{self, SignatureError, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use guga_ura_transport::transport::{encode_body, ContentEncoding};
     use std::path::PathBuf;
     use std::thread;
     use tiny_http::{Response, Server};
 
     #[test]
-    fn resolve_direction_should_use_fixed_value_first() {
-        assert_eq!(
-            resolve_direction("/notify/response", Some("request")),
-            "request"
-        );
-        assert_eq!(resolve_direction("/notify/response", None), "response");
+    fn resolve_capture_route_should_route_channels_and_legacy_paths() {
+        let legacy = resolve_capture_route("/notify/response").expect("旧版路由应被识别");
+        assert_eq!(legacy.channel, "http");
+        assert_eq!(legacy.direction.as_str(), "response");
+
+        let realtime = resolve_capture_route("/notify/realtime/request").expect("通道路由应被识别");
+        assert_eq!(realtime.channel, "realtime");
+        assert_eq!(realtime.direction.as_str(), "request");
+
+        let legacy_api = resolve_capture_route("/api/response").expect("旧式响应路由应被识别");
+        assert!(legacy_api.is_default_channel());
+        assert_eq!(legacy_api.direction.as_str(), "response");
+
+        assert!(resolve_capture_route("/upload/request").is_none());
     }
 
     #[test]
@@ -366,18 +480,31 @@ mod tests {
         let output_dir = PathBuf::from("unused");
         let body = [1_u8, 2, 3];
 
-        let outcome = prepare_receiver_payload(&output_dir, "/notify/request", None, &body, || 0)
-            .expect("非 response 路由应被忽略");
+        for route in ["/notify/request", "/notify/realtime/request"] {
+            let outcome =
+                prepare_receiver_payload(&Config::default(), &output_dir, route, &body, || 0)
+                    .expect("非 response 路由应被忽略");
 
-        assert!(matches!(outcome, ReceiverProcessOutcome::Ignored));
+            assert!(
+                matches!(outcome, ReceiverProcessOutcome::Ignored),
+                "{}",
+                route
+            );
+        }
     }
 
     #[test]
     fn prepare_receiver_payload_should_reject_empty_body() {
         let output_dir = PathBuf::from("unused");
 
-        let error = prepare_receiver_payload(&output_dir, "/notify/response", None, &[], || 0)
-            .expect_err("空请求体应报错");
+        let error = prepare_receiver_payload(
+            &Config::default(),
+            &output_dir,
+            "/notify/response",
+            &[],
+            || 0,
+        )
+        .expect_err("空请求体应报错");
 
         assert!(error.contains("Empty request body"));
     }
@@ -401,6 +528,7 @@ mod tests {
             target_host: Some("http://127.0.0.1:4800".to_string()),
             timeout_ms: 100,
             self_listen_addr: "127.0.0.1:4693".to_string(),
+            shared_secret: None,
         };
         let headers = vec![ReceiverHeader::new(RELAY_HEADER_NAME, RELAY_HEADER_VALUE)];
 
@@ -417,6 +545,7 @@ mod tests {
             target_host: Some("http://localhost:4693/relay".to_string()),
             timeout_ms: 100,
             self_listen_addr: "127.0.0.1:4693".to_string(),
+            shared_secret: None,
         };
 
         let outcome =
@@ -427,11 +556,12 @@ mod tests {
 
     #[test]
     fn relay_receiver_payload_should_forward_body_and_headers() {
-        let listener = TcpListener::bind("127.0.0.1:0").expect("预留测试端口失败");
-        let port = listener.local_addr().expect("读取测试端口失败").port();
-        drop(listener);
-
-        let server = Server::http(format!("127.0.0.1:{}", port)).expect("启动测试 HTTP 服务失败");
+        let server = Server::http("127.0.0.1:0").expect("启动测试 HTTP 服务失败");
+        let port = server
+            .server_addr()
+            .to_ip()
+            .expect("读取测试端口失败")
+            .port();
         let handle = thread::spawn(move || {
             let mut request = server.recv().expect("接收 relay 请求失败");
             let body = {
@@ -459,6 +589,7 @@ mod tests {
             target_host: Some(format!("http://127.0.0.1:{}/relay-base", port)),
             timeout_ms: 1000,
             self_listen_addr: "127.0.0.1:4693".to_string(),
+            shared_secret: None,
         };
         let headers = vec![
             ReceiverHeader::new("content-type", "application/octet-stream"),
@@ -484,4 +615,237 @@ mod tests {
         assert!(headers_lower.contains("x-gugaura-relayed: 1"));
         assert_eq!(body, "ping");
     }
+
+    /// 一次请求的小写请求头与请求体
+    type RecordedRequest = (Vec<String>, Vec<u8>);
+
+    /// 记录收到的请求，按顺序返回给定状态码
+    fn spawn_recording_server(
+        statuses: Vec<u16>,
+    ) -> (u16, thread::JoinHandle<Vec<RecordedRequest>>) {
+        let server = Server::http("127.0.0.1:0").expect("启动测试 HTTP 服务失败");
+        let port = server
+            .server_addr()
+            .to_ip()
+            .expect("读取测试端口失败")
+            .port();
+        let handle = thread::spawn(move || {
+            statuses
+                .into_iter()
+                .map(|status| {
+                    let mut request = server.recv().expect("接收 relay 请求失败");
+                    let mut body = Vec::new();
+                    request
+                        .as_reader()
+                        .read_to_end(&mut body)
+                        .expect("读取 relay 请求体失败");
+                    let headers = request
+                        .headers()
+                        .iter()
+                        .map(|header| {
+                            format!("{}: {}", header.field, header.value).to_ascii_lowercase()
+                        })
+                        .collect();
+                    request
+                        .respond(Response::empty(status))
+                        .expect("返回 relay 响应失败");
+                    (headers, body)
+                })
+                .collect()
+        });
+        (port, handle)
+    }
+
+    fn relay_settings(port: u16) -> ReceiverRelaySettings {
+        ReceiverRelaySettings {
+            enabled: true,
+            target_host: Some(format!("http://127.0.0.1:{}", port)),
+            timeout_ms: 1000,
+            self_listen_addr: "127.0.0.1:4693".to_string(),
+            shared_secret: None,
+        }
+    }
+
+    #[test]
+    fn decode_receiver_body_should_follow_content_encoding() {
+        let payload = b"msgpack payload".repeat(32);
+        let gzip = encode_body(&payload, ContentEncoding::Gzip).expect("gzip 压缩失败");
+        let zstd = encode_body(&payload, ContentEncoding::Zstd).expect("zstd 压缩失败");
+
+        assert_eq!(
+            decode_receiver_body(&gzip, &[ReceiverHeader::new("Content-Encoding", "gzip")]),
+            Ok(payload.clone())
+        );
+        assert_eq!(
+            decode_receiver_body(&zstd, &[ReceiverHeader::new("content-encoding", "zstd")]),
+            Ok(payload.clone())
+        );
+        assert_eq!(decode_receiver_body(&payload, &[]), Ok(payload.clone()));
+
+        let unsupported =
+            decode_receiver_body(&payload, &[ReceiverHeader::new("content-encoding", "br")])
+                .expect_err("未知编码应被拒绝");
+        assert_eq!(unsupported.status_code(), 415);
+        assert!(matches!(
+            decode_receiver_body(&payload, &[ReceiverHeader::new("content-encoding", "gzip")]),
+            Err(BodyDecodeError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn relay_should_pass_compressed_body_through() {
+        let payload = b"msgpack payload".repeat(32);
+        let compressed = encode_body(&payload, ContentEncoding::Zstd).expect("zstd 压缩失败");
+        let (port, handle) = spawn_recording_server(vec![200]);
+        let headers = vec![ReceiverHeader::new("content-encoding", "zstd")];
+
+        let outcome = relay_receiver_payload_with_settings(
+            &relay_settings(port),
+            "/notify/http/response",
+            &compressed,
+            &headers,
+        );
+
+        let requests = handle.join().expect("relay 线程退出异常");
+        assert!(matches!(outcome, super::RelayOutcome::Forwarded(_)));
+        assert!(requests[0]
+            .0
+            .contains(&"content-encoding: zstd".to_string()));
+        assert_eq!(requests[0].1, compressed);
+    }
+
+    #[test]
+    fn relay_should_decompress_when_downstream_rejects_encoding() {
+        let payload = b"msgpack payload".repeat(32);
+        let compressed = encode_body(&payload, ContentEncoding::Gzip).expect("gzip 压缩失败");
+        let (port, handle) = spawn_recording_server(vec![415, 200]);
+        let headers = vec![
+            ReceiverHeader::new("content-type", "application/x-msgpack"),
+            ReceiverHeader::new("content-encoding", "gzip"),
+        ];
+
+        let outcome = relay_receiver_payload_with_settings(
+            &relay_settings(port),
+            "/notify/http/response",
+            &compressed,
+            &headers,
+        );
+
+        let requests = handle.join().expect("relay 线程退出异常");
+        assert!(matches!(outcome, super::RelayOutcome::Forwarded(_)));
+        assert_eq!(requests[0].1, compressed);
+        let (retry_headers, retry_body) = &requests[1];
+        assert!(!retry_headers
+            .iter()
+            .any(|header| header.starts_with("content-encoding")));
+        assert!(retry_headers.contains(&"content-type: application/x-msgpack".to_string()));
+        assert_eq!(retry_body, &payload);
+    }
+
+    const SECRET: &str = "relay-test-secret-0123";
+
+    const ROUTE: &str = "/notify/http/response";
+
+    fn signed_headers(secret: &str, timestamp_ms: u64, body: &[u8]) -> Vec<ReceiverHeader> {
+        let signature = signing::sign_request(secret, timestamp_ms, "POST", ROUTE, body);
+        vec![
+            ReceiverHeader::new("X-GugaURA-Timestamp", signature.timestamp),
+            ReceiverHeader::new("X-GugaURA-Signature", signature.signature),
+        ]
+    }
+
+    #[test]
+    fn verify_receiver_signature_should_only_apply_with_secret() {
+        let body = b"payload";
+        let now = signing::now_unix_ms();
+
+        assert_eq!(
+            verify_receiver_signature(None, "POST", ROUTE, body, &[]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_receiver_signature(Some("  "), "POST", ROUTE, body, &[]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_receiver_signature(
+                Some(SECRET),
+                "POST",
+                ROUTE,
+                body,
+                &signed_headers(SECRET, now, body)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_receiver_signature(Some(SECRET), "POST", ROUTE, body, &[]),
+            Err(SignatureError::Missing)
+        );
+        assert_eq!(
+            verify_receiver_signature(
+                Some(SECRET),
+                "POST",
+                ROUTE,
+                body,
+                &signed_headers("forged-secret-0123456", now, body)
+            ),
+            Err(SignatureError::Mismatch)
+        );
+        assert!(matches!(
+            verify_receiver_signature(
+                Some(SECRET),
+                "POST",
+                ROUTE,
+                body,
+                &signed_headers(SECRET, now - signing::MAX_SIGNATURE_SKEW_MS - 60_000, body)
+            ),
+            Err(SignatureError::Stale { .. })
+        ));
+    }
+
+    #[test]
+    fn relay_should_resign_for_downstream() {
+        let payload = b"msgpack payload".repeat(32);
+        let compressed = encode_body(&payload, ContentEncoding::Gzip).expect("gzip 压缩失败");
+        let (port, handle) = spawn_recording_server(vec![415, 200]);
+        let mut headers = signed_headers("upstream-secret-0123456", 1, &compressed);
+        headers.push(ReceiverHeader::new("content-encoding", "gzip"));
+        let settings = ReceiverRelaySettings {
+            shared_secret: Some(SECRET.to_string()),
+            ..relay_settings(port)
+        };
+
+        let outcome = relay_receiver_payload_with_settings(
+            &settings,
+            "/notify/http/response",
+            &compressed,
+            &headers,
+        );
+
+        let requests = handle.join().expect("relay 线程退出异常");
+        assert!(matches!(outcome, super::RelayOutcome::Forwarded(_)));
+        for (recorded_headers, recorded_body) in &requests {
+            let recorded: Vec<ReceiverHeader> = recorded_headers
+                .iter()
+                .filter_map(|line| line.split_once(": "))
+                .map(|(name, value)| ReceiverHeader::new(name, value))
+                .collect();
+            assert_eq!(
+                recorded
+                    .iter()
+                    .filter(
+                        |header| header.name == TIMESTAMP_HEADER || header.name == SIGNATURE_HEADER
+                    )
+                    .count(),
+                2,
+                "上游签名不应被透传"
+            );
+            assert_eq!(
+                verify_receiver_signature(Some(SECRET), "POST", ROUTE, recorded_body, &recorded),
+                Ok(()),
+                "转发请求应按实际请求体重新签名"
+            );
+        }
+        assert_eq!(requests[1].1, payload);
+    }
 }
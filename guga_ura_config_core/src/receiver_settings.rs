@@ -0,0 +1,569 @@
+//! Receiver 分层设置
+//!
+//! Receiver 侧的每个字段都按 CLI > 环境变量 > EXE 同级配置 > 默认值 逐层解析，
+//! 并记录最终生效值的来源。EXE 同级配置仍按请求重新读取，CLI 与环境变量
+//! 覆盖叠加在其上，因此热重载与无配置文件的容器部署可以同时使用。
+
+use crate::config::Config;
+use crate::receiver;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Receiver 调试输出目录的环境变量（该目录不属于配置文件字段）
+pub const RECEIVER_OUTPUT_DIR_ENV: &str = "GUGAURA_RECEIVER_OUTPUT_DIR";
+
+static CLI_OVERRIDES: OnceLock<Mutex<ReceiverOverrides>> = OnceLock::new();
+
+/// 设置值的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiverSettingSource {
+    Cli,
+    Env,
+    ExeConfig,
+    Default,
+}
+
+impl ReceiverSettingSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::Env => "env",
+            Self::ExeConfig => "exeConfig",
+            Self::Default => "default",
+        }
+    }
+}
+
+/// 可覆盖的 Receiver 字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiverSettingSpec {
+    /// 配置字段名（与 JSON 键一致）
+    pub field: &'static str,
+    /// 对应的环境变量
+    pub env: &'static str,
+    /// 对应的命令行参数；密钥没有命令行参数，避免出现在进程列表中
+    pub cli_flag: Option<&'static str>,
+}
+
+/// Receiver 侧全部可覆盖字段
+pub const RECEIVER_SETTINGS: &[ReceiverSettingSpec] = &[
+    ReceiverSettingSpec {
+        field: "receiver_listen_addr",
+        env: "GUGAURA_RECEIVER_ADDR",
+        cli_flag: Some("--listen-addr"),
+    },
+    ReceiverSettingSpec {
+        field: "relay_enabled",
+        env: "GUGAURA_RELAY_ENABLED",
+        cli_flag: Some("--relay-enabled"),
+    },
+    ReceiverSettingSpec {
+        field: "relay_target_host",
+        env: "GUGAURA_RELAY_TARGET_HOST",
+        cli_flag: Some("--relay-target-host"),
+    },
+    ReceiverSettingSpec {
+        field: "timeout_ms",
+        env: "GUGAURA_TIMEOUT_MS",
+        cli_flag: Some("--timeout-ms"),
+    },
+    ReceiverSettingSpec {
+        field: "fans_enabled",
+        env: "GUGAURA_FANS_ENABLED",
+        cli_flag: Some("--fans-enabled"),
+    },
+    ReceiverSettingSpec {
+        field: "fans_output_dir",
+        env: "GUGAURA_FANS_OUTPUT_DIR",
+        cli_flag: Some("--fans-output-dir"),
+    },
+    ReceiverSettingSpec {
+        field: "stallion_output_enabled",
+        env: "GUGAURA_STALLION_OUTPUT_ENABLED",
+        cli_flag: Some("--stallion-output-enabled"),
+    },
+    ReceiverSettingSpec {
+        field: "stallion_output_dir",
+        env: "GUGAURA_STALLION_OUTPUT_DIR",
+        cli_flag: Some("--stallion-output-dir"),
+    },
+    ReceiverSettingSpec {
+        field: "stallion_output_schema_version",
+        env: "GUGAURA_STALLION_OUTPUT_SCHEMA_VERSION",
+        cli_flag: Some("--stallion-output-schema-version"),
+    },
+    ReceiverSettingSpec {
+        field: "time_output_format",
+        env: "GUGAURA_TIME_OUTPUT_FORMAT",
+        cli_flag: Some("--time-output-format"),
+    },
+    ReceiverSettingSpec {
+        field: "time_output_timezone",
+        env: "GUGAURA_TIME_OUTPUT_TIMEZONE",
+        cli_flag: Some("--time-output-timezone"),
+    },
+    ReceiverSettingSpec {
+        field: "shared_secret",
+        env: "GUGAURA_SHARED_SECRET",
+        cli_flag: None,
+    },
+];
+
+/// 一层覆盖值（CLI 或环境变量），保存原始字符串，解析在叠加时进行
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReceiverOverrides {
+    values: Vec<(&'static str, String)>,
+}
+
+impl ReceiverOverrides {
+    /// 设置字段覆盖值；未知字段返回错误
+    pub fn set(&mut self, field: &str, value: impl Into<String>) -> Result<(), String> {
+        let spec = find_spec(field).ok_or_else(|| format!("未知的 Receiver 字段: {}", field))?;
+        let value = value.into();
+        match self.values.iter_mut().find(|(name, _)| *name == spec.field) {
+            Some((_, existing)) => *existing = value,
+            None => self.values.push((spec.field, value)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 读取当前进程环境变量中的覆盖值
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut overrides = Self::default();
+        for spec in RECEIVER_SETTINGS {
+            if let Some(value) = lookup(spec.env) {
+                overrides.values.push((spec.field, value));
+            }
+        }
+        overrides
+    }
+}
+
+/// 单个字段的解析结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiverSettingReport {
+    pub field: &'static str,
+    pub source: ReceiverSettingSource,
+    /// 生效值的 JSON 表示
+    pub value: String,
+}
+
+/// 全部 Receiver 字段的解析结果
+#[derive(Debug, Clone)]
+pub struct ReceiverSettingsResolution {
+    /// 叠加覆盖后的生效配置
+    pub config: Config,
+    pub fields: Vec<ReceiverSettingReport>,
+    /// 被忽略的无效覆盖值
+    pub rejected: Vec<String>,
+}
+
+impl ReceiverSettingsResolution {
+    pub fn source_of(&self, field: &str) -> Option<ReceiverSettingSource> {
+        self.fields
+            .iter()
+            .find(|report| report.field == field)
+            .map(|report| report.source)
+    }
+}
+
+/// 设置本进程的 CLI 覆盖层（独立 Receiver 启动时调用一次）
+pub fn set_cli_overrides(overrides: ReceiverOverrides) {
+    if let Ok(mut guard) = cli_overrides().lock() {
+        *guard = overrides;
+    }
+}
+
+/// 解析全部 Receiver 字段
+pub fn resolve_receiver_settings() -> ReceiverSettingsResolution {
+    let config_path = Config::exe_config_path();
+    let config = Config::load_file_or_default(&config_path);
+    let cli = current_cli_overrides();
+    let env = ReceiverOverrides::from_env();
+    resolve_with_layers(
+        config,
+        |field| Config::effective_file_has_key(&config_path, field),
+        &cli,
+        &env,
+    )
+}
+
+/// 叠加覆盖后的 Receiver 生效配置
+pub fn load_receiver_config() -> Config {
+    resolve_receiver_settings().config
+}
+
+/// 解析 Receiver 调试输出目录：CLI > 环境变量 > EXE 同级 `debug/`
+pub fn resolve_receiver_output_dir(
+    cli_override: Option<&Path>,
+) -> (PathBuf, ReceiverSettingSource) {
+    let env_override = std::env::var(RECEIVER_OUTPUT_DIR_ENV).ok();
+    resolve_output_dir_with_inputs(cli_override, env_override.as_deref())
+}
+
+fn resolve_output_dir_with_inputs(
+    cli_override: Option<&Path>,
+    env_override: Option<&str>,
+) -> (PathBuf, ReceiverSettingSource) {
+    if let Some(path) = cli_override.filter(|path| !path.as_os_str().is_empty()) {
+        return (path.to_path_buf(), ReceiverSettingSource::Cli);
+    }
+
+    if let Some(path) = env_override.map(str::trim).filter(|raw| !raw.is_empty()) {
+        return (PathBuf::from(path), ReceiverSettingSource::Env);
+    }
+
+    (
+        receiver::default_output_dir(),
+        ReceiverSettingSource::Default,
+    )
+}
+
+fn resolve_with_layers(
+    mut config: Config,
+    configured: impl Fn(&str) -> bool,
+    cli: &ReceiverOverrides,
+    env: &ReceiverOverrides,
+) -> ReceiverSettingsResolution {
+    let mut fields = Vec::with_capacity(RECEIVER_SETTINGS.len());
+    let mut rejected = Vec::new();
+
+    for spec in RECEIVER_SETTINGS {
+        let source = if spec.field == "receiver_listen_addr" {
+            resolve_listen_addr(&mut config, cli, env, &mut rejected)
+        } else {
+            let mut source = if configured(spec.field) {
+                ReceiverSettingSource::ExeConfig
+            } else {
+                ReceiverSettingSource::Default
+            };
+            for (layer_source, layer) in [
+                (ReceiverSettingSource::Env, env),
+                (ReceiverSettingSource::Cli, cli),
+            ] {
+                let Some(raw) = layer.get(spec.field) else {
+                    continue;
+                };
+                match apply_field(&mut config, spec.field, raw) {
+                    Ok(()) => source = layer_source,
+                    Err(error) => rejected.push(format!(
+                        "{} {}={:?}: {}",
+                        layer_source.as_str(),
+                        spec.field,
+                        raw,
+                        error
+                    )),
+                }
+            }
+            source
+        };
+
+        fields.push(ReceiverSettingReport {
+            field: spec.field,
+            source,
+            value: field_value(&config, spec.field),
+        });
+    }
+
+    ReceiverSettingsResolution {
+        config,
+        fields,
+        rejected,
+    }
+}
+
+/// 监听地址沿用 [`receiver::resolve_receiver_listen_addr`] 的规则：
+/// 无效的配置值回退到默认地址
+fn resolve_listen_addr(
+    config: &mut Config,
+    cli: &ReceiverOverrides,
+    env: &ReceiverOverrides,
+    rejected: &mut Vec<String>,
+) -> ReceiverSettingSource {
+    for (layer_source, layer) in [
+        (ReceiverSettingSource::Cli, cli),
+        (ReceiverSettingSource::Env, env),
+    ] {
+        if let Some(raw) = layer.get("receiver_listen_addr") {
+            if receiver::normalize_receiver_listen_addr_input(raw).is_none() {
+                rejected.push(format!(
+                    "{} receiver_listen_addr={:?}: 监听地址无效",
+                    layer_source.as_str(),
+                    raw
+                ));
+            }
+        }
+    }
+
+    let resolution = receiver::resolve_receiver_listen_addr_with_inputs(
+        cli.get("receiver_listen_addr"),
+        env.get("receiver_listen_addr"),
+        &config.receiver_listen_addr,
+    );
+    config.receiver_listen_addr = resolution.listen_addr;
+    resolution.source
+}
+
+fn apply_field(config: &mut Config, field: &str, raw: &str) -> Result<(), String> {
+    match field {
+        "relay_enabled" => config.relay_enabled = parse_bool(raw)?,
+        "relay_target_host" => config.relay_target_host = optional_string(raw),
+        "timeout_ms" => {
+            let timeout_ms = parse_number::<u64>(raw)?;
+            if timeout_ms == 0 {
+                return Err("超时必须大于 0".to_string());
+            }
+            config.timeout_ms = timeout_ms;
+        }
+        "fans_enabled" => config.fans_enabled = parse_bool(raw)?,
+        "fans_output_dir" => config.fans_output_dir = optional_string(raw),
+        "stallion_output_enabled" => config.stallion_output_enabled = parse_bool(raw)?,
+        "stallion_output_dir" => config.stallion_output_dir = optional_string(raw),
+        "stallion_output_schema_version" => {
+            config.stallion_output_schema_version = match optional_string(raw) {
+                Some(value) => Some(parse_number::<u32>(&value)?),
+                None => None,
+            }
+        }
+        "time_output_format" => config.time_output_format = optional_string(raw),
+        "time_output_timezone" => config.time_output_timezone = optional_string(raw),
+        "shared_secret" => config.shared_secret = optional_string(raw),
+        _ => return Err(format!("未知的 Receiver 字段: {}", field)),
+    }
+    Ok(())
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err("布尔值只支持 true/false、1/0、yes/no、on/off".to_string()),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(raw: &str) -> Result<T, String> {
+    raw.trim()
+        .parse::<T>()
+        .map_err(|_| "不是有效的非负整数".to_string())
+}
+
+/// 空字符串表示清空可选字段
+fn optional_string(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// 字段的展示值；密钥只显示是否已设置
+fn field_value(config: &Config, field: &str) -> String {
+    if field == "shared_secret" {
+        let state = if config.shared_secret.is_some() {
+            "<set>"
+        } else {
+            "<unset>"
+        };
+        return state.to_string();
+    }
+    serde_json::to_value(config)
+        .ok()
+        .and_then(|document| document.get(field).cloned())
+        .unwrap_or(Value::Null)
+        .to_string()
+}
+
+fn find_spec(field: &str) -> Option<&'static ReceiverSettingSpec> {
+    RECEIVER_SETTINGS.iter().find(|spec| spec.field == field)
+}
+
+fn cli_overrides() -> &'static Mutex<ReceiverOverrides> {
+    CLI_OVERRIDES.get_or_init(|| Mutex::new(ReceiverOverrides::default()))
+}
+
+fn current_cli_overrides() -> ReceiverOverrides {
+    cli_overrides()
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::DEFAULT_RECEIVER_LISTEN_ADDR;
+
+    fn overrides(pairs: &[(&str, &str)]) -> ReceiverOverrides {
+        let mut overrides = ReceiverOverrides::default();
+        for (field, value) in pairs {
+            overrides.set(field, *value).expect("设置覆盖值失败");
+        }
+        overrides
+    }
+
+    #[test]
+    fn layers_should_follow_cli_env_config_default_precedence() {
+        let config = Config {
+            relay_enabled: true,
+            relay_target_host: Some("http://10.0.0.2:4693".to_string()),
+            timeout_ms: 800,
+            ..Config::default()
+        };
+        let cli = overrides(&[
+            ("timeout_ms", "1500"),
+            ("receiver_listen_addr", "0.0.0.0:4700"),
+        ]);
+        let env = overrides(&[
+            ("timeout_ms", "1200"),
+            ("relay_target_host", "http://10.0.0.3:4693"),
+        ]);
+
+        let resolution = resolve_with_layers(
+            config,
+            |field| matches!(field, "relay_enabled" | "relay_target_host" | "timeout_ms"),
+            &cli,
+            &env,
+        );
+
+        assert_eq!(resolution.config.timeout_ms, 1500);
+        assert_eq!(resolution.config.receiver_listen_addr, "0.0.0.0:4700");
+        assert_eq!(
+            resolution.config.relay_target_host.as_deref(),
+            Some("http://10.0.0.3:4693")
+        );
+        assert!(resolution.config.relay_enabled);
+        assert_eq!(
+            resolution.source_of("timeout_ms"),
+            Some(ReceiverSettingSource::Cli)
+        );
+        assert_eq!(
+            resolution.source_of("receiver_listen_addr"),
+            Some(ReceiverSettingSource::Cli)
+        );
+        assert_eq!(
+            resolution.source_of("relay_target_host"),
+            Some(ReceiverSettingSource::Env)
+        );
+        assert_eq!(
+            resolution.source_of("relay_enabled"),
+            Some(ReceiverSettingSource::ExeConfig)
+        );
+        assert_eq!(
+            resolution.source_of("fans_enabled"),
+            Some(ReceiverSettingSource::Default)
+        );
+        assert_eq!(resolution.fields.len(), RECEIVER_SETTINGS.len());
+        assert!(resolution.rejected.is_empty());
+    }
+
+    #[test]
+    fn invalid_override_should_fall_through_and_be_reported() {
+        let cli = overrides(&[("fans_enabled", "maybe"), ("receiver_listen_addr", "bad:1")]);
+        let env = overrides(&[("fans_enabled", "off"), ("timeout_ms", "0")]);
+
+        let resolution = resolve_with_layers(Config::default(), |_| false, &cli, &env);
+
+        assert!(!resolution.config.fans_enabled);
+        assert_eq!(
+            resolution.source_of("fans_enabled"),
+            Some(ReceiverSettingSource::Env)
+        );
+        assert_eq!(resolution.config.timeout_ms, Config::default().timeout_ms);
+        assert_eq!(
+            resolution.config.receiver_listen_addr,
+            DEFAULT_RECEIVER_LISTEN_ADDR
+        );
+        assert_eq!(resolution.rejected.len(), 3);
+    }
+
+    #[test]
+    fn empty_override_should_clear_optional_field() {
+        let config = Config {
+            fans_output_dir: Some("D:/fans".to_string()),
+            stallion_output_schema_version: Some(1),
+            ..Config::default()
+        };
+        let env = overrides(&[
+            ("fans_output_dir", ""),
+            ("stallion_output_schema_version", " "),
+        ]);
+
+        let resolution = resolve_with_layers(config, |_| true, &ReceiverOverrides::default(), &env);
+
+        assert_eq!(resolution.config.fans_output_dir, None);
+        assert_eq!(resolution.config.stallion_output_schema_version, None);
+        let report = resolution
+            .fields
+            .iter()
+            .find(|report| report.field == "fans_output_dir")
+            .expect("缺少 fans_output_dir");
+        assert_eq!(report.value, "null");
+    }
+
+    #[test]
+    fn shared_secret_report_should_not_leak_value() {
+        let env = overrides(&[("shared_secret", "do-not-log-this-secret")]);
+
+        let resolution = resolve_with_layers(
+            Config::default(),
+            |_| false,
+            &ReceiverOverrides::default(),
+            &env,
+        );
+
+        assert_eq!(
+            resolution.config.shared_secret.as_deref(),
+            Some("do-not-log-this-secret")
+        );
+        let report = resolution
+            .fields
+            .iter()
+            .find(|report| report.field == "shared_secret")
+            .expect("缺少 shared_secret");
+        assert_eq!(report.source, ReceiverSettingSource::Env);
+        assert_eq!(report.value, "<set>");
+    }
+
+    #[test]
+    fn env_lookup_should_map_variables_to_fields() {
+        let env = ReceiverOverrides::from_lookup(|name| match name {
+            "GUGAURA_RECEIVER_ADDR" => Some("127.0.0.1:4800".to_string()),
+            "GUGAURA_STALLION_OUTPUT_ENABLED" => Some("true".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(env.get("receiver_listen_addr"), Some("127.0.0.1:4800"));
+        assert_eq!(env.get("stallion_output_enabled"), Some("true"));
+        assert_eq!(env.get("fans_enabled"), None);
+        assert!(ReceiverOverrides::default()
+            .set("notifier_host", "x")
+            .is_err());
+    }
+
+    #[test]
+    fn output_dir_should_prefer_cli_then_env() {
+        let (cli_dir, cli_source) =
+            resolve_output_dir_with_inputs(Some(Path::new("/data/cli")), Some("/data/env"));
+        let (env_dir, env_source) = resolve_output_dir_with_inputs(None, Some("/data/env"));
+        let (_, default_source) = resolve_output_dir_with_inputs(None, Some("  "));
+
+        assert_eq!(cli_dir, PathBuf::from("/data/cli"));
+        assert_eq!(cli_source, ReceiverSettingSource::Cli);
+        assert_eq!(env_dir, PathBuf::from("/data/env"));
+        assert_eq!(env_source, ReceiverSettingSource::Env);
+        assert_eq!(default_source, ReceiverSettingSource::Default);
+    }
+}
@@ -4,7 +4,8 @@
 //! 按规范格式写入文件系统，供 Stallion Runner 消费。
 
 use crate::config::Config;
-use chrono::{Local, NaiveDateTime, TimeZone};
+use chrono::Local;
+use guga_ura_config::time::{OutputTimeZone, TimeSettings};
 use serde_json::{json, Map, Value};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -12,11 +13,24 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 static OUTPUT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
 
+/// 当前输出文档的 schema 版本
+///
+/// 每个版本对应 `schemas/` 下的一份 JSON Schema，输出结构有任何不兼容调整都需要
+/// 新增版本号与对应 schema，而不是直接修改旧版本。
+pub const LATEST_SCHEMA_VERSION: u32 = 1;
+
+/// 当前支持输出的 schema 版本
+pub const SUPPORTED_SCHEMA_VERSIONS: &[u32] = &[1];
+
 /// 种马输出设置
 #[derive(Debug, Clone)]
 pub struct StallionOutputSettings {
     pub enabled: bool,
     pub output_dir: PathBuf,
+    /// 输出文档使用的 schema 版本
+    pub schema_version: u32,
+    /// 游戏时间字段的输出格式与时区（默认 RFC3339 +09:00）
+    pub time: TimeSettings,
 }
 
 /// 种马输出结果
@@ -32,13 +46,30 @@ pub struct StallionOutputResult {
 
 /// 从 EXE 同级配置中解析种马输出设置
 pub fn resolve_stallion_output_settings() -> StallionOutputSettings {
-    let config = Config::load_from_exe_dir();
+    resolve_stallion_output_settings_from(&Config::load_from_exe_dir())
+}
+
+/// 按给定配置解析种马输出设置
+pub fn resolve_stallion_output_settings_from(config: &Config) -> StallionOutputSettings {
     StallionOutputSettings {
         enabled: config.stallion_output_enabled,
         output_dir: resolve_output_dir(config.stallion_output_dir.as_deref()),
+        schema_version: config
+            .stallion_output_schema_version
+            .unwrap_or(LATEST_SCHEMA_VERSION),
+        time: resolve_time_settings(config),
     }
 }
 
+/// 种马输出的时间设置：未配置时区时沿用游戏时区
+pub fn resolve_time_settings(config: &Config) -> TimeSettings {
+    TimeSettings::from_config(
+        config.time_output_format.as_deref(),
+        config.time_output_timezone.as_deref(),
+        OutputTimeZone::Game,
+    )
+}
+
 /// 默认输出根目录
 pub fn default_stallion_output_dir() -> PathBuf {
     if let Ok(mut exe_path) = std::env::current_exe() {
@@ -57,6 +88,25 @@ pub fn extract_and_write(
     direction: &str,
     route: &str,
     output_dir: &Path,
+) -> StallionOutputResult {
+    extract_and_write_with_schema(
+        payload,
+        direction,
+        route,
+        output_dir,
+        LATEST_SCHEMA_VERSION,
+        &TimeSettings::from_config(None, None, OutputTimeZone::Game),
+    )
+}
+
+/// 与 [`extract_and_write`] 相同，但按指定 schema 版本与时间设置输出
+pub fn extract_and_write_with_schema(
+    payload: &Value,
+    direction: &str,
+    route: &str,
+    output_dir: &Path,
+    schema_version: u32,
+    time: &TimeSettings,
 ) -> StallionOutputResult {
     let mut result = StallionOutputResult::default();
 
@@ -88,12 +138,21 @@ pub fn extract_and_write(
         }
     };
 
+    if !SUPPORTED_SCHEMA_VERSIONS.contains(&schema_version) {
+        result.error = Some(format!(
+            "不支持的 schema_version: {}（支持: {:?}）",
+            schema_version, SUPPORTED_SCHEMA_VERSIONS
+        ));
+        return result;
+    }
+
     let now = Local::now();
     let timestamp = build_output_timestamp(now);
-    let captured_at = now.to_rfc3339();
+    // captured_at 由 schema 约束为 date-time，始终输出 RFC3339
+    let captured_at = time.format_rfc3339(&now);
 
     // 输出 stallion_data
-    match build_stallion_data(viewer_id, &captured_at, partner_array) {
+    match build_stallion_data(schema_version, viewer_id, &captured_at, partner_array, time) {
         Ok(stallion_json) => {
             let stallion_dir = output_dir.join("stallion_data");
             let filename = format!("stallion_data_{}_{}.json", viewer_id, timestamp);
@@ -112,7 +171,7 @@ pub fn extract_and_write(
     }
 
     // 输出 player_profile
-    match build_player_profile(viewer_id, &captured_at, data) {
+    match build_player_profile(schema_version, viewer_id, &captured_at, data, time) {
         Ok(profile_json) => {
             let profile_dir = output_dir.join("player_profile");
             let filename = format!("player_profile_{}_{}.json", viewer_id, timestamp);
@@ -141,9 +200,11 @@ pub fn extract_and_write(
 
 /// 构建 stallion_data JSON
 fn build_stallion_data(
+    schema_version: u32,
     viewer_id: u64,
     captured_at: &str,
     partner_array: &Value,
+    time: &TimeSettings,
 ) -> Result<Value, String> {
     let charas = partner_array
         .as_array()
@@ -164,20 +225,17 @@ fn build_stallion_data(
                             .unwrap_or(false)
                     })
                     .cloned()
-                    .map(|mut item| {
-                        convert_time_fields_in_object(&mut item);
-                        item
-                    })
                     .collect();
                 chara_obj["succession_chara_array"] = Value::Array(filtered);
             }
         }
-        // 转换种马对象内的时间字段
-        convert_time_fields_in_object(&mut chara_obj);
+        // 按格式转换种马对象（含父辈）内的所有游戏时间字段
+        time.convert_game_times(&mut chara_obj);
         trained_charas.push(chara_obj);
     }
 
     Ok(json!({
+        "schema_version": schema_version,
         "type": "stallion_data",
         "viewer_id": viewer_id,
         "captured_at": captured_at,
@@ -186,7 +244,13 @@ fn build_stallion_data(
 }
 
 /// 构建 player_profile JSON，将嵌套字段扁平化到 data 顶层
-fn build_player_profile(viewer_id: u64, captured_at: &str, data: &Value) -> Result<Value, String> {
+fn build_player_profile(
+    schema_version: u32,
+    viewer_id: u64,
+    captured_at: &str,
+    data: &Value,
+    time: &TimeSettings,
+) -> Result<Value, String> {
     let user_summary = data
         .get("user_info_summary")
         .and_then(|v| v.as_object())
@@ -201,7 +265,7 @@ fn build_player_profile(viewer_id: u64, captured_at: &str, data: &Value) -> Resu
             "circle_info" | "circle_user" | "honor_data" => {}
             _ => {
                 let mut v = value.clone();
-                convert_time_fields_by_key(key, &mut v);
+                time.convert_game_times(&mut v);
                 profile.insert(key.clone(), v);
             }
         }
@@ -224,7 +288,7 @@ fn build_player_profile(viewer_id: u64, captured_at: &str, data: &Value) -> Resu
         }
         if let Some(jt) = circle_user.get("join_time") {
             let mut v = jt.clone();
-            convert_game_time_value(&mut v);
+            time.convert_game_times(&mut v);
             profile.insert("join_time".to_string(), v);
         }
     }
@@ -245,6 +309,7 @@ fn build_player_profile(viewer_id: u64, captured_at: &str, data: &Value) -> Resu
     }
 
     Ok(json!({
+        "schema_version": schema_version,
         "type": "player_profile",
         "viewer_id": viewer_id,
         "captured_at": captured_at,
@@ -252,74 +317,6 @@ fn build_player_profile(viewer_id: u64, captured_at: &str, data: &Value) -> Resu
     }))
 }
 
-/// 将游戏时间格式 "2026-04-06 22:40:05" 转换为 ISO 8601 "+09:00"
-fn convert_game_time_str(s: &str) -> Option<String> {
-    let s = s.trim();
-    if s.is_empty() || s.starts_with("0000") {
-        return None;
-    }
-    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok()?;
-    // 游戏服务器时区为 JST (+09:00)
-    let jst = chrono::FixedOffset::east_opt(9 * 3600)?;
-    let dt = jst.from_local_datetime(&naive).single()?;
-    Some(dt.to_rfc3339())
-}
-
-/// 对 Value 中的时间字段进行转换（就地修改）
-fn convert_game_time_value(v: &mut Value) {
-    if let Some(s) = v.as_str() {
-        if let Some(converted) = convert_game_time_str(s) {
-            *v = Value::String(converted);
-        }
-    }
-}
-
-/// 已知的时间字段名
-const TIME_FIELD_NAMES: &[&str] = &[
-    "create_time",
-    "register_time",
-    "last_login_time",
-    "join_time",
-    "follow_time",
-    "follower_time",
-    "make_time",
-    "item_request_end_time",
-    "penalty_end_time",
-    "ranking_result_check_time",
-    "expiration_date",
-];
-
-/// 根据字段名判断是否需要转换时间
-fn convert_time_fields_by_key(key: &str, value: &mut Value) {
-    if TIME_FIELD_NAMES.contains(&key) {
-        convert_game_time_value(value);
-    }
-}
-
-/// 递归转换对象内所有已知时间字段
-fn convert_time_fields_in_object(value: &mut Value) {
-    if let Some(obj) = value.as_object_mut() {
-        let keys: Vec<String> = obj.keys().cloned().collect();
-        for key in keys {
-            if let Some(v) = obj.get_mut(&key) {
-                if TIME_FIELD_NAMES.contains(&key.as_str()) {
-                    convert_game_time_value(v);
-                } else if v.is_object() {
-                    convert_time_fields_in_object(v);
-                } else if v.is_array() {
-                    if let Some(arr) = v.as_array_mut() {
-                        for item in arr.iter_mut() {
-                            if item.is_object() {
-                                convert_time_fields_in_object(item);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 /// 写入 JSON 文件到指定目录
 fn write_json_file(dir: &Path, filename: &str, value: &Value) -> Result<PathBuf, String> {
     fs::create_dir_all(dir).map_err(|e| format!("创建目录 {} 失败: {}", dir.display(), e))?;
@@ -354,23 +351,33 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    const STALLION_DATA_V1_SCHEMA: &str = include_str!("../schemas/stallion_data.v1.schema.json");
+    const PLAYER_PROFILE_V1_SCHEMA: &str = include_str!("../schemas/player_profile.v1.schema.json");
+
+    fn game_time() -> TimeSettings {
+        resolve_time_settings(&Config::default())
+    }
+
     #[test]
     fn convert_game_time_str_should_convert_valid_time() {
-        let result = convert_game_time_str("2026-04-06 22:40:05");
+        let result = game_time().convert_game_time_str("2026-04-06 22:40:05");
         assert!(result.is_some());
-        let s = result.unwrap();
+        let value = result.unwrap();
+        let s = value.as_str().expect("应为字符串");
         assert!(s.contains("2026-04-06"));
         assert!(s.contains("+09:00"));
     }
 
     #[test]
     fn convert_game_time_str_should_skip_zero_time() {
-        assert!(convert_game_time_str("0000-00-00 00:00:00").is_none());
+        assert!(game_time()
+            .convert_game_time_str("0000-00-00 00:00:00")
+            .is_none());
     }
 
     #[test]
     fn convert_game_time_str_should_skip_empty() {
-        assert!(convert_game_time_str("").is_none());
+        assert!(game_time().convert_game_time_str("").is_none());
     }
 
     #[test]
@@ -390,8 +397,14 @@ mod tests {
             ]
         }]);
 
-        let result = build_stallion_data(12345, "2026-04-07T14:30:25+09:00", &partner_array)
-            .expect("构建失败");
+        let result = build_stallion_data(
+            1,
+            12345,
+            "2026-04-07T14:30:25+09:00",
+            &partner_array,
+            &game_time(),
+        )
+        .expect("构建失败");
 
         assert_eq!(result["type"], "stallion_data");
         assert_eq!(result["viewer_id"], 12345);
@@ -412,14 +425,45 @@ mod tests {
             "succession_chara_array": []
         }]);
 
-        let result =
-            build_stallion_data(1, "2026-04-07T14:30:25+09:00", &partner_array).expect("构建失败");
+        let result = build_stallion_data(
+            1,
+            1,
+            "2026-04-07T14:30:25+09:00",
+            &partner_array,
+            &game_time(),
+        )
+        .expect("构建失败");
 
         let chara = &result["trained_charas"][0];
         assert!(chara["create_time"].as_str().unwrap().contains("+09:00"));
         assert!(chara["register_time"].as_str().unwrap().contains("+09:00"));
     }
 
+    #[test]
+    fn build_stallion_data_should_convert_unknown_time_fields_with_configured_format() {
+        let partner_array = json!([{
+            "trained_chara_id": 100,
+            "some_future_time": "2026-04-06 22:40:05",
+            "succession_chara_array": [
+                {"position_id": 10, "owner_change_time": "2026-04-06 22:40:05"}
+            ]
+        }]);
+        let time = resolve_time_settings(&Config {
+            time_output_format: Some("unix_ms".to_string()),
+            ..Config::default()
+        });
+
+        let result = build_stallion_data(1, 1, "2026-04-07T14:30:25+09:00", &partner_array, &time)
+            .expect("构建失败");
+
+        let chara = &result["trained_charas"][0];
+        assert_eq!(chara["some_future_time"], 1_775_482_805_000_i64);
+        assert_eq!(
+            chara["succession_chara_array"][0]["owner_change_time"],
+            1_775_482_805_000_i64
+        );
+    }
+
     #[test]
     fn build_player_profile_should_flatten_nested_fields() {
         let data = json!({
@@ -445,8 +489,14 @@ mod tests {
             "own_follow_num": 1
         });
 
-        let result = build_player_profile(681803745355, "2026-04-07T14:32:00+09:00", &data)
-            .expect("构建失败");
+        let result = build_player_profile(
+            1,
+            681803745355,
+            "2026-04-07T14:32:00+09:00",
+            &data,
+            &game_time(),
+        )
+        .expect("构建失败");
 
         assert_eq!(result["type"], "player_profile");
         assert_eq!(result["viewer_id"], 681803745355_u64);
@@ -518,6 +568,154 @@ mod tests {
         );
     }
 
+    fn schema_validator(schema_source: &str) -> jsonschema::Validator {
+        let schema: Value = serde_json::from_str(schema_source).expect("解析 schema 失败");
+        jsonschema::options()
+            .should_validate_formats(true)
+            .build(&schema)
+            .expect("编译 schema 失败")
+    }
+
+    fn assert_valid(validator: &jsonschema::Validator, document: &Value) {
+        let errors: Vec<String> = validator
+            .iter_errors(document)
+            .map(|error| format!("{} at {}", error, error.instance_path))
+            .collect();
+        assert!(errors.is_empty(), "schema 校验失败: {:?}", errors);
+    }
+
+    fn sample_profile_payload() -> Value {
+        json!({
+            "data": {
+                "partner_chara_info_array": [{
+                    "trained_chara_id": 975,
+                    "card_id": 112901,
+                    "create_time": "2026-03-23 07:34:47",
+                    "register_time": "0000-00-00 00:00:00",
+                    "succession_chara_array": [
+                        {"position_id": 10, "card_id": 100301, "factor_info_array": []},
+                        {"position_id": 11, "card_id": 100302, "factor_info_array": []},
+                        {"position_id": 20, "card_id": 100501, "factor_info_array": []}
+                    ]
+                }],
+                "user_info_summary": {
+                    "viewer_id": 681803745355_u64,
+                    "name": "测试玩家",
+                    "last_login_time": "2026-04-06 22:40:05",
+                    "circle_info": {"circle_id": 668677582, "name": "TestCircle"},
+                    "circle_user": {"membership": 1, "join_time": "2026-04-01 18:23:26"},
+                    "honor_data": {"honor_id": 760001, "step": 732}
+                },
+                "follower_num": 12,
+                "own_follow_num": 1
+            }
+        })
+    }
+
+    #[test]
+    fn stallion_data_v1_should_match_schema() {
+        let validator = schema_validator(STALLION_DATA_V1_SCHEMA);
+        let payload = sample_profile_payload();
+
+        let document = build_stallion_data(
+            1,
+            681803745355,
+            "2026-04-07T14:30:25+09:00",
+            &payload["data"]["partner_chara_info_array"],
+            &game_time(),
+        )
+        .expect("构建失败");
+
+        assert_eq!(document["schema_version"], 1);
+        assert_valid(&validator, &document);
+    }
+
+    #[test]
+    fn player_profile_v1_should_match_schema() {
+        let validator = schema_validator(PLAYER_PROFILE_V1_SCHEMA);
+        let payload = sample_profile_payload();
+
+        let document = build_player_profile(
+            1,
+            681803745355,
+            "2026-04-07T14:30:25+09:00",
+            &payload["data"],
+            &game_time(),
+        )
+        .expect("构建失败");
+
+        assert_eq!(document["schema_version"], 1);
+        assert_valid(&validator, &document);
+    }
+
+    #[test]
+    fn schema_v1_should_reject_unfiltered_succession_and_nested_profile() {
+        let stallion_validator = schema_validator(STALLION_DATA_V1_SCHEMA);
+        let unfiltered = json!({
+            "schema_version": 1,
+            "type": "stallion_data",
+            "viewer_id": 1,
+            "captured_at": "2026-04-07T14:30:25+09:00",
+            "trained_charas": [{"succession_chara_array": [{"position_id": 11}]}]
+        });
+        assert!(!stallion_validator.is_valid(&unfiltered));
+
+        let profile_validator = schema_validator(PLAYER_PROFILE_V1_SCHEMA);
+        let nested = json!({
+            "schema_version": 1,
+            "type": "player_profile",
+            "viewer_id": 1,
+            "captured_at": "2026-04-07T14:30:25+09:00",
+            "data": {"viewer_id": 1, "circle_info": {"circle_id": 1}}
+        });
+        assert!(!profile_validator.is_valid(&nested));
+    }
+
+    #[test]
+    fn extract_and_write_outputs_should_match_schema_files() {
+        let output_dir = tempfile::tempdir().expect("创建临时目录失败");
+        let result = extract_and_write(
+            &sample_profile_payload(),
+            "response",
+            "/notify/response",
+            output_dir.path(),
+        );
+        assert!(result.error.is_none(), "{:?}", result.error);
+
+        let read = |path: &Option<PathBuf>| -> Value {
+            let content =
+                fs::read_to_string(path.as_ref().expect("缺少输出路径")).expect("读取输出失败");
+            serde_json::from_str(&content).expect("解析输出失败")
+        };
+
+        assert_valid(
+            &schema_validator(STALLION_DATA_V1_SCHEMA),
+            &read(&result.stallion_data_path),
+        );
+        assert_valid(
+            &schema_validator(PLAYER_PROFILE_V1_SCHEMA),
+            &read(&result.player_profile_path),
+        );
+    }
+
+    #[test]
+    fn extract_and_write_should_reject_unsupported_schema_version() {
+        let output_dir = tempfile::tempdir().expect("创建临时目录失败");
+
+        let result = extract_and_write_with_schema(
+            &sample_profile_payload(),
+            "response",
+            "/notify/response",
+            output_dir.path(),
+            99,
+            &game_time(),
+        );
+
+        assert!(result.stallion_data_path.is_none());
+        assert!(result.player_profile_path.is_none());
+        assert!(result.error.is_some_and(|e| e.contains("schema_version")));
+    }
+
     #[test]
     fn resolve_output_dir_should_use_custom_path() {
         let path = resolve_output_dir(Some("C:\\custom\\output"));
@@ -0,0 +1,206 @@
+//! 支持包
+//!
+//! 把 DLL 运行时日志、安装日志、配置工具 / 内置 Receiver 日志与独立 Receiver
+//! 日志（含轮转的历史文件）以及最近一次 DLL 捕获状态汇总为一个 JSON 文件，
+//! 用户反馈问题时只需附上这一份文件。
+
+use crate::capture_health::{self, CaptureHealthView};
+use crate::receiver;
+use chrono::{Local, SecondsFormat};
+use guga_ura_diagnostics::{
+    log_files, read_log, shared_log_dirs, DiagnosticsLog, LogLine, INSTALL_LOG_FILE_NAME,
+    RECEIVER_LOG_FILE_NAME, RUNTIME_LOG_FILE_NAME,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 支持包格式标识
+pub const SUPPORT_BUNDLE_FORMAT: &str = "guga_ura_support_bundle";
+
+/// 当前支持包格式版本
+pub const SUPPORT_BUNDLE_VERSION: u32 = 1;
+
+/// 支持包
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportBundle {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub tool_version: String,
+    pub os: String,
+    pub game_dir: Option<String>,
+    /// 内置 Receiver 收到的最近一次 DLL 状态
+    pub capture_health: CaptureHealthView,
+    pub logs: Vec<SupportLog>,
+}
+
+/// 一份日志及其历史文件
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportLog {
+    /// 日志种类：`runtime` / `install` / `tool` / `receiver`
+    pub kind: String,
+    pub path: String,
+    /// 实际读取的文件，从旧到新
+    pub files: Vec<String>,
+    pub lines: Vec<LogLine>,
+    pub error: Option<String>,
+}
+
+/// 可能存在日志的位置；同一种日志可能因目录不可写而落在多个候选位置
+pub fn support_log_sources(game_dir: Option<&Path>) -> Vec<(&'static str, PathBuf)> {
+    let shared_dirs = shared_log_dirs();
+    let game_data_dir = game_dir.map(|dir| dir.join("guga_ura_data"));
+
+    let mut sources: Vec<(&'static str, PathBuf)> = Vec::new();
+    sources.extend(
+        shared_dirs
+            .iter()
+            .chain(game_data_dir.iter())
+            .map(|dir| ("runtime", dir.join(RUNTIME_LOG_FILE_NAME))),
+    );
+    sources.extend(
+        game_data_dir
+            .iter()
+            .map(|dir| ("install", dir.join(INSTALL_LOG_FILE_NAME))),
+    );
+    sources.extend(
+        receiver::tool_log()
+            .candidates()
+            .iter()
+            .map(|path| ("tool", path.clone())),
+    );
+    sources.extend(
+        DiagnosticsLog::for_exe(RECEIVER_LOG_FILE_NAME)
+            .candidates()
+            .iter()
+            .map(|path| ("receiver", path.clone())),
+    );
+
+    let mut seen = Vec::new();
+    sources.retain(|(_, path)| {
+        if seen.contains(path) {
+            return false;
+        }
+        seen.push(path.clone());
+        true
+    });
+    sources
+}
+
+/// 读取存在的日志，不存在的位置直接跳过
+pub fn collect_logs(sources: &[(&str, PathBuf)]) -> Vec<SupportLog> {
+    sources
+        .iter()
+        .filter_map(|(kind, path)| {
+            let files = log_files(path);
+            if files.is_empty() {
+                return None;
+            }
+            let (lines, error) = match read_log(path) {
+                Ok(lines) => (lines, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            Some(SupportLog {
+                kind: kind.to_string(),
+                path: path.display().to_string(),
+                files: files
+                    .iter()
+                    .map(|file| file.display().to_string())
+                    .collect(),
+                lines,
+                error,
+            })
+        })
+        .collect()
+}
+
+/// 生成支持包
+pub fn collect_support_bundle(game_dir: Option<&Path>) -> SupportBundle {
+    SupportBundle {
+        format: SUPPORT_BUNDLE_FORMAT.to_string(),
+        version: SUPPORT_BUNDLE_VERSION,
+        created_at: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        game_dir: game_dir.map(|dir| dir.display().to_string()),
+        capture_health: capture_health::capture_health_view(),
+        logs: collect_logs(&support_log_sources(game_dir)),
+    }
+}
+
+/// 生成支持包并写入文件
+pub fn export_support_bundle(
+    game_dir: Option<&Path>,
+    file: &Path,
+) -> Result<SupportBundle, String> {
+    let bundle = collect_support_bundle(game_dir);
+    let json =
+        serde_json::to_string_pretty(&bundle).map_err(|e| format!("序列化支持包失败: {}", e))?;
+    fs::write(file, json).map_err(|e| format!("写入 {} 失败: {}", file.display(), e))?;
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use guga_ura_diagnostics::{DiagnosticsLog, Rotation};
+
+    #[test]
+    fn collect_logs_should_read_rotated_files_and_skip_missing() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let runtime = dir.path().join(RUNTIME_LOG_FILE_NAME);
+        let install = dir.path().join(INSTALL_LOG_FILE_NAME);
+        let log = DiagnosticsLog::new(&runtime).with_rotation(Rotation {
+            max_bytes: 200,
+            keep: 1,
+        });
+        log.info("core", "first");
+        log.warn("core", "second");
+        fs::write(&install, "[install] legacy line\n").expect("写入旧版日志失败");
+
+        let logs = collect_logs(&[
+            ("runtime", runtime.clone()),
+            ("install", install),
+            ("receiver", dir.path().join(RECEIVER_LOG_FILE_NAME)),
+        ]);
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].kind, "runtime");
+        assert_eq!(logs[0].files.len(), 2);
+        assert_eq!(logs[0].lines.len(), 2);
+        assert_eq!(
+            logs[1].lines,
+            vec![LogLine::Raw("[install] legacy line".to_string())]
+        );
+    }
+
+    #[test]
+    fn sources_should_include_game_logs_without_duplicates() {
+        let game_dir = Path::new("game");
+        let sources = support_log_sources(Some(game_dir));
+        let data_dir = game_dir.join("guga_ura_data");
+
+        assert!(sources.contains(&("runtime", data_dir.join(RUNTIME_LOG_FILE_NAME))));
+        assert!(sources.contains(&("install", data_dir.join(INSTALL_LOG_FILE_NAME))));
+        let mut paths: Vec<_> = sources.iter().map(|(_, path)| path.clone()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), sources.len());
+    }
+
+    #[test]
+    fn export_should_write_bundle_with_format_marker() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let file = dir.path().join("support.json");
+
+        export_support_bundle(Some(dir.path()), &file).expect("导出支持包失败");
+
+        let value: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&file).expect("读取支持包失败"))
+                .expect("支持包不是合法 JSON");
+        assert_eq!(value["format"], SUPPORT_BUNDLE_FORMAT);
+        assert_eq!(value["version"], SUPPORT_BUNDLE_VERSION);
+        assert!(value["logs"].is_array());
+    }
+}
@@ -0,0 +1,47 @@
+export interface ConfigIssue {
+  field: string;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+export function resolveConfigIssueAlertType(issues: ConfigIssue[]): 'error' | 'warning' {
+  return issues.some((issue) => issue.severity === 'error') ? 'error' : 'warning';
+}
+
+export function formatConfigIssues(issues: ConfigIssue[]): string {
+  return issues.map((issue) => `${issue.field}：${issue.message}`).join('；');
+}
+
+export interface ConfigReloadStatus {
+  state: 'applied' | 'rejected' | 'failed' | 'missing';
+  trigger: string;
+  atUnixMs: number;
+  message: string;
+  issues: ConfigIssue[];
+  activeProfile: string | null;
+}
+
+const RELOAD_TRIGGER_LABELS: Record<string, string> = {
+  startup: '游戏启动',
+  modify: '文件修改',
+  create: '文件创建',
+  rename: '文件替换',
+  remove: '文件删除',
+};
+
+export function resolveReloadStatusAlertType(
+  status: ConfigReloadStatus,
+): 'success' | 'warning' | 'error' {
+  if (status.state !== 'applied') {
+    return 'error';
+  }
+  return status.issues.length > 0 ? 'warning' : 'success';
+}
+
+export function formatReloadStatus(status: ConfigReloadStatus): string {
+  const trigger = RELOAD_TRIGGER_LABELS[status.trigger] ?? status.trigger;
+  const time = new Date(status.atUnixMs).toLocaleString();
+  const profile = status.activeProfile ? `，方案 ${status.activeProfile}` : '';
+  const issues = status.issues.length > 0 ? `；${formatConfigIssues(status.issues)}` : '';
+  return `${time}（${trigger}${profile}）${status.message}${issues}`;
+}
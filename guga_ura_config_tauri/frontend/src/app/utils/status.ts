@@ -16,7 +16,7 @@ export function resolveInstallStatusType(status: string): StatusTagType {
   if (status === 'installed') {
     return 'success';
   }
-  if (status === 'needsUpdate') {
+  if (status === 'needsUpdate' || status === 'modified' || status === 'incomplete') {
     return 'danger';
   }
   if (status === 'notInstalled') {
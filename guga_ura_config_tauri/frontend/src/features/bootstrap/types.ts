@@ -21,4 +21,5 @@ export interface InspectGameDirResult {
   detectedVersionLabel: string;
   installStatus: string;
   installStatusLabel: string;
+  hasInterruptedTransaction: boolean;
 }
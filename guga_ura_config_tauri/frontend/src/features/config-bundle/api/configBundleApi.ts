@@ -0,0 +1,19 @@
+import { invokeCommand } from '@/app/services/tauri';
+import type { ConfigProfilesActionResult } from '@/features/config-profiles/types';
+import type { ConfigBundleInput, ConfigBundlePreview } from '../types';
+
+export async function exportConfigBundle(input: ConfigBundleInput): Promise<string | null> {
+  return invokeCommand<string | null>('export_config_bundle', { input });
+}
+
+export async function previewConfigBundleImport(
+  input: ConfigBundleInput,
+): Promise<ConfigBundlePreview | null> {
+  return invokeCommand<ConfigBundlePreview | null>('preview_config_bundle_import', { input });
+}
+
+export async function applyConfigBundleImport(
+  input: ConfigBundleInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('apply_config_bundle_import', { input });
+}
@@ -0,0 +1,26 @@
+export interface ConfigBundleInput {
+  path: string | null;
+  file: string | null;
+}
+
+export interface ConfigFieldChange {
+  field: string;
+  old: string;
+  new: string;
+}
+
+export interface ConfigBundleProfilePreview {
+  name: string;
+  exists: boolean;
+  changes: ConfigFieldChange[];
+}
+
+export interface ConfigBundlePreview {
+  file: string;
+  exportedAtUnixMs: number;
+  changes: ConfigFieldChange[];
+  profiles: ConfigBundleProfilePreview[];
+  unresolved: string[];
+  issues: string[];
+  canApply: boolean;
+}
@@ -0,0 +1,41 @@
+import { invokeCommand } from '@/app/services/tauri';
+import type {
+  ConfigProfileInput,
+  ConfigProfileRenameInput,
+  ConfigProfiles,
+  ConfigProfilesActionResult,
+} from '../types';
+
+export async function getConfigProfiles(path?: string | null): Promise<ConfigProfiles> {
+  return invokeCommand<ConfigProfiles>('get_config_profiles', { path: path ?? null });
+}
+
+export async function createConfigProfile(
+  input: ConfigProfileInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('create_config_profile', { input });
+}
+
+export async function cloneConfigProfile(
+  input: ConfigProfileRenameInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('clone_config_profile', { input });
+}
+
+export async function renameConfigProfile(
+  input: ConfigProfileRenameInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('rename_config_profile', { input });
+}
+
+export async function deleteConfigProfile(
+  input: ConfigProfileInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('delete_config_profile', { input });
+}
+
+export async function activateConfigProfile(
+  input: ConfigProfileInput,
+): Promise<ConfigProfilesActionResult> {
+  return invokeCommand<ConfigProfilesActionResult>('activate_config_profile', { input });
+}
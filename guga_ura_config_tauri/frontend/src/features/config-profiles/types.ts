@@ -0,0 +1,20 @@
+export interface ConfigProfiles {
+  profiles: string[];
+  activeProfile: string | null;
+}
+
+export interface ConfigProfilesActionResult {
+  profiles: ConfigProfiles;
+  notice: string;
+}
+
+export interface ConfigProfileInput {
+  path: string | null;
+  name: string | null;
+}
+
+export interface ConfigProfileRenameInput {
+  path: string | null;
+  from: string;
+  to: string;
+}
@@ -5,6 +5,7 @@ import type {
   DllInjectionContext,
   SaveDebugModeInput,
   SaveDllInjectionConfigInput,
+  UninstallMode,
 } from '../types';
 
 export async function scanDllInjectionInstalledGames(): Promise<DetectedGame[]> {
@@ -37,6 +38,13 @@ export async function saveDebugMode(input: SaveDebugModeInput): Promise<DllInjec
   return invokeCommand<DllInjectionActionResult>('save_debug_mode', { input });
 }
 
-export async function uninstallDllInjection(path: string): Promise<DllInjectionActionResult> {
-  return invokeCommand<DllInjectionActionResult>('uninstall_dll_injection', { path });
+export async function uninstallDllInjection(
+  path: string,
+  mode: UninstallMode,
+): Promise<DllInjectionActionResult> {
+  return invokeCommand<DllInjectionActionResult>('uninstall_dll_injection', { path, mode });
+}
+
+export async function recoverInterruptedInstall(path: string): Promise<DllInjectionActionResult> {
+  return invokeCommand<DllInjectionActionResult>('recover_interrupted_install', { path });
 }
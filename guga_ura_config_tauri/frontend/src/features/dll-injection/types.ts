@@ -1,3 +1,5 @@
+import type { ConfigIssue, ConfigReloadStatus } from '@/app/utils/configIssues';
+
 export interface DetectedGame {
   path: string;
   version: string;
@@ -13,24 +15,35 @@ export interface DllInjectionContext {
   detectedVersionLabel: string;
   installStatus: string;
   installStatusLabel: string;
+  hasInterruptedTransaction: boolean;
   notifierHost: string;
   timeoutMs: number;
+  transportCompression: string;
+  sharedSecret: string;
   debugMode: boolean;
   debugOutputDir: string;
+  debugKeepRaw: boolean;
   fansEnabled: boolean;
   fansOutputDir: string;
   steamRequirementNote: string;
+  configIssues: ConfigIssue[];
+  reloadStatus: ConfigReloadStatus | null;
 }
 
 export interface SaveDllInjectionConfigInput {
   path: string;
   notifierHost: string;
   timeoutMs: number;
+  transportCompression?: string;
+  sharedSecret?: string;
 }
 
+export type UninstallMode = 'keep' | 'archive' | 'purge';
+
 export interface SaveDebugModeInput {
   path: string;
   debugMode: boolean;
+  debugKeepRaw?: boolean;
 }
 
 export interface DllInjectionActionResult {
@@ -1,3 +1,5 @@
+import type { ConfigIssue } from '@/app/utils/configIssues';
+
 export interface GameSettingsContext {
   path: string;
   hasPath: boolean;
@@ -7,6 +9,7 @@ export interface GameSettingsContext {
   detectedVersionLabel: string;
   targetFps: number;
   vsyncCount: number;
+  configIssues: ConfigIssue[];
 }
 
 export interface SaveGameSettingsInput {
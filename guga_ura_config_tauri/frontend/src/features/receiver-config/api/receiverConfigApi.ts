@@ -1,5 +1,6 @@
 import { invokeCommand } from '@/app/services/tauri';
 import type {
+  CaptureHealth,
   ReceiverRuntimeSettings,
   ReceiverRuntimeSettingsActionResult,
   SaveReceiverRuntimeSettingsInput,
@@ -9,6 +10,10 @@ export async function getReceiverRuntimeSettings(): Promise<ReceiverRuntimeSetti
   return invokeCommand<ReceiverRuntimeSettings>('get_receiver_runtime_settings');
 }
 
+export async function getCaptureHealth(): Promise<CaptureHealth> {
+  return invokeCommand<CaptureHealth>('get_capture_health');
+}
+
 export async function saveReceiverRuntimeSettings(
   input: SaveReceiverRuntimeSettingsInput,
 ): Promise<ReceiverRuntimeSettingsActionResult> {
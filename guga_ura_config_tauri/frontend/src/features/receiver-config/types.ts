@@ -1,3 +1,5 @@
+import type { ConfigIssue } from '@/app/utils/configIssues';
+
 export interface ReceiverRuntimeSettings {
   receiverListenAddr: string;
   relayEnabled: boolean;
@@ -6,6 +8,7 @@ export interface ReceiverRuntimeSettings {
   fansOutputDir: string;
   stallionOutputEnabled: boolean;
   stallionOutputDir: string;
+  configIssues: ConfigIssue[];
 }
 
 export interface SaveReceiverRuntimeSettingsInput {
@@ -18,6 +21,38 @@ export interface SaveReceiverRuntimeSettingsInput {
   stallionOutputDir: string;
 }
 
+export interface CaptureHook {
+  channel: string;
+  role: string;
+  target: string;
+  candidateIndex: number;
+}
+
+export interface CaptureHealth {
+  reported: boolean;
+  online: boolean;
+  ageMs: number | null;
+  dllVersion: string;
+  processId: number;
+  proxyEntry: string;
+  gameRelease: string;
+  releaseEvidence: string[];
+  symbolMapState: string;
+  symbolMapSource: string;
+  symbolMapEntries: number;
+  symbolMapError: string | null;
+  hooksState: string;
+  hookManifestSource: string;
+  hookAttempts: number;
+  hooks: CaptureHook[];
+  hooksError: string | null;
+  captureMatched: number;
+  captureSkipped: number;
+  deliverySent: number;
+  deliveryFailed: number;
+  deliveryLastError: string | null;
+}
+
 export interface ReceiverRuntimeSettingsActionResult {
   settings: ReceiverRuntimeSettings;
   notice: string;
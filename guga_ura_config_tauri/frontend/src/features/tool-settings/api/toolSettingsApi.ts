@@ -20,6 +20,10 @@ export async function checkAppUpdate(): Promise<AppUpdateCheckResult> {
   return invokeCommand<AppUpdateCheckResult>('check_app_update');
 }
 
+export async function exportSupportBundle(path?: string | null): Promise<string | null> {
+  return invokeCommand<string | null>('export_support_bundle', { path: path ?? null });
+}
+
 export async function openLatestReleasePage(url?: string | null): Promise<void> {
   return invokeCommand<void>('open_latest_release_page', { url: url ?? null });
 }
@@ -4,6 +4,7 @@ import { resolveCommandError } from '@/app/services/tauri';
 import {
   getDllInjectionContext,
   installDllInjection,
+  recoverInterruptedInstall,
   saveDebugMode as saveDebugModeRequest,
   saveDllInjectionConfig,
   scanDllInjectionInstalledGames,
@@ -15,6 +16,7 @@ import type {
   DllInjectionActionResult,
   DllInjectionContext,
   SaveDllInjectionConfigInput,
+  UninstallMode,
 } from '@/features/dll-injection/types';
 
 export const useDllInjectionStore = defineStore('dllInjection', () => {
@@ -32,6 +34,8 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
   const form = reactive({
     notifierHost: '',
     timeoutInput: '100',
+    transportCompression: 'none',
+    sharedSecret: '',
   });
 
   function applyContext(nextContext: DllInjectionContext): void {
@@ -39,6 +43,8 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     pathInput.value = nextContext.path;
     form.notifierHost = nextContext.notifierHost;
     form.timeoutInput = String(nextContext.timeoutMs);
+    form.transportCompression = nextContext.transportCompression || 'none';
+    form.sharedSecret = nextContext.sharedSecret;
   }
 
   async function initialize(force = false): Promise<void> {
@@ -159,7 +165,10 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     }
   }
 
-  async function saveDebugMode(debugMode: boolean): Promise<DllInjectionActionResult | null> {
+  async function saveDebugMode(
+    debugMode: boolean,
+    debugKeepRaw?: boolean,
+  ): Promise<DllInjectionActionResult | null> {
     const normalizedPath = pathInput.value.trim() || context.value?.path.trim() || '';
     if (!normalizedPath) {
       lastError.value = '请先选择游戏目录';
@@ -173,6 +182,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
       const result = await saveDebugModeRequest({
         path: normalizedPath,
         debugMode,
+        debugKeepRaw,
       });
       applyContext(result.context);
       return result;
@@ -184,7 +194,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     }
   }
 
-  async function uninstall(): Promise<DllInjectionActionResult | null> {
+  async function uninstall(mode: UninstallMode = 'keep'): Promise<DllInjectionActionResult | null> {
     const normalizedPath = pathInput.value.trim() || context.value?.path.trim() || '';
     if (!normalizedPath) {
       lastError.value = '请先选择游戏目录';
@@ -195,7 +205,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     lastError.value = '';
 
     try {
-      const result = await uninstallDllInjection(normalizedPath);
+      const result = await uninstallDllInjection(normalizedPath, mode);
       applyContext(result.context);
       return result;
     } catch (error) {
@@ -206,6 +216,28 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     }
   }
 
+  async function recoverInterrupted(): Promise<DllInjectionActionResult | null> {
+    const normalizedPath = pathInput.value.trim() || context.value?.path.trim() || '';
+    if (!normalizedPath) {
+      lastError.value = '请先选择游戏目录';
+      return null;
+    }
+
+    actionLoading.value = true;
+    lastError.value = '';
+
+    try {
+      const result = await recoverInterruptedInstall(normalizedPath);
+      applyContext(result.context);
+      return result;
+    } catch (error) {
+      lastError.value = resolveCommandError(error, '回滚中断的安装/卸载失败');
+      return null;
+    } finally {
+      actionLoading.value = false;
+    }
+  }
+
   function buildSaveInput(): SaveDllInjectionConfigInput | null {
     const normalizedPath = pathInput.value.trim() || context.value?.path.trim() || '';
     if (!normalizedPath) {
@@ -227,6 +259,8 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
       path: normalizedPath,
       notifierHost: form.notifierHost.trim(),
       timeoutMs: Number(form.timeoutInput.trim()),
+      transportCompression: form.transportCompression,
+      sharedSecret: form.sharedSecret.trim(),
     };
   }
 
@@ -238,6 +272,10 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     () => Boolean(context.value?.isValidGameDir && context.value?.detectedVersion !== 'unknown'),
   );
   const isInstalled = computed(() => context.value?.installStatus === 'installed');
+  // 需要更新、文件被修改或安装不完整时仍可卸载，也可直接重新安装
+  const hasInstallation = computed(
+    () => !['notInstalled', 'unknown'].includes(context.value?.installStatus ?? 'unknown'),
+  );
   const notifierError = computed(() => {
     const value = form.notifierHost.trim();
     if (!value) {
@@ -286,7 +324,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     if (!hasValidGameContext.value) {
       return '请先选择并检测有效游戏目录';
     }
-    if (!isInstalled.value) {
+    if (!hasInstallation.value) {
       return '当前未安装，无需卸载';
     }
     return '';
@@ -301,6 +339,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     contextLoading,
     detectedGames,
     form,
+    hasInstallation,
     hasValidGameContext,
     initialize,
     install,
@@ -311,6 +350,7 @@ export const useDllInjectionStore = defineStore('dllInjection', () => {
     loadContext,
     notifierError,
     pathInput,
+    recoverInterrupted,
     refreshCurrentContext,
     saveConfig,
     saveDebugMode,
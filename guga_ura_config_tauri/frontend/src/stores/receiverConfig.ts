@@ -4,11 +4,13 @@ import { resolveCommandError } from '@/app/services/tauri';
 import { getTerminalSnapshot } from '@/features/terminal/api/terminalApi';
 import type { TerminalSnapshot } from '@/features/terminal/types';
 import {
+  getCaptureHealth,
   getReceiverRuntimeSettings,
   pickReceiverDirectory,
   saveReceiverRuntimeSettings,
 } from '@/features/receiver-config/api/receiverConfigApi';
 import type {
+  CaptureHealth,
   ReceiverRuntimeSettings,
   ReceiverRuntimeSettingsActionResult,
   SaveReceiverRuntimeSettingsInput,
@@ -19,6 +21,7 @@ const SUMMARY_LOG_LIMIT = 1;
 export const useReceiverConfigStore = defineStore('receiverConfig', () => {
   const runtimeSnapshot = ref<TerminalSnapshot | null>(null);
   const settings = ref<ReceiverRuntimeSettings | null>(null);
+  const captureHealth = ref<CaptureHealth | null>(null);
   const lastError = ref('');
   const runtimeLoading = ref(false);
   const settingsLoading = ref(false);
@@ -72,8 +75,12 @@ export const useReceiverConfigStore = defineStore('receiverConfig', () => {
       }
 
       try {
-        const nextSnapshot = await getTerminalSnapshot(SUMMARY_LOG_LIMIT);
+        const [nextSnapshot, nextCaptureHealth] = await Promise.all([
+          getTerminalSnapshot(SUMMARY_LOG_LIMIT),
+          getCaptureHealth(),
+        ]);
         runtimeSnapshot.value = nextSnapshot;
+        captureHealth.value = nextCaptureHealth;
         lastUpdatedAt.value = Date.now();
         lastError.value = '';
         return nextSnapshot;
@@ -294,6 +301,7 @@ export const useReceiverConfigStore = defineStore('receiverConfig', () => {
     browseFansOutputDirectory,
     browseStallionDirLoading,
     browseStallionOutputDirectory,
+    captureHealth,
     clearError,
     configuredListenAddr,
     fansOutputDirDisplay,
@@ -3,6 +3,7 @@ import { computed, ref } from 'vue';
 import { resolveCommandError } from '@/app/services/tauri';
 import {
   checkAppUpdate,
+  exportSupportBundle,
   getToolSettingsContext,
   openLatestReleasePage,
   setAutostartEnabled,
@@ -21,6 +22,7 @@ export const useToolSettingsStore = defineStore('toolSettings', () => {
   const savingAutostart = ref(false);
   const checkingUpdate = ref(false);
   const openingReleasePage = ref(false);
+  const exportingSupportBundle = ref(false);
   const hasInitialized = ref(false);
 
   async function initialize(force = false): Promise<void> {
@@ -96,6 +98,21 @@ export const useToolSettingsStore = defineStore('toolSettings', () => {
     }
   }
 
+  async function exportSupportBundleFile(): Promise<string | null> {
+    exportingSupportBundle.value = true;
+
+    try {
+      const file = await exportSupportBundle();
+      lastError.value = '';
+      return file;
+    } catch (error) {
+      lastError.value = resolveCommandError(error, '导出支持包失败');
+      return null;
+    } finally {
+      exportingSupportBundle.value = false;
+    }
+  }
+
   function clearError(): void {
     lastError.value = '';
   }
@@ -110,6 +127,8 @@ export const useToolSettingsStore = defineStore('toolSettings', () => {
     clearError,
     context,
     currentVersion,
+    exportSupportBundleFile,
+    exportingSupportBundle,
     hasInitialized,
     initialize,
     lastError,
@@ -5,16 +5,26 @@ use crate::state::AppState;
 use crate::tool_settings::{
     AppUpdateCheckDto, ToolSettingsActionResultDto, ToolSettingsContextDto,
 };
+use guga_ura_config_core::bundle;
+use guga_ura_config_core::capture_health::{self, CaptureHealthView};
+use guga_ura_config_core::config::reload::{self, ReloadIssue, ReloadState, ReloadStatus};
 use guga_ura_config_core::config::Config;
 use guga_ura_config_core::detector::{
     detect_game_version, is_valid_game_dir, scan_installed_games as scan_installed_games_core,
     DetectedGame, GameVersion,
 };
+use guga_ura_config_core::install_journal::{self, RollbackReport};
 use guga_ura_config_core::installer::{
-    check_install_status, install_dll, uninstall_dll, InstallStatus,
+    check_install_status, install_dll, recover_interrupted_transaction, uninstall_dll,
+    InstallStatus, UninstallMode, UninstallReport,
 };
+use guga_ura_config_core::profiles;
 use guga_ura_config_core::receiver;
-use guga_ura_config_core::receiver_pipeline;
+use guga_ura_config_core::support_bundle;
+use guga_ura_config_core::validation::{
+    ConfigIssue, CAPTURE_RULE_FIELDS, DLL_INJECTION_FIELDS, GAME_SETTINGS_FIELDS,
+    RECEIVER_RUNTIME_FIELDS,
+};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -51,6 +61,8 @@ pub struct InspectGameDirResultDto {
     pub detected_version_label: String,
     pub install_status: String,
     pub install_status_label: String,
+    /// 上次安装/卸载中途退出、等待回滚
+    pub has_interrupted_transaction: bool,
 }
 
 impl InspectGameDirResultDto {
@@ -72,6 +84,8 @@ impl InspectGameDirResultDto {
             detected_version_label: version_label.to_string(),
             install_status: status.to_string(),
             install_status_label: status_label.to_string(),
+            has_interrupted_transaction: is_valid_game_dir
+                && install_journal::has_pending(game_dir),
         }
     }
 }
@@ -88,13 +102,50 @@ pub struct DllInjectionContextDto {
     pub detected_version_label: String,
     pub install_status: String,
     pub install_status_label: String,
+    /// 上次安装/卸载中途退出、等待回滚
+    pub has_interrupted_transaction: bool,
     pub notifier_host: String,
     pub timeout_ms: u64,
+    /// 发往 Receiver 的压缩方式（none / gzip / zstd）
+    pub transport_compression: String,
+    /// DLL / Receiver / Relay 共用的签名密钥，为空表示不签名
+    pub shared_secret: String,
     pub debug_mode: bool,
     pub debug_output_dir: String,
+    /// Debug 模式下同时保留原始 `.msgpack`
+    pub debug_keep_raw: bool,
     pub fans_enabled: bool,
     pub fans_output_dir: String,
     pub steam_requirement_note: String,
+    /// 当前配置中注入链路字段的校验问题
+    pub config_issues: Vec<ConfigIssue>,
+    /// DLL 最近一次加载/热重载配置的结果（游戏目录中的状态文件）
+    pub reload_status: Option<ConfigReloadStatusDto>,
+}
+
+/// DLL 配置重载状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadStatusDto {
+    pub state: ReloadState,
+    pub trigger: String,
+    pub at_unix_ms: u64,
+    pub message: String,
+    pub issues: Vec<ReloadIssue>,
+    pub active_profile: Option<String>,
+}
+
+impl From<ReloadStatus> for ConfigReloadStatusDto {
+    fn from(status: ReloadStatus) -> Self {
+        Self {
+            state: status.state,
+            trigger: status.trigger,
+            at_unix_ms: status.at_unix_ms,
+            message: status.message,
+            issues: status.issues,
+            active_profile: status.active_profile,
+        }
+    }
 }
 
 /// DLL 注入页保存输入
@@ -104,6 +155,8 @@ pub struct SaveDllInjectionConfigInput {
     pub path: String,
     pub notifier_host: String,
     pub timeout_ms: u64,
+    pub transport_compression: Option<String>,
+    pub shared_secret: Option<String>,
     pub fans_enabled: Option<bool>,
     pub fans_output_dir: Option<String>,
 }
@@ -114,6 +167,9 @@ pub struct SaveDllInjectionConfigInput {
 pub struct SaveDebugModeInput {
     pub path: String,
     pub debug_mode: bool,
+    /// 未提供时保持原值
+    #[serde(default)]
+    pub debug_keep_raw: Option<bool>,
 }
 
 /// DLL 注入页动作返回
@@ -135,6 +191,8 @@ pub struct ReceiverRuntimeSettingsDto {
     pub fans_output_dir: String,
     pub stallion_output_enabled: bool,
     pub stallion_output_dir: String,
+    /// EXE 同级配置中 Receiver 字段的校验问题
+    pub config_issues: Vec<ConfigIssue>,
 }
 
 /// Receiver 运行时设置保存输入
@@ -158,6 +216,117 @@ pub struct ReceiverRuntimeSettingsActionResultDto {
     pub notice: String,
 }
 
+/// DLL 捕获状态 DTO
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureHealthDto {
+    /// 是否收到过 DLL 状态
+    pub reported: bool,
+    pub online: bool,
+    pub age_ms: Option<u64>,
+    pub dll_version: String,
+    pub process_id: u32,
+    pub proxy_entry: String,
+    pub game_release: String,
+    pub release_evidence: Vec<String>,
+    pub symbol_map_state: String,
+    pub symbol_map_source: String,
+    pub symbol_map_entries: usize,
+    pub symbol_map_error: Option<String>,
+    pub hooks_state: String,
+    pub hook_manifest_source: String,
+    pub hook_attempts: u32,
+    pub hooks: Vec<CaptureHookDto>,
+    pub hooks_error: Option<String>,
+    pub capture_matched: u64,
+    pub capture_skipped: u64,
+    pub delivery_sent: u64,
+    pub delivery_failed: u64,
+    pub delivery_last_error: Option<String>,
+}
+
+/// 单个捕获 Hook DTO
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureHookDto {
+    pub channel: String,
+    pub role: String,
+    pub target: String,
+    pub candidate_index: usize,
+}
+
+/// 配置方案列表 DTO
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfilesDto {
+    pub profiles: Vec<String>,
+    pub active_profile: Option<String>,
+}
+
+/// 配置方案动作返回
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfilesActionResultDto {
+    pub profiles: ConfigProfilesDto,
+    pub notice: String,
+}
+
+/// 配置方案输入（新建、删除、切换；切换时 `name` 为空表示回到主配置）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfileInput {
+    pub path: Option<String>,
+    pub name: Option<String>,
+}
+
+/// 配置方案复制/重命名输入
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfileRenameInput {
+    pub path: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+/// 配置导入导出输入（`path` 为游戏目录，`file` 为导出文件）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundleInput {
+    pub path: Option<String>,
+    pub file: Option<String>,
+}
+
+/// 导入预览中的字段差异（值为 JSON 文本）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFieldChangeDto {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// 导入预览中的方案
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundleProfilePreviewDto {
+    pub name: String,
+    pub exists: bool,
+    pub changes: Vec<ConfigFieldChangeDto>,
+}
+
+/// 导入预览 DTO
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundlePreviewDto {
+    pub file: String,
+    pub exported_at_unix_ms: u64,
+    pub changes: Vec<ConfigFieldChangeDto>,
+    pub profiles: Vec<ConfigBundleProfilePreviewDto>,
+    pub unresolved: Vec<String>,
+    pub issues: Vec<String>,
+    pub can_apply: bool,
+}
+
 /// 终端页快照 DTO
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -182,6 +351,8 @@ pub struct GameSettingsContextDto {
     pub detected_version_label: String,
     pub target_fps: i32,
     pub vsync_count: i32,
+    /// 当前配置中游戏设置字段的校验问题
+    pub config_issues: Vec<ConfigIssue>,
 }
 
 /// 游戏设置页保存输入
@@ -283,6 +454,12 @@ pub fn get_receiver_runtime_settings() -> ReceiverRuntimeSettingsDto {
     build_receiver_runtime_settings()
 }
 
+/// 读取内置 Receiver 收到的 DLL 捕获状态
+#[tauri::command]
+pub fn get_capture_health() -> CaptureHealthDto {
+    map_capture_health(capture_health::capture_health_view())
+}
+
 /// 保存 DLL 注入链路相关配置
 #[tauri::command]
 pub fn save_dll_injection_config(
@@ -329,6 +506,8 @@ pub fn install_dll_injection(
         return Err("无法识别游戏版本".to_string());
     }
 
+    let recovered =
+        recover_interrupted_transaction(&game_dir).map_err(|e| format!("安装 DLL 失败: {}", e))?;
     install_dll(&game_dir, version).map_err(|e| format!("安装 DLL 失败: {}", e))?;
 
     let mut config = load_effective_config(Some(&game_dir));
@@ -345,6 +524,7 @@ pub fn install_dll_injection(
         Ok(()) => base_notice,
         Err(error) => format!("{} 配置同步失败: {}", base_notice, error),
     };
+    let notice = with_recovery_notice(recovered.as_ref(), notice);
 
     Ok(DllInjectionActionResultDto {
         context: build_dll_injection_context(Some(input.path.as_str())),
@@ -359,6 +539,9 @@ pub fn save_debug_mode(input: SaveDebugModeInput) -> Result<DllInjectionActionRe
     let mut config = load_effective_config(Some(&game_dir));
 
     apply_debug_mode_field(&mut config, input.debug_mode);
+    if let Some(keep_raw) = input.debug_keep_raw {
+        config.debug_keep_raw = keep_raw;
+    }
     save_config_to_targets(&game_dir, &config)?;
 
     Ok(DllInjectionActionResultDto {
@@ -371,9 +554,12 @@ pub fn save_debug_mode(input: SaveDebugModeInput) -> Result<DllInjectionActionRe
     })
 }
 
-/// 卸载 DLL
+/// 卸载 DLL；`mode` 为 `keep` / `archive` / `purge`，缺省保留配置与日志
 #[tauri::command]
-pub fn uninstall_dll_injection(path: String) -> Result<DllInjectionActionResultDto, String> {
+pub fn uninstall_dll_injection(
+    path: String,
+    mode: Option<String>,
+) -> Result<DllInjectionActionResultDto, String> {
     let game_dir = require_valid_game_dir(&path)?;
     let version = detect_game_version(&game_dir);
 
@@ -381,11 +567,31 @@ pub fn uninstall_dll_injection(path: String) -> Result<DllInjectionActionResultD
         return Err("无法识别游戏版本".to_string());
     }
 
-    uninstall_dll(&game_dir, version).map_err(|e| format!("卸载 DLL 失败: {}", e))?;
+    let mode = map_uninstall_mode(mode.as_deref())?;
+    let recovered =
+        recover_interrupted_transaction(&game_dir).map_err(|e| format!("卸载 DLL 失败: {}", e))?;
+    let report =
+        uninstall_dll(&game_dir, version, mode).map_err(|e| format!("卸载 DLL 失败: {}", e))?;
 
     Ok(DllInjectionActionResultDto {
         context: build_dll_injection_context(Some(path.as_str())),
-        notice: "已卸载".to_string(),
+        notice: with_recovery_notice(recovered.as_ref(), uninstall_notice(mode, &report)),
+    })
+}
+
+/// 回滚上次中途退出的安装或卸载
+#[tauri::command]
+pub fn recover_interrupted_install(path: String) -> Result<DllInjectionActionResultDto, String> {
+    let game_dir = require_valid_game_dir(&path)?;
+    let recovered =
+        recover_interrupted_transaction(&game_dir).map_err(|e| format!("回滚失败: {}", e))?;
+
+    Ok(DllInjectionActionResultDto {
+        context: build_dll_injection_context(Some(path.as_str())),
+        notice: recovered
+            .as_ref()
+            .map(recovery_notice)
+            .unwrap_or_else(|| "没有需要回滚的中断操作".to_string()),
     })
 }
 
@@ -415,7 +621,215 @@ pub fn save_game_settings(
     })
 }
 
+/// 读取配置方案列表
+#[tauri::command]
+pub fn get_config_profiles(path: Option<String>) -> Result<ConfigProfilesDto, String> {
+    let game_dir = resolve_profile_game_dir(path.as_deref())?;
+    Ok(map_profile_overview(profiles::list_profiles(
+        game_dir.as_deref(),
+    )))
+}
+
+/// 以当前生效配置新建方案
+#[tauri::command]
+pub fn create_config_profile(
+    input: ConfigProfileInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let name = input.name.unwrap_or_default();
+    let config = load_effective_config(game_dir.as_deref());
+    let overview = profiles::create_profile(game_dir.as_deref(), &name, &config)?;
+
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(overview),
+        notice: format!("已将当前配置保存为方案 {}", name.trim()),
+    })
+}
+
+/// 复制方案
+#[tauri::command]
+pub fn clone_config_profile(
+    input: ConfigProfileRenameInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let overview = profiles::clone_profile(game_dir.as_deref(), &input.from, &input.to)?;
+
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(overview),
+        notice: format!("已复制方案 {} 为 {}", input.from, input.to.trim()),
+    })
+}
+
+/// 重命名方案
+#[tauri::command]
+pub fn rename_config_profile(
+    input: ConfigProfileRenameInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let overview = profiles::rename_profile(game_dir.as_deref(), &input.from, &input.to)?;
+
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(overview),
+        notice: format!("已将方案 {} 重命名为 {}", input.from, input.to.trim()),
+    })
+}
+
+/// 删除方案
+#[tauri::command]
+pub fn delete_config_profile(
+    state: State<'_, AppState>,
+    input: ConfigProfileInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let name = input.name.unwrap_or_default();
+    let was_active = profiles::list_profiles(game_dir.as_deref())
+        .active
+        .as_deref()
+        == Some(name.trim());
+    let overview = profiles::delete_profile(game_dir.as_deref(), &name)?;
+
+    let mut notice = format!("已删除方案 {}", name.trim());
+    if was_active {
+        notice = format!(
+            "{}，已回到主配置；{}",
+            notice,
+            hot_reload_receiver_runtime(state.inner())
+        );
+    }
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(overview),
+        notice,
+    })
+}
+
+/// 切换激活方案；DLL 会通过配置文件监控热应用，内置 Receiver 在这里热更新
+#[tauri::command]
+pub fn activate_config_profile(
+    state: State<'_, AppState>,
+    input: ConfigProfileInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let name = input
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+    let overview = profiles::activate_profile(game_dir.as_deref(), name)?;
+    let switched = match name {
+        Some(name) => format!("已切换到方案 {}", name),
+        None => "已回到主配置".to_string(),
+    };
+
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(overview),
+        notice: format!(
+            "{}；{}",
+            switched,
+            hot_reload_receiver_runtime(state.inner())
+        ),
+    })
+}
+
+/// 导出主配置与全部方案；未指定文件时弹出保存对话框，取消时返回 `None`
+#[tauri::command]
+pub fn export_config_bundle(input: ConfigBundleInput) -> Result<Option<String>, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let file = match input.file {
+        Some(file) if !file.trim().is_empty() => PathBuf::from(file.trim()),
+        _ => match rfd::FileDialog::new()
+            .set_title("导出配置")
+            .set_file_name("gugaura_config_bundle.json")
+            .add_filter("JSON", &["json"])
+            .save_file()
+        {
+            Some(file) => file,
+            None => return Ok(None),
+        },
+    };
+
+    bundle::export_to_file(game_dir.as_deref(), &file)?;
+    Ok(Some(file.display().to_string()))
+}
+
+/// 读取导出文件并生成导入预览；未指定文件时弹出选择框，取消时返回 `None`
+#[tauri::command]
+pub fn preview_config_bundle_import(
+    input: ConfigBundleInput,
+) -> Result<Option<ConfigBundlePreviewDto>, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let file = match input.file {
+        Some(file) if !file.trim().is_empty() => PathBuf::from(file.trim()),
+        _ => match rfd::FileDialog::new()
+            .set_title("导入配置")
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        {
+            Some(file) => file,
+            None => return Ok(None),
+        },
+    };
+
+    let bundle = bundle::read_bundle(&file)?;
+    let preview = bundle::preview_import(&bundle, game_dir.as_deref())?;
+    Ok(Some(map_bundle_preview(&file, &bundle, &preview)))
+}
+
+/// 按预览导入配置；重新读取文件，保证写入内容与预览一致
+#[tauri::command]
+pub fn apply_config_bundle_import(
+    state: State<'_, AppState>,
+    input: ConfigBundleInput,
+) -> Result<ConfigProfilesActionResultDto, String> {
+    let game_dir = resolve_profile_game_dir(input.path.as_deref())?;
+    let file = input
+        .file
+        .as_deref()
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .ok_or_else(|| "请先选择要导入的配置文件".to_string())?;
+
+    let bundle = bundle::read_bundle(Path::new(file))?;
+    let preview = bundle::preview_import(&bundle, game_dir.as_deref())?;
+    bundle::apply_import(&preview, game_dir.as_deref())?;
+
+    let mut notice = format!(
+        "已导入 {} 项变更、{} 个方案",
+        preview.changes.len(),
+        preview.profiles.len()
+    );
+    if !preview.unresolved.is_empty() {
+        notice.push_str(&format!(
+            "，{} 个路径无法还原已改用默认目录",
+            preview.unresolved.len()
+        ));
+    }
+    Ok(ConfigProfilesActionResultDto {
+        profiles: map_profile_overview(profiles::list_profiles(game_dir.as_deref())),
+        notice: format!("{}；{}", notice, hot_reload_receiver_runtime(state.inner())),
+    })
+}
+
 /// 读取工具设置页上下文
+/// 导出支持包：汇总各组件诊断日志与 DLL 捕获状态
+#[tauri::command]
+pub fn export_support_bundle(path: Option<String>) -> Result<Option<String>, String> {
+    let game_dir = match normalize_optional_input(path.as_deref()) {
+        Some(path) => Some(require_valid_game_dir(&path)?),
+        None => resolve_default_game_dir(),
+    };
+    let Some(file) = rfd::FileDialog::new()
+        .set_title("导出支持包")
+        .set_file_name("gugaura_support_bundle.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    support_bundle::export_support_bundle(game_dir.as_deref(), &file)?;
+    Ok(Some(file.display().to_string()))
+}
+
 #[tauri::command]
 pub fn get_tool_settings_context(app: AppHandle) -> Result<ToolSettingsContextDto, String> {
     crate::tool_settings::get_tool_settings_context(&app, env!("CARGO_PKG_VERSION"))
@@ -442,6 +856,62 @@ pub fn open_latest_release_page(app: AppHandle, url: Option<String>) -> Result<(
     crate::tool_settings::open_latest_release_page(&app, url.as_deref())
 }
 
+fn resolve_profile_game_dir(path: Option<&str>) -> Result<Option<PathBuf>, String> {
+    match path.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => require_valid_game_dir(path).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn map_profile_overview(overview: profiles::ProfileOverview) -> ConfigProfilesDto {
+    ConfigProfilesDto {
+        profiles: overview.profiles,
+        active_profile: overview.active,
+    }
+}
+
+fn map_bundle_preview(
+    file: &Path,
+    bundle: &bundle::ConfigBundle,
+    preview: &bundle::ImportPreview,
+) -> ConfigBundlePreviewDto {
+    ConfigBundlePreviewDto {
+        file: file.display().to_string(),
+        exported_at_unix_ms: bundle.exported_at_unix_ms,
+        changes: map_field_changes(&preview.changes),
+        profiles: preview
+            .profiles
+            .iter()
+            .map(|profile| ConfigBundleProfilePreviewDto {
+                name: profile.name.clone(),
+                exists: profile.exists,
+                changes: map_field_changes(&profile.changes),
+            })
+            .collect(),
+        unresolved: preview.unresolved.clone(),
+        issues: preview
+            .validation
+            .issues
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        can_apply: preview.validation.is_valid(),
+    }
+}
+
+fn map_field_changes(
+    changes: &[guga_ura_config_core::config::observer::FieldChange],
+) -> Vec<ConfigFieldChangeDto> {
+    changes
+        .iter()
+        .map(|change| ConfigFieldChangeDto {
+            field: change.field.clone(),
+            old: change.old.to_string(),
+            new: change.new.to_string(),
+        })
+        .collect()
+}
+
 fn map_game_version(version: GameVersion) -> (&'static str, &'static str) {
     match version {
         GameVersion::Steam => ("steam", "Steam 版"),
@@ -455,10 +925,68 @@ fn map_install_status(status: InstallStatus) -> (&'static str, &'static str) {
         InstallStatus::Installed => ("installed", "已安装"),
         InstallStatus::NotInstalled => ("notInstalled", "未安装"),
         InstallStatus::NeedsUpdate => ("needsUpdate", "需要更新"),
+        InstallStatus::Modified => ("modified", "文件被修改"),
+        InstallStatus::Incomplete => ("incomplete", "安装不完整"),
         InstallStatus::Unknown => ("unknown", "未知"),
     }
 }
 
+fn map_uninstall_mode(mode: Option<&str>) -> Result<UninstallMode, String> {
+    match mode.unwrap_or("keep") {
+        "keep" => Ok(UninstallMode::Keep),
+        "archive" => Ok(UninstallMode::Archive),
+        "purge" => Ok(UninstallMode::Purge),
+        other => Err(format!("未知的卸载方式: {}", other)),
+    }
+}
+
+fn recovery_notice(report: &RollbackReport) -> String {
+    let operation = match report.operation.as_str() {
+        "install" => "安装",
+        "uninstall" => "卸载",
+        other => other,
+    };
+    format!(
+        "已回滚上次中断的{}（撤销 {} 步更改）",
+        operation, report.undone
+    )
+}
+
+/// 在操作结果前附上回滚上次中断操作的提示
+fn with_recovery_notice(recovered: Option<&RollbackReport>, notice: String) -> String {
+    match recovered {
+        Some(report) => format!("{}；{}", recovery_notice(report), notice),
+        None => notice,
+    }
+}
+
+fn uninstall_notice(mode: UninstallMode, report: &UninstallReport) -> String {
+    let mut notice = "已卸载".to_string();
+    if !report.user_data.is_empty() {
+        match (mode, &report.archive_dir) {
+            (UninstallMode::Archive, Some(archive_dir)) => {
+                notice.push_str(&format!("，配置与日志已归档到 {}", archive_dir.display()))
+            }
+            (UninstallMode::Purge, _) => notice.push_str("，配置与日志已删除"),
+            _ => notice.push_str("，配置与日志已保留"),
+        }
+    }
+    if !report.modified.is_empty() {
+        notice.push_str(&format!(
+            "；以下文件已被其它程序修改，未删除: {}",
+            report.modified.join(", ")
+        ));
+    }
+    if !report.skipped.is_empty() {
+        notice.push_str(&format!(
+            "（{} 项被占用未处理: {}）",
+            report.skipped.len(),
+            report.skipped.join("; ")
+        ));
+    }
+    notice
+}
+
 fn build_inspect_game_dir_result(game_dir: &Path) -> InspectGameDirResultDto {
     let exists = game_dir.exists();
     let is_valid = is_valid_game_dir(game_dir);
@@ -469,6 +997,8 @@ fn build_inspect_game_dir_result(game_dir: &Path) -> InspectGameDirResultDto {
         GameVersion::Unknown
     };
 
+    // 只读检测：中断的安装/卸载只在 DTO 中标出，回滚由安装、卸载或
+    // `recover_interrupted_install` 执行
     let install_status = if is_valid {
         check_install_status(game_dir, detected_version)
     } else {
@@ -509,14 +1039,25 @@ fn build_dll_injection_context(path: Option<&str>) -> DllInjectionContextDto {
     let config = load_effective_config(game_dir.as_deref());
     let notifier_host = config.notifier_host.clone();
     let timeout_ms = config.timeout_ms;
+    let transport_compression = config
+        .transport_compression
+        .clone()
+        .unwrap_or_else(|| "none".to_string());
+    let shared_secret = config.shared_secret.clone().unwrap_or_default();
     let debug_mode = config.debug_mode;
     let fans_enabled = config.fans_enabled;
     let debug_output_dir = resolve_debug_output_dir(&config);
+    let debug_keep_raw = config.debug_keep_raw;
     let fans_output_dir = resolve_fans_output_dir(&config);
     let steam_requirement_note = game_dir
         .as_deref()
         .map(|dir| build_steam_requirement_note(dir, inspect_game_version))
         .unwrap_or_default();
+    let config_issues = config
+        .validate()
+        .only_fields(&[DLL_INJECTION_FIELDS, CAPTURE_RULE_FIELDS].concat())
+        .issues;
+    let reload_status = game_dir.as_deref().and_then(read_dll_reload_status);
 
     DllInjectionContextDto {
         path: normalized_path,
@@ -527,16 +1068,29 @@ fn build_dll_injection_context(path: Option<&str>) -> DllInjectionContextDto {
         detected_version_label: inspect.detected_version_label,
         install_status: inspect.install_status,
         install_status_label: inspect.install_status_label,
+        has_interrupted_transaction: inspect.has_interrupted_transaction,
         notifier_host,
         timeout_ms,
+        transport_compression,
+        shared_secret,
         debug_mode,
         debug_output_dir,
+        debug_keep_raw,
         fans_enabled,
         fans_output_dir,
         steam_requirement_note,
+        config_issues,
+        reload_status,
     }
 }
 
+fn read_dll_reload_status(game_dir: &Path) -> Option<ConfigReloadStatusDto> {
+    reload::read_reload_status(&Config::config_path(game_dir))
+        .ok()
+        .flatten()
+        .map(ConfigReloadStatusDto::from)
+}
+
 fn build_steam_requirement_note(game_dir: &Path, version: GameVersion) -> String {
     if !is_jp_steam_game_dir(game_dir, version) {
         return String::new();
@@ -566,6 +1120,51 @@ fn build_receiver_runtime_settings_from_config(config: &Config) -> ReceiverRunti
         fans_output_dir: resolve_fans_output_dir(config),
         stallion_output_enabled: config.stallion_output_enabled,
         stallion_output_dir: resolve_stallion_output_dir(config),
+        config_issues: config
+            .validate()
+            .only_fields(RECEIVER_RUNTIME_FIELDS)
+            .issues,
+    }
+}
+
+fn map_capture_health(view: CaptureHealthView) -> CaptureHealthDto {
+    let Some(report) = view.report else {
+        return CaptureHealthDto::default();
+    };
+
+    CaptureHealthDto {
+        reported: true,
+        online: view.online,
+        age_ms: view.age_ms,
+        dll_version: report.dll_version,
+        process_id: report.process_id,
+        proxy_entry: report.proxy_entry,
+        game_release: report.game_release,
+        release_evidence: report.release_evidence,
+        symbol_map_state: report.symbol_map.state,
+        symbol_map_source: report.symbol_map.source,
+        symbol_map_entries: report.symbol_map.entries,
+        symbol_map_error: report.symbol_map.error,
+        hooks_state: report.hooks.state,
+        hook_manifest_source: report.hooks.manifest_source,
+        hook_attempts: report.hooks.attempts,
+        hooks: report
+            .hooks
+            .hooks
+            .into_iter()
+            .map(|hook| CaptureHookDto {
+                channel: hook.channel,
+                role: hook.role,
+                target: hook.target,
+                candidate_index: hook.candidate_index,
+            })
+            .collect(),
+        hooks_error: report.hooks.error,
+        capture_matched: report.capture.matched,
+        capture_skipped: report.capture.skipped,
+        delivery_sent: report.delivery.sent,
+        delivery_failed: report.delivery.failed,
+        delivery_last_error: report.delivery.last_error,
     }
 }
 
@@ -606,6 +1205,7 @@ fn build_game_settings_context(path: Option<&str>) -> GameSettingsContextDto {
         detected_version_label: inspect.detected_version_label,
         target_fps: config.target_fps,
         vsync_count: config.vsync_count,
+        config_issues: config.validate().only_fields(GAME_SETTINGS_FIELDS).issues,
     }
 }
 
@@ -656,58 +1256,31 @@ fn load_effective_config(game_dir: Option<&Path>) -> Config {
         return exe_config;
     }
 
-    let game_config_exists = Config::config_path(path).exists();
-    let game_config_has_fans_enabled = Config::game_config_has_key(path, "fans_enabled");
-    let mut config = Config::load_from(path);
-
-    backfill_exe_side_receiver_fields(
-        &mut config,
-        exe_config,
-        game_config_exists,
-        game_config_has_fans_enabled,
-    );
-
-    config
-}
-
-fn backfill_exe_side_receiver_fields(
-    config: &mut Config,
-    exe_config: Config,
-    game_config_exists: bool,
-    game_config_has_fans_enabled: bool,
-) {
-    if !game_config_exists || !game_config_has_fans_enabled {
-        config.fans_enabled = exe_config.fans_enabled;
-    }
-
-    if config
-        .fans_output_dir
-        .as_ref()
-        .map(|value| value.trim().is_empty())
-        .unwrap_or(true)
-    {
-        config.fans_output_dir = exe_config.fans_output_dir;
-    }
-
-    config.stallion_output_enabled = exe_config.stallion_output_enabled;
-    config.stallion_output_dir = exe_config.stallion_output_dir;
+    Config::load_from_with_exe_side(path, &exe_config)
 }
 
 fn apply_dll_injection_fields(
     config: &mut Config,
     input: &SaveDllInjectionConfigInput,
 ) -> Result<(), String> {
-    let notifier_host = input.notifier_host.trim();
-    if !notifier_host.starts_with("http://") && !notifier_host.starts_with("https://") {
-        return Err("转发地址必须以 http:// 或 https:// 开头".to_string());
+    let mut candidate = config.clone();
+    candidate.notifier_host = input.notifier_host.trim().to_string();
+    candidate.timeout_ms = input.timeout_ms;
+    if let Some(compression) = input.transport_compression.as_deref() {
+        let compression = compression.trim().to_ascii_lowercase();
+        candidate.transport_compression =
+            (!compression.is_empty() && compression != "none").then_some(compression);
     }
-
-    if input.timeout_ms == 0 {
-        return Err("超时必须大于 0".to_string());
+    if let Some(secret) = input.shared_secret.as_deref() {
+        let secret = secret.trim();
+        candidate.shared_secret = (!secret.is_empty()).then(|| secret.to_string());
     }
+    candidate.validate().ensure_fields(DLL_INJECTION_FIELDS)?;
 
-    config.notifier_host = notifier_host.to_string();
-    config.timeout_ms = input.timeout_ms;
+    config.notifier_host = candidate.notifier_host;
+    config.timeout_ms = candidate.timeout_ms;
+    config.transport_compression = candidate.transport_compression;
+    config.shared_secret = candidate.shared_secret;
     if let Some(fans_enabled) = input.fans_enabled {
         config.fans_enabled = fans_enabled;
     }
@@ -728,38 +1301,22 @@ fn apply_receiver_runtime_fields(
     config: &mut Config,
     input: &SaveReceiverRuntimeSettingsInput,
 ) -> Result<(), String> {
-    let receiver_listen_addr = input.receiver_listen_addr.trim();
-    if receiver_listen_addr.is_empty() {
-        return Err("监听地址不能为空".to_string());
-    }
-
-    let Some(receiver_listen_addr) =
-        receiver::normalize_receiver_listen_addr_input(receiver_listen_addr)
-    else {
-        return Err(
-            "监听地址必须使用 host:port 格式，host 仅支持 localhost 或 IP；也可填写 http://host:port"
-                .to_string(),
-        );
-    };
-
-    let relay_target_host = normalize_optional_input(input.relay_target_host.as_deref());
-    if let Some(target) = relay_target_host.as_deref() {
-        if !target.starts_with("http://") && !target.starts_with("https://") {
-            return Err("Relay 目标地址必须以 http:// 或 https:// 开头".to_string());
-        }
-
-        if input.relay_enabled
-            && receiver_pipeline::relay_target_would_loop(&receiver_listen_addr, target)
-        {
-            return Err("Relay 目标地址不能指向当前 Receiver 自身".to_string());
-        }
-    }
-
+    let mut candidate = config.clone();
+    candidate.receiver_listen_addr = input.receiver_listen_addr.trim().to_string();
+    candidate.relay_enabled = input.relay_enabled;
+    candidate.relay_target_host = normalize_optional_input(input.relay_target_host.as_deref());
+    candidate
+        .validate()
+        .ensure_fields(RECEIVER_RUNTIME_FIELDS)?;
+
+    let receiver_listen_addr =
+        receiver::normalize_receiver_listen_addr_input(&candidate.receiver_listen_addr)
+            .unwrap_or(candidate.receiver_listen_addr);
     let fans_output_dir = normalize_optional_input(input.fans_output_dir.as_deref());
 
-    config.receiver_listen_addr = receiver_listen_addr.to_string();
-    config.relay_enabled = input.relay_enabled;
-    config.relay_target_host = relay_target_host;
+    config.receiver_listen_addr = receiver_listen_addr;
+    config.relay_enabled = candidate.relay_enabled;
+    config.relay_target_host = candidate.relay_target_host;
     config.fans_enabled = input.fans_enabled;
     config.fans_output_dir = Some(
         fans_output_dir.unwrap_or_else(|| resolve_default_fans_output_dir().display().to_string()),
@@ -835,15 +1392,13 @@ fn save_config_to_targets(game_dir: &Path, config: &Config) -> Result<(), String
 }
 
 fn validate_game_settings(input: &SaveGameSettingsInput) -> Result<(), String> {
-    if input.target_fps != -1 && input.target_fps <= 0 {
-        return Err("目标 FPS 必须是默认值或正整数".to_string());
-    }
-
-    if !matches!(input.vsync_count, -1 | 0 | 1) {
-        return Err("VSync 取值必须是默认、关闭或开启".to_string());
+    Config {
+        target_fps: input.target_fps,
+        vsync_count: input.vsync_count,
+        ..Config::default()
     }
-
-    Ok(())
+    .validate()
+    .ensure_fields(GAME_SETTINGS_FIELDS)
 }
 
 fn require_valid_game_dir(path: &str) -> Result<PathBuf, String> {
@@ -928,12 +1483,13 @@ fn normalize_optional_input(value: Option<&str>) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_receiver_runtime_fields, backfill_exe_side_receiver_fields,
-        build_receiver_runtime_settings_from_config, select_default_game_dir,
-        SaveReceiverRuntimeSettingsInput,
+        apply_dll_injection_fields, apply_receiver_runtime_fields,
+        build_receiver_runtime_settings_from_config, select_default_game_dir, with_recovery_notice,
+        SaveDllInjectionConfigInput, SaveReceiverRuntimeSettingsInput,
     };
     use guga_ura_config_core::config::Config;
     use guga_ura_config_core::detector::{DetectedGame, GameVersion};
+    use guga_ura_config_core::install_journal::RollbackReport;
     use std::fs;
     use std::thread;
     use std::time::Duration;
@@ -968,56 +1524,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn backfill_exe_side_receiver_fields_should_preserve_exe_stallion_runtime_settings() {
-        let mut game_config = Config {
-            fans_enabled: true,
-            fans_output_dir: Some("C:\\game\\fans".to_string()),
-            stallion_output_enabled: true,
-            stallion_output_dir: Some("C:\\game\\stallion".to_string()),
-            ..Config::default()
-        };
-        let exe_config = Config {
-            fans_enabled: false,
-            fans_output_dir: Some("C:\\exe\\fans".to_string()),
-            stallion_output_enabled: false,
-            stallion_output_dir: Some("C:\\exe\\stallion".to_string()),
-            ..Config::default()
-        };
-
-        backfill_exe_side_receiver_fields(&mut game_config, exe_config, true, true);
-
-        assert!(game_config.fans_enabled);
-        assert_eq!(
-            game_config.fans_output_dir.as_deref(),
-            Some("C:\\game\\fans")
-        );
-        assert!(!game_config.stallion_output_enabled);
-        assert_eq!(
-            game_config.stallion_output_dir.as_deref(),
-            Some("C:\\exe\\stallion")
-        );
-    }
-
-    #[test]
-    fn backfill_exe_side_receiver_fields_should_use_exe_stallion_defaults() {
-        let mut game_config = Config {
-            stallion_output_enabled: true,
-            stallion_output_dir: Some("C:\\game\\stallion".to_string()),
-            ..Config::default()
-        };
-        let exe_config = Config {
-            stallion_output_enabled: false,
-            stallion_output_dir: None,
-            ..Config::default()
-        };
-
-        backfill_exe_side_receiver_fields(&mut game_config, exe_config, true, true);
-
-        assert!(!game_config.stallion_output_enabled);
-        assert_eq!(game_config.stallion_output_dir, None);
-    }
-
     #[test]
     fn apply_receiver_runtime_fields_should_reject_invalid_inputs() {
         let mut config = Config::default();
@@ -1064,6 +1570,36 @@ mod tests {
         assert!(self_loop_error.contains("当前 Receiver 自身"));
     }
 
+    #[test]
+    fn apply_dll_injection_fields_should_ignore_unrelated_invalid_fields() {
+        let mut config = Config {
+            receiver_listen_addr: "not-an-addr".to_string(),
+            ..Config::default()
+        };
+        let zero_timeout = SaveDllInjectionConfigInput {
+            path: String::new(),
+            notifier_host: "http://127.0.0.1:4800".to_string(),
+            timeout_ms: 0,
+            transport_compression: None,
+            shared_secret: None,
+            fans_enabled: None,
+            fans_output_dir: None,
+        };
+        let valid = SaveDllInjectionConfigInput {
+            timeout_ms: 250,
+            ..zero_timeout.clone()
+        };
+
+        let timeout_error =
+            apply_dll_injection_fields(&mut config, &zero_timeout).expect_err("应拒绝 0 超时");
+        assert!(timeout_error.contains("超时"));
+        assert_eq!(config.timeout_ms, Config::default().timeout_ms);
+
+        apply_dll_injection_fields(&mut config, &valid).expect("注入设置保存失败");
+        assert_eq!(config.notifier_host, "http://127.0.0.1:4800");
+        assert_eq!(config.timeout_ms, 250);
+    }
+
     #[test]
     fn build_receiver_runtime_settings_should_fall_back_to_defaults() {
         let config = Config::default();
@@ -1112,4 +1648,22 @@ mod tests {
         assert_eq!(selected, dmm_dir);
         let _ = fs::remove_dir_all(root);
     }
+
+    #[test]
+    fn with_recovery_notice_should_prefix_rollback_result() {
+        let report = RollbackReport {
+            operation: "uninstall".to_string(),
+            undone: 3,
+            errors: Vec::new(),
+        };
+
+        assert_eq!(
+            with_recovery_notice(Some(&report), "安装成功".to_string()),
+            "已回滚上次中断的卸载（撤销 3 步更改）；安装成功"
+        );
+        assert_eq!(
+            with_recovery_notice(None, "安装成功".to_string()),
+            "安装成功"
+        );
+    }
 }
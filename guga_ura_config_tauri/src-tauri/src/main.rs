@@ -29,13 +29,25 @@ fn main() {
             commands::pick_directory,
             commands::get_dll_injection_context,
             commands::get_receiver_runtime_settings,
+            commands::get_capture_health,
             commands::save_dll_injection_config,
             commands::save_receiver_runtime_settings,
             commands::install_dll_injection,
             commands::save_debug_mode,
             commands::uninstall_dll_injection,
+            commands::recover_interrupted_install,
             commands::get_game_settings_context,
             commands::save_game_settings,
+            commands::get_config_profiles,
+            commands::create_config_profile,
+            commands::clone_config_profile,
+            commands::rename_config_profile,
+            commands::delete_config_profile,
+            commands::activate_config_profile,
+            commands::export_config_bundle,
+            commands::preview_config_bundle_import,
+            commands::apply_config_bundle_import,
+            commands::export_support_bundle,
             commands::get_tool_settings_context,
             commands::set_autostart_enabled,
             commands::check_app_update,
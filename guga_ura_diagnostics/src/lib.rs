@@ -0,0 +1,43 @@
+//! GugaURA 诊断日志
+//!
+//! DLL、安装器与两个 Receiver 共用的落盘日志：每行一条 JSON 记录，带可读时间戳、
+//! 级别与组件标签，超过大小上限时按 `name.log.1`、`name.log.2` 依次轮转。
+//! 配置工具可用 [`read_log`] 读回全部轮转文件，汇总为支持包。
+
+pub mod reader;
+pub mod record;
+pub mod writer;
+
+pub use reader::{log_files, read_log};
+pub use record::{Level, LogLine, LogRecord};
+pub use writer::{DiagnosticsLog, Rotation};
+
+use std::path::PathBuf;
+
+/// DLL 运行时日志
+pub const RUNTIME_LOG_FILE_NAME: &str = "guga_ura_runtime.log";
+
+/// 安装 / 卸载日志（位于游戏目录 `guga_ura_data/`）
+pub const INSTALL_LOG_FILE_NAME: &str = "install_trace.log";
+
+/// 配置工具日志（含内置 Receiver）
+pub const TOOL_LOG_FILE_NAME: &str = "guga_ura_tool.log";
+
+/// 独立 Receiver 日志
+pub const RECEIVER_LOG_FILE_NAME: &str = "guga_ura_receiver.log";
+
+/// 各进程共用的日志目录，按优先级排列：
+/// `%LOCALAPPDATA%\GugaURA\logs`、`%TEMP%\GugaURA`
+pub fn shared_log_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local_app_data).join("GugaURA").join("logs"));
+    }
+
+    if let Some(temp_dir) = std::env::var_os("TEMP") {
+        dirs.push(PathBuf::from(temp_dir).join("GugaURA"));
+    }
+
+    dirs
+}
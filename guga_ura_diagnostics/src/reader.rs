@@ -0,0 +1,37 @@
+//! 读回日志
+
+use crate::record::LogLine;
+use crate::writer::rotated_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 扫描历史文件时的上限，避免误配置导致无限循环
+const MAX_ROTATED_FILES: usize = 32;
+
+/// 当前文件及其全部历史文件，按从旧到新排列，只包含存在的文件
+pub fn log_files(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..=MAX_ROTATED_FILES)
+        .map(|index| rotated_path(path, index))
+        .take_while(|rotated| rotated.exists())
+        .collect();
+    files.reverse();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+    files
+}
+
+/// 按时间顺序读取当前文件与历史文件的全部行
+pub fn read_log(path: &Path) -> Result<Vec<LogLine>, String> {
+    let mut lines = Vec::new();
+    for file in log_files(path) {
+        let bytes = fs::read(&file).map_err(|e| format!("读取 {} 失败: {}", file.display(), e))?;
+        lines.extend(
+            String::from_utf8_lossy(&bytes)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(LogLine::parse),
+        );
+    }
+    Ok(lines)
+}
@@ -0,0 +1,95 @@
+//! 日志记录格式
+
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+/// 日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+/// 一条日志记录，落盘为一行 JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// 本地时间，RFC 3339（毫秒精度，带时区偏移）
+    pub ts: String,
+    pub level: Level,
+    /// 组件标签，如 `core`、`installer`、`receiver`
+    pub component: String,
+    pub pid: u32,
+    pub message: String,
+}
+
+impl LogRecord {
+    /// 以当前时间与进程生成记录，消息中的换行替换为空格
+    pub fn now(level: Level, component: &str, message: &str) -> Self {
+        Self {
+            ts: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            level,
+            component: component.to_string(),
+            pid: std::process::id(),
+            message: message.replace(['\r', '\n'], " "),
+        }
+    }
+
+    /// 序列化为一行 JSON（不含换行）
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.message.clone())
+    }
+}
+
+/// 读回的一行：结构化记录，或旧版纯文本日志
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LogLine {
+    Record(LogRecord),
+    Raw(String),
+}
+
+impl LogLine {
+    pub fn parse(line: &str) -> Self {
+        serde_json::from_str(line)
+            .map(Self::Record)
+            .unwrap_or_else(|_| Self::Raw(line.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_should_round_trip_as_single_line() {
+        let record = LogRecord::now(Level::Warn, "core", "first\r\nsecond");
+        let line = record.to_line();
+
+        assert!(!line.contains('\n'));
+        assert!(line.contains(r#""level":"WARN""#));
+        assert_eq!(record.message, "first  second");
+        assert_eq!(LogLine::parse(&line), LogLine::Record(record));
+    }
+
+    #[test]
+    fn legacy_plain_text_should_be_kept_raw() {
+        assert_eq!(
+            LogLine::parse("[1700000000000] [core] init"),
+            LogLine::Raw("[1700000000000] [core] init".to_string())
+        );
+    }
+}
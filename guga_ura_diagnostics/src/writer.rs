@@ -0,0 +1,227 @@
+//! 日志写入与轮转
+
+use crate::record::{Level, LogRecord};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 同一进程内的写入与轮转串行执行
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 轮转策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    /// 单个文件大小上限
+    pub max_bytes: u64,
+    /// 保留的历史文件数（`name.log.1` 为最近一份）
+    pub keep: usize,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            keep: 3,
+        }
+    }
+}
+
+/// 一份诊断日志
+///
+/// 可给出多个候选路径，写入时使用第一个可写的位置。
+#[derive(Debug, Clone)]
+pub struct DiagnosticsLog {
+    candidates: Vec<PathBuf>,
+    rotation: Rotation,
+}
+
+impl DiagnosticsLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_fallbacks(vec![path.into()])
+    }
+
+    /// 按顺序尝试多个候选路径
+    pub fn with_fallbacks(candidates: Vec<PathBuf>) -> Self {
+        Self {
+            candidates,
+            rotation: Rotation::default(),
+        }
+    }
+
+    /// 配置工具与独立 Receiver 的日志：优先写到 [`shared_log_dirs`]，
+    /// 都不可写时退回当前进程 EXE 同级 `logs/`
+    ///
+    /// [`shared_log_dirs`]: crate::shared_log_dirs
+    pub fn for_exe(file_name: &str) -> Self {
+        let mut dirs = crate::shared_log_dirs();
+        if let Ok(mut exe_path) = std::env::current_exe() {
+            exe_path.pop();
+            dirs.push(exe_path.join("logs"));
+        }
+        Self::with_fallbacks(dirs.into_iter().map(|dir| dir.join(file_name)).collect())
+    }
+
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 首选路径
+    pub fn path(&self) -> Option<&Path> {
+        self.candidates.first().map(PathBuf::as_path)
+    }
+
+    pub fn candidates(&self) -> &[PathBuf] {
+        &self.candidates
+    }
+
+    pub fn debug(&self, component: &str, message: &str) {
+        self.write(Level::Debug, component, message);
+    }
+
+    pub fn info(&self, component: &str, message: &str) {
+        self.write(Level::Info, component, message);
+    }
+
+    pub fn warn(&self, component: &str, message: &str) {
+        self.write(Level::Warn, component, message);
+    }
+
+    pub fn error(&self, component: &str, message: &str) {
+        self.write(Level::Error, component, message);
+    }
+
+    /// 追加一条记录；日志只用于排查问题，写入失败时静默忽略
+    pub fn write(&self, level: Level, component: &str, message: &str) {
+        let _ = self.try_write(&LogRecord::now(level, component, message));
+    }
+
+    /// 追加一条记录，返回实际写入的文件
+    pub fn try_write(&self, record: &LogRecord) -> Result<PathBuf, String> {
+        let mut line = record.to_line();
+        line.push('\n');
+
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut last_error = "未配置日志路径".to_string();
+        for path in &self.candidates {
+            match append_line(path, &line, self.rotation) {
+                Ok(()) => return Ok(path.clone()),
+                Err(e) => last_error = format!("写入 {} 失败: {}", path.display(), e),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// 第 `index` 份历史文件路径，如 `guga_ura_runtime.log.1`
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", index));
+    path.with_file_name(name)
+}
+
+fn append_line(path: &Path, line: &str, rotation: Rotation) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    rotate_if_needed(path, line.len() as u64, rotation)?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+fn rotate_if_needed(path: &Path, incoming: u64, rotation: Rotation) -> std::io::Result<()> {
+    let current = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    if current == 0 || current + incoming <= rotation.max_bytes {
+        return Ok(());
+    }
+
+    if rotation.keep == 0 {
+        return fs::remove_file(path);
+    }
+
+    let _ = fs::remove_file(rotated_path(path, rotation.keep));
+    for index in (1..rotation.keep).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, index + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{log_files, read_log};
+    use crate::record::LogLine;
+
+    #[test]
+    fn write_should_rotate_and_keep_limited_history() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join("test.log");
+        let log = DiagnosticsLog::new(&path).with_rotation(Rotation {
+            max_bytes: 300,
+            keep: 2,
+        });
+
+        for index in 0..20 {
+            log.info("test", &format!("line {}", index));
+        }
+
+        assert!(fs::metadata(&path).expect("缺少当前日志").len() <= 300);
+        assert!(rotated_path(&path, 1).exists());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+        assert_eq!(log_files(&path).len(), 3);
+
+        let lines = read_log(&path).expect("读取日志失败");
+        let messages: Vec<_> = lines
+            .iter()
+            .map(|line| match line {
+                LogLine::Record(record) => record.message.as_str(),
+                LogLine::Raw(raw) => raw.as_str(),
+            })
+            .collect();
+        assert_eq!(messages.last(), Some(&"line 19"));
+        assert!(!messages.contains(&"line 0"));
+        assert!(messages.windows(2).all(|pair| {
+            let index = |message: &str| message[5..].parse::<u32>().expect("消息格式错误");
+            index(pair[0]) < index(pair[1])
+        }));
+    }
+
+    #[test]
+    fn write_should_fall_back_to_next_candidate() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let blocker = dir.path().join("blocker");
+        fs::write(&blocker, b"file").expect("写入占位文件失败");
+        let fallback = dir.path().join("logs").join("test.log");
+        let log = DiagnosticsLog::with_fallbacks(vec![blocker.join("test.log"), fallback.clone()]);
+
+        let written = log
+            .try_write(&LogRecord::now(Level::Error, "test", "boom"))
+            .expect("写入日志失败");
+
+        assert_eq!(written, fallback);
+        assert_eq!(read_log(&fallback).expect("读取日志失败").len(), 1);
+    }
+
+    #[test]
+    fn for_exe_should_prefer_shared_dirs_then_exe_logs() {
+        let log = DiagnosticsLog::for_exe("test.log");
+        let mut expected: Vec<PathBuf> = crate::shared_log_dirs()
+            .into_iter()
+            .map(|dir| dir.join("test.log"))
+            .collect();
+        let exe_dir = std::env::current_exe()
+            .expect("获取 EXE 路径失败")
+            .parent()
+            .expect("EXE 应有上级目录")
+            .to_path_buf();
+        expected.push(exe_dir.join("logs").join("test.log"));
+
+        assert_eq!(log.candidates(), expected.as_slice());
+    }
+}
@@ -1,30 +1,19 @@
-use chrono::{Local, TimeZone};
+use guga_ura_config::time::{OutputTimeZone, TimeSettings};
+use guga_ura_config::Config;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-const CONFIG_FILE_NAME: &str = "guga_ura_config.json";
 const DEFAULT_FANS_DIR_NAME: &str = "fans";
 
-fn default_fans_enabled() -> bool {
-    true
-}
-
-#[derive(Debug, Default, Deserialize)]
-struct ReceiverConfig {
-    #[serde(default = "default_fans_enabled")]
-    fans_enabled: bool,
-
-    #[serde(default)]
-    fans_output_dir: Option<String>,
-}
-
 #[derive(Debug, Clone)]
 pub struct FansSettings {
     pub enabled: bool,
     pub output_dir: PathBuf,
+    /// 日期分桶使用的时区（默认本机时区）
+    pub time: TimeSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -48,46 +37,70 @@ pub fn resolve_fans_output_dir_from_exe_config() -> PathBuf {
     resolve_fans_settings_from_exe_config().output_dir
 }
 
+/// 按 EXE 同级生效配置（含激活方案）解析 fans 设置
 pub fn resolve_fans_settings_from_exe_config() -> FansSettings {
-    let exe_dir = exe_dir();
-    let config_path = exe_dir.join(CONFIG_FILE_NAME);
-
-    if let Ok(content) = fs::read_to_string(&config_path) {
-        if let Some(config) = parse_receiver_config(&content) {
-            if let Some(raw) = config.fans_output_dir {
-                let trimmed = raw.trim();
-                if !trimmed.is_empty() {
-                    let path = PathBuf::from(trimmed);
-                    let output_dir = if path.is_absolute() {
-                        path
-                    } else {
-                        exe_dir.join(path)
-                    };
-                    return FansSettings {
-                        enabled: config.fans_enabled,
-                        output_dir,
-                    };
-                }
+    resolve_fans_settings(&Config::load_from_exe_dir())
+}
+
+/// 按给定配置解析 fans 设置
+pub fn resolve_fans_settings(config: &Config) -> FansSettings {
+    let time = TimeSettings::from_config(
+        config.time_output_format.as_deref(),
+        config.time_output_timezone.as_deref(),
+        OutputTimeZone::Local,
+    );
+    let output_dir = config
+        .fans_output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| {
+            let path = PathBuf::from(raw);
+            if path.is_absolute() {
+                path
+            } else {
+                exe_dir().join(path)
             }
-            return FansSettings {
-                enabled: config.fans_enabled,
-                output_dir: default_fans_output_dir(),
-            };
-        }
-    }
+        })
+        .unwrap_or_else(default_fans_output_dir);
 
     FansSettings {
-        enabled: default_fans_enabled(),
-        output_dir: default_fans_output_dir(),
+        enabled: config.fans_enabled,
+        output_dir,
+        time,
     }
 }
 
+/// fans 默认时间设置：按本机时区分桶
+pub fn default_fans_time_settings() -> TimeSettings {
+    TimeSettings::from_config(None, None, OutputTimeZone::Local)
+}
+
 pub fn upsert_fans_from_decoded_payload(
     decoded_payload: &Value,
     direction: &str,
     route: &str,
     received_at_unix_ms: u128,
     fans_output_dir: &Path,
+) -> Result<Option<PathBuf>, String> {
+    upsert_fans_from_decoded_payload_with_time(
+        decoded_payload,
+        direction,
+        route,
+        received_at_unix_ms,
+        fans_output_dir,
+        &default_fans_time_settings(),
+    )
+}
+
+/// 按指定时间设置计算日期分桶后写入 fans 聚合
+pub fn upsert_fans_from_decoded_payload_with_time(
+    decoded_payload: &Value,
+    direction: &str,
+    route: &str,
+    received_at_unix_ms: u128,
+    fans_output_dir: &Path,
+    time: &TimeSettings,
 ) -> Result<Option<PathBuf>, String> {
     if !direction.eq_ignore_ascii_case("response") {
         return Ok(None);
@@ -96,7 +109,7 @@ pub fn upsert_fans_from_decoded_payload(
         return Ok(None);
     }
 
-    let (ts, extracted) = extract_records(decoded_payload, received_at_unix_ms);
+    let (ts, extracted) = extract_records(decoded_payload, received_at_unix_ms, time);
     if extracted.is_empty() {
         return Ok(None);
     }
@@ -125,8 +138,9 @@ pub fn upsert_fans_from_decoded_payload(
 fn extract_records(
     decoded_payload: &Value,
     received_at_unix_ms: u128,
+    time: &TimeSettings,
 ) -> (String, Vec<(String, FanRecord)>) {
-    let ts = yyyymmdd_from_unix_millis(received_at_unix_ms);
+    let ts = time.date_key(u64::try_from(received_at_unix_ms).unwrap_or(u64::MAX));
     let mut out = Vec::new();
 
     let data = decoded_payload.get("data").and_then(Value::as_object);
@@ -321,25 +335,6 @@ fn value_to_u64(value: Option<&Value>) -> Option<u64> {
     }
 }
 
-fn yyyymmdd_from_unix_millis(ms: u128) -> String {
-    let Ok(ms_i64) = i64::try_from(ms) else {
-        return Local::now().format("%Y%m%d").to_string();
-    };
-    match Local.timestamp_millis_opt(ms_i64).single() {
-        Some(dt) => dt.format("%Y%m%d").to_string(),
-        None => Local::now().format("%Y%m%d").to_string(),
-    }
-}
-
-fn parse_receiver_config(content: &str) -> Option<ReceiverConfig> {
-    if let Ok(cfg) = serde_json::from_str::<ReceiverConfig>(content) {
-        return Some(cfg);
-    }
-
-    let trimmed = content.trim_start_matches('\u{feff}');
-    serde_json::from_str::<ReceiverConfig>(trimmed).ok()
-}
-
 fn exe_dir() -> PathBuf {
     if let Ok(mut exe_path) = std::env::current_exe() {
         exe_path.pop();
@@ -362,26 +357,6 @@ pub fn decode_payload(body: &[u8]) -> Result<(&'static str, serde_json::Value),
     }
 }
 
-/// 从路由推断方向（request/response/unknown）
-pub fn infer_direction(route: &str) -> String {
-    let route_lower = route.to_ascii_lowercase();
-    if route_lower.contains("request") {
-        "request".to_string()
-    } else if route_lower.contains("response") {
-        "response".to_string()
-    } else {
-        "unknown".to_string()
-    }
-}
-
-/// 判断是否应该持久化 debug payload
-pub fn should_persist_debug_payload(direction: &str, route: &str) -> bool {
-    if direction.eq_ignore_ascii_case("response") {
-        return true;
-    }
-    route.to_ascii_lowercase().contains("response")
-}
-
 /// 获取当前时间戳（毫秒）
 pub fn now_millis() -> u128 {
     std::time::SystemTime::now()
@@ -393,6 +368,7 @@ pub fn now_millis() -> u128 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use guga_ura_config::time;
     use serde_json::json;
 
     fn build_payload(
@@ -526,6 +502,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn day_file_follows_configured_timezone() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let payload = build_payload(
+            1,
+            "circle-a",
+            &[100],
+            vec![member_detail(100, 1, "circle-a", 10, 20)],
+        );
+        // 2026-04-06T15:30:00Z，JST 已是次日
+        let game_time =
+            time::TimeSettings::new(time::TimeOutputFormat::Rfc3339, time::OutputTimeZone::Game);
+
+        let written = upsert_fans_from_decoded_payload_with_time(
+            &payload,
+            "response",
+            "/notify/response",
+            1_775_489_400_000,
+            dir.path(),
+            &game_time,
+        )
+        .expect("write")
+        .expect("path");
+
+        assert_eq!(written, dir.path().join("20260407.json"));
+        let value: Value =
+            serde_json::from_str(&fs::read_to_string(&written).expect("read")).expect("json");
+        assert_eq!(
+            value
+                .get("100")
+                .and_then(|row| row.get("ts"))
+                .and_then(Value::as_str),
+            Some("20260407")
+        );
+    }
+
     #[test]
     fn supports_multiple_circles_in_one_day_file() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -598,7 +610,8 @@ mod tests {
             ],
         );
 
-        let (_, extracted) = extract_records(&payload, 1_772_641_517_934);
+        let (_, extracted) =
+            extract_records(&payload, 1_772_641_517_934, &default_fans_time_settings());
         let extracted_map: HashMap<_, _> = extracted.into_iter().collect();
 
         assert_eq!(extracted_map.len(), 3);
@@ -633,7 +646,8 @@ mod tests {
     fn current_member_without_summary_detail_is_still_exported() {
         let payload = build_payload(9, "circle-z", &[123], vec![]);
 
-        let (_, extracted) = extract_records(&payload, 1_772_641_517_934);
+        let (_, extracted) =
+            extract_records(&payload, 1_772_641_517_934, &default_fans_time_settings());
         let extracted_map: HashMap<_, _> = extracted.into_iter().collect();
         let record = extracted_map.get("123").expect("member should exist");
 
@@ -699,7 +713,7 @@ mod tests {
     #[test]
     fn parse_receiver_config_with_bom() {
         let json = "\u{feff}{\"fans_enabled\":false,\"fans_output_dir\":\"./my_fans\"}";
-        let cfg = parse_receiver_config(json).expect("parse with bom");
+        let cfg = guga_ura_config::parse_config_json(json).expect("parse with bom");
         assert!(!cfg.fans_enabled);
         assert_eq!(cfg.fans_output_dir.as_deref(), Some("./my_fans"));
     }
@@ -0,0 +1,63 @@
+//! 日志同时输出到终端与诊断日志文件
+//!
+//! 终端输出仍由 env_logger 负责（沿用 `RUST_LOG` 过滤），通过过滤的记录
+//! 另写一份到 `guga_ura_receiver.log`，便于配置工具收集支持包。
+
+use guga_ura_diagnostics::{DiagnosticsLog, Level, RECEIVER_LOG_FILE_NAME};
+use log::{Log, Metadata, Record};
+
+struct TeeLogger {
+    console: env_logger::Logger,
+    file: DiagnosticsLog,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.console.matches(record) {
+            return;
+        }
+        self.console.log(record);
+
+        let component = if record.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            "receiver"
+        } else {
+            record.target()
+        };
+        self.file.write(
+            map_level(record.level()),
+            component,
+            &record.args().to_string(),
+        );
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+    }
+}
+
+/// 安装全局日志器
+pub fn init() {
+    let console =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).build();
+    let max_level = console.filter();
+    let logger = TeeLogger {
+        console,
+        file: DiagnosticsLog::for_exe(RECEIVER_LOG_FILE_NAME),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+fn map_level(level: log::Level) -> Level {
+    match level {
+        log::Level::Error => Level::Error,
+        log::Level::Warn => Level::Warn,
+        log::Level::Info => Level::Info,
+        log::Level::Debug | log::Level::Trace => Level::Debug,
+    }
+}
@@ -3,27 +3,39 @@ use axum::{
     extract::{Path, State},
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::post,
-    Router,
+    routing::{get, post},
+    Json, Router,
 };
 use clap::Parser;
+use guga_ura_config::validation::RECEIVER_RUNTIME_FIELDS;
+use guga_ura_config::Config;
+use guga_ura_config_core::capture_health::{self, CaptureHealthView, STATUS_ROUTE};
 use guga_ura_config_core::receiver;
 use guga_ura_config_core::receiver_pipeline::{
     self, ReceiverHeader, ReceiverProcessOutcome, RelayOutcome,
 };
+use guga_ura_config_core::receiver_settings::{self, ReceiverOverrides};
 use log::{error, info, warn};
 use serde_json::json;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::path::{Path as FsPath, PathBuf};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::net::TcpListener;
 
+mod file_log;
+
 #[derive(Debug, Parser)]
 #[command(name = "guga_ura_receiver")]
 #[command(about = "Receive msgpack payloads from local plugins and save as JSON")]
+#[command(
+    after_help = "shared_secret has no command-line flag so it never shows up in the process list; set GUGAURA_SHARED_SECRET or shared_secret in guga_ura_config.json next to the executable."
+)]
 struct Cli {
+    #[arg(long)]
+    listen_addr: Option<String>,
+
     #[arg(long)]
     host: Option<String>,
 
@@ -32,6 +44,36 @@ struct Cli {
 
     #[arg(long)]
     output_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "BOOL")]
+    relay_enabled: Option<String>,
+
+    #[arg(long)]
+    relay_target_host: Option<String>,
+
+    #[arg(long)]
+    timeout_ms: Option<String>,
+
+    #[arg(long, value_name = "BOOL")]
+    fans_enabled: Option<String>,
+
+    #[arg(long)]
+    fans_output_dir: Option<String>,
+
+    #[arg(long, value_name = "BOOL")]
+    stallion_output_enabled: Option<String>,
+
+    #[arg(long)]
+    stallion_output_dir: Option<String>,
+
+    #[arg(long)]
+    stallion_output_schema_version: Option<String>,
+
+    #[arg(long)]
+    time_output_format: Option<String>,
+
+    #[arg(long)]
+    time_output_timezone: Option<String>,
 }
 
 #[derive(Clone)]
@@ -43,17 +85,15 @@ struct AppState {
 
 #[tokio::main]
 async fn main() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    file_log::init();
 
     let cli = Cli::parse();
-    let base_resolution = receiver::resolve_receiver_listen_addr(None);
-    let cli_listen_addr = build_cli_listen_addr(&cli, &base_resolution.listen_addr);
-    let listen_resolution = cli_listen_addr
-        .as_deref()
-        .map(|listen_addr| receiver::resolve_receiver_listen_addr(Some(listen_addr)))
-        .unwrap_or(base_resolution);
-
-    let output_dir = cli.output_dir.unwrap_or_else(default_output_dir);
+    receiver_settings::set_cli_overrides(build_cli_overrides(&cli));
+    let settings = receiver_settings::resolve_receiver_settings();
+    let listen_addr = settings.config.receiver_listen_addr.clone();
+
+    let (output_dir, output_dir_source) =
+        receiver_settings::resolve_receiver_output_dir(cli.output_dir.as_deref());
     if let Err(e) = fs::create_dir_all(&output_dir) {
         error!(
             "Failed to create output dir {}: {}",
@@ -63,12 +103,9 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let (listen_host, listen_port) =
-        receiver::parse_receiver_listen_addr(&listen_resolution.listen_addr).unwrap_or_else(|| {
-            error!(
-                "Failed to parse resolved listen addr {}",
-                listen_resolution.listen_addr
-            );
+    let (listen_host, listen_port) = receiver::parse_receiver_listen_addr(&listen_addr)
+        .unwrap_or_else(|| {
+            error!("Failed to parse resolved listen addr {}", listen_addr);
             std::process::exit(1);
         });
     let ip = parse_ip(&listen_host);
@@ -77,25 +114,51 @@ async fn main() {
     let state = AppState {
         output_dir: Arc::new(output_dir.clone()),
         seq: Arc::new(AtomicU64::new(0)),
-        self_listen_addr: Arc::new(listen_resolution.listen_addr.clone()),
+        self_listen_addr: Arc::new(listen_addr.clone()),
     };
 
     let app = Router::new()
-        .route("/notify/request", post(handle_notify_request))
-        .route("/notify/response", post(handle_notify_response))
         .route("/", post(handle_root))
+        .route(STATUS_ROUTE, get(handle_status).post(handle_status_report))
         .route("/{*path}", post(handle_any))
         .with_state(state);
 
     info!("Receiver listening on http://{}", addr);
     info!(
-        "Receiver listen addr source: {} (configured={})",
-        listen_resolution.source.as_str(),
-        listen_resolution.configured_listen_addr
+        "Debug output dir: {} (source={})",
+        output_dir.display(),
+        output_dir_source.as_str()
     );
-    info!("Debug output dir: {}", output_dir.display());
-    let fans_settings = guga_ura_fans::resolve_fans_settings_from_exe_config();
-    info!("Fans aggregate enabled: {}", fans_settings.enabled);
+    let exe_config_path = Config::exe_config_path();
+    info!(
+        "Exe config: {} (exists={})",
+        exe_config_path.display(),
+        exe_config_path.exists()
+    );
+    for report in &settings.fields {
+        info!(
+            "Setting {} = {} (source={})",
+            report.field,
+            report.value,
+            report.source.as_str()
+        );
+    }
+    for rejected in &settings.rejected {
+        warn!("Ignored invalid override {}", rejected);
+    }
+    for issue in settings
+        .config
+        .validate()
+        .only_fields(RECEIVER_RUNTIME_FIELDS)
+        .issues
+    {
+        if issue.is_error() {
+            error!("Receiver config invalid field {}", issue);
+        } else {
+            warn!("Receiver config suspicious field {}", issue);
+        }
+    }
+    let fans_settings = guga_ura_fans::resolve_fans_settings(&settings.config);
     info!("Fans output dir: {}", fans_settings.output_dir.display());
 
     let listener = match TcpListener::bind(addr).await {
@@ -111,28 +174,40 @@ async fn main() {
     }
 }
 
-async fn handle_notify_request(
+async fn handle_root(
     State(state): State<AppState>,
     headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
-    handle_payload(state, headers, body, "/notify/request", Some("request"))
+    handle_payload(state, headers, body, "/")
 }
 
-async fn handle_notify_response(
-    State(state): State<AppState>,
+/// 配置了签名密钥时，查询状态同样需要签名（请求体为空）
+async fn handle_status(
     headers: HeaderMap,
-    body: Bytes,
-) -> impl IntoResponse {
-    handle_payload(state, headers, body, "/notify/response", Some("response"))
+) -> Result<Json<CaptureHealthView>, (StatusCode, String)> {
+    let config = receiver_settings::load_receiver_config();
+    let relay_headers = headers_to_relay_headers(&headers);
+    if let Err(e) = receiver_pipeline::verify_receiver_request(
+        &config,
+        "GET",
+        STATUS_ROUTE,
+        &[],
+        &relay_headers,
+    ) {
+        warn!("Rejected status query: {}", e.message());
+        let status = StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::UNAUTHORIZED);
+        return Err((status, e.message()));
+    }
+    Ok(Json(capture_health::capture_health_view()))
 }
 
-async fn handle_root(
+async fn handle_status_report(
     State(state): State<AppState>,
     headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
-    handle_payload(state, headers, body, "/", None)
+    handle_payload(state, headers, body, STATUS_ROUTE)
 }
 
 async fn handle_any(
@@ -142,7 +217,7 @@ async fn handle_any(
     body: Bytes,
 ) -> impl IntoResponse {
     let route = format!("/{}", path);
-    handle_payload(state, headers, body, &route, None)
+    handle_payload(state, headers, body, &route)
 }
 
 fn handle_payload(
@@ -150,18 +225,45 @@ fn handle_payload(
     headers: HeaderMap,
     body: Bytes,
     route: &str,
-    fixed_direction: Option<&str>,
 ) -> (StatusCode, String) {
     if body.is_empty() {
         return (StatusCode::BAD_REQUEST, "Empty request body".to_string());
     }
 
     let relay_headers = headers_to_relay_headers(&headers);
-    let direction = fixed_direction
-        .map(std::string::ToString::to_string)
-        .unwrap_or_else(|| guga_ura_fans::infer_direction(route));
+    let config = receiver_settings::load_receiver_config();
+
+    // 配置了签名密钥时拒绝未签名、签名错误或过期的请求，也不再转发
+    if let Err(e) =
+        receiver_pipeline::verify_receiver_request(&config, "POST", route, &body, &relay_headers)
+    {
+        warn!("Rejected request on route {}: {}", route, e.message());
+        let status = StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::UNAUTHORIZED);
+        return (status, e.message());
+    }
 
-    let response = match save_payload_as_json(&state, route, &direction, &headers, &body) {
+    // 按 Content-Encoding 解压；relay 仍透传原始请求体
+    let payload_body = match receiver_pipeline::decode_receiver_body(&body, &relay_headers) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            warn!("Decode body failed on route {}: {}", route, e.message());
+            let status = StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+            return (status, e.message().to_string());
+        }
+    };
+
+    // DLL 心跳只更新最近状态，不落盘也不转发
+    if capture_health::is_status_route(route) {
+        return match capture_health::record_capture_health(&payload_body) {
+            Ok(()) => (StatusCode::OK, "status recorded".to_string()),
+            Err(e) => {
+                warn!("Invalid status report: {}", e);
+                (StatusCode::BAD_REQUEST, e)
+            }
+        };
+    }
+
+    let response = match save_payload_as_json(&state, &config, route, &headers, &payload_body) {
         Ok(Some(file_path)) => (StatusCode::OK, format!("saved: {}", file_path.display())),
         Ok(None) => (StatusCode::OK, "ignored: non-response payload".to_string()),
         Err(e) => {
@@ -172,6 +274,7 @@ fn handle_payload(
 
     log_relay_outcome(
         receiver_pipeline::relay_receiver_payload(
+            &config,
             &state.self_listen_addr,
             route,
             &body,
@@ -185,8 +288,8 @@ fn handle_payload(
 
 fn save_payload_as_json(
     state: &AppState,
+    config: &Config,
     route: &str,
-    direction: &str,
     headers: &HeaderMap,
     body: &[u8],
 ) -> Result<Option<PathBuf>, String> {
@@ -194,9 +297,9 @@ fn save_payload_as_json(
     let content_type = header_value(headers, "content-type").unwrap_or("unknown");
 
     match receiver_pipeline::prepare_receiver_payload(
+        config,
         state.output_dir.as_ref(),
         route,
-        Some(direction),
         body,
         || state.seq.fetch_add(1, Ordering::Relaxed),
     )? {
@@ -222,18 +325,26 @@ fn save_payload_as_json(
                 }
             }
 
-            let wrapped = json!({
-                "direction": prepared.direction,
-                "route": prepared.route,
-                "received_at_unix_ms": prepared.now_ms,
-                "payload_size": body.len(),
-                "decoded_as": prepared.decoded_as,
-                "source": {
+            // 自定义提取规则日志
+            for outcome in &prepared.extractor_outcomes {
+                for path in &outcome.output_paths {
+                    info!("Extractor rule {} output: {}", outcome.rule, path.display());
+                }
+                if let Some(error) = outcome.error.as_ref() {
+                    warn!(
+                        "Extractor rule {} failed on route {}: {}",
+                        outcome.rule, route, error
+                    );
+                }
+            }
+
+            let wrapped = prepared.payload_file(body.len()).with_extra(
+                "source",
+                json!({
                     "plugin": plugin,
                     "content_type": content_type
-                },
-                "payload": prepared.payload
-            });
+                }),
+            );
 
             receiver_pipeline::write_receiver_payload_json(&prepared.file_path, &wrapped)?;
 
@@ -242,7 +353,7 @@ fn save_payload_as_json(
                 body.len(),
                 route,
                 prepared.file_path.display(),
-                wrapped["decoded_as"].as_str().unwrap_or("unknown")
+                wrapped.decoded_as
             );
 
             Ok(Some(prepared.file_path))
@@ -266,14 +377,6 @@ fn headers_to_relay_headers(headers: &HeaderMap) -> Vec<ReceiverHeader> {
         .collect()
 }
 
-fn default_output_dir() -> PathBuf {
-    if let Ok(mut exe_path) = std::env::current_exe() {
-        exe_path.pop();
-        return exe_path.join("debug");
-    }
-    FsPath::new(".").join("debug")
-}
-
 fn parse_ip(host: &str) -> IpAddr {
     if host.eq_ignore_ascii_case("localhost") {
         return IpAddr::V4(Ipv4Addr::LOCALHOST);
@@ -284,6 +387,44 @@ fn parse_ip(host: &str) -> IpAddr {
     })
 }
 
+fn build_cli_overrides(cli: &Cli) -> ReceiverOverrides {
+    let base_listen_addr = receiver::resolve_receiver_listen_addr(None).listen_addr;
+    let listen_addr = cli
+        .listen_addr
+        .clone()
+        .or_else(|| build_cli_listen_addr(cli, &base_listen_addr));
+
+    let values = [
+        ("receiver_listen_addr", listen_addr),
+        ("relay_enabled", cli.relay_enabled.clone()),
+        ("relay_target_host", cli.relay_target_host.clone()),
+        ("timeout_ms", cli.timeout_ms.clone()),
+        ("fans_enabled", cli.fans_enabled.clone()),
+        ("fans_output_dir", cli.fans_output_dir.clone()),
+        (
+            "stallion_output_enabled",
+            cli.stallion_output_enabled.clone(),
+        ),
+        ("stallion_output_dir", cli.stallion_output_dir.clone()),
+        (
+            "stallion_output_schema_version",
+            cli.stallion_output_schema_version.clone(),
+        ),
+        ("time_output_format", cli.time_output_format.clone()),
+        ("time_output_timezone", cli.time_output_timezone.clone()),
+    ];
+
+    let mut overrides = ReceiverOverrides::default();
+    for (field, value) in values {
+        if let Some(value) = value {
+            if let Err(e) = overrides.set(field, value) {
+                warn!("Ignored CLI override {}: {}", field, e);
+            }
+        }
+    }
+    overrides
+}
+
 fn build_cli_listen_addr(cli: &Cli, base_listen_addr: &str) -> Option<String> {
     if cli.host.is_none() && cli.port.is_none() {
         return None;
@@ -0,0 +1,258 @@
+//! 符号映射缓存
+//!
+//! 解析出的 `il2cpp_* -> 混淆名` 映射写入 `guga_ura_data/guga_ura_symbol_cache.json`，
+//! 以 UnityPlayer.dll 的大小与 SHA-256 作为键。下次启动时指纹一致且覆盖全部
+//! 所需符号才直接使用，否则回落到重新扫描并覆盖缓存。
+
+use crate::scan::SymbolTable;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 缓存文件名（位于 `guga_ura_data/`）
+pub const SYMBOL_CACHE_FILE_NAME: &str = "guga_ura_symbol_cache.json";
+
+/// 缓存格式版本；扫描规则变化导致旧缓存不可信时递增
+pub const SYMBOL_CACHE_VERSION: u32 = 1;
+
+/// DLL 文件指纹
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DllFingerprint {
+    pub size: u64,
+    /// 小写十六进制 SHA-256
+    pub sha256: String,
+}
+
+/// 缓存内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolCache {
+    pub version: u32,
+    pub fingerprint: DllFingerprint,
+    /// 映射来源，例如 `scan` 或 `legacy`
+    pub source: String,
+    pub start_rva: u32,
+    pub first_stride: u32,
+    pub stride: u32,
+    pub created_at_unix_ms: u64,
+    /// `il2cpp_*` 名称到混淆导出名
+    pub symbols: BTreeMap<String, String>,
+}
+
+/// 查询缓存的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolCacheLookup {
+    Hit(SymbolCache),
+    /// 未命中及原因（缓存不存在、损坏、指纹不一致等）
+    Miss(String),
+}
+
+impl DllFingerprint {
+    /// 计算文件字节的指纹
+    pub fn of(bytes: &[u8]) -> Self {
+        let digest = Sha256::digest(bytes);
+        Self {
+            size: bytes.len() as u64,
+            sha256: digest.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        }
+    }
+}
+
+impl SymbolCache {
+    /// 由扫描结果生成缓存；`symbol_list` 与表项一一对应
+    pub fn from_table(
+        fingerprint: DllFingerprint,
+        source: &str,
+        symbol_list: &[&str],
+        table: &SymbolTable,
+    ) -> Self {
+        Self {
+            version: SYMBOL_CACHE_VERSION,
+            fingerprint,
+            source: source.to_string(),
+            start_rva: table.start_rva,
+            first_stride: table.first_stride,
+            stride: table.stride,
+            created_at_unix_ms: now_unix_ms(),
+            symbols: symbol_list
+                .iter()
+                .zip(&table.names)
+                .map(|(symbol, name)| (symbol.to_string(), name.clone()))
+                .collect(),
+        }
+    }
+
+    /// 缓存不可用的原因；可用时返回 `None`
+    pub fn mismatch(&self, fingerprint: &DllFingerprint, symbol_list: &[&str]) -> Option<String> {
+        if self.version != SYMBOL_CACHE_VERSION {
+            return Some(format!(
+                "cache version {} != {}",
+                self.version, SYMBOL_CACHE_VERSION
+            ));
+        }
+        if self.fingerprint.size != fingerprint.size {
+            return Some(format!(
+                "UnityPlayer.dll size changed ({} -> {})",
+                self.fingerprint.size, fingerprint.size
+            ));
+        }
+        if self.fingerprint.sha256 != fingerprint.sha256 {
+            return Some("UnityPlayer.dll hash changed".to_string());
+        }
+        if let Some(symbol) = symbol_list
+            .iter()
+            .find(|symbol| !self.symbols.contains_key(**symbol))
+        {
+            return Some(format!("cache is missing symbol {}", symbol));
+        }
+        None
+    }
+
+    /// 读取并校验缓存
+    pub fn lookup(
+        path: &Path,
+        fingerprint: &DllFingerprint,
+        symbol_list: &[&str],
+    ) -> SymbolCacheLookup {
+        let cache = match Self::load(path) {
+            Ok(Some(cache)) => cache,
+            Ok(None) => return SymbolCacheLookup::Miss("no cache file".to_string()),
+            Err(e) => return SymbolCacheLookup::Miss(e),
+        };
+
+        match cache.mismatch(fingerprint, symbol_list) {
+            Some(reason) => SymbolCacheLookup::Miss(reason),
+            None => SymbolCacheLookup::Hit(cache),
+        }
+    }
+
+    /// 读取缓存文件；文件不存在时返回 `Ok(None)`
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// 写入缓存文件（目录不存在时创建）
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize symbol cache: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYMBOLS: &[&str] = &["il2cpp_init", "il2cpp_domain_get", "il2cpp_string_new"];
+
+    fn table() -> SymbolTable {
+        SymbolTable {
+            start_rva: 0x782c92,
+            first_stride: 0x28,
+            stride: 0x26,
+            names: vec![
+                "Abc01".to_string(),
+                "Def02".to_string(),
+                "Ghi03".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn fingerprint_should_hash_bytes_and_size() {
+        let fingerprint = DllFingerprint::of(b"abc");
+
+        assert_eq!(fingerprint.size, 3);
+        assert_eq!(
+            fingerprint.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(DllFingerprint::of(b"abd"), fingerprint);
+    }
+
+    #[test]
+    fn cache_should_round_trip_and_hit_for_same_dll() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir
+            .path()
+            .join("guga_ura_data")
+            .join(SYMBOL_CACHE_FILE_NAME);
+        let fingerprint = DllFingerprint::of(b"UnityPlayer");
+
+        assert!(matches!(
+            SymbolCache::lookup(&path, &fingerprint, SYMBOLS),
+            SymbolCacheLookup::Miss(_)
+        ));
+
+        let cache = SymbolCache::from_table(fingerprint.clone(), "scan", SYMBOLS, &table());
+        cache.save(&path).expect("写入缓存失败");
+
+        assert_eq!(
+            SymbolCache::lookup(&path, &fingerprint, SYMBOLS),
+            SymbolCacheLookup::Hit(cache.clone())
+        );
+        assert_eq!(cache.symbols["il2cpp_domain_get"], "Def02");
+    }
+
+    #[test]
+    fn cache_should_miss_when_dll_or_symbols_change() {
+        let fingerprint = DllFingerprint::of(b"UnityPlayer");
+        let cache = SymbolCache::from_table(fingerprint.clone(), "scan", SYMBOLS, &table());
+
+        let resized = DllFingerprint {
+            size: fingerprint.size + 1,
+            ..fingerprint.clone()
+        };
+        assert!(cache
+            .mismatch(&resized, SYMBOLS)
+            .expect("大小变化应失效")
+            .contains("size"));
+
+        let rehashed = DllFingerprint {
+            sha256: "00".repeat(32),
+            ..fingerprint.clone()
+        };
+        assert!(cache
+            .mismatch(&rehashed, SYMBOLS)
+            .expect("哈希变化应失效")
+            .contains("hash"));
+
+        let more_symbols = [SYMBOLS, &["il2cpp_free"]].concat();
+        assert!(cache.mismatch(&fingerprint, &more_symbols).is_some());
+        assert!(cache.mismatch(&fingerprint, &SYMBOLS[..2]).is_none());
+    }
+
+    #[test]
+    fn broken_cache_should_miss_with_reason() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join(SYMBOL_CACHE_FILE_NAME);
+        fs::write(&path, "{ broken").expect("写入文件失败");
+
+        match SymbolCache::lookup(&path, &DllFingerprint::of(b""), SYMBOLS) {
+            SymbolCacheLookup::Miss(reason) => assert!(reason.contains("parse"), "{}", reason),
+            SymbolCacheLookup::Hit(_) => panic!("损坏的缓存不应命中"),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! 符号映射清单
+//!
+//! 把当前使用的映射与未能解析的符号写成纯文本，写入
+//! `guga_ura_data/guga_ura_symbol_map.txt`，便于用户反馈问题时直接附上。
+
+use crate::cache::DllFingerprint;
+use std::fmt::Write;
+
+/// 清单文件名（位于 `guga_ura_data/`）
+pub const SYMBOL_DUMP_FILE_NAME: &str = "guga_ura_symbol_map.txt";
+
+/// 单个符号的解析结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolResolution {
+    /// 已在 GameAssembly.dll 中找到地址
+    Address(usize),
+    /// 映射中有混淆名，但尚未查询地址
+    Mapped,
+    /// 映射中有混淆名，但 GameAssembly.dll 中找不到
+    NotExported,
+    /// 映射中没有该符号
+    NotInMap,
+}
+
+/// 清单中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDumpEntry<'a> {
+    pub symbol: &'a str,
+    pub mangled: Option<&'a str>,
+    pub resolution: SymbolResolution,
+}
+
+impl SymbolResolution {
+    fn is_unresolved(self) -> bool {
+        matches!(self, Self::NotExported | Self::NotInMap)
+    }
+}
+
+/// 生成清单文本；`source` 说明映射来源，`error` 为整体解析失败时的原因
+pub fn render_symbol_dump(
+    fingerprint: Option<&DllFingerprint>,
+    source: &str,
+    error: Option<&str>,
+    entries: &[SymbolDumpEntry<'_>],
+) -> String {
+    let unresolved: Vec<&SymbolDumpEntry<'_>> = entries
+        .iter()
+        .filter(|entry| entry.resolution.is_unresolved())
+        .collect();
+
+    let mut text = String::new();
+    let _ = writeln!(text, "# GugaURA il2cpp symbol map");
+    match fingerprint {
+        Some(fingerprint) => {
+            let _ = writeln!(
+                text,
+                "# UnityPlayer.dll size={} sha256={}",
+                fingerprint.size, fingerprint.sha256
+            );
+        }
+        None => {
+            let _ = writeln!(text, "# UnityPlayer.dll unavailable");
+        }
+    }
+    let _ = writeln!(text, "# source: {}", source);
+    if let Some(error) = error {
+        let _ = writeln!(text, "# error: {}", error);
+    }
+    let _ = writeln!(
+        text,
+        "# resolved {}/{}, unresolved {}",
+        entries.len() - unresolved.len(),
+        entries.len(),
+        unresolved.len()
+    );
+
+    let width = entries
+        .iter()
+        .map(|entry| entry.symbol.len())
+        .max()
+        .unwrap_or(0);
+    let _ = writeln!(text);
+    for entry in entries
+        .iter()
+        .filter(|entry| !entry.resolution.is_unresolved())
+    {
+        let _ = write!(
+            text,
+            "{:width$} = {}",
+            entry.symbol,
+            entry.mangled.unwrap_or("-"),
+            width = width
+        );
+        if let SymbolResolution::Address(address) = entry.resolution {
+            let _ = write!(text, " @ 0x{:X}", address);
+        }
+        let _ = writeln!(text);
+    }
+
+    if !unresolved.is_empty() {
+        let _ = writeln!(text, "\n# unresolved");
+        for entry in unresolved {
+            let reason = match entry.resolution {
+                SymbolResolution::NotExported => "not exported by GameAssembly.dll",
+                _ => "not in map",
+            };
+            let _ = writeln!(
+                text,
+                "{:width$} = {} ({})",
+                entry.symbol,
+                entry.mangled.unwrap_or("-"),
+                reason,
+                width = width
+            );
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_should_list_resolved_and_unresolved_symbols() {
+        let fingerprint = DllFingerprint::of(b"UnityPlayer");
+        let entries = [
+            SymbolDumpEntry {
+                symbol: "il2cpp_init",
+                mangled: Some("Abc01"),
+                resolution: SymbolResolution::Address(0x7FF0_1234),
+            },
+            SymbolDumpEntry {
+                symbol: "il2cpp_free",
+                mangled: Some("Def02"),
+                resolution: SymbolResolution::NotExported,
+            },
+            SymbolDumpEntry {
+                symbol: "il2cpp_alloc",
+                mangled: None,
+                resolution: SymbolResolution::NotInMap,
+            },
+        ];
+
+        let text = render_symbol_dump(Some(&fingerprint), "cache", None, &entries);
+
+        assert!(text.contains(&fingerprint.sha256));
+        assert!(text.contains("# source: cache"));
+        assert!(text.contains("# resolved 1/3, unresolved 2"));
+        assert!(text.contains("il2cpp_init  = Abc01 @ 0x7FF01234"));
+        let unresolved = text.split("# unresolved").nth(1).expect("应有未解析段");
+        assert!(unresolved.contains("il2cpp_free  = Def02 (not exported by GameAssembly.dll)"));
+        assert!(unresolved.contains("il2cpp_alloc = - (not in map)"));
+    }
+
+    #[test]
+    fn dump_should_record_failure_reason() {
+        let entries = [SymbolDumpEntry {
+            symbol: "il2cpp_init",
+            mangled: None,
+            resolution: SymbolResolution::NotInMap,
+        }];
+
+        let text = render_symbol_dump(None, "none", Some("Symbol table not found"), &entries);
+
+        assert!(text.contains("# UnityPlayer.dll unavailable"));
+        assert!(text.contains("# error: Symbol table not found"));
+        assert!(text.contains("# resolved 0/1, unresolved 1"));
+    }
+}
@@ -0,0 +1,460 @@
+//! Hook 目标清单
+//!
+//! 需要拦截的游戏方法以清单描述：程序集、命名空间、类、方法名、参数个数与用途，
+//! 每个用途可列出多个候选，按顺序尝试，第一个能找到的生效。清单内置于 DLL，
+//! 也可以在 `guga_ura_data/guga_ura_hooks.json` 放置覆盖文件，游戏更新改名时
+//! 无需重新编译 DLL。
+//!
+//! 每个 Hook 属于一个捕获通道（默认 `http`），拦截到的数据按
+//! `/notify/{channel}/{direction}` 发送，见 [`guga_ura_transport::capture`]。
+
+use guga_ura_transport::capture::{self, CaptureDirection, DEFAULT_CAPTURE_CHANNEL};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 覆盖清单文件名（位于 `guga_ura_data/`）
+pub const HOOK_MANIFEST_FILE_NAME: &str = "guga_ura_hooks.json";
+
+/// 当前支持的清单版本
+pub const HOOK_MANIFEST_VERSION: u32 = 1;
+
+/// 内置清单
+pub const EMBEDDED_HOOK_MANIFEST: &str = include_str!("default_hooks.json");
+
+/// Hook 转发的寄存器参数个数上限（x64 调用约定前 4 个整数参数走寄存器，
+/// 其中最后一个留给 IL2CPP 的 `MethodInfo*`）
+pub const MAX_HOOK_ARITY: u8 = 3;
+
+/// 清单最多声明的 Hook 数（DLL 中预留的跳板数量）
+pub const MAX_HOOK_COUNT: usize = 8;
+
+/// Hook 用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookRole {
+    /// 调用原方法前读取参数中的请求明文
+    RequestPayload,
+    /// 调用原方法后读取返回值中的响应明文
+    ResponsePayload,
+}
+
+/// 一个候选方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookTarget {
+    pub assembly: String,
+    pub namespace: String,
+    pub class: String,
+    pub method: String,
+    /// 方法参数个数（不含 `MethodInfo*`），仅支持静态方法
+    pub arity: u8,
+    /// 请求明文所在参数的下标，仅 `request_payload` 使用
+    #[serde(default)]
+    pub payload_arg: u8,
+}
+
+/// 一个用途及其候选
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookSpec {
+    /// 捕获通道名，省略时为 `http`
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    pub role: HookRole,
+    pub candidates: Vec<HookTarget>,
+}
+
+/// Hook 清单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookManifest {
+    pub version: u32,
+    pub hooks: Vec<HookSpec>,
+}
+
+/// 清单来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookManifestSource {
+    Embedded,
+    Override(PathBuf),
+}
+
+/// 读取清单的结果；覆盖文件无效时回落内置清单并给出原因
+#[derive(Debug, Clone)]
+pub struct HookManifestLoad {
+    pub manifest: HookManifest,
+    pub source: HookManifestSource,
+    pub warning: Option<String>,
+}
+
+/// 按候选顺序解析后的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedHook<'a, T> {
+    pub channel: &'a str,
+    pub role: HookRole,
+    pub target: &'a HookTarget,
+    /// 生效候选在列表中的位置（0 为首选）
+    pub candidate_index: usize,
+    pub value: T,
+}
+
+impl HookRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RequestPayload => "request_payload",
+            Self::ResponsePayload => "response_payload",
+        }
+    }
+
+    /// 对应的捕获方向
+    pub fn direction(self) -> CaptureDirection {
+        match self {
+            Self::RequestPayload => CaptureDirection::Request,
+            Self::ResponsePayload => CaptureDirection::Response,
+        }
+    }
+}
+
+impl HookSpec {
+    /// 日志中使用的名称，例如 `http/request_payload`
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.channel, self.role.as_str())
+    }
+}
+
+fn default_channel() -> String {
+    DEFAULT_CAPTURE_CHANNEL.to_string()
+}
+
+impl std::fmt::Display for HookTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}.{}::{}/{}",
+            self.assembly, self.namespace, self.class, self.method, self.arity
+        )
+    }
+}
+
+impl HookManifest {
+    /// 解析并校验清单
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let manifest: Self = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+            .map_err(|e| format!("Invalid hook manifest: {}", e))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// 内置清单
+    pub fn embedded() -> Self {
+        Self::parse(EMBEDDED_HOOK_MANIFEST).expect("内置 Hook 清单必须有效")
+    }
+
+    /// 读取清单：覆盖文件存在且有效时使用，否则使用内置清单
+    pub fn load(override_path: &Path) -> HookManifestLoad {
+        let embedded = |warning| HookManifestLoad {
+            manifest: Self::embedded(),
+            source: HookManifestSource::Embedded,
+            warning,
+        };
+
+        if !override_path.exists() {
+            return embedded(None);
+        }
+
+        let parsed = fs::read_to_string(override_path)
+            .map_err(|e| format!("Failed to read {}: {}", override_path.display(), e))
+            .and_then(|content| Self::parse(&content));
+        match parsed {
+            Ok(manifest) => HookManifestLoad {
+                manifest,
+                source: HookManifestSource::Override(override_path.to_path_buf()),
+                warning: None,
+            },
+            Err(e) => embedded(Some(format!(
+                "{} ignored, using embedded manifest: {}",
+                override_path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.version != HOOK_MANIFEST_VERSION {
+            return Err(format!(
+                "Unsupported hook manifest version {} (expected {})",
+                self.version, HOOK_MANIFEST_VERSION
+            ));
+        }
+        if self.hooks.is_empty() {
+            return Err("Hook manifest has no hooks".to_string());
+        }
+        if self.hooks.len() > MAX_HOOK_COUNT {
+            return Err(format!(
+                "Hook manifest declares {} hooks, at most {} are supported",
+                self.hooks.len(),
+                MAX_HOOK_COUNT
+            ));
+        }
+
+        for (index, hook) in self.hooks.iter().enumerate() {
+            if !capture::is_valid_channel_name(&hook.channel) {
+                return Err(format!(
+                    "Invalid hook channel '{}' (use lowercase letters, digits, '_' or '-')",
+                    hook.channel
+                ));
+            }
+            if self.hooks[..index]
+                .iter()
+                .any(|other| other.channel == hook.channel && other.role == hook.role)
+            {
+                return Err(format!("Duplicate hook {}", hook.label()));
+            }
+            if hook.candidates.is_empty() {
+                return Err(format!("Hook {} has no candidates", hook.label()));
+            }
+            for target in &hook.candidates {
+                validate_target(hook, target)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_target(hook: &HookSpec, target: &HookTarget) -> Result<(), String> {
+    let label = hook.label();
+    let fields = [
+        ("assembly", &target.assembly),
+        ("class", &target.class),
+        ("method", &target.method),
+    ];
+    if let Some((field, _)) = fields.iter().find(|(_, value)| value.trim().is_empty()) {
+        return Err(format!("Hook {} candidate has empty {}", label, field));
+    }
+    if target.arity > MAX_HOOK_ARITY {
+        return Err(format!(
+            "Hook {} candidate {} has arity {}, at most {} is supported",
+            label, target, target.arity, MAX_HOOK_ARITY
+        ));
+    }
+    if hook.role == HookRole::RequestPayload && target.payload_arg >= target.arity {
+        return Err(format!(
+            "Hook {} candidate {} reads argument {} but has only {}",
+            label, target, target.payload_arg, target.arity
+        ));
+    }
+    Ok(())
+}
+
+/// 按顺序尝试候选，返回第一个 `probe` 成功的；全部失败时汇总各候选的原因
+pub fn resolve_hook<T>(
+    hook: &HookSpec,
+    mut probe: impl FnMut(&HookTarget) -> Result<T, String>,
+) -> Result<ResolvedHook<'_, T>, String> {
+    let mut failures = Vec::with_capacity(hook.candidates.len());
+    for (candidate_index, target) in hook.candidates.iter().enumerate() {
+        match probe(target) {
+            Ok(value) => {
+                return Ok(ResolvedHook {
+                    channel: &hook.channel,
+                    role: hook.role,
+                    target,
+                    candidate_index,
+                    value,
+                })
+            }
+            Err(e) => failures.push(format!("{}: {}", target, e)),
+        }
+    }
+
+    Err(format!(
+        "No candidate found for {} ({})",
+        hook.label(),
+        failures.join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(method: &str) -> HookTarget {
+        HookTarget {
+            assembly: "umamusume.dll".to_string(),
+            namespace: "Gallop".to_string(),
+            class: "HttpHelper".to_string(),
+            method: method.to_string(),
+            arity: 1,
+            payload_arg: 0,
+        }
+    }
+
+    #[test]
+    fn embedded_manifest_should_cover_both_roles() {
+        let manifest = HookManifest::embedded();
+
+        let roles: Vec<String> = manifest.hooks.iter().map(HookSpec::label).collect();
+        assert_eq!(roles, vec!["http/request_payload", "http/response_payload"]);
+        assert_eq!(manifest.hooks[0].candidates[0], target("CompressRequest"));
+        assert_eq!(
+            manifest.hooks[1].candidates[0].to_string(),
+            "umamusume.dll:Gallop.HttpHelper::DecompressResponse/1"
+        );
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_manifests() {
+        let cases = [
+            (r#"{"version":2,"hooks":[]}"#, "version"),
+            (r#"{"version":1,"hooks":[]}"#, "no hooks"),
+            (
+                r#"{"version":1,"hooks":[{"role":"request_payload","candidates":[]}]}"#,
+                "no candidates",
+            ),
+            (
+                r#"{"version":1,"hooks":[{"role":"response_payload","candidates":[
+                    {"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":4}]}]}"#,
+                "arity 4",
+            ),
+            (
+                r#"{"version":1,"hooks":[{"role":"request_payload","candidates":[
+                    {"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":1,"payload_arg":1}]}]}"#,
+                "argument 1",
+            ),
+            (
+                r#"{"version":1,"hooks":[{"role":"request_payload","candidates":[
+                    {"assembly":"a.dll","namespace":"","class":" ","method":"M","arity":1}]}]}"#,
+                "empty class",
+            ),
+            (
+                r#"{"version":1,"hooks":[
+                    {"role":"response_payload","candidates":[{"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":1}]},
+                    {"role":"response_payload","candidates":[{"assembly":"a.dll","namespace":"","class":"C","method":"N","arity":1}]}]}"#,
+                "Duplicate hook http/response_payload",
+            ),
+            (
+                r#"{"version":1,"hooks":[{"channel":"Realtime","role":"response_payload","candidates":[
+                    {"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":1}]}]}"#,
+                "Invalid hook channel",
+            ),
+            (
+                r#"{"version":1,"hooks":[{"role":"unknown","candidates":[]}]}"#,
+                "Invalid",
+            ),
+        ];
+
+        for (content, expected) in cases {
+            let error = HookManifest::parse(content).expect_err("应拒绝无效清单");
+            assert!(error.contains(expected), "{} -> {}", expected, error);
+        }
+    }
+
+    #[test]
+    fn parse_should_allow_same_role_on_different_channels() {
+        let manifest = HookManifest::parse(
+            r#"{"version":1,"hooks":[
+                {"role":"response_payload","candidates":[{"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":1}]},
+                {"channel":"realtime","role":"response_payload","candidates":[{"assembly":"b.dll","namespace":"Net","class":"Socket","method":"OnMessage","arity":2}]}]}"#,
+        )
+        .expect("不同通道可以有相同用途");
+
+        assert_eq!(manifest.hooks[0].channel, DEFAULT_CAPTURE_CHANNEL);
+        assert_eq!(manifest.hooks[1].label(), "realtime/response_payload");
+        assert_eq!(
+            manifest.hooks[1].role.direction(),
+            CaptureDirection::Response
+        );
+    }
+
+    #[test]
+    fn parse_should_limit_hook_count() {
+        let hooks: Vec<String> = (0..=MAX_HOOK_COUNT)
+            .map(|index| {
+                format!(
+                    r#"{{"channel":"c{}","role":"response_payload","candidates":[{{"assembly":"a.dll","namespace":"","class":"C","method":"M","arity":1}}]}}"#,
+                    index
+                )
+            })
+            .collect();
+        let content = format!(r#"{{"version":1,"hooks":[{}]}}"#, hooks.join(","));
+
+        let error = HookManifest::parse(&content).expect_err("超过跳板数量应报错");
+        assert!(error.contains("at most"));
+    }
+
+    #[test]
+    fn load_should_prefer_valid_override_and_fall_back_otherwise() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join(HOOK_MANIFEST_FILE_NAME);
+
+        let missing = HookManifest::load(&path);
+        assert_eq!(missing.source, HookManifestSource::Embedded);
+        assert!(missing.warning.is_none());
+
+        let mut manifest = HookManifest::embedded();
+        manifest.hooks[0]
+            .candidates
+            .insert(0, target("CompressRequestV2"));
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&manifest).expect("序列化失败"),
+        )
+        .expect("写入清单失败");
+        let overridden = HookManifest::load(&path);
+        assert_eq!(
+            overridden.source,
+            HookManifestSource::Override(path.clone())
+        );
+        assert_eq!(overridden.manifest, manifest);
+
+        fs::write(&path, r#"{"version":1,"hooks":[]}"#).expect("写入清单失败");
+        let broken = HookManifest::load(&path);
+        assert_eq!(broken.source, HookManifestSource::Embedded);
+        assert!(broken.warning.expect("应给出回落原因").contains("no hooks"));
+    }
+
+    #[test]
+    fn resolve_should_try_candidates_in_order() {
+        let hook = HookSpec {
+            channel: "http".to_string(),
+            role: HookRole::RequestPayload,
+            candidates: vec![
+                target("CompressRequestV2"),
+                target("CompressRequest"),
+                target("CompressRequestLegacy"),
+            ],
+        };
+        let mut probed = Vec::new();
+
+        let resolved = resolve_hook(&hook, |target| {
+            probed.push(target.method.clone());
+            match target.method.as_str() {
+                "CompressRequest" | "CompressRequestLegacy" => Ok(0x1000usize),
+                _ => Err("method not found".to_string()),
+            }
+        })
+        .expect("应找到候选");
+
+        assert_eq!(resolved.channel, "http");
+        assert_eq!(resolved.candidate_index, 1);
+        assert_eq!(resolved.target.method, "CompressRequest");
+        assert_eq!(resolved.value, 0x1000);
+        assert_eq!(probed, vec!["CompressRequestV2", "CompressRequest"]);
+    }
+
+    #[test]
+    fn resolve_should_report_every_failed_candidate() {
+        let hook = HookSpec {
+            channel: "realtime".to_string(),
+            role: HookRole::ResponsePayload,
+            candidates: vec![target("DecompressResponse"), target("DecodeResponse")],
+        };
+
+        let error = resolve_hook(&hook, |target| {
+            Err::<usize, _>(format!("{} missing", target.method))
+        })
+        .expect_err("全部失败时应报错");
+
+        assert!(error.contains("realtime/response_payload"));
+        assert!(error.contains("DecompressResponse missing"));
+        assert!(error.contains("DecodeResponse missing"));
+    }
+}
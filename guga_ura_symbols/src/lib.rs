@@ -0,0 +1,23 @@
+//! GugaURA IL2CPP 符号表定位
+//!
+//! UnityPlayer.dll 在启动 IL2CPP 时用一串 `lea reg, [rip+name]` 指令逐个引用
+//! GameAssembly.dll 的（混淆后）导出名。这里只依赖 `pelite` 解析 PE 文件字节，
+//! 在代码节中按指令特征定位这张表，不依赖 Windows API，可在任意平台测试。
+//! 解析结果按 UnityPlayer.dll 的哈希缓存，并可输出便于反馈问题的文本清单。
+//! 需要 Hook 的游戏方法同样以清单描述，见 [`hooks`]。
+
+pub mod cache;
+pub mod dump;
+pub mod hooks;
+pub mod scan;
+
+pub use cache::{DllFingerprint, SymbolCache, SymbolCacheLookup, SYMBOL_CACHE_FILE_NAME};
+pub use dump::{render_symbol_dump, SymbolDumpEntry, SymbolResolution, SYMBOL_DUMP_FILE_NAME};
+pub use hooks::{
+    resolve_hook, HookManifest, HookManifestLoad, HookManifestSource, HookRole, HookSpec,
+    HookTarget, ResolvedHook, HOOK_MANIFEST_FILE_NAME, MAX_HOOK_COUNT,
+};
+pub use scan::{
+    decode_symbol_table, export_names, is_export_like_name, scan_symbol_table,
+    validate_symbol_names, SymbolTable,
+};
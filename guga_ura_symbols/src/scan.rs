@@ -0,0 +1,547 @@
+//! 代码节特征扫描
+//!
+//! 表中每一项都以 `REX.W 8D /r`（`lea r64, [rip+disp32]`）引用一个导出名，
+//! 相邻两项的间距固定，只有第一项与第二项之间可能因寄存器编码不同而多出几个字节。
+//! 扫描时先收集所有指向“像导出名”的字符串的 lea-rip，再在其中寻找满足
+//! “首个间距 + 固定间距”且长度足够的相邻序列（表项之间没有其他名称引用），
+//! 最后对解码出的名称做一次校验。
+
+use pelite::image::IMAGE_SCN_MEM_EXECUTE;
+use pelite::pe64::{Pe, PeFile, PeObject};
+use std::collections::HashSet;
+
+/// 相邻表项之间允许的最大间距
+const MAX_STRIDE: u32 = 0x100;
+
+/// 导出名的最大长度
+const MAX_NAME_LEN: usize = 128;
+
+/// lea-rip 指令长度：REX + opcode + ModRM + disp32
+const LEA_RIP_LEN: usize = 7;
+
+/// 定位到的符号表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolTable {
+    /// 第一项 disp32 所在的 RVA
+    pub start_rva: u32,
+    /// 第一项到第二项的间距
+    pub first_stride: u32,
+    /// 其余相邻项的间距
+    pub stride: u32,
+    /// 按表顺序解码出的导出名
+    pub names: Vec<String>,
+}
+
+/// 一处指向导出名的 lea-rip
+#[derive(Debug, Clone)]
+struct NameReference {
+    disp_rva: u32,
+    name: String,
+}
+
+/// 在 PE 文件的代码节中扫描符号表
+///
+/// `count` 为需要的表项数量；提供 `known_exports`（GameAssembly.dll 的导出名）时，
+/// 解码出的名称必须全部位于其中，用于在多个候选中选出真正的表。
+pub fn scan_symbol_table(
+    image: &[u8],
+    count: usize,
+    known_exports: Option<&HashSet<String>>,
+) -> Result<SymbolTable, String> {
+    if count < 3 {
+        return Err(format!(
+            "Symbol table scan needs at least 3 entries, got {}",
+            count
+        ));
+    }
+
+    let image = AlignedImage::new(image);
+    let pe = PeFile::from_bytes(image.bytes()).map_err(|e| format!("Failed to parse PE: {}", e))?;
+    let references = collect_name_references(pe)?;
+
+    let mut candidates = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, run) in references.windows(count).enumerate() {
+        let head = run[0].disp_rva;
+        let first_stride = run[1].disp_rva - head;
+        let stride = run[2].disp_rva - run[1].disp_rva;
+        if first_stride > MAX_STRIDE || stride > MAX_STRIDE {
+            continue;
+        }
+        // 紧挨在前面、间距相同的引用说明这里是表的中间
+        let continues_previous = index
+            .checked_sub(1)
+            .map(|previous| head - references[previous].disp_rva)
+            .is_some_and(|gap| gap == first_stride || gap == stride);
+        if continues_previous
+            || !table_positions(head, first_stride, stride, count)
+                .zip(run)
+                .all(|(rva, reference)| rva == reference.disp_rva)
+        {
+            continue;
+        }
+
+        let names: Vec<String> = run.iter().map(|reference| reference.name.clone()).collect();
+        match validate_symbol_names(&names, known_exports) {
+            Ok(()) => candidates.push(SymbolTable {
+                start_rva: head,
+                first_stride,
+                stride,
+                names,
+            }),
+            Err(e) => rejected.push(format!("0x{:X}: {}", head, e)),
+        }
+    }
+
+    // 表比需要的更长时，从表中间开始的序列同样成立，保留最前面的那个
+    let starts: Vec<(u32, u32, u32)> = candidates
+        .iter()
+        .map(|table| (table.start_rva, table.first_stride, table.stride))
+        .collect();
+    candidates.retain(|table| {
+        !starts.iter().any(|&(start, first_stride, stride)| {
+            start < table.start_rva
+                && table_positions(start, first_stride, stride, count)
+                    .any(|rva| rva == table.start_rva)
+        })
+    });
+
+    match candidates.len() {
+        1 => Ok(candidates.remove(0)),
+        0 if rejected.is_empty() => Err(format!(
+            "No lea-rip table with {} export-like names found in code sections ({} name references)",
+            count,
+            references.len()
+        )),
+        0 => Err(format!(
+            "All symbol table candidates failed the name check: {}",
+            rejected.join("; ")
+        )),
+        _ => Err(format!(
+            "Symbol table is ambiguous, candidates at {}",
+            candidates
+                .iter()
+                .map(|table| format!("0x{:X}", table.start_rva))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// 按已知的起点与间距解码符号表（旧版本的固定偏移走这里）
+pub fn decode_symbol_table(
+    image: &[u8],
+    start_rva: u32,
+    first_stride: u32,
+    stride: u32,
+    count: usize,
+    known_exports: Option<&HashSet<String>>,
+) -> Result<SymbolTable, String> {
+    let image = AlignedImage::new(image);
+    let pe = PeFile::from_bytes(image.bytes()).map_err(|e| format!("Failed to parse PE: {}", e))?;
+
+    let mut names = Vec::with_capacity(count);
+    for (index, disp_rva) in table_positions(start_rva, first_stride, stride, count).enumerate() {
+        let target = read_disp32(pe, disp_rva)
+            .map(|disp| lea_target(disp_rva, disp))
+            .ok_or_else(|| {
+                format!(
+                    "Failed to read lea-rip displacement at RVA 0x{:X}",
+                    disp_rva
+                )
+            })?;
+        let name = read_name(pe, target).ok_or_else(|| {
+            format!(
+                "Entry {} at RVA 0x{:X} does not reference an export-like name",
+                index, disp_rva
+            )
+        })?;
+        names.push(name);
+    }
+
+    validate_symbol_names(&names, known_exports)?;
+    Ok(SymbolTable {
+        start_rva,
+        first_stride,
+        stride,
+        names,
+    })
+}
+
+/// 校验解码出的名称：格式像导出名、互不重复，且（如提供）都在导出表中
+pub fn validate_symbol_names(
+    names: &[String],
+    known_exports: Option<&HashSet<String>>,
+) -> Result<(), String> {
+    if let Some(name) = names.iter().find(|name| !is_export_like_name(name)) {
+        return Err(format!("'{}' does not look like an export name", name));
+    }
+
+    let mut seen = HashSet::with_capacity(names.len());
+    if let Some(name) = names.iter().find(|name| !seen.insert(name.as_str())) {
+        return Err(format!("Duplicate name '{}' in symbol table", name));
+    }
+
+    if let Some(exports) = known_exports {
+        let missing: Vec<&str> = names
+            .iter()
+            .filter(|name| !exports.contains(name.as_str()))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "{} of {} names are not exported by GameAssembly.dll (first: '{}')",
+                missing.len(),
+                names.len(),
+                missing[0]
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 名称是否像 PE 导出名（可打印、无空白、长度合理）
+pub fn is_export_like_name(name: &str) -> bool {
+    (2..=MAX_NAME_LEN).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '@' | '?' | '$'))
+}
+
+/// 读取 PE 文件的导出名（用于校验 GameAssembly.dll）
+pub fn export_names(image: &[u8]) -> Result<HashSet<String>, String> {
+    let image = AlignedImage::new(image);
+    let pe = PeFile::from_bytes(image.bytes()).map_err(|e| format!("Failed to parse PE: {}", e))?;
+    let by = pe
+        .exports()
+        .and_then(|exports| exports.by())
+        .map_err(|e| format!("Failed to read export table: {}", e))?;
+
+    Ok(by
+        .iter_names()
+        .filter_map(|(name, _)| name.ok())
+        .filter_map(|name| name.to_str().ok().map(str::to_string))
+        .collect())
+}
+
+fn collect_name_references(pe: PeFile<'_>) -> Result<Vec<NameReference>, String> {
+    let mut references = Vec::new();
+
+    for section in pe.section_headers() {
+        if section.Characteristics & IMAGE_SCN_MEM_EXECUTE == 0 {
+            continue;
+        }
+        let bytes = pe
+            .get_section_bytes(section)
+            .map_err(|e| format!("Failed to read section {}: {}", section_name(section), e))?;
+
+        for offset in 0..bytes.len().saturating_sub(LEA_RIP_LEN - 1) {
+            if !is_lea_rip(&bytes[offset..offset + 3]) {
+                continue;
+            }
+            let disp_rva = section.VirtualAddress + offset as u32 + 3;
+            let disp = i32::from_le_bytes(
+                bytes[offset + 3..offset + LEA_RIP_LEN]
+                    .try_into()
+                    .expect("disp32 长度固定为 4"),
+            );
+            if let Some(name) = read_name(pe, lea_target(disp_rva, disp)) {
+                references.push(NameReference { disp_rva, name });
+            }
+        }
+    }
+
+    references.sort_by_key(|reference| reference.disp_rva);
+    Ok(references)
+}
+
+fn table_positions(
+    head: u32,
+    first_stride: u32,
+    stride: u32,
+    count: usize,
+) -> impl Iterator<Item = u32> {
+    (0..count).map(move |index| match index {
+        0 => head,
+        _ => head
+            .wrapping_add(first_stride)
+            .wrapping_add(stride.wrapping_mul(index as u32 - 1)),
+    })
+}
+
+/// `48/4C 8D /r`，ModRM 的 mod=00、r/m=101 表示 RIP 相对寻址
+fn is_lea_rip(bytes: &[u8]) -> bool {
+    matches!(bytes[0], 0x48 | 0x4C) && bytes[1] == 0x8D && bytes[2] & 0xC7 == 0x05
+}
+
+/// disp32 位于指令末尾，目标为“下一条指令地址 + disp”
+fn lea_target(disp_rva: u32, disp: i32) -> u32 {
+    disp_rva.wrapping_add(4).wrapping_add_signed(disp)
+}
+
+fn read_disp32(pe: PeFile<'_>, rva: u32) -> Option<i32> {
+    let offset = pe.rva_to_file_offset(rva).ok()?;
+    let bytes = pe.image().get(offset..offset + 4)?;
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_name(pe: PeFile<'_>, rva: u32) -> Option<String> {
+    let offset = pe.rva_to_file_offset(rva).ok()?;
+    let bytes = pe.image().get(offset..)?;
+    let len = bytes.iter().take(MAX_NAME_LEN + 1).position(|b| *b == 0)?;
+    let name = std::str::from_utf8(&bytes[..len]).ok()?;
+    is_export_like_name(name).then(|| name.to_string())
+}
+
+fn section_name(section: &pelite::image::IMAGE_SECTION_HEADER) -> String {
+    let len = section
+        .Name
+        .iter()
+        .position(|b| *b == 0)
+        .unwrap_or(section.Name.len());
+    String::from_utf8_lossy(&section.Name[..len]).into_owned()
+}
+
+/// pelite 要求映像起始地址 4 字节对齐；未对齐时复制到按 `u32` 分配的缓冲区
+enum AlignedImage<'a> {
+    Borrowed(&'a [u8]),
+    Owned(Vec<u32>, usize),
+}
+
+impl<'a> AlignedImage<'a> {
+    fn new(image: &'a [u8]) -> Self {
+        if (image.as_ptr() as usize).is_multiple_of(4) {
+            return Self::Borrowed(image);
+        }
+
+        let words = image
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0u8; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_ne_bytes(word)
+            })
+            .collect();
+        Self::Owned(words, image.len())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(image) => image,
+            // u32 缓冲区按字节读取总是合法的，长度不超过分配大小
+            Self::Owned(words, len) => unsafe {
+                std::slice::from_raw_parts(words.as_ptr() as *const u8, *len)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT_RVA: u32 = 0x1000;
+    const RDATA_RVA: u32 = 0x2000;
+    const SECTION_SIZE: usize = 0x1000;
+    const FILE_ALIGNMENT: usize = 0x200;
+
+    /// 构造只含 .text 与 .rdata 两个节的最小 PE32+ 文件
+    fn build_pe(text: &[u8], rdata: &[u8]) -> Vec<u8> {
+        assert!(text.len() <= SECTION_SIZE && rdata.len() <= SECTION_SIZE);
+        let mut image = vec![0u8; FILE_ALIGNMENT + SECTION_SIZE * 2];
+        let put16 = |image: &mut Vec<u8>, at: usize, value: u16| {
+            image[at..at + 2].copy_from_slice(&value.to_le_bytes())
+        };
+        let put32 = |image: &mut Vec<u8>, at: usize, value: u32| {
+            image[at..at + 4].copy_from_slice(&value.to_le_bytes())
+        };
+
+        // DOS 头
+        image[0..2].copy_from_slice(b"MZ");
+        put32(&mut image, 0x3C, 0x40);
+        // NT 头与文件头
+        image[0x40..0x44].copy_from_slice(b"PE\0\0");
+        put16(&mut image, 0x44, 0x8664);
+        put16(&mut image, 0x46, 2);
+        put16(&mut image, 0x54, 240);
+        put16(&mut image, 0x56, 0x2022);
+        // 可选头
+        let optional = 0x58;
+        put16(&mut image, optional, 0x20B);
+        put32(&mut image, optional + 32, SECTION_SIZE as u32);
+        put32(&mut image, optional + 36, FILE_ALIGNMENT as u32);
+        put32(&mut image, optional + 56, 0x3000);
+        put32(&mut image, optional + 60, FILE_ALIGNMENT as u32);
+        put32(&mut image, optional + 108, 16);
+        // 节表
+        let sections = [
+            (b".text\0\0\0", TEXT_RVA, 0x6000_0020u32, text),
+            (b".rdata\0\0", RDATA_RVA, 0x4000_0040u32, rdata),
+        ];
+        for (index, (name, rva, characteristics, data)) in sections.into_iter().enumerate() {
+            let header = optional + 240 + index * 40;
+            let raw = FILE_ALIGNMENT + index * SECTION_SIZE;
+            image[header..header + 8].copy_from_slice(name);
+            put32(&mut image, header + 8, SECTION_SIZE as u32);
+            put32(&mut image, header + 12, rva);
+            put32(&mut image, header + 16, SECTION_SIZE as u32);
+            put32(&mut image, header + 20, raw as u32);
+            put32(&mut image, header + 36, characteristics);
+            image[raw..raw + data.len()].copy_from_slice(data);
+        }
+        image
+    }
+
+    /// 在 .rdata 中写入名称，返回各名称的 RVA
+    fn write_names(rdata: &mut Vec<u8>, names: &[String]) -> Vec<u32> {
+        names
+            .iter()
+            .map(|name| {
+                let rva = RDATA_RVA + rdata.len() as u32;
+                rdata.extend_from_slice(name.as_bytes());
+                rdata.push(0);
+                rva
+            })
+            .collect()
+    }
+
+    /// 在 .text 的 `offset` 处写入 `lea rdx, [rip+target]`，返回 disp32 的 RVA
+    fn write_lea(text: &mut [u8], offset: usize, target: u32) -> u32 {
+        let disp_rva = TEXT_RVA + offset as u32 + 3;
+        let disp = target.wrapping_sub(disp_rva + 4) as i32;
+        text[offset..offset + 3].copy_from_slice(&[0x48, 0x8D, 0x15]);
+        text[offset + 3..offset + 7].copy_from_slice(&disp.to_le_bytes());
+        disp_rva
+    }
+
+    fn mangled_names(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|index| format!("Qx{:02}vZk_{}", index, index * 7))
+            .collect()
+    }
+
+    /// 生成带干扰项的映像：表从 `table_offset` 开始，首个间距与其余间距可不同
+    fn image_with_table(
+        names: &[String],
+        table_offset: usize,
+        first_stride: usize,
+        stride: usize,
+    ) -> (Vec<u8>, u32) {
+        let mut text = vec![0x90u8; SECTION_SIZE];
+        let mut rdata = b"not an export name!\0".to_vec();
+        let name_rvas = write_names(&mut rdata, names);
+        let decoy_rvas = write_names(
+            &mut rdata,
+            &["DecoyOne".to_string(), "DecoyTwo".to_string()],
+        );
+
+        // 表前的零散引用：一个非导出名字符串、两个间距不规律的导出名
+        write_lea(&mut text, 0x10, RDATA_RVA);
+        write_lea(&mut text, 0x30, decoy_rvas[0]);
+        write_lea(&mut text, 0x4D, decoy_rvas[1]);
+
+        let mut offset = table_offset;
+        let mut start = 0;
+        for (index, rva) in name_rvas.iter().enumerate() {
+            let disp_rva = write_lea(&mut text, offset, *rva);
+            if index == 0 {
+                start = disp_rva;
+            }
+            offset += if index == 0 { first_stride } else { stride };
+        }
+        (build_pe(&text, &rdata), start)
+    }
+
+    #[test]
+    fn scan_should_find_table_with_distinct_first_stride() {
+        let names = mangled_names(12);
+        let (image, start) = image_with_table(&names, 0x100, 0x28, 0x26);
+
+        let table = scan_symbol_table(&image, names.len(), None).expect("应找到符号表");
+
+        assert_eq!(table.start_rva, start);
+        assert_eq!((table.first_stride, table.stride), (0x28, 0x26));
+        assert_eq!(table.names, names);
+    }
+
+    #[test]
+    fn scan_should_detect_uniform_stride() {
+        let names = mangled_names(8);
+        let (image, _) = image_with_table(&names, 0x200, 0x1F, 0x1F);
+
+        let table = scan_symbol_table(&image, names.len(), None).expect("应找到符号表");
+
+        assert_eq!((table.first_stride, table.stride), (0x1F, 0x1F));
+        assert_eq!(table.names, names);
+    }
+
+    #[test]
+    fn scan_should_start_at_table_head_when_table_is_longer() {
+        let names = mangled_names(15);
+        let (image, start) = image_with_table(&names, 0x100, 0x28, 0x26);
+
+        let table = scan_symbol_table(&image, 12, None).expect("应找到符号表");
+
+        assert_eq!(table.start_rva, start);
+        assert_eq!(table.names, names[..12]);
+    }
+
+    #[test]
+    fn scan_should_check_names_against_exports() {
+        let names = mangled_names(10);
+        let (image, _) = image_with_table(&names, 0x100, 0x28, 0x26);
+
+        let exports: HashSet<String> = names.iter().cloned().collect();
+        assert!(scan_symbol_table(&image, names.len(), Some(&exports)).is_ok());
+
+        let partial: HashSet<String> = names.iter().skip(1).cloned().collect();
+        let error = scan_symbol_table(&image, names.len(), Some(&partial))
+            .expect_err("导出表缺少名称时应失败");
+        assert!(error.contains("not exported"), "{}", error);
+    }
+
+    #[test]
+    fn scan_should_fail_without_table() {
+        let names = mangled_names(4);
+        let (image, _) = image_with_table(&names, 0x100, 0x28, 0x26);
+
+        let error = scan_symbol_table(&image, 12, None).expect_err("表太短时应失败");
+
+        assert!(error.contains("No lea-rip table"), "{}", error);
+        assert!(scan_symbol_table(b"MZ not a pe", 12, None).is_err());
+    }
+
+    #[test]
+    fn decode_should_follow_known_offsets() {
+        let names = mangled_names(6);
+        let (image, start) = image_with_table(&names, 0x100, 0x28, 0x26);
+
+        let table =
+            decode_symbol_table(&image, start, 0x28, 0x26, names.len(), None).expect("应能解码");
+        assert_eq!(table.names, names);
+
+        assert!(decode_symbol_table(&image, start + 1, 0x28, 0x26, names.len(), None).is_err());
+    }
+
+    #[test]
+    fn unaligned_image_should_be_parsed() {
+        let names = mangled_names(5);
+        let (image, _) = image_with_table(&names, 0x100, 0x28, 0x26);
+        let mut shifted = vec![0u8];
+        shifted.extend_from_slice(&image);
+
+        let table = scan_symbol_table(&shifted[1..], names.len(), None).expect("应找到符号表");
+
+        assert_eq!(table.names, names);
+    }
+
+    #[test]
+    fn name_check_should_reject_duplicates_and_garbage() {
+        let names = vec!["Abc".to_string(), "Abc".to_string()];
+        assert!(validate_symbol_names(&names, None).is_err());
+        assert!(!is_export_like_name("has space"));
+        assert!(!is_export_like_name("x"));
+        assert!(is_export_like_name("il2cpp_init"));
+    }
+}
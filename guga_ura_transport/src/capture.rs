@@ -0,0 +1,344 @@
+//! 捕获通道
+//!
+//! DLL 拦截到的每段明文都属于一个通道（默认 `http`，即 `Gallop.HttpHelper`）
+//! 和一个方向，按 `/notify/{channel}/{direction}` 发送给 Receiver。默认通道仍发往
+//! 旧版的 `/notify/request`、`/notify/response`，旧版 Receiver 与转发目标不用升级；
+//! 两种写法都会被解析为 `http` 通道。
+//!
+//! Receiver 与 DLL 的 debug 输出都按 [`capture_file_stem`] 命名，并写成同一种
+//! 包装结构 [`CapturedPayloadFile`]，工具可以用同一套逻辑读取两者。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// 默认通道：游戏 HTTP API
+pub const DEFAULT_CAPTURE_CHANNEL: &str = "http";
+
+/// 通道名最大长度
+pub const MAX_CHANNEL_NAME_LEN: usize = 32;
+
+const NOTIFY_PREFIX: &str = "/notify/";
+
+/// 捕获方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureDirection {
+    /// 游戏发出的数据
+    Request,
+    /// 游戏收到的数据
+    Response,
+}
+
+/// 从 notify 路由解析出的通道与方向
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRoute {
+    pub channel: String,
+    pub direction: CaptureDirection,
+}
+
+impl CaptureDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Request => "request",
+            Self::Response => "response",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "request" => Some(Self::Request),
+            "response" => Some(Self::Response),
+            _ => None,
+        }
+    }
+}
+
+impl CaptureRoute {
+    pub fn new(channel: impl Into<String>, direction: CaptureDirection) -> Self {
+        Self {
+            channel: channel.into(),
+            direction,
+        }
+    }
+
+    /// 该捕获发送时使用的 notify 路由：默认通道为旧版 `/notify/{direction}`，
+    /// 其它通道为 `/notify/{channel}/{direction}`
+    pub fn path(&self) -> String {
+        if self.is_default_channel() {
+            format!("{}{}", NOTIFY_PREFIX, self.direction.as_str())
+        } else {
+            notify_path(&self.channel, self.direction)
+        }
+    }
+
+    pub fn is_default_channel(&self) -> bool {
+        self.channel == DEFAULT_CAPTURE_CHANNEL
+    }
+}
+
+/// 通道名只允许小写字母、数字、`_` 与 `-`，便于直接用作路由和文件名
+pub fn is_valid_channel_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_CHANNEL_NAME_LEN
+        && name.bytes().all(|byte| {
+            byte.is_ascii_lowercase() || byte.is_ascii_digit() || matches!(byte, b'_' | b'-')
+        })
+}
+
+/// 生成 `/notify/{channel}/{direction}`
+pub fn notify_path(channel: &str, direction: CaptureDirection) -> String {
+    format!("{}{}/{}", NOTIFY_PREFIX, channel, direction.as_str())
+}
+
+/// 落盘文件名（不含扩展名）：默认通道为 `{direction}_{seq}_{unix_ms}`，
+/// 其它通道加上通道名前缀
+pub fn capture_file_stem(route: &CaptureRoute, seq: u64, unix_ms: u64) -> String {
+    if route.is_default_channel() {
+        format!("{}_{:06}_{}", route.direction.as_str(), seq, unix_ms)
+    } else {
+        format!(
+            "{}_{}_{:06}_{}",
+            route.channel,
+            route.direction.as_str(),
+            seq,
+            unix_ms
+        )
+    }
+}
+
+/// 原始 payload 文件扩展名，与 `.json` 包装文件同名
+pub const RAW_PAYLOAD_EXTENSION: &str = "msgpack";
+
+/// 无法解码、只保留原始数据时的 `decoded_as`
+pub const DECODED_AS_RAW: &str = "raw";
+
+/// 落盘 payload 的包装结构
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedPayloadFile {
+    pub channel: String,
+    pub direction: String,
+    pub route: String,
+    pub received_at_unix_ms: u64,
+    /// 按配置时间格式输出的时间；DLL 直接写入 unix 毫秒
+    pub received_at: Value,
+    pub payload_size: usize,
+    /// `msgpack` / `json`；无法解码时为 [`DECODED_AS_RAW`]
+    pub decoded_as: String,
+    /// 同目录下保留的原始数据文件名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+    /// 写入方附加的来源信息，如 Receiver 的 `headers`、`source`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+    pub payload: Value,
+}
+
+impl CapturedPayloadFile {
+    /// `route` 为实际收到的路由，旧版路由原样记录
+    pub fn new(
+        capture_route: &CaptureRoute,
+        route: &str,
+        received_at_unix_ms: u64,
+        received_at: Value,
+    ) -> Self {
+        Self {
+            channel: capture_route.channel.clone(),
+            direction: capture_route.direction.as_str().to_string(),
+            route: route.to_string(),
+            received_at_unix_ms,
+            received_at,
+            payload_size: 0,
+            decoded_as: DECODED_AS_RAW.to_string(),
+            raw_file: None,
+            decode_error: None,
+            extra: Map::new(),
+            payload: Value::Null,
+        }
+    }
+
+    /// 已解码的 payload
+    pub fn with_payload(mut self, payload_size: usize, decoded_as: &str, payload: Value) -> Self {
+        self.payload_size = payload_size;
+        self.decoded_as = decoded_as.to_string();
+        self.payload = payload;
+        self
+    }
+
+    /// 无法解码的 payload：只记录错误，数据见原始文件
+    pub fn with_decode_error(mut self, payload_size: usize, error: impl Into<String>) -> Self {
+        self.payload_size = payload_size;
+        self.decoded_as = DECODED_AS_RAW.to_string();
+        self.decode_error = Some(error.into());
+        self.payload = Value::Null;
+        self
+    }
+
+    pub fn with_raw_file(mut self, file_name: impl Into<String>) -> Self {
+        self.raw_file = Some(file_name.into());
+        self
+    }
+
+    pub fn with_extra(mut self, key: &str, value: Value) -> Self {
+        self.extra.insert(key.to_string(), value);
+        self
+    }
+
+    /// 以缩进 JSON 写入文件
+    pub fn write_json(&self, file_path: &Path) -> Result<(), String> {
+        let json_str = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("to_string_pretty failed: {}", e))?;
+        fs::write(file_path, json_str)
+            .map_err(|e| format!("write {} failed: {}", file_path.display(), e))
+    }
+}
+
+/// 与包装文件同名的原始数据文件名
+pub fn raw_payload_file_name(file_stem: &str) -> String {
+    format!("{}.{}", file_stem, RAW_PAYLOAD_EXTENSION)
+}
+
+/// 解析 notify 路由；不是 notify 路由或通道名无效时返回 `None`
+pub fn parse_notify_route(route: &str) -> Option<CaptureRoute> {
+    let path = route.split(['?', '#']).next().unwrap_or_default();
+    let rest = path.strip_prefix(NOTIFY_PREFIX)?.trim_end_matches('/');
+    let segments: Vec<&str> = rest.split('/').collect();
+
+    match segments.as_slice() {
+        [direction] => Some(CaptureRoute::new(
+            DEFAULT_CAPTURE_CHANNEL,
+            CaptureDirection::parse(direction)?,
+        )),
+        [channel, direction] => {
+            let channel = channel.to_ascii_lowercase();
+            if !is_valid_channel_name(&channel) {
+                return None;
+            }
+            Some(CaptureRoute::new(
+                channel,
+                CaptureDirection::parse(direction)?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_accept_channel_routes_and_legacy_routes() {
+        assert_eq!(
+            parse_notify_route("/notify/realtime/response"),
+            Some(CaptureRoute::new("realtime", CaptureDirection::Response))
+        );
+        assert_eq!(
+            parse_notify_route("/notify/Race-Room/request/?seq=1"),
+            Some(CaptureRoute::new("race-room", CaptureDirection::Request))
+        );
+        assert_eq!(
+            parse_notify_route("/notify/request"),
+            Some(CaptureRoute::new("http", CaptureDirection::Request))
+        );
+        assert!(parse_notify_route("/notify/response")
+            .expect("旧版路由应被识别")
+            .is_default_channel());
+    }
+
+    #[test]
+    fn parse_should_reject_unknown_routes() {
+        for route in [
+            "/",
+            "/notify",
+            "/notify/",
+            "/api/response",
+            "/notify/http/upload",
+            "/notify/a/b/response",
+            "/notify/bad.name/response",
+            "/prefix/notify/http/response",
+        ] {
+            assert_eq!(parse_notify_route(route), None, "{}", route);
+        }
+    }
+
+    #[test]
+    fn notify_path_should_round_trip() {
+        let route = CaptureRoute::new("realtime", CaptureDirection::Request);
+
+        assert_eq!(route.path(), "/notify/realtime/request");
+        assert_eq!(parse_notify_route(&route.path()), Some(route));
+
+        let http = CaptureRoute::new(DEFAULT_CAPTURE_CHANNEL, CaptureDirection::Response);
+        assert_eq!(http.path(), "/notify/response", "默认通道应沿用旧版路由");
+        assert_eq!(parse_notify_route(&http.path()), Some(http));
+    }
+
+    #[test]
+    fn file_stem_should_prefix_non_default_channels() {
+        let http = CaptureRoute::new(DEFAULT_CAPTURE_CHANNEL, CaptureDirection::Response);
+        let realtime = CaptureRoute::new("realtime", CaptureDirection::Request);
+
+        assert_eq!(capture_file_stem(&http, 7, 1000), "response_000007_1000");
+        assert_eq!(
+            capture_file_stem(&realtime, 7, 1000),
+            "realtime_request_000007_1000"
+        );
+    }
+
+    #[test]
+    fn payload_file_should_flatten_extra_and_round_trip() {
+        let route = CaptureRoute::new("realtime", CaptureDirection::Response);
+        let file = CapturedPayloadFile::new(
+            &route,
+            &route.path(),
+            1000,
+            Value::from("2024-01-01 00:00:00"),
+        )
+        .with_payload(3, "msgpack", serde_json::json!({"a": 1}))
+        .with_raw_file(raw_payload_file_name("realtime_response_000001_1000"))
+        .with_extra("source", serde_json::json!({"plugin": "guga_ura_dll"}));
+
+        let value = serde_json::to_value(&file).expect("序列化包装文件失败");
+        assert_eq!(value["route"], "/notify/realtime/response");
+        assert_eq!(value["source"]["plugin"], "guga_ura_dll");
+        assert_eq!(value["raw_file"], "realtime_response_000001_1000.msgpack");
+        assert!(value.get("decode_error").is_none());
+
+        let parsed: CapturedPayloadFile = serde_json::from_value(value).expect("解析包装文件失败");
+        assert_eq!(parsed, file);
+    }
+
+    #[test]
+    fn payload_file_should_keep_decode_error_without_payload() {
+        let dir = tempfile::tempdir().expect("创建临时目录失败");
+        let path = dir.path().join("response_000001_1000.json");
+        let route = CaptureRoute::new(DEFAULT_CAPTURE_CHANNEL, CaptureDirection::Response);
+
+        CapturedPayloadFile::new(&route, "/notify/response", 1000, Value::from(1000))
+            .with_decode_error(4, "invalid marker")
+            .write_json(&path)
+            .expect("写入包装文件失败");
+
+        let value: Value = serde_json::from_str(&fs::read_to_string(&path).expect("读取失败"))
+            .expect("包装文件不是合法 JSON");
+        assert_eq!(value["decoded_as"], DECODED_AS_RAW);
+        assert_eq!(value["decode_error"], "invalid marker");
+        assert_eq!(value["payload"], Value::Null);
+        assert_eq!(value["payload_size"], 4);
+    }
+
+    #[test]
+    fn channel_name_should_be_route_and_file_safe() {
+        assert!(is_valid_channel_name("http"));
+        assert!(is_valid_channel_name("race_room-2"));
+        assert!(!is_valid_channel_name(""));
+        assert!(!is_valid_channel_name("Http"));
+        assert!(!is_valid_channel_name("a/b"));
+        assert!(!is_valid_channel_name(
+            &"a".repeat(MAX_CHANNEL_NAME_LEN + 1)
+        ));
+    }
+}
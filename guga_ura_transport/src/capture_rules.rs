@@ -0,0 +1,418 @@
+//! 捕获规则
+//!
+//! DLL 在发送前按规则过滤拦截到的 payload，减少转发量：方向开关、大小上下限、
+//! msgpack 顶层键的包含/排除以及采样率。规则求值不依赖 DLL 运行环境，
+//! 计数器使用原子变量，可在 Hook 线程中直接更新。
+
+use crate::capture::CaptureDirection;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 配置中的捕获规则（`capture_rules`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureRules {
+    /// 是否发送请求方向
+    #[serde(default = "default_true")]
+    pub request_enabled: bool,
+
+    /// 是否发送响应方向
+    #[serde(default = "default_true")]
+    pub response_enabled: bool,
+
+    /// 小于该字节数的 payload 不发送
+    #[serde(default)]
+    pub min_size_bytes: Option<u64>,
+
+    /// 大于该字节数的 payload 不发送（如 master 数据、资源列表）
+    #[serde(default)]
+    pub max_size_bytes: Option<u64>,
+
+    /// 非空时，msgpack 顶层键中至少包含其中一个才发送
+    #[serde(default)]
+    pub include_keys: Vec<String>,
+
+    /// msgpack 顶层键中包含其中任意一个即不发送
+    #[serde(default)]
+    pub exclude_keys: Vec<String>,
+
+    /// 采样率 0.0 ~ 1.0，在其它规则通过后按比例均匀抽取
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64,
+}
+
+/// 跳过原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    DirectionDisabled,
+    TooSmall,
+    TooLarge,
+    MissingKey,
+    ExcludedKey,
+    Sampled,
+}
+
+/// 规则求值结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureDecision {
+    Send,
+    Skip(SkipReason),
+}
+
+/// 匹配与跳过计数
+#[derive(Debug)]
+pub struct CaptureCounters {
+    matched: AtomicU64,
+    direction_disabled: AtomicU64,
+    too_small: AtomicU64,
+    too_large: AtomicU64,
+    missing_key: AtomicU64,
+    excluded_key: AtomicU64,
+    sampled: AtomicU64,
+    /// 采样序号，只统计通过其它规则的 payload
+    sample_seq: AtomicU64,
+}
+
+/// 计数快照
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureCounterSnapshot {
+    pub matched: u64,
+    pub skipped: u64,
+    pub direction_disabled: u64,
+    pub too_small: u64,
+    pub too_large: u64,
+    pub missing_key: u64,
+    pub excluded_key: u64,
+    pub sampled: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_sample_rate() -> f64 {
+    1.0
+}
+
+impl Default for CaptureRules {
+    fn default() -> Self {
+        Self {
+            request_enabled: true,
+            response_enabled: true,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            include_keys: Vec::new(),
+            exclude_keys: Vec::new(),
+            sample_rate: default_sample_rate(),
+        }
+    }
+}
+
+impl CaptureRules {
+    /// 是否需要解析 msgpack 顶层键
+    pub fn needs_keys(&self) -> bool {
+        !self.include_keys.is_empty() || !self.exclude_keys.is_empty()
+    }
+
+    /// 不含采样的规则求值
+    pub fn evaluate(&self, direction: CaptureDirection, payload: &[u8]) -> CaptureDecision {
+        let enabled = match direction {
+            CaptureDirection::Request => self.request_enabled,
+            CaptureDirection::Response => self.response_enabled,
+        };
+        if !enabled {
+            return CaptureDecision::Skip(SkipReason::DirectionDisabled);
+        }
+
+        let size = payload.len() as u64;
+        if self.min_size_bytes.is_some_and(|min| size < min) {
+            return CaptureDecision::Skip(SkipReason::TooSmall);
+        }
+        if self.max_size_bytes.is_some_and(|max| size > max) {
+            return CaptureDecision::Skip(SkipReason::TooLarge);
+        }
+
+        if self.needs_keys() {
+            let keys = top_level_keys(payload);
+            let has_any = |wanted: &[String]| {
+                wanted
+                    .iter()
+                    .any(|key| keys.iter().any(|present| present == key))
+            };
+            if has_any(&self.exclude_keys) {
+                return CaptureDecision::Skip(SkipReason::ExcludedKey);
+            }
+            if !self.include_keys.is_empty() && !has_any(&self.include_keys) {
+                return CaptureDecision::Skip(SkipReason::MissingKey);
+            }
+        }
+
+        CaptureDecision::Send
+    }
+
+    /// 完整求值（含采样）并更新计数
+    pub fn decide(
+        &self,
+        direction: CaptureDirection,
+        payload: &[u8],
+        counters: &CaptureCounters,
+    ) -> CaptureDecision {
+        let mut decision = self.evaluate(direction, payload);
+        if decision == CaptureDecision::Send {
+            let seq = counters.sample_seq.fetch_add(1, Ordering::Relaxed);
+            if !sample_hit(self.sample_rate, seq) {
+                decision = CaptureDecision::Skip(SkipReason::Sampled);
+            }
+        }
+        counters.record(decision);
+        decision
+    }
+}
+
+/// 按序号均匀采样：第 `seq` 个 payload 在 `rate` 比例下是否保留
+///
+/// 当 `floor((seq + 1) * rate)` 比 `floor(seq * rate)` 大时保留，
+/// 例如 0.25 时每 4 个保留 1 个。
+pub fn sample_hit(rate: f64, seq: u64) -> bool {
+    if !rate.is_finite() || rate <= 0.0 {
+        return false;
+    }
+    if rate >= 1.0 {
+        return true;
+    }
+    let before = (seq as f64 * rate).floor();
+    let after = ((seq + 1) as f64 * rate).floor();
+    after > before
+}
+
+/// 读取 msgpack 顶层 map 的字符串键；不是 map 或解码失败时返回空
+pub fn top_level_keys(payload: &[u8]) -> Vec<String> {
+    rmp_serde::from_slice::<BTreeMap<String, IgnoredAny>>(payload)
+        .map(|map| map.into_keys().collect())
+        .unwrap_or_default()
+}
+
+impl SkipReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DirectionDisabled => "direction_disabled",
+            Self::TooSmall => "too_small",
+            Self::TooLarge => "too_large",
+            Self::MissingKey => "missing_key",
+            Self::ExcludedKey => "excluded_key",
+            Self::Sampled => "sampled",
+        }
+    }
+}
+
+impl Default for CaptureCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptureCounters {
+    /// 可用于 `static` 初始化
+    pub const fn new() -> Self {
+        Self {
+            matched: AtomicU64::new(0),
+            direction_disabled: AtomicU64::new(0),
+            too_small: AtomicU64::new(0),
+            too_large: AtomicU64::new(0),
+            missing_key: AtomicU64::new(0),
+            excluded_key: AtomicU64::new(0),
+            sampled: AtomicU64::new(0),
+            sample_seq: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, decision: CaptureDecision) {
+        let counter = match decision {
+            CaptureDecision::Send => &self.matched,
+            CaptureDecision::Skip(SkipReason::DirectionDisabled) => &self.direction_disabled,
+            CaptureDecision::Skip(SkipReason::TooSmall) => &self.too_small,
+            CaptureDecision::Skip(SkipReason::TooLarge) => &self.too_large,
+            CaptureDecision::Skip(SkipReason::MissingKey) => &self.missing_key,
+            CaptureDecision::Skip(SkipReason::ExcludedKey) => &self.excluded_key,
+            CaptureDecision::Skip(SkipReason::Sampled) => &self.sampled,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> CaptureCounterSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let mut snapshot = CaptureCounterSnapshot {
+            matched: load(&self.matched),
+            skipped: 0,
+            direction_disabled: load(&self.direction_disabled),
+            too_small: load(&self.too_small),
+            too_large: load(&self.too_large),
+            missing_key: load(&self.missing_key),
+            excluded_key: load(&self.excluded_key),
+            sampled: load(&self.sampled),
+        };
+        snapshot.skipped = snapshot.direction_disabled
+            + snapshot.too_small
+            + snapshot.too_large
+            + snapshot.missing_key
+            + snapshot.excluded_key
+            + snapshot.sampled;
+        snapshot
+    }
+}
+
+impl CaptureCounterSnapshot {
+    /// 已求值的 payload 总数
+    pub fn total(&self) -> u64 {
+        self.matched + self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn msgpack(value: serde_json::Value) -> Vec<u8> {
+        rmp_serde::to_vec_named(&value).expect("编码 msgpack 失败")
+    }
+
+    #[test]
+    fn default_rules_should_send_everything() {
+        let rules = CaptureRules::default();
+
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Request, b""),
+            CaptureDecision::Send
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &[0xC1]),
+            CaptureDecision::Send
+        );
+    }
+
+    #[test]
+    fn direction_and_size_rules_should_skip() {
+        let rules = CaptureRules {
+            request_enabled: false,
+            min_size_bytes: Some(4),
+            max_size_bytes: Some(8),
+            ..CaptureRules::default()
+        };
+
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Request, &[0; 6]),
+            CaptureDecision::Skip(SkipReason::DirectionDisabled)
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &[0; 3]),
+            CaptureDecision::Skip(SkipReason::TooSmall)
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &[0; 9]),
+            CaptureDecision::Skip(SkipReason::TooLarge)
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &[0; 8]),
+            CaptureDecision::Send
+        );
+    }
+
+    #[test]
+    fn key_rules_should_check_top_level_msgpack_keys() {
+        let rules = CaptureRules {
+            include_keys: vec!["data".to_string()],
+            exclude_keys: vec!["master_data".to_string()],
+            ..CaptureRules::default()
+        };
+        let data = msgpack(json!({"data_headers": {}, "data": {"nested": {"master_data": 1}}}));
+        let master = msgpack(json!({"data": {}, "master_data": []}));
+        let other = msgpack(json!({"result_code": 1}));
+
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &data),
+            CaptureDecision::Send
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &master),
+            CaptureDecision::Skip(SkipReason::ExcludedKey)
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, &other),
+            CaptureDecision::Skip(SkipReason::MissingKey)
+        );
+        assert_eq!(
+            rules.evaluate(CaptureDirection::Response, b"not msgpack"),
+            CaptureDecision::Skip(SkipReason::MissingKey)
+        );
+    }
+
+    #[test]
+    fn top_level_keys_should_ignore_non_maps() {
+        assert_eq!(
+            top_level_keys(&msgpack(json!({"b": 1, "a": [1, 2]}))),
+            vec!["a", "b"]
+        );
+        assert!(top_level_keys(&msgpack(json!([1, 2, 3]))).is_empty());
+        assert!(top_level_keys(&[]).is_empty());
+    }
+
+    #[test]
+    fn sampling_should_spread_evenly() {
+        let kept = |rate: f64| (0..100).filter(|seq| sample_hit(rate, *seq)).count();
+
+        assert_eq!(kept(1.0), 100);
+        assert_eq!(kept(0.25), 25);
+        assert_eq!(kept(0.1), 10);
+        assert_eq!(kept(0.0), 0);
+        assert_eq!(kept(f64::NAN), 0);
+        assert_eq!(
+            (0..8).map(|seq| sample_hit(0.25, seq)).collect::<Vec<_>>(),
+            vec![false, false, false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn decide_should_count_matched_and_skipped() {
+        let rules = CaptureRules {
+            max_size_bytes: Some(4),
+            sample_rate: 0.5,
+            ..CaptureRules::default()
+        };
+        let counters = CaptureCounters::new();
+
+        let decisions: Vec<CaptureDecision> = [1, 2, 3, 9]
+            .iter()
+            .map(|size| rules.decide(CaptureDirection::Response, &vec![0; *size], &counters))
+            .collect();
+
+        assert_eq!(
+            decisions,
+            vec![
+                CaptureDecision::Skip(SkipReason::Sampled),
+                CaptureDecision::Send,
+                CaptureDecision::Skip(SkipReason::Sampled),
+                CaptureDecision::Skip(SkipReason::TooLarge),
+            ]
+        );
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.matched, 1);
+        assert_eq!(snapshot.skipped, 3);
+        assert_eq!(snapshot.sampled, 2);
+        assert_eq!(snapshot.too_large, 1);
+        assert_eq!(snapshot.total(), 4);
+    }
+
+    #[test]
+    fn rules_should_deserialize_with_defaults() {
+        let rules: CaptureRules =
+            serde_json::from_str(r#"{"request_enabled": false, "max_size_bytes": 1048576}"#)
+                .expect("解析规则失败");
+
+        assert!(!rules.request_enabled);
+        assert!(rules.response_enabled);
+        assert_eq!(rules.max_size_bytes, Some(1_048_576));
+        assert_eq!(rules.sample_rate, 1.0);
+    }
+}
@@ -0,0 +1,192 @@
+//! 捕获健康状态
+//!
+//! DLL 定期把自身状态（版本、代理入口、Steam/DMM 判定、符号映射、Hook 安装
+//! 结果与 payload 计数）以 JSON 发送到 Receiver 的 `POST /status`；Receiver 只保留
+//! 最近一份，通过 `GET /status` 提供给配置工具或其它程序查看。
+
+use crate::capture_rules::CaptureCounterSnapshot;
+use serde::{Deserialize, Serialize};
+
+/// 状态上报与查询路由
+pub const STATUS_ROUTE: &str = "/status";
+
+/// 状态文档结构版本
+pub const HEALTH_SCHEMA_VERSION: u32 = 1;
+
+/// DLL 心跳间隔
+pub const HEARTBEAT_INTERVAL_MS: u64 = 30_000;
+
+/// 超过该时长未收到心跳视为 DLL 已离线（三个心跳周期）
+pub const HEARTBEAT_STALE_AFTER_MS: u64 = HEARTBEAT_INTERVAL_MS * 3;
+
+/// 状态文档大小上限，超出时 Receiver 直接拒绝
+pub const MAX_HEALTH_REPORT_BYTES: usize = 256 * 1024;
+
+/// DLL 上报的状态文档
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaptureHealth {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// DLL 版本
+    #[serde(default)]
+    pub dll_version: String,
+    /// 游戏进程 ID
+    #[serde(default)]
+    pub process_id: u32,
+    /// DLL 初始化时间
+    #[serde(default)]
+    pub started_at_unix_ms: u64,
+    /// 本次上报时间
+    #[serde(default)]
+    pub sent_at_unix_ms: u64,
+    /// 心跳序号，从 0 开始
+    #[serde(default)]
+    pub heartbeat_seq: u64,
+    /// 代理入口：`unity_player` / `cri_mana_vpx` / `unknown`
+    #[serde(default)]
+    pub proxy_entry: String,
+    /// 运行时判定的发行版本：`steam` / `dmm` / `unknown`
+    #[serde(default)]
+    pub game_release: String,
+    /// 发行版本判定依据，如 `steam_api64=true`
+    #[serde(default)]
+    pub release_evidence: Vec<String>,
+    #[serde(default)]
+    pub symbol_map: SymbolMapHealth,
+    #[serde(default)]
+    pub hooks: HookHealth,
+    /// DLL 侧捕获规则计数
+    #[serde(default)]
+    pub capture: CaptureCounterSnapshot,
+    #[serde(default)]
+    pub delivery: DeliveryHealth,
+}
+
+/// 符号映射状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMapHealth {
+    /// `pending`（尚未解析）/ `ready` / `failed`
+    #[serde(default)]
+    pub state: String,
+    /// 映射来源，如 `scan`、`legacy`、`cache (scan)`
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub entries: usize,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 捕获 Hook 安装状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookHealth {
+    /// `pending`（游戏尚未就绪）/ `installed` / `failed`
+    #[serde(default)]
+    pub state: String,
+    /// 清单来源：`embedded` 或覆盖文件路径
+    #[serde(default)]
+    pub manifest_source: String,
+    /// 已尝试安装的次数
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub hooks: Vec<HookInstallHealth>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 单个 Hook 的安装结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookInstallHealth {
+    pub channel: String,
+    pub role: String,
+    /// 实际命中的目标，如 `umamusume.dll:Gallop.HttpHelper::DecompressResponse/1`
+    pub target: String,
+    /// 命中的候选序号，大于 0 表示使用了后备候选
+    #[serde(default)]
+    pub candidate_index: usize,
+}
+
+/// DLL 向 Receiver 发送 payload 的结果计数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryHealth {
+    #[serde(default)]
+    pub sent: u64,
+    #[serde(default)]
+    pub failed: u64,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_sent_at_unix_ms: Option<u64>,
+}
+
+impl CaptureHealth {
+    /// 解析 DLL 上报的状态文档
+    pub fn parse(body: &[u8]) -> Result<Self, String> {
+        if body.len() > MAX_HEALTH_REPORT_BYTES {
+            return Err(format!(
+                "Status report exceeds {} bytes",
+                MAX_HEALTH_REPORT_BYTES
+            ));
+        }
+        serde_json::from_slice(body).map_err(|e| format!("Invalid status report: {}", e))
+    }
+
+    /// 以接收时间判断是否已超过心跳超时
+    pub fn is_stale(received_at_unix_ms: u64, now_unix_ms: u64) -> bool {
+        now_unix_ms.saturating_sub(received_at_unix_ms) > HEARTBEAT_STALE_AFTER_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_should_round_trip_through_json() {
+        let health = CaptureHealth {
+            schema_version: HEALTH_SCHEMA_VERSION,
+            dll_version: "2.0.6".to_string(),
+            proxy_entry: "cri_mana_vpx".to_string(),
+            game_release: "steam".to_string(),
+            release_evidence: vec!["steam_api64=true".to_string()],
+            hooks: HookHealth {
+                state: "installed".to_string(),
+                hooks: vec![HookInstallHealth {
+                    channel: "http".to_string(),
+                    role: "response_payload".to_string(),
+                    target: "umamusume.dll:Gallop.HttpHelper::DecompressResponse/1".to_string(),
+                    candidate_index: 0,
+                }],
+                ..HookHealth::default()
+            },
+            ..CaptureHealth::default()
+        };
+
+        let body = serde_json::to_vec(&health).expect("序列化状态失败");
+        assert_eq!(CaptureHealth::parse(&body), Ok(health));
+    }
+
+    #[test]
+    fn parse_should_fill_missing_sections_and_reject_garbage() {
+        let health = CaptureHealth::parse(br#"{"dll_version":"2.0.5"}"#).expect("旧版状态应可解析");
+        assert_eq!(health.dll_version, "2.0.5");
+        assert_eq!(health.delivery, DeliveryHealth::default());
+
+        assert!(CaptureHealth::parse(b"not json").is_err());
+        assert!(CaptureHealth::parse(&vec![b' '; MAX_HEALTH_REPORT_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn heartbeat_should_go_stale_after_three_intervals() {
+        assert!(!CaptureHealth::is_stale(
+            1_000,
+            1_000 + HEARTBEAT_STALE_AFTER_MS
+        ));
+        assert!(CaptureHealth::is_stale(
+            1_000,
+            1_001 + HEARTBEAT_STALE_AFTER_MS
+        ));
+        assert!(!CaptureHealth::is_stale(5_000, 1_000));
+    }
+}
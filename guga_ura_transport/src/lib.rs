@@ -0,0 +1,10 @@
+//! GugaURA 共享传输与遥测
+//!
+//! DLL、配置工具与独立 Receiver 共用的数据发送与接收相关代码。配置结构本身在
+//! `guga_ura_config` 中，这里保持平台无关。
+
+pub mod capture;
+pub mod capture_rules;
+pub mod health;
+pub mod signing;
+pub mod transport;
@@ -0,0 +1,339 @@
+//! 请求签名
+//!
+//! 配置了 `shared_secret` 时，DLL 与 Relay 发出的每个请求都带上时间戳和
+//! HMAC-SHA256 签名；Receiver 校验签名与时间窗口，拒绝伪造或过期的 payload。
+//! 签名覆盖请求方法、请求路径与线上实际传输的字节（压缩后的请求体），截获的请求
+//! 不能被重放到其它路由；Relay 转发时按下游地址与当前时间重新签名。
+//!
+//! 签名不含 nonce，Receiver 也不记录见过的签名：截获的请求在
+//! [`MAX_SIGNATURE_SKEW_MS`] 窗口内可以原样重放到同一路由。签名只防伪造与篡改，
+//! 不防窗口内重放。
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 签名时间戳请求头（Unix 毫秒）
+pub const TIMESTAMP_HEADER: &str = "x-gugaura-timestamp";
+
+/// 签名请求头（小写十六进制 HMAC-SHA256）
+pub const SIGNATURE_HEADER: &str = "x-gugaura-signature";
+
+/// 签名校验失败时返回的状态码
+pub const SIGNATURE_REJECTED_STATUS: u16 = 401;
+
+/// 允许的时间偏差（前后各 5 分钟），超出视为过期；窗口内的重放不会被拒绝
+pub const MAX_SIGNATURE_SKEW_MS: u64 = 5 * 60 * 1000;
+
+/// 低于该长度的密钥会给出校验警告
+pub const MIN_SECRET_LEN: usize = 16;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 一次请求的签名头
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHeaders {
+    pub timestamp: String,
+    pub signature: String,
+}
+
+/// 签名校验失败原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// 缺少时间戳或签名头
+    Missing,
+    /// 时间戳或签名格式不正确
+    Malformed,
+    /// 时间戳超出允许窗口
+    Stale { skew_ms: u64 },
+    /// 签名与请求体不匹配
+    Mismatch,
+}
+
+impl SignatureError {
+    pub fn status_code(&self) -> u16 {
+        SIGNATURE_REJECTED_STATUS
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::Missing => format!("Missing {} / {} header", TIMESTAMP_HEADER, SIGNATURE_HEADER),
+            Self::Malformed => "Malformed signature headers".to_string(),
+            Self::Stale { skew_ms } => format!(
+                "Stale signature: timestamp is {} ms away from receiver clock (max {} ms)",
+                skew_ms, MAX_SIGNATURE_SKEW_MS
+            ),
+            Self::Mismatch => "Signature mismatch".to_string(),
+        }
+    }
+}
+
+/// 取有效密钥：去掉首尾空白，为空视为未配置
+pub fn effective_secret(secret: Option<&str>) -> Option<&str> {
+    secret.map(str::trim).filter(|secret| !secret.is_empty())
+}
+
+/// 当前 Unix 毫秒
+pub fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 取 URL 或请求目标中的路径部分（不含查询串），用于签名
+pub fn request_path(target: &str) -> &str {
+    let without_scheme = match target.find("://") {
+        Some(index) => &target[index + 3..],
+        None => target,
+    };
+    let path = if target.contains("://") {
+        without_scheme
+            .find('/')
+            .map(|index| &without_scheme[index..])
+            .unwrap_or("/")
+    } else {
+        without_scheme
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+/// 按给定时间戳为一次请求签名；`path` 为请求路径，见 [`request_path`]
+pub fn sign_request(
+    secret: &str,
+    timestamp_ms: u64,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> SignatureHeaders {
+    let timestamp = timestamp_ms.to_string();
+    let signature = new_mac(secret, &timestamp, method, path, body)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    SignatureHeaders {
+        timestamp,
+        signature,
+    }
+}
+
+/// 校验签名与时间窗口；`method` 与 `path` 取请求实际到达的路由
+pub fn verify_request(
+    secret: &str,
+    timestamp: Option<&str>,
+    signature: Option<&str>,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    now_ms: u64,
+) -> Result<(), SignatureError> {
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return Err(SignatureError::Missing);
+    };
+    let timestamp = timestamp.trim();
+    let timestamp_ms = timestamp
+        .parse::<u64>()
+        .map_err(|_| SignatureError::Malformed)?;
+    let expected = decode_hex(signature.trim()).ok_or(SignatureError::Malformed)?;
+
+    let skew_ms = now_ms.abs_diff(timestamp_ms);
+    if skew_ms > MAX_SIGNATURE_SKEW_MS {
+        return Err(SignatureError::Stale { skew_ms });
+    }
+
+    new_mac(secret, timestamp, method, path, body)
+        .verify_slice(&expected)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+/// 签名内容为 `{timestamp}\n{METHOD}\n{path}\n{body}`
+fn new_mac(secret: &str, timestamp: &str, method: &str, path: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b"\n");
+    mac.update(method.to_ascii_uppercase().as_bytes());
+    mac.update(b"\n");
+    mac.update(request_path(path).as_bytes());
+    mac.update(b"\n");
+    mac.update(body);
+    mac
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&value[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "correct horse battery staple";
+    const NOW: u64 = 1_700_000_000_000;
+    const ROUTE: &str = "/notify/guga_ura/response";
+
+    #[test]
+    fn signed_body_should_verify() {
+        let headers = sign_request(SECRET, NOW, "POST", ROUTE, b"payload");
+
+        assert_eq!(headers.timestamp, NOW.to_string());
+        assert_eq!(headers.signature.len(), 64);
+        assert_eq!(
+            verify_request(
+                SECRET,
+                Some(&headers.timestamp),
+                Some(&headers.signature.to_ascii_uppercase()),
+                "post",
+                ROUTE,
+                b"payload",
+                NOW + 1000,
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn verify_should_reject_tampered_or_foreign_signatures() {
+        let headers = sign_request(SECRET, NOW, "POST", ROUTE, b"payload");
+        let verify = |secret: &str, body: &[u8]| {
+            verify_request(
+                secret,
+                Some(&headers.timestamp),
+                Some(&headers.signature),
+                "POST",
+                ROUTE,
+                body,
+                NOW,
+            )
+        };
+
+        assert_eq!(verify(SECRET, b"payload!"), Err(SignatureError::Mismatch));
+        assert_eq!(
+            verify("another secret", b"payload"),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verify_request(
+                SECRET,
+                Some(&(NOW + 1).to_string()),
+                Some(&headers.signature),
+                "POST",
+                ROUTE,
+                b"payload",
+                NOW,
+            ),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn verify_should_reject_missing_malformed_and_stale_headers() {
+        let headers = sign_request(SECRET, NOW, "POST", ROUTE, b"payload");
+
+        assert_eq!(
+            verify_request(
+                SECRET,
+                None,
+                Some(&headers.signature),
+                "POST",
+                ROUTE,
+                b"payload",
+                NOW
+            ),
+            Err(SignatureError::Missing)
+        );
+        assert_eq!(
+            verify_request(
+                SECRET,
+                Some("yesterday"),
+                Some(&headers.signature),
+                "POST",
+                ROUTE,
+                b"payload",
+                NOW
+            ),
+            Err(SignatureError::Malformed)
+        );
+        assert_eq!(
+            verify_request(
+                SECRET,
+                Some(&headers.timestamp),
+                Some("zz"),
+                "POST",
+                ROUTE,
+                b"payload",
+                NOW
+            ),
+            Err(SignatureError::Malformed)
+        );
+        assert_eq!(
+            verify_request(
+                SECRET,
+                Some(&headers.timestamp),
+                Some(&headers.signature),
+                "POST",
+                ROUTE,
+                b"payload",
+                NOW + MAX_SIGNATURE_SKEW_MS + 1,
+            ),
+            Err(SignatureError::Stale {
+                skew_ms: MAX_SIGNATURE_SKEW_MS + 1
+            })
+        );
+    }
+
+    #[test]
+    fn signature_should_be_bound_to_method_and_route() {
+        let headers = sign_request(SECRET, NOW, "POST", ROUTE, b"payload");
+        let verify = |method: &str, path: &str| {
+            verify_request(
+                SECRET,
+                Some(&headers.timestamp),
+                Some(&headers.signature),
+                method,
+                path,
+                b"payload",
+                NOW,
+            )
+        };
+
+        assert_eq!(verify("POST", "/notify/guga_ura/response?x=1"), Ok(()));
+        assert_eq!(
+            verify("POST", "/notify/guga_ura/request"),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(verify("POST", "/status"), Err(SignatureError::Mismatch));
+        assert_eq!(verify("PUT", ROUTE), Err(SignatureError::Mismatch));
+    }
+
+    #[test]
+    fn request_path_should_strip_origin_and_query() {
+        assert_eq!(
+            request_path("http://127.0.0.1:4693/notify/a/b?x=1"),
+            "/notify/a/b"
+        );
+        assert_eq!(request_path("https://example.com"), "/");
+        assert_eq!(request_path("/status#frag"), "/status");
+        assert_eq!(request_path(""), "/");
+    }
+
+    #[test]
+    fn blank_secret_should_count_as_unset() {
+        assert_eq!(effective_secret(None), None);
+        assert_eq!(effective_secret(Some("  ")), None);
+        assert_eq!(effective_secret(Some(" abc ")), Some("abc"));
+    }
+}
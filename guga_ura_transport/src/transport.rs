@@ -0,0 +1,450 @@
+//! 传输压缩
+//!
+//! DLL 发往 Receiver 的 payload 可选用 gzip 或 zstd 压缩，通过标准的
+//! `Content-Encoding` 头声明。Receiver 与 Relay 按该头透明解压；不认识的编码
+//! 以 `415 Unsupported Media Type` 拒绝，发送方收到后改为不压缩重发。
+//! [`post_payload`] 把压缩、签名与这次回退放在一起，DLL 直接调用。
+
+use crate::signing::{self, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 声明压缩方式的请求头
+pub const CONTENT_ENCODING_HEADER: &str = "content-encoding";
+
+/// 接收方不支持该编码时返回的状态码
+pub const UNSUPPORTED_ENCODING_STATUS: u16 = 415;
+
+/// 解压后的大小上限，防止异常数据占满内存
+pub const MAX_DECODED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 配置中可选的压缩方式（`transport_compression`）
+pub const TRANSPORT_COMPRESSION_VALUES: &[&str] = &["none", "gzip", "zstd"];
+
+/// 小于该字节数的 payload 压缩收益很低，直接原样发送
+pub const MIN_COMPRESS_BYTES: usize = 256;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// 传输编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// 解析配置值：为空或 `none` 表示不压缩
+    pub fn from_config(value: Option<&str>) -> Result<Self, String> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("none") => Ok(Self::Identity),
+            Some("gzip") => Ok(Self::Gzip),
+            Some("zstd") => Ok(Self::Zstd),
+            Some(other) => Err(format!(
+                "不支持的压缩方式 {}，可选 {}",
+                other,
+                TRANSPORT_COMPRESSION_VALUES.join(" / ")
+            )),
+        }
+    }
+
+    /// 解析 `Content-Encoding` 头；缺失或 `identity` 表示未压缩
+    pub fn from_header(value: Option<&str>) -> Result<Self, String> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("identity") => Ok(Self::Identity),
+            Some("gzip") | Some("x-gzip") => Ok(Self::Gzip),
+            Some("zstd") => Ok(Self::Zstd),
+            Some(other) => Err(format!("Unsupported content-encoding: {}", other)),
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
+        self == Self::Identity
+    }
+}
+
+/// 按编码压缩
+pub fn encode_body(data: &[u8], encoding: ContentEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        ContentEncoding::Identity => Ok(data.to_vec()),
+        ContentEncoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder
+                .write_all(data)
+                .and_then(|()| encoder.finish())
+                .map_err(|e| format!("gzip encode failed: {}", e))
+        }
+        ContentEncoding::Zstd => {
+            zstd::bulk::compress(data, ZSTD_LEVEL).map_err(|e| format!("zstd encode failed: {}", e))
+        }
+    }
+}
+
+/// 按编码解压，超过 [`MAX_DECODED_BYTES`] 时报错
+pub fn decode_body(data: &[u8], encoding: ContentEncoding) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read + '_> = match encoding {
+        ContentEncoding::Identity => return Ok(data.to_vec()),
+        ContentEncoding::Gzip => Box::new(GzDecoder::new(data)),
+        ContentEncoding::Zstd => Box::new(
+            zstd::stream::read::Decoder::new(data)
+                .map_err(|e| format!("zstd decode failed: {}", e))?,
+        ),
+    };
+
+    let mut decoded = Vec::new();
+    reader
+        .take(MAX_DECODED_BYTES + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("{} decode failed: {}", encoding.as_str(), e))?;
+    if decoded.len() as u64 > MAX_DECODED_BYTES {
+        return Err(format!(
+            "{} body exceeds {} bytes after decoding",
+            encoding.as_str(),
+            MAX_DECODED_BYTES
+        ));
+    }
+    Ok(decoded)
+}
+
+/// [`post_payload`] 的发送结果
+#[derive(Debug)]
+pub struct PayloadDelivery {
+    /// 本次请求被接收方以 415 拒绝并首次关闭了压缩（已改为原样重发）
+    pub compression_disabled: bool,
+    /// 压缩失败的原因；此时已改为原样发送
+    pub compress_error: Option<String>,
+    pub result: Result<(), ureq::Error>,
+}
+
+/// 压缩、签名并 POST 一份 payload
+///
+/// 数据足够大且 `encoding` 不是 identity 时压缩并声明 `Content-Encoding`；接收方以
+/// [`UNSUPPORTED_ENCODING_STATUS`] 拒绝时置位 `compression_rejected` 并原样重发，
+/// 之后的调用不再压缩。配置了 `shared_secret` 时按实际发送的请求体签名
+pub fn post_payload(
+    agent: &ureq::Agent,
+    url: &str,
+    data: &[u8],
+    encoding: ContentEncoding,
+    shared_secret: Option<&str>,
+    compression_rejected: &AtomicBool,
+) -> PayloadDelivery {
+    let mut compression_disabled = false;
+    let mut compress_error = None;
+    let compressed = if encoding.is_identity()
+        || data.len() < MIN_COMPRESS_BYTES
+        || compression_rejected.load(Ordering::Relaxed)
+    {
+        None
+    } else {
+        match encode_body(data, encoding) {
+            Ok(body) => Some(body),
+            Err(error) => {
+                compress_error = Some(error);
+                None
+            }
+        }
+    };
+
+    let result = match compressed {
+        Some(body) => match sign_post(agent.post(url), url, &body, shared_secret)
+            .set(CONTENT_ENCODING_HEADER, encoding.as_str())
+            .send_bytes(&body)
+        {
+            Err(ureq::Error::Status(status, _)) if status == UNSUPPORTED_ENCODING_STATUS => {
+                compression_disabled = !compression_rejected.swap(true, Ordering::Relaxed);
+                sign_post(agent.post(url), url, data, shared_secret).send_bytes(data)
+            }
+            other => other,
+        },
+        None => sign_post(agent.post(url), url, data, shared_secret).send_bytes(data),
+    };
+
+    PayloadDelivery {
+        compression_disabled,
+        compress_error,
+        result: result.map(|_| ()),
+    }
+}
+
+/// 配置了密钥时为 POST 请求附加时间戳与签名头；签名绑定 POST 与 `url` 的路径
+pub fn sign_post(
+    request: ureq::Request,
+    url: &str,
+    body: &[u8],
+    shared_secret: Option<&str>,
+) -> ureq::Request {
+    let Some(secret) = shared_secret else {
+        return request;
+    };
+    let signature = signing::sign_request(
+        secret,
+        signing::now_unix_ms(),
+        "POST",
+        signing::request_path(url),
+        body,
+    );
+    request
+        .set(TIMESTAMP_HEADER, &signature.timestamp)
+        .set(SIGNATURE_HEADER, &signature.signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Response, Server};
+
+    const SECRET: &str = "transport-test-secret";
+    const ROUTE: &str = "/notify/http/response";
+
+    /// 一次请求的请求头（小写名、原值）与请求体
+    struct RecordedRequest {
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl RecordedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        }
+
+        fn verify_signature(&self) -> Result<(), signing::SignatureError> {
+            signing::verify_request(
+                SECRET,
+                self.header(TIMESTAMP_HEADER),
+                self.header(SIGNATURE_HEADER),
+                "POST",
+                ROUTE,
+                &self.body,
+                signing::now_unix_ms(),
+            )
+        }
+    }
+
+    /// 启动一个记录请求的 Receiver 替身，按顺序返回给定状态码
+    fn spawn_receiver(statuses: Vec<u16>) -> (String, thread::JoinHandle<Vec<RecordedRequest>>) {
+        let server = Server::http("127.0.0.1:0").expect("启动测试 HTTP 服务失败");
+        let port = server
+            .server_addr()
+            .to_ip()
+            .expect("读取测试端口失败")
+            .port();
+        let handle = thread::spawn(move || {
+            statuses
+                .into_iter()
+                .map(|status| {
+                    let mut request = server.recv().expect("接收请求失败");
+                    let mut body = Vec::new();
+                    request
+                        .as_reader()
+                        .read_to_end(&mut body)
+                        .expect("读取请求体失败");
+                    let headers = request
+                        .headers()
+                        .iter()
+                        .map(|header| {
+                            (
+                                header.field.as_str().as_str().to_ascii_lowercase(),
+                                header.value.as_str().to_string(),
+                            )
+                        })
+                        .collect();
+                    request
+                        .respond(Response::empty(status))
+                        .expect("返回响应失败");
+                    RecordedRequest { headers, body }
+                })
+                .collect()
+        });
+        (format!("http://127.0.0.1:{}{}", port, ROUTE), handle)
+    }
+
+    fn agent() -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+    }
+
+    fn sample() -> Vec<u8> {
+        b"{\"data\":{\"circle_user_array\":[1,2,3]}}".repeat(64)
+    }
+
+    #[test]
+    fn encodings_should_round_trip() {
+        let data = sample();
+
+        for encoding in [
+            ContentEncoding::Identity,
+            ContentEncoding::Gzip,
+            ContentEncoding::Zstd,
+        ] {
+            let encoded = encode_body(&data, encoding).expect("压缩失败");
+            if !encoding.is_identity() {
+                assert!(encoded.len() < data.len(), "{:?} 应变小", encoding);
+            }
+            assert_eq!(
+                decode_body(&encoded, encoding).expect("解压失败"),
+                data,
+                "{:?}",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn header_and_config_values_should_parse() {
+        assert_eq!(
+            ContentEncoding::from_header(None),
+            Ok(ContentEncoding::Identity)
+        );
+        assert_eq!(
+            ContentEncoding::from_header(Some(" GZIP ")),
+            Ok(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            ContentEncoding::from_header(Some("zstd")),
+            Ok(ContentEncoding::Zstd)
+        );
+        assert!(ContentEncoding::from_header(Some("br")).is_err());
+
+        assert_eq!(
+            ContentEncoding::from_config(Some("none")),
+            Ok(ContentEncoding::Identity)
+        );
+        assert_eq!(
+            ContentEncoding::from_config(Some("zstd")),
+            Ok(ContentEncoding::Zstd)
+        );
+        assert!(ContentEncoding::from_config(Some("identity")).is_err());
+    }
+
+    #[test]
+    fn decode_should_reject_corrupt_bodies() {
+        assert!(decode_body(b"not gzip", ContentEncoding::Gzip).is_err());
+        assert!(decode_body(b"not zstd", ContentEncoding::Zstd).is_err());
+    }
+
+    #[test]
+    fn post_payload_should_compress_and_sign_body() {
+        let data = sample();
+        let (url, handle) = spawn_receiver(vec![200, 200]);
+        let rejected = AtomicBool::new(false);
+
+        let delivery = post_payload(
+            &agent(),
+            &url,
+            &data,
+            ContentEncoding::Zstd,
+            Some(SECRET),
+            &rejected,
+        );
+        assert!(delivery.result.is_ok(), "{:?}", delivery.result);
+        assert!(!delivery.compression_disabled);
+        assert!(delivery.compress_error.is_none());
+
+        // 小于阈值的数据不压缩
+        let small = post_payload(
+            &agent(),
+            &url,
+            b"ping",
+            ContentEncoding::Zstd,
+            Some(SECRET),
+            &rejected,
+        );
+        assert!(small.result.is_ok(), "{:?}", small.result);
+
+        let requests = handle.join().expect("Receiver 线程退出异常");
+        assert_eq!(requests[0].header(CONTENT_ENCODING_HEADER), Some("zstd"));
+        assert_eq!(
+            decode_body(&requests[0].body, ContentEncoding::Zstd).expect("解压失败"),
+            data
+        );
+        assert_eq!(requests[0].verify_signature(), Ok(()));
+
+        assert_eq!(requests[1].header(CONTENT_ENCODING_HEADER), None);
+        assert_eq!(requests[1].body, b"ping");
+        assert_eq!(requests[1].verify_signature(), Ok(()));
+    }
+
+    #[test]
+    fn post_payload_should_resend_raw_after_unsupported_encoding() {
+        let data = sample();
+        let (url, handle) = spawn_receiver(vec![415, 200, 200]);
+        let rejected = AtomicBool::new(false);
+
+        let first = post_payload(
+            &agent(),
+            &url,
+            &data,
+            ContentEncoding::Gzip,
+            Some(SECRET),
+            &rejected,
+        );
+        assert!(first.result.is_ok(), "{:?}", first.result);
+        assert!(first.compression_disabled, "首次 415 应关闭压缩");
+        assert!(rejected.load(Ordering::Relaxed));
+
+        let second = post_payload(
+            &agent(),
+            &url,
+            &data,
+            ContentEncoding::Gzip,
+            Some(SECRET),
+            &rejected,
+        );
+        assert!(second.result.is_ok(), "{:?}", second.result);
+        assert!(!second.compression_disabled);
+
+        let requests = handle.join().expect("Receiver 线程退出异常");
+        assert_eq!(requests[0].header(CONTENT_ENCODING_HEADER), Some("gzip"));
+        for request in &requests[1..] {
+            assert_eq!(request.header(CONTENT_ENCODING_HEADER), None);
+            assert_eq!(request.body, data);
+            assert_eq!(request.verify_signature(), Ok(()), "重发应按原始请求体签名");
+        }
+    }
+
+    #[test]
+    fn post_payload_should_skip_signature_without_secret() {
+        let (url, handle) = spawn_receiver(vec![401]);
+
+        let delivery = post_payload(
+            &agent(),
+            &url,
+            b"ping",
+            ContentEncoding::Identity,
+            None,
+            &AtomicBool::new(false),
+        );
+
+        assert!(matches!(
+            delivery.result,
+            Err(ureq::Error::Status(status, _)) if status == signing::SIGNATURE_REJECTED_STATUS
+        ));
+        let requests = handle.join().expect("Receiver 线程退出异常");
+        assert_eq!(requests[0].header(TIMESTAMP_HEADER), None);
+        assert_eq!(requests[0].header(SIGNATURE_HEADER), None);
+    }
+}